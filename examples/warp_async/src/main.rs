@@ -76,7 +76,10 @@ impl Query {
 type Schema = RootNode<'static, Query, EmptyMutation<Context>, EmptySubscription<Context>>;
 
 fn schema() -> Schema {
+    // `User::friends` can be nested indefinitely, so reject overly deep queries.
+    // Introspection queries, like the one of GraphiQL, are not limited.
     Schema::new(Query, EmptyMutation::<Context>::new(), EmptySubscription::<Context>::new())
+        .max_query_depth(5)
 }
 
 #[tokio::main]
@@ -2,10 +2,13 @@
 
 use std::{pin::Pin, sync::Arc, time::Duration};
 
-use futures::{Future, FutureExt as _, Stream};
+use futures::{future, Future, FutureExt as _, Stream, StreamExt as _};
 use juniper::{DefaultScalarValue, EmptyMutation, FieldError, RootNode};
 use juniper_subscriptions::Coordinator;
-use juniper_warp::{playground_filter, subscriptions::graphql_subscriptions};
+use juniper_warp::{
+    playground_filter,
+    subscriptions::{graphql_subscriptions, graphql_subscriptions_sse},
+};
 use warp::{http::Response, Filter};
 
 #[derive(Clone)]
@@ -13,7 +16,7 @@ struct Context {}
 
 impl juniper::Context for Context {}
 
-#[derive(Clone, Copy, juniper::GraphQLEnum)]
+#[derive(Clone, Copy, PartialEq, juniper::GraphQLEnum)]
 enum UserKind {
     Admin,
     User,
@@ -126,6 +129,27 @@ impl Subscription {
 
         Box::pin(stream)
     }
+
+    /// Streams only the users of the given `kind`
+    async fn users_of_kind(kind: UserKind) -> Pin<Box<dyn Stream<Item = User> + Send>> {
+        let mut counter = 0;
+        let stream = tokio::time::interval(Duration::from_secs(5))
+            .map(move |_| {
+                counter += 1;
+                User {
+                    id: counter,
+                    kind: if counter % 2 == 0 {
+                        UserKind::User
+                    } else {
+                        UserKind::Admin
+                    },
+                    name: "stream user".to_string(),
+                }
+            })
+            .filter(move |user| future::ready(user.kind == kind));
+
+        Box::pin(stream)
+    }
 }
 
 type Schema = RootNode<'static, Query, EmptyMutation<Context>, Subscription>;
@@ -153,6 +177,7 @@ async fn main() {
 
     let sub_state = warp::any().map(move || Context {});
     let coordinator = Arc::new(juniper_subscriptions::Coordinator::new(schema()));
+    let sse_filter = graphql_subscriptions_sse(coordinator.clone(), sub_state.clone().boxed());
 
     log::info!("Listening on 127.0.0.1:8080");
 
@@ -179,6 +204,7 @@ async fn main() {
         // TODO#584: remove this workaround
         warp::reply::with_header(reply, "Sec-WebSocket-Protocol", "graphql-ws")
     })
+    .or(warp::path("sse").and(sse_filter))
     .or(warp::post()
         .and(warp::path("graphql"))
         .and(qm_graphql_filter))
@@ -224,6 +224,67 @@ async fn test_derived_object() {
     );
 }
 
+#[tokio::test]
+async fn test_derived_object_deprecated_field() {
+    let doc = r#"
+        {
+            __type(name: "MyObj") {
+                fields(includeDeprecated: true) {
+                    name
+                    isDeprecated
+                    deprecationReason
+                }
+            }
+        }"#;
+
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    assert_eq!(
+        execute(doc, None, &schema, &Variables::new(), &()).await,
+        Ok((
+            Value::object(
+                vec![(
+                    "__type",
+                    Value::object(
+                        vec![(
+                            "fields",
+                            Value::list(vec![
+                                Value::object(
+                                    vec![
+                                        ("name", Value::scalar("regularField")),
+                                        ("isDeprecated", Value::scalar(false)),
+                                        ("deprecationReason", Value::null()),
+                                    ]
+                                    .into_iter()
+                                    .collect(),
+                                ),
+                                Value::object(
+                                    vec![
+                                        ("name", Value::scalar("renamedField")),
+                                        ("isDeprecated", Value::scalar(true)),
+                                        ("deprecationReason", Value::scalar("field deprecation")),
+                                    ]
+                                    .into_iter()
+                                    .collect(),
+                                ),
+                            ]),
+                        )]
+                        .into_iter()
+                        .collect(),
+                    ),
+                )]
+                .into_iter()
+                .collect()
+            ),
+            vec![]
+        ))
+    );
+}
+
 #[tokio::test]
 #[should_panic]
 async fn test_cannot_query_skipped_field() {
@@ -1,7 +1,7 @@
 extern crate serde_json;
 
 use juniper::{
-    execute,
+    execute, execute_sync,
     parser::{ParseError, ScalarToken, Spanning, Token},
     serde::de,
     EmptyMutation, EmptySubscription, InputValue, Object, ParseScalarResult, RootNode, ScalarValue,
@@ -169,13 +169,17 @@ impl TestType {
     fn long_with_arg(long_arg: i64) -> i64 {
         long_arg
     }
+
+    fn name() -> &str {
+        "test"
+    }
 }
 
 async fn run_variable_query<F>(query: &str, vars: Variables<MyScalarValue>, f: F)
 where
     F: Fn(&Object<MyScalarValue>) -> (),
 {
-    let schema = RootNode::new(
+    let schema = RootNode::new_with_scalar(
         TestType,
         EmptyMutation::<()>::new(),
         EmptySubscription::<()>::new(),
@@ -264,3 +268,34 @@ fn deserialize_variable() {
         )])
     );
 }
+
+#[test]
+fn building_schema_over_custom_scalar() {
+    let schema = RootNode::new_with_scalar(
+        TestType,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let (result, errs) = execute_sync("{ name longField }", None, &schema, &Variables::new(), &())
+        .expect("Execution failed");
+
+    assert_eq!(errs, []);
+    assert_eq!(
+        result,
+        Value::object(
+            vec![
+                (
+                    "name",
+                    Value::Scalar(MyScalarValue::String("test".to_owned()))
+                ),
+                (
+                    "longField",
+                    Value::Scalar(MyScalarValue::Long((::std::i32::MAX as i64) + 1))
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        ),
+    );
+}
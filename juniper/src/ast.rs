@@ -112,19 +112,29 @@ pub struct Directive<'a, S> {
     pub arguments: Option<Spanning<Arguments<'a, S>>>,
 }
 
+/// The kind of an operation
 #[derive(Clone, PartialEq, Debug)]
 pub enum OperationType {
+    /// A `query` operation
     Query,
+    /// A `mutation` operation
     Mutation,
+    /// A `subscription` operation
     Subscription,
 }
 
+/// An operation of a document
 #[derive(Clone, PartialEq, Debug)]
 pub struct Operation<'a, S> {
+    /// Whether this is a query, a mutation or a subscription
     pub operation_type: OperationType,
+    /// The name of the operation, if it has one
     pub name: Option<Spanning<&'a str>>,
+    /// The variables declared by the operation
     pub variable_definitions: Option<Spanning<VariableDefinitions<'a, S>>>,
+    /// The directives applied to the operation
     pub directives: Option<Vec<Spanning<Directive<'a, S>>>>,
+    /// The fields selected at the root of the operation
     pub selection_set: Vec<Selection<'a, S>>,
 }
 
@@ -136,12 +146,16 @@ pub struct Fragment<'a, S> {
     pub selection_set: Vec<Selection<'a, S>>,
 }
 
+/// A top-level definition of a document
 #[derive(Clone, PartialEq, Debug)]
 pub enum Definition<'a, S> {
+    /// A query, mutation or subscription
     Operation(Spanning<Operation<'a, S>>),
+    /// A named fragment
     Fragment(Spanning<Fragment<'a, S>>),
 }
 
+/// A parsed GraphQL document
 pub type Document<'a, S> = Vec<Definition<'a, S>>;
 
 /// Parse an unstructured input value into a Rust data type.
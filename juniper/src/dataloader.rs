@@ -0,0 +1,263 @@
+use std::{
+    collections::{HashMap, HashSet},
+    error::Error,
+    fmt,
+    future::Future,
+    hash::Hash,
+    pin::Pin,
+    sync::Mutex,
+    task::{Context, Poll, Waker},
+};
+
+use crate::BoxFuture;
+
+/// Batches the keys loaded by concurrently resolving fields into a single
+/// call of a batch function
+///
+/// Store a `DataLoader` in the context of a request and call
+/// [`load`](DataLoader::load) from async resolvers instead of fetching every
+/// object on its own. The async executor polls the fields of a selection set,
+/// and the items of a list, together: all keys loaded before these futures
+/// stall are passed to the batch function at once, and the values are cached
+/// for the lifetime of the loader.
+///
+/// ```
+/// # use juniper::DataLoader;
+/// let loader = DataLoader::new(|ids: Vec<i32>| async move {
+///     // e.g. `SELECT * FROM users WHERE id IN (...)`
+///     ids.into_iter().map(|id| format!("user{}", id)).collect()
+/// });
+/// # let name = futures::executor::block_on(loader.load(1));
+/// # assert_eq!(name, Ok("user1".to_owned()));
+/// ```
+pub struct DataLoader<K, V> {
+    batch_fn: Box<dyn Fn(Vec<K>) -> BoxFuture<'static, Vec<V>> + Send + Sync>,
+    state: Mutex<State<K, V>>,
+}
+
+struct State<K, V> {
+    cache: HashMap<K, V>,
+    failed: HashMap<K, DataLoaderError>,
+    queued: Vec<K>,
+    in_flight: HashSet<K>,
+    waiting: HashMap<usize, Waker>,
+    next_waiter: usize,
+}
+
+impl<K, V> State<K, V> {
+    fn wake_all(&mut self) {
+        for (_, waker) in self.waiting.drain() {
+            waker.wake();
+        }
+    }
+}
+
+/// Error returned by [`DataLoader::load`](DataLoader::load)
+#[derive(Clone, Debug, PartialEq)]
+pub enum DataLoaderError {
+    /// The batch function returned a different number of values than it was
+    /// given keys.
+    BatchSize {
+        /// The number of keys passed to the batch function
+        keys: usize,
+        /// The number of values it returned
+        values: usize,
+    },
+    /// The future running the batch loading the key was dropped before the
+    /// batch completed.
+    Cancelled,
+}
+
+impl fmt::Display for DataLoaderError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            DataLoaderError::BatchSize { keys, values } => write!(
+                f,
+                "DataLoader batch function returned {} values for {} keys",
+                values, keys,
+            ),
+            DataLoaderError::Cancelled => write!(f, "DataLoader batch was cancelled"),
+        }
+    }
+}
+
+impl Error for DataLoaderError {}
+
+impl<K, V> DataLoader<K, V>
+where
+    K: Clone + Eq + Hash + Send + 'static,
+    V: Clone + Send + 'static,
+{
+    /// Construct a new loader from a batch function.
+    ///
+    /// `batch_fn` receives distinct keys and must return exactly one value per
+    /// key, in the same order.
+    pub fn new<F, Fut>(batch_fn: F) -> Self
+    where
+        F: Fn(Vec<K>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Vec<V>> + Send + 'static,
+    {
+        DataLoader {
+            batch_fn: Box::new(move |keys| Box::pin(batch_fn(keys))),
+            state: Mutex::new(State {
+                cache: HashMap::new(),
+                failed: HashMap::new(),
+                queued: Vec::new(),
+                in_flight: HashSet::new(),
+                waiting: HashMap::new(),
+                next_waiter: 0,
+            }),
+        }
+    }
+
+    /// Load the value of `key`.
+    ///
+    /// The key is queued until the futures polled together with the returned
+    /// one stall, and then loaded along with the other queued keys. Fails if
+    /// the batch function doesn't return one value per key, or if the future
+    /// running the batch is dropped before it completes.
+    pub async fn load(&self, key: K) -> Result<V, DataLoaderError> {
+        {
+            let mut state = self.state.lock().unwrap();
+            if let Some(value) = state.cache.get(&key) {
+                return Ok(value.clone());
+            }
+            if !state.in_flight.contains(&key) && !state.queued.contains(&key) {
+                state.failed.remove(&key);
+                state.queued.push(key.clone());
+            }
+        }
+
+        // Give sibling futures the chance to queue their keys as well.
+        YieldNow(false).await;
+
+        let keys = {
+            let mut state = self.state.lock().unwrap();
+            let keys = std::mem::take(&mut state.queued);
+            state.in_flight.extend(keys.iter().cloned());
+            keys
+        };
+
+        if !keys.is_empty() {
+            let batch = InFlight {
+                state: &self.state,
+                keys,
+            };
+            let values = (self.batch_fn)(batch.keys.clone()).await;
+
+            let mut state = self.state.lock().unwrap();
+            if values.len() == batch.keys.len() {
+                for (key, value) in batch.keys.iter().cloned().zip(values) {
+                    state.cache.insert(key, value);
+                }
+            } else {
+                let error = DataLoaderError::BatchSize {
+                    keys: batch.keys.len(),
+                    values: values.len(),
+                };
+                for key in batch.keys.iter().cloned() {
+                    state.failed.insert(key, error.clone());
+                }
+            }
+        }
+
+        let id = {
+            let mut state = self.state.lock().unwrap();
+            state.next_waiter += 1;
+            state.next_waiter
+        };
+        Loaded {
+            state: &self.state,
+            key,
+            id,
+        }
+        .await
+    }
+
+    /// Remove the cached value of `key`, so the next load fetches it again.
+    pub fn clear(&self, key: &K) {
+        self.state.lock().unwrap().cache.remove(key);
+    }
+}
+
+impl<K, V> fmt::Debug for DataLoader<K, V> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("DataLoader").finish()
+    }
+}
+
+/// Future returning `Pending` once, after waking itself.
+struct YieldNow(bool);
+
+impl Future for YieldNow {
+    type Output = ();
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context) -> Poll<()> {
+        if self.0 {
+            Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            Poll::Pending
+        }
+    }
+}
+
+/// Keys of a running batch, no longer in flight once the batch completes or
+/// its future is dropped.
+struct InFlight<'a, K: Eq + Hash, V> {
+    state: &'a Mutex<State<K, V>>,
+    keys: Vec<K>,
+}
+
+impl<'a, K: Eq + Hash, V> Drop for InFlight<'a, K, V> {
+    fn drop(&mut self) {
+        let mut state = match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        for key in &self.keys {
+            state.in_flight.remove(key);
+        }
+        state.wake_all();
+    }
+}
+
+/// Future resolving once the batch loading `key` has completed.
+struct Loaded<'a, K: Eq + Hash, V> {
+    state: &'a Mutex<State<K, V>>,
+    key: K,
+    id: usize,
+}
+
+impl<'a, K, V> Future for Loaded<'a, K, V>
+where
+    K: Eq + Hash,
+    V: Clone,
+{
+    type Output = Result<V, DataLoaderError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Self::Output> {
+        let mut state = self.state.lock().unwrap();
+        if let Some(value) = state.cache.get(&self.key) {
+            return Poll::Ready(Ok(value.clone()));
+        }
+        if state.in_flight.contains(&self.key) || state.queued.contains(&self.key) {
+            state.waiting.insert(self.id, cx.waker().clone());
+            return Poll::Pending;
+        }
+        Poll::Ready(Err(state
+            .failed
+            .get(&self.key)
+            .cloned()
+            .unwrap_or(DataLoaderError::Cancelled)))
+    }
+}
+
+impl<'a, K: Eq + Hash, V> Drop for Loaded<'a, K, V> {
+    fn drop(&mut self) {
+        if let Ok(mut state) = self.state.lock() {
+            state.waiting.remove(&self.id);
+        }
+    }
+}
@@ -0,0 +1,155 @@
+use crate::{
+    ast::{Definition, Directive, Document, Operation, OperationType, Selection},
+    executor::Variables,
+    parser::Spanning,
+    schema::{meta::MetaType, model::SchemaType},
+    types::base::is_excluded,
+    util::ensure_sufficient_stack,
+    value::ScalarValue,
+};
+
+/// Computes the cache hint of an operation, in seconds.
+///
+/// The hint is the minimum `maxAge` of the `@cacheControl` directive on the
+/// operation, the `@cacheControl` directives on its selected fields, and the
+/// cache hints the schema sets on those fields. Selections excluded through
+/// `@skip` or `@include` do not take part. Returns `None` if no hint applies.
+pub(crate) fn operation_cache_max_age<S>(
+    document: &Document<S>,
+    operation: &Spanning<Operation<S>>,
+    schema: &SchemaType<S>,
+    variables: &Variables<S>,
+) -> Option<u32>
+where
+    S: ScalarValue,
+{
+    let root_type = match operation.item.operation_type {
+        OperationType::Query => Some(schema.concrete_query_type()),
+        OperationType::Mutation => schema.concrete_mutation_type(),
+        OperationType::Subscription => schema.concrete_subscription_type(),
+    }?;
+
+    let collector = CacheHintCollector {
+        document,
+        schema,
+        variables,
+    };
+
+    min_max_age(
+        collector.directives(&operation.item.directives),
+        collector.selection_set(&operation.item.selection_set, root_type),
+    )
+}
+
+struct CacheHintCollector<'a, S> {
+    document: &'a Document<'a, S>,
+    schema: &'a SchemaType<'a, S>,
+    variables: &'a Variables<S>,
+}
+
+impl<'a, S> CacheHintCollector<'a, S>
+where
+    S: ScalarValue,
+{
+    fn selection_set(
+        &self,
+        selection_set: &[Selection<S>],
+        meta_type: &MetaType<S>,
+    ) -> Option<u32> {
+        ensure_sufficient_stack(|| self.selection_set_inner(selection_set, meta_type))
+    }
+
+    fn selection_set_inner(
+        &self,
+        selection_set: &[Selection<S>],
+        meta_type: &MetaType<S>,
+    ) -> Option<u32> {
+        let mut max_age = None;
+
+        for selection in selection_set {
+            let selection_max_age = match *selection {
+                Selection::Field(Spanning { item: ref f, .. }) => {
+                    if is_excluded(&f.directives, self.variables) {
+                        continue;
+                    }
+
+                    let meta_field = match meta_type.field_by_name(f.name.item) {
+                        Some(meta_field) => meta_field,
+                        None => continue,
+                    };
+
+                    let sub_max_age = f.selection_set.as_ref().and_then(|selection_set| {
+                        self.schema
+                            .concrete_type_by_name(meta_field.field_type.innermost_name())
+                            .and_then(|sub_type| self.selection_set(selection_set, sub_type))
+                    });
+
+                    min_max_age(
+                        min_max_age(meta_field.cache_max_age, self.directives(&f.directives)),
+                        sub_max_age,
+                    )
+                }
+                Selection::FragmentSpread(Spanning {
+                    item: ref spread, ..
+                }) => {
+                    if is_excluded(&spread.directives, self.variables) {
+                        continue;
+                    }
+
+                    self.document.iter().find_map(|def| match *def {
+                        Definition::Fragment(Spanning { item: ref f, .. })
+                            if f.name.item == spread.name.item =>
+                        {
+                            self.schema
+                                .concrete_type_by_name(f.type_condition.item)
+                                .and_then(|sub_type| self.selection_set(&f.selection_set, sub_type))
+                        }
+                        _ => None,
+                    })
+                }
+                Selection::InlineFragment(Spanning {
+                    item: ref fragment, ..
+                }) => {
+                    if is_excluded(&fragment.directives, self.variables) {
+                        continue;
+                    }
+
+                    let sub_type = match fragment.type_condition {
+                        Some(ref type_condition) => {
+                            match self.schema.concrete_type_by_name(type_condition.item) {
+                                Some(sub_type) => sub_type,
+                                None => continue,
+                            }
+                        }
+                        None => meta_type,
+                    };
+
+                    self.selection_set(&fragment.selection_set, sub_type)
+                }
+            };
+
+            max_age = min_max_age(max_age, selection_max_age);
+        }
+
+        max_age
+    }
+
+    fn directives(&self, directives: &Option<Vec<Spanning<Directive<S>>>>) -> Option<u32> {
+        directives
+            .iter()
+            .flatten()
+            .filter(|d| d.item.name.item == "cacheControl")
+            .flat_map(|d| d.item.arguments.as_ref())
+            .flat_map(|args| args.item.get("maxAge"))
+            .flat_map(|v| v.item.clone().into_const(self.variables).convert::<i32>())
+            .map(|max_age| max_age.max(0) as u32)
+            .min()
+    }
+}
+
+fn min_max_age(a: Option<u32>, b: Option<u32>) -> Option<u32> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.min(b)),
+        (a, b) => a.or(b),
+    }
+}
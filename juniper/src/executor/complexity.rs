@@ -0,0 +1,186 @@
+use crate::{
+    ast::{Definition, Document, Operation, OperationType, Selection, Type},
+    executor::Variables,
+    parser::Spanning,
+    schema::{meta::MetaType, model::SchemaType},
+    types::base::is_excluded,
+    util::ensure_sufficient_stack,
+    validation::RuleError,
+    value::ScalarValue,
+};
+
+/// Estimated number of items of a list field without a `list_size` hint.
+pub(super) const DEFAULT_LIST_SIZE: usize = 10;
+
+/// Checks the complexity of an operation against the maximum complexity
+/// configured on the schema, if any.
+pub(crate) fn validate_operation_complexity<S>(
+    document: &Document<S>,
+    operation: &Spanning<Operation<S>>,
+    schema: &SchemaType<S>,
+    variables: &Variables<S>,
+) -> Vec<RuleError>
+where
+    S: ScalarValue,
+{
+    let max_complexity = match schema.max_query_complexity {
+        Some(max_complexity) => max_complexity,
+        None => return vec![],
+    };
+
+    let complexity = operation_complexity(document, operation, schema, variables);
+    if complexity > max_complexity {
+        vec![RuleError::new(
+            &error_message(complexity, max_complexity),
+            &[operation.start],
+        )]
+    } else {
+        vec![]
+    }
+}
+
+/// Computes the estimated cost of executing an operation.
+///
+/// Every selected field adds its cost, `1` unless set otherwise, plus the cost
+/// of its own selection set. The total of a list field is multiplied by its
+/// estimated size. Fragments add the cost of their selections, while
+/// selections excluded through `@skip` or `@include` are free.
+pub(crate) fn operation_complexity<S>(
+    document: &Document<S>,
+    operation: &Spanning<Operation<S>>,
+    schema: &SchemaType<S>,
+    variables: &Variables<S>,
+) -> usize
+where
+    S: ScalarValue,
+{
+    let root_type = match operation.item.operation_type {
+        OperationType::Query => Some(schema.concrete_query_type()),
+        OperationType::Mutation => schema.concrete_mutation_type(),
+        OperationType::Subscription => schema.concrete_subscription_type(),
+    };
+
+    let calculator = ComplexityCalculator {
+        document,
+        schema,
+        variables,
+    };
+
+    root_type.map_or(0, |root_type| {
+        calculator.selection_set(&operation.item.selection_set, root_type)
+    })
+}
+
+struct ComplexityCalculator<'a, S> {
+    document: &'a Document<'a, S>,
+    schema: &'a SchemaType<'a, S>,
+    variables: &'a Variables<S>,
+}
+
+impl<'a, S> ComplexityCalculator<'a, S>
+where
+    S: ScalarValue,
+{
+    fn selection_set(&self, selection_set: &[Selection<S>], meta_type: &MetaType<S>) -> usize {
+        ensure_sufficient_stack(|| self.selection_set_inner(selection_set, meta_type))
+    }
+
+    fn selection_set_inner(
+        &self,
+        selection_set: &[Selection<S>],
+        meta_type: &MetaType<S>,
+    ) -> usize {
+        let mut complexity = 0usize;
+
+        for selection in selection_set {
+            let selection_complexity = match *selection {
+                Selection::Field(Spanning { item: ref f, .. }) => {
+                    if is_excluded(&f.directives, self.variables) {
+                        continue;
+                    }
+
+                    let meta_field = match meta_type.field_by_name(f.name.item) {
+                        Some(meta_field) => meta_field,
+                        None => continue,
+                    };
+
+                    let sub_complexity = f.selection_set.as_ref().map_or(0, |selection_set| {
+                        self.schema
+                            .concrete_type_by_name(meta_field.field_type.innermost_name())
+                            .map_or(0, |sub_type| self.selection_set(selection_set, sub_type))
+                    });
+
+                    let cost = meta_field.complexity.map_or(1, |cost| cost as usize);
+                    let total = cost.saturating_add(sub_complexity);
+
+                    if is_list(&meta_field.field_type) {
+                        let list_size = meta_field
+                            .list_size
+                            .map_or(DEFAULT_LIST_SIZE, |size| size as usize);
+                        total.saturating_mul(list_size)
+                    } else {
+                        total
+                    }
+                }
+                Selection::FragmentSpread(Spanning {
+                    item: ref spread, ..
+                }) => {
+                    if is_excluded(&spread.directives, self.variables) {
+                        continue;
+                    }
+
+                    self.document
+                        .iter()
+                        .find_map(|def| match *def {
+                            Definition::Fragment(Spanning { item: ref f, .. })
+                                if f.name.item == spread.name.item =>
+                            {
+                                self.schema
+                                    .concrete_type_by_name(f.type_condition.item)
+                                    .map(|sub_type| self.selection_set(&f.selection_set, sub_type))
+                            }
+                            _ => None,
+                        })
+                        .unwrap_or(0)
+                }
+                Selection::InlineFragment(Spanning {
+                    item: ref fragment, ..
+                }) => {
+                    if is_excluded(&fragment.directives, self.variables) {
+                        continue;
+                    }
+
+                    let sub_type = match fragment.type_condition {
+                        Some(ref type_condition) => {
+                            match self.schema.concrete_type_by_name(type_condition.item) {
+                                Some(sub_type) => sub_type,
+                                None => continue,
+                            }
+                        }
+                        None => meta_type,
+                    };
+
+                    self.selection_set(&fragment.selection_set, sub_type)
+                }
+            };
+
+            complexity = complexity.saturating_add(selection_complexity);
+        }
+
+        complexity
+    }
+}
+
+pub(super) fn is_list(t: &Type) -> bool {
+    match *t {
+        Type::List(_) | Type::NonNullList(_) => true,
+        Type::Named(_) | Type::NonNullNamed(_) => false,
+    }
+}
+
+fn error_message(complexity: usize, max_complexity: usize) -> String {
+    format!(
+        "Operation has a complexity of {}, which exceeds the maximum complexity of {}",
+        complexity, max_complexity
+    )
+}
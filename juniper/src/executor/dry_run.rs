@@ -0,0 +1,191 @@
+use crate::{
+    ast::{Definition, Document, InputValue, Operation, OperationType, Selection},
+    executor::{complexity::is_list, Variables},
+    parser::Spanning,
+    schema::{meta::MetaType, model::SchemaType},
+    types::base::is_excluded,
+    util::ensure_sufficient_stack,
+    value::{DefaultScalarValue, ScalarValue},
+};
+
+/// A resolver call planned by [`RootNode::dry_run`](crate::RootNode::dry_run)
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlannedResolver<S = DefaultScalarValue> {
+    /// The name of the type the field is resolved on, which is the type
+    /// condition of the enclosing fragment for fields of abstract types
+    pub parent_type: String,
+    /// The name of the field
+    pub field_name: String,
+    /// The arguments passed to the resolver, with variables substituted and
+    /// default values applied to omitted arguments
+    pub arguments: Vec<(String, InputValue<S>)>,
+    /// The estimated number of calls, multiplied by the estimated size of
+    /// every enclosing list field
+    pub count: usize,
+}
+
+/// Lists the resolvers executing an operation would call, in the order the
+/// fields are selected.
+///
+/// List fields are assumed to hold as many items as their `list_size` hint,
+/// or `default_list_size` items without one. Introspection fields and selections excluded through
+/// `@skip` or `@include` are not listed.
+pub(crate) fn plan_operation<S>(
+    document: &Document<S>,
+    operation: &Spanning<Operation<S>>,
+    schema: &SchemaType<S>,
+    variables: &Variables<S>,
+    default_list_size: usize,
+) -> Vec<PlannedResolver<S>>
+where
+    S: ScalarValue,
+{
+    let root_type = match operation.item.operation_type {
+        OperationType::Query => Some(schema.concrete_query_type()),
+        OperationType::Mutation => schema.concrete_mutation_type(),
+        OperationType::Subscription => schema.concrete_subscription_type(),
+    };
+
+    let mut planner = Planner {
+        document,
+        schema,
+        variables,
+        default_list_size,
+        planned: vec![],
+    };
+    if let Some(root_type) = root_type {
+        planner.selection_set(&operation.item.selection_set, root_type, 1);
+    }
+    planner.planned
+}
+
+struct Planner<'a, S> {
+    document: &'a Document<'a, S>,
+    schema: &'a SchemaType<'a, S>,
+    variables: &'a Variables<S>,
+    default_list_size: usize,
+    planned: Vec<PlannedResolver<S>>,
+}
+
+impl<'a, S> Planner<'a, S>
+where
+    S: ScalarValue,
+{
+    fn selection_set(
+        &mut self,
+        selection_set: &[Selection<S>],
+        meta_type: &MetaType<S>,
+        count: usize,
+    ) {
+        ensure_sufficient_stack(|| self.selection_set_inner(selection_set, meta_type, count))
+    }
+
+    fn selection_set_inner(
+        &mut self,
+        selection_set: &[Selection<S>],
+        meta_type: &MetaType<S>,
+        count: usize,
+    ) {
+        for selection in selection_set {
+            match *selection {
+                Selection::Field(Spanning { item: ref f, .. }) => {
+                    if is_excluded(&f.directives, self.variables) || f.name.item.starts_with("__") {
+                        continue;
+                    }
+
+                    let meta_field = match meta_type.field_by_name(f.name.item) {
+                        Some(meta_field) => meta_field,
+                        None => continue,
+                    };
+
+                    let arguments = meta_field
+                        .arguments
+                        .iter()
+                        .flatten()
+                        .filter_map(|meta_arg| {
+                            let provided = f.arguments.as_ref().and_then(|args| {
+                                args.item
+                                    .items
+                                    .iter()
+                                    .find(|(name, _)| name.item == meta_arg.name)
+                                    .map(|(_, value)| value.item.clone().into_const(self.variables))
+                            });
+                            let value = match provided {
+                                Some(ref value) if !value.is_null() => provided,
+                                _ => meta_arg.default_value.clone().or(provided),
+                            };
+                            value.map(|value| (meta_arg.name.clone(), value))
+                        })
+                        .collect();
+
+                    self.planned.push(PlannedResolver {
+                        parent_type: meta_type.name().unwrap_or_default().to_owned(),
+                        field_name: f.name.item.to_owned(),
+                        arguments,
+                        count,
+                    });
+
+                    if let Some(ref selection_set) = f.selection_set {
+                        let sub_count = if is_list(&meta_field.field_type) {
+                            let list_size = meta_field
+                                .list_size
+                                .map_or(self.default_list_size, |size| size as usize);
+                            count.saturating_mul(list_size)
+                        } else {
+                            count
+                        };
+                        let schema = self.schema;
+                        if let Some(sub_type) =
+                            schema.concrete_type_by_name(meta_field.field_type.innermost_name())
+                        {
+                            self.selection_set(selection_set, sub_type, sub_count);
+                        }
+                    }
+                }
+                Selection::FragmentSpread(Spanning {
+                    item: ref spread, ..
+                }) => {
+                    if is_excluded(&spread.directives, self.variables) {
+                        continue;
+                    }
+
+                    let (document, schema) = (self.document, self.schema);
+                    let fragment = document.iter().find_map(|def| match *def {
+                        Definition::Fragment(Spanning { item: ref f, .. })
+                            if f.name.item == spread.name.item =>
+                        {
+                            Some(f)
+                        }
+                        _ => None,
+                    });
+                    if let Some(fragment) = fragment {
+                        if let Some(sub_type) =
+                            schema.concrete_type_by_name(fragment.type_condition.item)
+                        {
+                            self.selection_set(&fragment.selection_set, sub_type, count);
+                        }
+                    }
+                }
+                Selection::InlineFragment(Spanning {
+                    item: ref fragment, ..
+                }) => {
+                    if is_excluded(&fragment.directives, self.variables) {
+                        continue;
+                    }
+
+                    let sub_type = match fragment.type_condition {
+                        Some(ref type_condition) => {
+                            match self.schema.concrete_type_by_name(type_condition.item) {
+                                Some(sub_type) => sub_type,
+                                None => continue,
+                            }
+                        }
+                        None => meta_type,
+                    };
+
+                    self.selection_set(&fragment.selection_set, sub_type, count);
+                }
+            }
+        }
+    }
+}
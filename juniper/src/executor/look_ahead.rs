@@ -1,10 +1,10 @@
 use crate::{
     ast::{Directive, Fragment, InputValue, Selection},
     parser::Spanning,
-    value::ScalarValue,
+    value::{Object, ScalarValue, Value},
 };
 
-use std::collections::HashMap;
+use std::{borrow::Borrow, collections::HashMap, hash::Hash};
 
 use super::Variables;
 
@@ -319,6 +319,30 @@ where
             arguments: self.arguments.clone(),
         }
     }
+
+    /// Build the object selected from a generic row of column values
+    ///
+    /// Every selected field without children is looked up in `row` by its
+    /// name and written under its alias, if any. Fields that are not
+    /// selected, have a selection set of their own or have no matching
+    /// column are left out, so resolvers backed by dynamic data sources
+    /// don't need an arm per field.
+    pub fn select_row<K>(&self, row: &HashMap<K, Value<S>>) -> Value<S>
+    where
+        K: Borrow<str> + Hash + Eq,
+    {
+        let mut object = Object::with_capacity(self.children.len());
+        for child in &self.children {
+            let child = &child.inner;
+            if !child.children.is_empty() {
+                continue;
+            }
+            if let Some(value) = row.get(child.name) {
+                object.add_field(child.alias.unwrap_or(child.name), value.clone());
+            }
+        }
+        Value::Object(object)
+    }
 }
 
 /// A selection performed by a query on a concrete type
@@ -359,6 +383,9 @@ pub trait LookAheadMethods<S> {
 
     /// Get the top level children for the current selection
     fn child_names(&self) -> Vec<&str>;
+
+    /// Get the top level child selections for the current selection
+    fn children(&self) -> Vec<&Self>;
 }
 
 impl<'a, S> LookAheadMethods<S> for ConcreteLookAheadSelection<'a, S> {
@@ -381,6 +408,10 @@ impl<'a, S> LookAheadMethods<S> for ConcreteLookAheadSelection<'a, S> {
             .collect()
     }
 
+    fn children(&self) -> Vec<&Self> {
+        self.children.iter().collect()
+    }
+
     fn has_arguments(&self) -> bool {
         !self.arguments.is_empty()
     }
@@ -413,6 +444,10 @@ impl<'a, S> LookAheadMethods<S> for LookAheadSelection<'a, S> {
             .collect()
     }
 
+    fn children(&self) -> Vec<&Self> {
+        self.children.iter().map(|c| &c.inner).collect()
+    }
+
     fn has_arguments(&self) -> bool {
         !self.arguments.is_empty()
     }
@@ -1440,6 +1475,58 @@ fragment heroFriendNames on Hero {
         }
     }
 
+    #[test]
+    fn check_select_row() {
+        let docs = parse_document_source::<DefaultScalarValue>(
+            "
+query Hero {
+    hero {
+        id
+        heroName: name
+        friends {
+            name
+        }
+    }
+}
+",
+        )
+        .unwrap();
+        let fragments = extract_fragments(&docs);
+
+        if let crate::ast::Definition::Operation(ref op) = docs[0] {
+            let vars = Variables::default();
+            let look_ahead = LookAheadSelection::build_from_selection(
+                &op.item.selection_set[0],
+                &vars,
+                &fragments,
+            )
+            .unwrap();
+
+            let row = vec![
+                ("id", Value::scalar("1000")),
+                ("name", Value::scalar("Luke Skywalker")),
+                ("homePlanet", Value::scalar("Tatooine")),
+                ("friends", Value::scalar("1002,1003")),
+            ]
+            .into_iter()
+            .collect::<HashMap<_, _>>();
+
+            assert_eq!(
+                look_ahead.select_row(&row),
+                Value::object(
+                    vec![
+                        ("id", Value::scalar("1000")),
+                        ("heroName", Value::scalar("Luke Skywalker")),
+                    ]
+                    .into_iter()
+                    .collect()
+                ),
+            );
+        } else {
+            panic!("No Operation found");
+        }
+    }
+
     #[test]
     fn check_visitability() {
         let docs = parse_document_source::<DefaultScalarValue>(
@@ -2,16 +2,19 @@ use std::{
     borrow::Cow,
     cmp::Ordering,
     collections::HashMap,
-    fmt::{Debug, Display},
+    error::Error,
+    fmt::{self, Debug, Display},
+    future::Future,
     sync::{Arc, RwLock},
 };
 
-use fnv::FnvHashMap;
+use fnv::{FnvHashMap, FnvHashSet};
+use indexmap::IndexMap;
 
 use crate::{
     ast::{
-        Definition, Document, Fragment, FromInputValue, InputValue, Operation, OperationType,
-        Selection, ToInputValue, Type,
+        Definition, Directive, Document, Fragment, FromInputValue, InputValue, Operation,
+        OperationType, Selection, ToInputValue, Type,
     },
     parser::{SourcePosition, Spanning},
     schema::{
@@ -20,23 +23,48 @@ use crate::{
             InterfaceMeta, ListMeta, MetaType, NullableMeta, ObjectMeta, PlaceholderMeta,
             ScalarMeta, UnionMeta,
         },
-        model::{RootNode, SchemaType, TypeType},
+        model::{FieldNaming, RootNode, SchemaType, TypeType},
     },
-    types::{base::GraphQLType, name::Name},
-    value::{DefaultScalarValue, ParseScalarValue, ScalarValue, Value},
+    types::{
+        base::{Arguments, GraphQLType},
+        name::Name,
+        subscriptions::{BufferOverflow, BufferPressure, ConcurrencyLimit},
+    },
+    validation::{visit_fragment_cycles, ValidatorContext},
+    value::{DefaultScalarValue, Object, ParseScalarValue, ScalarValue, Value},
     GraphQLError,
 };
 
+#[cfg(test)]
+pub(crate) use self::complexity::operation_complexity;
+#[cfg(any(test, feature = "resolver-log"))]
+pub(crate) use self::resolver_log::ResolverLog;
+pub(crate) use self::{
+    cache_control::operation_cache_max_age,
+    complexity::validate_operation_complexity,
+    dry_run::plan_operation,
+    tracing::{FieldTimer, Tracer},
+};
 pub use self::{
+    dry_run::PlannedResolver,
     look_ahead::{
         Applies, ChildSelection, ConcreteLookAheadSelection, LookAheadArgument, LookAheadMethods,
         LookAheadSelection, LookAheadValue,
     },
     owned_executor::OwnedExecutor,
+    response_context::ResponseContext,
+    tracing::{FieldTiming, Tracing},
 };
 
+mod cache_control;
+mod complexity;
+mod dry_run;
 mod look_ahead;
 mod owned_executor;
+#[cfg(any(test, feature = "resolver-log"))]
+mod resolver_log;
+mod response_context;
+mod tracing;
 
 /// A type registry used to build schemas
 ///
@@ -46,12 +74,28 @@ mod owned_executor;
 pub struct Registry<'r, S = DefaultScalarValue> {
     /// Currently registered types
     pub types: FnvHashMap<Name, MetaType<'r, S>>,
+    type_sources: Option<TypeSources>,
+    /// Names of the types passed to `flattened_args` which are not input
+    /// objects.
+    unflattenable_types: Vec<String>,
+    pub(crate) field_naming: FieldNaming,
+}
+
+/// The Rust types registered under each type name, used to find names
+/// shared by several types.
+#[derive(Default)]
+struct TypeSources {
+    /// Rust types passed to `get_type`, whose meta type has been built.
+    seen: FnvHashSet<(String, &'static str)>,
+    /// Rust types which built a meta type, by type name.
+    built: FnvHashMap<String, Vec<&'static str>>,
 }
 
 #[derive(Clone)]
 pub enum FieldPath<'a> {
     Root(SourcePosition),
     Field(&'a str, SourcePosition, Arc<FieldPath<'a>>),
+    Index(usize, Arc<FieldPath<'a>>),
 }
 
 /// Query execution engine
@@ -72,6 +116,142 @@ where
     context: &'a CtxT,
     errors: &'r RwLock<Vec<ExecutionError<S>>>,
     field_path: Arc<FieldPath<'a>>,
+    field_authorizer: Option<FieldAuthorizer<'a, S>>,
+    field_directive_hooks: Option<FieldDirectiveHooks<'a, S>>,
+    tracer: Option<&'r Tracer>,
+    response_context: Option<&'r ResponseContext>,
+    buffer_pressure: BufferPressure,
+    concurrency_limit: Option<ConcurrencyLimit>,
+}
+
+/// Authorization hook bound to the context of an operation, called with the
+/// parent type and name of every field before it resolves
+pub(crate) type FieldAuthorizer<'a, S> =
+    Arc<dyn Fn(&str, &str) -> FieldResult<(), S> + Send + Sync + 'a>;
+
+/// Binds the field authorization hook of a schema to the context of an
+/// operation
+pub(crate) struct FieldAuthorizerFactory<CtxT, S>(
+    Box<dyn for<'c> Fn(&'c CtxT) -> FieldAuthorizer<'c, S> + Send + Sync>,
+);
+
+impl<CtxT, S> FieldAuthorizerFactory<CtxT, S> {
+    pub(crate) fn new<F>(hook: F) -> Self
+    where
+        F: Fn(&str, &str, &CtxT) -> FieldResult<(), S> + Send + Sync + 'static,
+        CtxT: Sync,
+    {
+        let hook = Arc::new(hook);
+        FieldAuthorizerFactory(Box::new(move |ctx| {
+            let hook = Arc::clone(&hook);
+            Arc::new(move |parent_type, field_name| hook(parent_type, field_name, ctx))
+        }))
+    }
+
+    pub(crate) fn bind<'c>(&self, ctx: &'c CtxT) -> FieldAuthorizer<'c, S> {
+        (self.0)(ctx)
+    }
+}
+
+impl<CtxT, S> Debug for FieldAuthorizerFactory<CtxT, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("FieldAuthorizerFactory")
+    }
+}
+
+/// Hook of a custom field directive bound to the context of an operation,
+/// called with the arguments of the directive and the resolved value of the
+/// field it is used on
+pub(crate) type FieldDirectiveHook<'a, S> =
+    Arc<dyn Fn(&Arguments<S>, Value<S>) -> FieldResult<Value<S>, S> + Send + Sync + 'a>;
+
+/// Guard of a custom field directive bound to the context of an operation,
+/// called with the arguments of the directive before the field it is used on
+/// is resolved
+pub(crate) type FieldDirectiveGuard<'a, S> =
+    Arc<dyn Fn(&Arguments<S>) -> FieldResult<(), S> + Send + Sync + 'a>;
+
+/// Hook or guard of a custom field directive bound to the context of an
+/// operation
+pub(crate) enum BoundFieldDirective<'a, S> {
+    Guard(FieldDirectiveGuard<'a, S>),
+    Hook(FieldDirectiveHook<'a, S>),
+}
+
+/// Hooks of the custom field directives of a schema, by directive name
+pub(crate) type FieldDirectiveHooks<'a, S> = Arc<FnvHashMap<String, BoundFieldDirective<'a, S>>>;
+
+/// Binds the custom field directive hooks of a schema to the context of an
+/// operation
+pub(crate) struct FieldDirectiveHooksFactory<CtxT, S>(
+    Vec<(String, FieldDirectiveHookBinder<CtxT, S>)>,
+);
+
+type FieldDirectiveHookBinder<CtxT, S> =
+    Box<dyn for<'c> Fn(&'c CtxT) -> BoundFieldDirective<'c, S> + Send + Sync>;
+
+impl<CtxT, S> Default for FieldDirectiveHooksFactory<CtxT, S> {
+    fn default() -> Self {
+        FieldDirectiveHooksFactory(Vec::new())
+    }
+}
+
+impl<CtxT, S> FieldDirectiveHooksFactory<CtxT, S> {
+    pub(crate) fn insert<F>(&mut self, name: &str, hook: F)
+    where
+        F: Fn(&Arguments<S>, Value<S>, &CtxT) -> FieldResult<Value<S>, S> + Send + Sync + 'static,
+        CtxT: Sync,
+    {
+        let hook = Arc::new(hook);
+        self.push(
+            name,
+            Box::new(move |ctx| {
+                let hook = Arc::clone(&hook);
+                BoundFieldDirective::Hook(Arc::new(move |args, value| hook(args, value, ctx)))
+            }),
+        );
+    }
+
+    pub(crate) fn insert_guard<F>(&mut self, name: &str, guard: F)
+    where
+        F: Fn(&Arguments<S>, &CtxT) -> FieldResult<(), S> + Send + Sync + 'static,
+        CtxT: Sync,
+    {
+        let guard = Arc::new(guard);
+        self.push(
+            name,
+            Box::new(move |ctx| {
+                let guard = Arc::clone(&guard);
+                BoundFieldDirective::Guard(Arc::new(move |args| guard(args, ctx)))
+            }),
+        );
+    }
+
+    fn push(&mut self, name: &str, bind: FieldDirectiveHookBinder<CtxT, S>) {
+        self.0.retain(|(n, _)| n != name);
+        self.0.push((name.to_owned(), bind));
+    }
+
+    pub(crate) fn bind<'c>(&self, ctx: &'c CtxT) -> Option<FieldDirectiveHooks<'c, S>> {
+        if self.0.is_empty() {
+            return None;
+        }
+
+        Some(Arc::new(
+            self.0
+                .iter()
+                .map(|(name, bind)| (name.clone(), bind(ctx)))
+                .collect(),
+        ))
+    }
+}
+
+impl<CtxT, S> Debug for FieldDirectiveHooksFactory<CtxT, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_list()
+            .entries(self.0.iter().map(|(name, _)| name))
+            .finish()
+    }
 }
 
 /// Error type for errors that occur during query execution
@@ -81,10 +261,52 @@ where
 #[derive(Debug, PartialEq)]
 pub struct ExecutionError<S> {
     location: SourcePosition,
-    path: Vec<String>,
+    path: Vec<PathSegment>,
     error: FieldError<S>,
 }
 
+/// A segment of the response path leading to the field an execution error
+/// occurred in
+///
+/// Serialized as a string for fields and as a number for list indices.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum PathSegment {
+    /// The response key of a field, i.e. its alias or name
+    Field(String),
+    /// The index of an item in a list
+    Index(usize),
+}
+
+impl<'a> From<&'a str> for PathSegment {
+    fn from(name: &'a str) -> PathSegment {
+        PathSegment::Field(name.to_owned())
+    }
+}
+
+impl From<usize> for PathSegment {
+    fn from(index: usize) -> PathSegment {
+        PathSegment::Index(index)
+    }
+}
+
+impl<'a> PartialEq<&'a str> for PathSegment {
+    fn eq(&self, other: &&'a str) -> bool {
+        match *self {
+            PathSegment::Field(ref name) => name == other,
+            PathSegment::Index(_) => false,
+        }
+    }
+}
+
+impl PartialEq<usize> for PathSegment {
+    fn eq(&self, other: &usize) -> bool {
+        match *self {
+            PathSegment::Field(_) => false,
+            PathSegment::Index(index) => index == *other,
+        }
+    }
+}
+
 impl<S> Eq for ExecutionError<S> where Self: PartialEq {}
 
 impl<S> ExecutionError<S> {
@@ -140,10 +362,20 @@ where
 ///     Ok(s)
 /// }
 /// ```
-#[derive(Debug, PartialEq)]
+#[derive(Debug)]
 pub struct FieldError<S = DefaultScalarValue> {
     message: String,
     extensions: Value<S>,
+    source: Option<Box<dyn Error + Send + Sync + 'static>>,
+}
+
+/// Compares the message and extensions of field errors
+///
+/// Their sources are ignored, as Rust errors can't be compared in general.
+impl<S: PartialEq> PartialEq for FieldError<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.message == other.message && self.extensions == other.extensions
+    }
 }
 
 impl<T: Display, S> From<T> for FieldError<S>
@@ -154,6 +386,7 @@ where
         FieldError {
             message: format!("{}", e),
             extensions: Value::null(),
+            source: None,
         }
     }
 }
@@ -199,9 +432,125 @@ impl<S> FieldError<S> {
         FieldError {
             message: format!("{}", e),
             extensions,
+            source: None,
         }
     }
 
+    /// Construct a new error with a machine-readable `code`
+    ///
+    /// The code is added as the `"code"` entry of the `"extensions"` of the
+    /// error object, so clients can branch on it instead of parsing messages:
+    ///
+    /// ```rust
+    /// # use juniper::DefaultScalarValue;
+    /// use juniper::FieldError;
+    ///
+    /// # let _: FieldError<DefaultScalarValue> =
+    /// FieldError::with_code("Rate limit exceeded", "RATE_LIMITED");
+    /// ```
+    ///
+    /// ```json
+    /// {
+    ///   "message": "Rate limit exceeded",
+    ///   "locations": [{"line": 2, "column": 4}],
+    ///   "path": ["search"],
+    ///   "extensions": {
+    ///     "code": "RATE_LIMITED"
+    ///   }
+    /// }
+    /// ```
+    pub fn with_code<T: Display>(e: T, code: &str) -> FieldError<S>
+    where
+        S: ScalarValue,
+    {
+        FieldError::with_extensions(
+            e,
+            Value::object(
+                vec![("code", Value::scalar(code.to_owned()))]
+                    .into_iter()
+                    .collect(),
+            ),
+        )
+    }
+
+    /// Construct a new error with structured data for clients
+    ///
+    /// The entries of the `extensions` object are added to the
+    /// `"extensions"` of the error object, as required by the GraphQL
+    /// specification. Use a `"code"` entry for the machine-readable kind of
+    /// the error, as [`with_code`](FieldError::with_code) does.
+    ///
+    /// ```rust
+    /// # use juniper::DefaultScalarValue;
+    /// use juniper::{graphql_value, FieldError};
+    ///
+    /// # let _: FieldError<DefaultScalarValue> =
+    /// FieldError::with_extensions(
+    ///     "Rate limit exceeded",
+    ///     graphql_value!({ "code": "RATE_LIMITED", "retryAfter": 30 }),
+    /// );
+    /// ```
+    ///
+    /// Any other value is wrapped as the `"value"` entry of the extensions,
+    /// so `graphql_value!("TOO_LARGE")` becomes `{"value": "TOO_LARGE"}`.
+    pub fn with_extensions<T: Display>(e: T, extensions: Value<S>) -> FieldError<S>
+    where
+        S: ScalarValue,
+    {
+        let extensions = match extensions {
+            Value::Object(_) => extensions,
+            value => {
+                let mut object = Object::with_capacity(1);
+                object.add_field("value", value);
+                Value::object(object)
+            }
+        };
+        FieldError::new(e, extensions)
+    }
+
+    /// Construct a new error from a Rust error, keeping it as the source of
+    /// the field error
+    ///
+    /// The message of the field error is the `Display` output of `e`. Unlike
+    /// the `From` conversion used by the `?` operator, `e` itself is retained,
+    /// so server-side code can walk its cause chain with
+    /// [`source_chain`](FieldError::source_chain). Sources are not serialized
+    /// into responses unless
+    /// [`GraphQLResponse::expose_error_sources`](crate::http::GraphQLResponse::expose_error_sources)
+    /// is used.
+    ///
+    /// ```rust
+    /// # use juniper::FieldResult;
+    /// use juniper::FieldError;
+    ///
+    /// fn parse_age(age: &str) -> FieldResult<i32> {
+    ///     Ok(age.parse().map_err(FieldError::from_error)?)
+    /// }
+    /// # fn main() {
+    /// # assert!(parse_age("x").unwrap_err().source().is_some());
+    /// # }
+    /// ```
+    pub fn from_error<E>(e: E) -> FieldError<S>
+    where
+        E: Error + Send + Sync + 'static,
+        S: ScalarValue,
+    {
+        FieldError {
+            message: format!("{}", e),
+            extensions: Value::null(),
+            source: Some(Box::new(e)),
+        }
+    }
+
+    /// Retain `source` as the cause of this error, for server-side logging
+    pub fn with_source<E>(mut self, source: E) -> FieldError<S>
+    where
+        E: Error + Send + Sync + 'static,
+    {
+        self.source = Some(Box::new(source));
+        self
+    }
+
     #[doc(hidden)]
     pub fn message(&self) -> &str {
         &self.message
@@ -211,11 +560,88 @@ impl<S> FieldError<S> {
     pub fn extensions(&self) -> &Value<S> {
         &self.extensions
     }
+
+    /// The Rust error this error was constructed from, if it was retained
+    pub fn source(&self) -> Option<&(dyn Error + Send + Sync + 'static)> {
+        self.source.as_deref()
+    }
+
+    /// Iterate over the retained source of this error and its causes, from
+    /// the outermost to the innermost one
+    pub fn source_chain(&self) -> impl Iterator<Item = &(dyn Error + 'static)> {
+        let mut next = self.source().map(|e| e as &(dyn Error + 'static));
+        std::iter::from_fn(move || {
+            let current = next?;
+            next = current.source();
+            Some(current)
+        })
+    }
+
+    /// Add the messages of the source chain of this error to its extensions,
+    /// as the `"sources"` entry
+    pub(crate) fn expose_source_chain(&mut self)
+    where
+        S: ScalarValue,
+    {
+        let sources = self
+            .source_chain()
+            .map(|e| Value::scalar(e.to_string()))
+            .collect::<Vec<_>>();
+        if sources.is_empty() {
+            return;
+        }
+
+        let mut object = match std::mem::replace(&mut self.extensions, Value::null()) {
+            Value::Object(object) => object,
+            Value::Null => Object::with_capacity(1),
+            value => {
+                let mut object = Object::with_capacity(2);
+                object.add_field("value", value);
+                object
+            }
+        };
+        object.add_field("sources", Value::list(sources));
+        self.extensions = Value::object(object);
+    }
 }
 
 /// The result of resolving the value of a field of type `T`
 pub type FieldResult<T, S = DefaultScalarValue> = Result<T, FieldError<S>>;
 
+/// The result of looking up a single object of type `T`
+///
+/// A field returning `Lookup<T>` has the nullable type of `T`. `Found` resolves
+/// to the object, while `NotFound` resolves to `null` and adds an error with a
+/// `NOT_FOUND` code to the response, which clients can tell apart from other
+/// errors:
+///
+/// ```json
+/// {
+///   "message": "Not found",
+///   "locations": [{"line": 2, "column": 4}],
+///   "path": ["human"],
+///   "extensions": {
+///     "code": "NOT_FOUND"
+///   }
+/// }
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub enum Lookup<T> {
+    /// The object was found
+    Found(T),
+    /// No object exists for the lookup
+    NotFound,
+}
+
+impl<T> From<Option<T>> for Lookup<T> {
+    fn from(value: Option<T>) -> Lookup<T> {
+        match value {
+            Some(value) => Lookup::Found(value),
+            None => Lookup::NotFound,
+        }
+    }
+}
+
 /// The result of resolving an unspecified field
 pub type ExecutionResult<S = DefaultScalarValue> = Result<Value<S>, FieldError<S>>;
 
@@ -273,6 +699,20 @@ where
     }
 }
 
+impl<'a, S, T, C> IntoResolvable<'a, S, Option<T>, C> for Lookup<T>
+where
+    S: ScalarValue,
+    T: GraphQLType<S>,
+    T::Context: FromContext<C>,
+{
+    fn into(self, ctx: &'a C) -> FieldResult<Option<(&'a T::Context, Option<T>)>, S> {
+        match self {
+            Lookup::Found(v) => Ok(Some((FromContext::from(ctx), Some(v)))),
+            Lookup::NotFound => Err(FieldError::with_code("Not found", "NOT_FOUND")),
+        }
+    }
+}
+
 impl<'a, S, T, C> IntoResolvable<'a, S, T, C> for (&'a T::Context, T)
 where
     S: ScalarValue,
@@ -500,9 +940,39 @@ where
             context: ctx,
             errors: self.errors,
             field_path: self.field_path.clone(),
+            field_authorizer: self.field_authorizer.clone(),
+            field_directive_hooks: self.field_directive_hooks.clone(),
+            tracer: self.tracer,
+            response_context: self.response_context,
+            buffer_pressure: self.buffer_pressure.clone(),
+            concurrency_limit: self.concurrency_limit.clone(),
         }
     }
 
+    /// Derive a new executor resolving with a narrowed context of the same
+    /// type
+    ///
+    /// This lets a resolver scope the context of a child, e.g. to a single
+    /// tenant, without the child knowing about it. The narrowed context only
+    /// needs to outlive the returned executor, so it can be built inside the
+    /// resolver. Bind the executor to a local declared after the context, so
+    /// that it is dropped first:
+    ///
+    /// ```rust,ignore
+    /// "tenant" => {
+    ///     let scoped = executor.context().for_tenant(&args.get::<String>("id").unwrap());
+    ///     let executor = executor.with_context(&scoped);
+    ///     executor.resolve(info, &Tenant)
+    /// }
+    /// ```
+    ///
+    /// Errors are still reported to the original executor. Use
+    /// [`replaced_context`](Executor::replaced_context) to switch to a context
+    /// of another type.
+    pub fn with_context<'b>(&'b self, ctx: &'b CtxT) -> Executor<'b, 'b, CtxT, S> {
+        self.replaced_context(ctx)
+    }
+
     #[doc(hidden)]
     pub fn field_sub_executor<'s>(
         &'s self,
@@ -532,6 +1002,33 @@ where
                 location,
                 Arc::clone(&self.field_path),
             )),
+            field_authorizer: self.field_authorizer.clone(),
+            field_directive_hooks: self.field_directive_hooks.clone(),
+            tracer: self.tracer,
+            response_context: self.response_context,
+            buffer_pressure: self.buffer_pressure.clone(),
+            concurrency_limit: self.concurrency_limit.clone(),
+        }
+    }
+
+    /// Executor for the item at `index` of the list being resolved
+    pub(crate) fn index_sub_executor<'s>(&'s self, index: usize) -> Executor<'s, 'a, CtxT, S> {
+        Executor {
+            fragments: self.fragments,
+            variables: self.variables,
+            current_selection_set: self.current_selection_set,
+            parent_selection_set: self.parent_selection_set,
+            current_type: self.current_type.clone(),
+            schema: self.schema,
+            context: self.context,
+            errors: self.errors,
+            field_path: Arc::new(FieldPath::Index(index, Arc::clone(&self.field_path))),
+            field_authorizer: self.field_authorizer.clone(),
+            field_directive_hooks: self.field_directive_hooks.clone(),
+            tracer: self.tracer,
+            response_context: self.response_context,
+            buffer_pressure: self.buffer_pressure.clone(),
+            concurrency_limit: self.concurrency_limit.clone(),
         }
     }
 
@@ -554,6 +1051,12 @@ where
             context: self.context,
             errors: self.errors,
             field_path: self.field_path.clone(),
+            field_authorizer: self.field_authorizer.clone(),
+            field_directive_hooks: self.field_directive_hooks.clone(),
+            tracer: self.tracer,
+            response_context: self.response_context,
+            buffer_pressure: self.buffer_pressure.clone(),
+            concurrency_limit: self.concurrency_limit.clone(),
         }
     }
 
@@ -575,6 +1078,20 @@ where
         self.schema
     }
 
+    /// The name of a field of `type_name` derived from its Rust identifier,
+    /// following the field naming policy of the schema
+    #[doc(hidden)]
+    pub fn derived_field_name<'n>(
+        &self,
+        type_name: &str,
+        camel_case: &'n str,
+        snake_case: &'n str,
+    ) -> &'n str {
+        self.schema
+            .field_naming
+            .derived_name(type_name, camel_case, snake_case)
+    }
+
     #[doc(hidden)]
     pub fn current_type(&self) -> &TypeType<'a, S> {
         &self.current_type
@@ -590,6 +1107,18 @@ where
         self.fragments.get(name)
     }
 
+    /// Whether the selection set of `type_name` resolved here is the root of
+    /// a mutation, whose fields must execute one after another
+    pub(crate) fn is_mutation_root(&self, type_name: &str) -> bool {
+        match *self.field_path {
+            FieldPath::Root(_) => self
+                .schema
+                .concrete_mutation_type()
+                .map_or(false, |t| t.name() == Some(type_name)),
+            _ => false,
+        }
+    }
+
     /// The current location of the executor
     pub fn location(&self) -> &SourcePosition {
         self.field_path.location()
@@ -600,6 +1129,20 @@ where
         self.push_error_at(error, self.location().clone());
     }
 
+    /// Add several errors to the execution engine at the current executor
+    /// location
+    ///
+    /// Lets a resolver report every problem it found, e.g. with the items of
+    /// a bulk mutation, while still returning a partial value.
+    pub fn push_errors<I>(&self, errors: I)
+    where
+        I: IntoIterator<Item = FieldError<S>>,
+    {
+        for error in errors {
+            self.push_error(error);
+        }
+    }
+
     /// Add an error to the execution engine at a specific location
     pub fn push_error_at(&self, error: FieldError<S>, location: SourcePosition) {
         let mut path = Vec::new();
@@ -614,6 +1157,249 @@ where
         });
     }
 
+    /// Add an error for a selected field the current type does not define,
+    /// which only reaches execution when a document skipped validation
+    pub(crate) fn push_unknown_field_error(
+        &self,
+        type_name: &str,
+        field_alias: &str,
+        field_name: &str,
+        location: SourcePosition,
+    ) {
+        let mut path = Vec::new();
+        self.field_path.construct_path(&mut path);
+        path.push(PathSegment::Field(field_alias.to_owned()));
+
+        let mut errors = self.errors.write().unwrap();
+
+        errors.push(ExecutionError {
+            location,
+            path,
+            error: FieldError::new(
+                format!(r#"Unknown field "{}" on type "{}""#, field_name, type_name),
+                Value::null(),
+            ),
+        });
+    }
+
+    /// Runs the field authorization hook of the schema, if any, for a field
+    /// about to be resolved
+    #[doc(hidden)]
+    pub fn authorize_field(&self, parent_type: &str, field_name: &str) -> FieldResult<(), S> {
+        match self.field_authorizer {
+            Some(ref authorize) => authorize(parent_type, field_name),
+            None => Ok(()),
+        }
+    }
+
+    /// Runs the guards of the custom directives used on the field being
+    /// resolved, before its resolver is called
+    pub(crate) fn guard_field_directives(
+        &self,
+        directives: &Option<Vec<Spanning<Directive<S>>>>,
+    ) -> FieldResult<(), S> {
+        self.each_field_directive(directives, |directive, args| match directive {
+            BoundFieldDirective::Guard(guard) => guard(args),
+            BoundFieldDirective::Hook(_) => Ok(()),
+        })
+    }
+
+    /// Runs the hooks of the custom directives used on a field, in order, on
+    /// its resolved value
+    #[doc(hidden)]
+    pub fn apply_field_directives(
+        &self,
+        directives: &Option<Vec<Spanning<Directive<S>>>>,
+        mut value: Value<S>,
+    ) -> FieldResult<Value<S>, S> {
+        self.each_field_directive(directives, |directive, args| {
+            if let BoundFieldDirective::Hook(hook) = directive {
+                value = hook(args, std::mem::replace(&mut value, Value::null()))?;
+            }
+            Ok(())
+        })?;
+
+        Ok(value)
+    }
+
+    /// Runs the hooks of the custom directives used on the subscription field
+    /// being resolved on every event of its stream
+    pub(crate) fn apply_field_directives_to_stream<'res>(
+        &self,
+        directives: &Option<Vec<Spanning<Directive<S>>>>,
+        value: Value<ValuesStream<'res, S>>,
+    ) -> Value<ValuesStream<'res, S>>
+    where
+        'a: 'res,
+        S: Send + Sync,
+    {
+        let stream = match value {
+            Value::Scalar(stream) => stream,
+            value => return value,
+        };
+
+        let mut hooks = Vec::new();
+        if let (Some(bound), Some(directives)) = (&self.field_directive_hooks, directives) {
+            for directive in directives {
+                let name = directive.item.name.item;
+                if let Some(BoundFieldDirective::Hook(hook)) = bound.get(name) {
+                    hooks.push((
+                        Arc::clone(hook),
+                        self.field_directive_arguments(&directive.item).map(|args| {
+                            args.into_iter()
+                                .map(|(k, v)| (k.to_owned(), v))
+                                .collect::<Vec<_>>()
+                        }),
+                        self.schema
+                            .directive_by_name(name)
+                            .map(|d| d.arguments.clone()),
+                    ));
+                }
+            }
+        }
+        if hooks.is_empty() {
+            return Value::Scalar(stream);
+        }
+
+        let schema = self.schema;
+        let location = *self.location();
+        let mut path = Vec::new();
+        self.field_path.construct_path(&mut path);
+
+        Value::Scalar(Box::pin(futures::StreamExt::map(stream, move |event| {
+            hooks.iter().fold(event, |event, (hook, args, meta_args)| {
+                let value = event?;
+                let args = Arguments::new(
+                    args.as_ref()
+                        .map(|args| args.iter().map(|(k, v)| (k.as_str(), v.clone())).collect()),
+                    meta_args,
+                    schema,
+                );
+                hook(&args, value).map_err(|error| ExecutionError {
+                    location,
+                    path: path.clone(),
+                    error,
+                })
+            })
+        })))
+    }
+
+    fn each_field_directive<F>(
+        &self,
+        directives: &Option<Vec<Spanning<Directive<S>>>>,
+        mut f: F,
+    ) -> FieldResult<(), S>
+    where
+        F: FnMut(&BoundFieldDirective<'a, S>, &Arguments<S>) -> FieldResult<(), S>,
+    {
+        let (bound, directives) = match (&self.field_directive_hooks, directives) {
+            (Some(bound), Some(directives)) => (bound, directives),
+            _ => return Ok(()),
+        };
+
+        for directive in directives {
+            let name = directive.item.name.item;
+            let bound = match bound.get(name) {
+                Some(bound) => bound,
+                None => continue,
+            };
+
+            let meta_args = self
+                .schema
+                .directive_by_name(name)
+                .map(|d| d.arguments.clone());
+            let args = Arguments::new(
+                self.field_directive_arguments(&directive.item),
+                &meta_args,
+                self.schema,
+            );
+            f(bound, &args)?;
+        }
+
+        Ok(())
+    }
+
+    fn field_directive_arguments<'d>(
+        &self,
+        directive: &'d Directive<S>,
+    ) -> Option<IndexMap<&'d str, InputValue<S>>> {
+        directive.arguments.as_ref().map(|m| {
+            m.item
+                .iter()
+                .map(|&(ref k, ref v)| (k.item, v.item.clone().into_const(self.variables)))
+                .collect()
+        })
+    }
+
+    /// Starts timing the field being resolved, if the execution is traced
+    pub(crate) fn field_timer(
+        &self,
+        parent_type: &str,
+        field: &Field<S>,
+    ) -> Option<FieldTimer<'r>> {
+        self.tracer.map(|tracer| {
+            let mut path = Vec::new();
+            self.field_path.construct_path(&mut path);
+            tracer.time_field(path, parent_type, &field.name, field.field_type.to_string())
+        })
+    }
+
+    /// Passes the field being resolved to the resolver log of the schema, if
+    /// it is slow or failed
+    #[cfg(any(test, feature = "resolver-log"))]
+    pub(crate) fn log_resolver(
+        &self,
+        duration: std::time::Duration,
+        error: Option<&FieldError<S>>,
+    ) {
+        if let Some(ref log) = self.schema.resolver_log {
+            log.record(&self.field_path, duration, error);
+        }
+    }
+
+    /// How many events of the subscribed field being resolved are waiting in
+    /// its buffer to be taken by the client
+    ///
+    /// The returned handle can be kept by the producer of the field's stream
+    /// to skip or coalesce events while the client falls behind. It always
+    /// reads `0` outside of subscriptions and unless the schema sets
+    /// [`RootNode::subscription_buffer_size`].
+    pub fn buffer_pressure(&self) -> BufferPressure {
+        self.buffer_pressure.clone()
+    }
+
+    /// Lower the cache hint of the response to `max_age` seconds
+    ///
+    /// The response keeps the lowest cache hint set by its resolvers or
+    /// declared by its fields, see
+    /// [`GraphQLResponse::cache_max_age`](crate::http::GraphQLResponse::cache_max_age).
+    /// Ignored outside of queries and mutations.
+    pub fn set_cache_max_age(&self, max_age: u32) {
+        if let Some(response_context) = self.response_context {
+            response_context.lower_cache_max_age(max_age);
+        }
+    }
+
+    /// Suggest an HTTP header for the response
+    ///
+    /// The headers are exposed by
+    /// [`GraphQLResponse::response_context`](crate::http::GraphQLResponse::response_context)
+    /// for integrations to apply. Ignored outside of queries and mutations.
+    pub fn add_response_header<N, V>(&self, name: N, value: V)
+    where
+        N: Into<String>,
+        V: Into<String>,
+    {
+        if let Some(response_context) = self.response_context {
+            response_context.add_header(name.into(), value.into());
+        }
+    }
+
+    pub(crate) fn with_buffer_pressure(mut self, buffer_pressure: BufferPressure) -> Self {
+        self.buffer_pressure = buffer_pressure;
+        self
+    }
+
     /// Returns new [`ExecutionError`] at current location
     pub fn new_error(&self, error: FieldError<S>) -> ExecutionError<S> {
         let mut path = Vec::new();
@@ -626,15 +1412,52 @@ where
         }
     }
 
+    /// Turn the result of resolving an event of a subscription stream into an
+    /// item of its `ValuesStream`
+    ///
+    /// An event resolving to `null` because one of its fields failed becomes
+    /// the first error pushed while resolving it, instead of a bare `null`.
+    ///
+    /// `resolve` waits for a slot of the
+    /// [`RootNode::subscription_concurrency_limit`] shared by all the fields
+    /// of the subscription, so each tick resolves at most that many events at
+    /// once.
+    #[doc(hidden)]
+    pub async fn stream_item<F>(&self, resolve: F) -> Result<Value<S>, ExecutionError<S>>
+    where
+        F: Future<Output = ExecutionResult<S>>,
+    {
+        let result = match self.concurrency_limit {
+            Some(ref limit) => {
+                let _permit = limit.acquire().await;
+                resolve.await
+            }
+            None => resolve.await,
+        };
+        match result {
+            Ok(Value::Null) => {
+                let mut errors = self.errors.write().unwrap();
+                if errors.is_empty() {
+                    Ok(Value::Null)
+                } else {
+                    errors.sort();
+                    Err(errors.remove(0))
+                }
+            }
+            Ok(value) => Ok(value),
+            Err(e) => Err(self.new_error(e)),
+        }
+    }
+
     /// Construct a lookahead selection for the current selection.
     ///
     /// This allows seeing the whole selection and perform operations
     /// affecting the children.
     pub fn look_ahead(&'a self) -> LookAheadSelection<'a, S> {
-        let field_name = match *self.field_path {
-            FieldPath::Field(x, ..) => x,
-            FieldPath::Root(_) => unreachable!(),
-        };
+        let field_name = self
+            .field_path
+            .field_name()
+            .expect("No field is being resolved");
         self.parent_selection_set
             .map(|p| {
                 let found_field = p.iter().find(|&x| {
@@ -700,17 +1523,25 @@ where
             context: self.context,
             errors: RwLock::new(vec![]),
             field_path: Arc::clone(&self.field_path),
+            field_authorizer: self.field_authorizer.clone(),
+            field_directive_hooks: self.field_directive_hooks.clone(),
+            buffer_pressure: self.buffer_pressure.clone(),
+            concurrency_limit: self.concurrency_limit.clone(),
         }
     }
 }
 
 impl<'a> FieldPath<'a> {
-    fn construct_path(&self, acc: &mut Vec<String>) {
+    fn construct_path(&self, acc: &mut Vec<PathSegment>) {
         match self {
             FieldPath::Root(_) => (),
             FieldPath::Field(name, _, parent) => {
                 parent.construct_path(acc);
-                acc.push((*name).to_owned());
+                acc.push(PathSegment::Field((*name).to_owned()));
+            }
+            FieldPath::Index(index, parent) => {
+                parent.construct_path(acc);
+                acc.push(PathSegment::Index(*index));
             }
         }
     }
@@ -718,16 +1549,28 @@ impl<'a> FieldPath<'a> {
     fn location(&self) -> &SourcePosition {
         match *self {
             FieldPath::Root(ref pos) | FieldPath::Field(_, ref pos, _) => pos,
+            FieldPath::Index(_, ref parent) => parent.location(),
+        }
+    }
+
+    fn field_name(&self) -> Option<&'a str> {
+        match *self {
+            FieldPath::Root(_) => None,
+            FieldPath::Field(name, ..) => Some(name),
+            FieldPath::Index(_, ref parent) => parent.field_name(),
         }
     }
 }
 
 impl<S> ExecutionError<S> {
     #[doc(hidden)]
-    pub fn new(location: SourcePosition, path: &[&str], error: FieldError<S>) -> ExecutionError<S> {
+    pub fn new<P>(location: SourcePosition, path: &[P], error: FieldError<S>) -> ExecutionError<S>
+    where
+        P: Clone + Into<PathSegment>,
+    {
         ExecutionError {
             location,
-            path: path.iter().map(|s| (*s).to_owned()).collect(),
+            path: path.iter().cloned().map(Into::into).collect(),
             error,
         }
     }
@@ -737,19 +1580,26 @@ impl<S> ExecutionError<S> {
         &self.error
     }
 
+    pub(crate) fn error_mut(&mut self) -> &mut FieldError<S> {
+        &mut self.error
+    }
+
     /// The source location _in the query_ of the field that failed to resolve
     pub fn location(&self) -> &SourcePosition {
         &self.location
     }
 
-    /// The path of fields leading to the field that generated this error
-    pub fn path(&self) -> &[String] {
+    /// The response path leading to the field that generated this error: the
+    /// response keys of the enclosing fields and the indices of the enclosing
+    /// list items
+    pub fn path(&self) -> &[PathSegment] {
         &self.path
     }
 }
 
 /// Create new `Executor` and start query/mutation execution.
 /// Returns `IsSubscription` error if subscription is passed.
+#[allow(dead_code)]
 pub fn execute_validated_query<'a, 'b, QueryT, MutationT, SubscriptionT, CtxT, S>(
     document: &'b Document<S>,
     operation: &'b Spanning<Operation<S>>,
@@ -757,6 +1607,41 @@ pub fn execute_validated_query<'a, 'b, QueryT, MutationT, SubscriptionT, CtxT, S
     variables: &Variables<S>,
     context: &CtxT,
 ) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    S: ScalarValue,
+    QueryT: GraphQLType<S, Context = CtxT>,
+    MutationT: GraphQLType<S, Context = CtxT>,
+    SubscriptionT: GraphQLType<S, Context = CtxT>,
+{
+    execute_validated_query_with_response_context(
+        document,
+        operation,
+        root_node,
+        variables,
+        context,
+        &ResponseContext::default(),
+    )
+}
+
+/// Create new `Executor` and start query/mutation execution, collecting the
+/// cache hints and headers set by resolvers into `response_context`.
+/// Returns `IsSubscription` error if subscription is passed.
+pub fn execute_validated_query_with_response_context<
+    'a,
+    'b,
+    QueryT,
+    MutationT,
+    SubscriptionT,
+    CtxT,
+    S,
+>(
+    document: &'b Document<S>,
+    operation: &'b Spanning<Operation<S>>,
+    root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &CtxT,
+    response_context: &ResponseContext,
+) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
 where
     S: ScalarValue,
     QueryT: GraphQLType<S, Context = CtxT>,
@@ -767,6 +1652,8 @@ where
         return Err(GraphQLError::IsSubscription);
     }
 
+    reject_fragment_cycles(&root_node.schema, document)?;
+
     let mut fragments = vec![];
     for def in document.iter() {
         if let Definition::Fragment(f) = def {
@@ -825,6 +1712,15 @@ where
             context,
             errors: &errors,
             field_path: Arc::new(FieldPath::Root(operation.start)),
+            field_authorizer: root_node
+                .field_authorizer
+                .as_ref()
+                .map(|factory| factory.bind(context)),
+            field_directive_hooks: root_node.field_directive_hooks.bind(context),
+            tracer: None,
+            response_context: Some(response_context),
+            buffer_pressure: BufferPressure::default(),
+            concurrency_limit: None,
         };
 
         value = match operation.item.operation_type {
@@ -844,6 +1740,7 @@ where
 
 /// Create new `Executor` and start asynchronous query execution.
 /// Returns `IsSubscription` error if subscription is passed.
+#[allow(dead_code)]
 pub async fn execute_validated_query_async<'a, 'b, QueryT, MutationT, SubscriptionT, CtxT, S>(
     document: &'b Document<'a, S>,
     operation: &'b Spanning<Operation<'_, S>>,
@@ -851,6 +1748,50 @@ pub async fn execute_validated_query_async<'a, 'b, QueryT, MutationT, Subscripti
     variables: &Variables<S>,
     context: &CtxT,
 ) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    S: ScalarValue + Send + Sync,
+    QueryT: crate::GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: crate::GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLType<S, Context = CtxT> + Send + Sync,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Send + Sync,
+{
+    execute_validated_query_async_with_response_context(
+        document,
+        operation,
+        root_node,
+        variables,
+        context,
+        &ResponseContext::default(),
+    )
+    .await
+    .map(|(value, errors, _)| (value, errors))
+}
+
+/// Create new `Executor` and start asynchronous query execution, collecting
+/// the cache hints and headers set by resolvers into `response_context` and
+/// returning the field timings of the execution if the schema enables
+/// tracing.
+/// Returns `IsSubscription` error if subscription is passed.
+#[allow(clippy::type_complexity)]
+pub async fn execute_validated_query_async_with_response_context<
+    'a,
+    'b,
+    QueryT,
+    MutationT,
+    SubscriptionT,
+    CtxT,
+    S,
+>(
+    document: &'b Document<'a, S>,
+    operation: &'b Spanning<Operation<'_, S>>,
+    root_node: &RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &CtxT,
+    response_context: &ResponseContext,
+) -> Result<(Value<S>, Vec<ExecutionError<S>>, Option<Tracing>), GraphQLError<'a>>
 where
     S: ScalarValue + Send + Sync,
     QueryT: crate::GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
@@ -865,6 +1806,8 @@ where
         return Err(GraphQLError::IsSubscription);
     }
 
+    reject_fragment_cycles(&root_node.schema, document)?;
+
     let mut fragments = vec![];
     for def in document.iter() {
         if let Definition::Fragment(f) = def {
@@ -885,6 +1828,11 @@ where
     });
 
     let errors = RwLock::new(Vec::new());
+    let tracer = if root_node.schema.tracing_enabled {
+        Some(Tracer::new())
+    } else {
+        None
+    };
     let value;
 
     {
@@ -923,6 +1871,15 @@ where
             context,
             errors: &errors,
             field_path: Arc::new(FieldPath::Root(operation.start)),
+            field_authorizer: root_node
+                .field_authorizer
+                .as_ref()
+                .map(|factory| factory.bind(context)),
+            field_directive_hooks: root_node.field_directive_hooks.bind(context),
+            tracer: tracer.as_ref(),
+            response_context: Some(response_context),
+            buffer_pressure: BufferPressure::default(),
+            concurrency_limit: None,
         };
 
         value = match operation.item.operation_type {
@@ -943,7 +1900,7 @@ where
     let mut errors = errors.into_inner().unwrap();
     errors.sort();
 
-    Ok((value, errors))
+    Ok((value, errors, tracer.map(Tracer::finish)))
 }
 
 pub fn get_operation<'b, 'd, 'e, S>(
@@ -968,11 +1925,37 @@ where
             }
         };
     }
-    let op = match operation {
-        Some(op) => op,
-        None => return Err(GraphQLError::UnknownOperationName),
-    };
-    Ok(op)
+    match (operation, operation_name) {
+        (Some(op), _) => Ok(op),
+        (None, Some(name)) => Err(GraphQLError::UnknownOperationName(name.to_owned())),
+        (None, None) => Err(GraphQLError::NoOperationProvided),
+    }
+}
+
+/// The variables of an operation, with the default values of its variable
+/// definitions filled in for the variables that were not provided
+pub(crate) fn variables_with_defaults<'v, S>(
+    operation: &Spanning<Operation<S>>,
+    variables: &'v Variables<S>,
+) -> Cow<'v, Variables<S>>
+where
+    S: ScalarValue,
+{
+    let mut all_vars = Cow::Borrowed(variables);
+
+    if let Some(ref defs) = operation.item.variable_definitions {
+        for &(ref name, ref def) in defs.item.iter() {
+            if let Some(ref default) = def.default_value {
+                if !all_vars.contains_key(name.item) {
+                    all_vars
+                        .to_mut()
+                        .insert(name.item.to_owned(), default.item.clone());
+                }
+            }
+        }
+    }
+
+    all_vars
 }
 
 /// Initialize new `Executor` and start resolving subscription into stream
@@ -1012,6 +1995,8 @@ where
         return Err(GraphQLError::NotSubscription);
     }
 
+    reject_fragment_cycles(&root_node.schema, document)?;
+
     let mut fragments = vec![];
     for def in document.iter() {
         match def {
@@ -1070,6 +2055,18 @@ where
             context,
             errors: &errors,
             field_path: Arc::new(FieldPath::Root(operation.start)),
+            field_authorizer: root_node
+                .field_authorizer
+                .as_ref()
+                .map(|factory| factory.bind(context)),
+            field_directive_hooks: root_node.field_directive_hooks.bind(context),
+            tracer: None,
+            response_context: None,
+            buffer_pressure: BufferPressure::default(),
+            concurrency_limit: root_node
+                .schema
+                .subscription_concurrency_limit
+                .map(ConcurrencyLimit::new),
         };
 
         value = match operation.item.operation_type {
@@ -1094,7 +2091,77 @@ where
 {
     /// Construct a new registry
     pub fn new(types: FnvHashMap<Name, MetaType<'r, S>>) -> Registry<'r, S> {
-        Registry { types }
+        Registry {
+            types,
+            type_sources: None,
+            unflattenable_types: vec![],
+            field_naming: FieldNaming::default(),
+        }
+    }
+
+    /// The name of a field of `type_name` derived from its Rust identifier,
+    /// following the field naming policy of the schema
+    #[doc(hidden)]
+    pub fn derived_field_name<'n>(
+        &self,
+        type_name: &str,
+        camel_case: &'n str,
+        snake_case: &'n str,
+    ) -> &'n str {
+        self.field_naming
+            .derived_name(type_name, camel_case, snake_case)
+    }
+
+    /// Track the Rust types registered under each type name, so that
+    /// [`duplicate_type_names`](Self::duplicate_type_names) can find names
+    /// shared by several types.
+    ///
+    /// This builds the meta type of every distinct Rust type passed to
+    /// [`get_type`](Self::get_type), even if its name is already registered.
+    pub(crate) fn track_type_sources(&mut self) {
+        self.type_sources = Some(TypeSources::default());
+    }
+
+    /// Type names whose meta type was built by more than one Rust type,
+    /// along with these Rust types.
+    pub(crate) fn duplicate_type_names(&self) -> Vec<(&str, &[&'static str])> {
+        let mut duplicates = self
+            .type_sources
+            .iter()
+            .flat_map(|sources| sources.built.iter())
+            .filter(|(_, rust_types)| rust_types.len() > 1)
+            .map(|(name, rust_types)| (name.as_str(), rust_types.as_slice()))
+            .collect::<Vec<_>>();
+        duplicates.sort();
+        duplicates
+    }
+
+    /// Names of the types which were flattened into arguments without being
+    /// input objects.
+    pub(crate) fn unflattenable_types(&self) -> &[String] {
+        &self.unflattenable_types
+    }
+
+    /// Returns whether the Rust type `T` hasn't been seen under `name` yet,
+    /// marking it as seen.
+    fn see_type_source<T>(&mut self, name: &str) -> bool {
+        match self.type_sources {
+            Some(ref mut sources) => sources
+                .seen
+                .insert((name.to_owned(), std::any::type_name::<T>())),
+            None => false,
+        }
+    }
+
+    /// Records the Rust type `T` as building the meta type named `name`.
+    fn record_type_source<T>(&mut self, name: &str) {
+        if let Some(ref mut sources) = self.type_sources {
+            let rust_types = sources.built.entry(name.to_owned()).or_default();
+            let rust_type = std::any::type_name::<T>();
+            if !rust_types.contains(&rust_type) {
+                rust_types.push(rust_type);
+            }
+        }
     }
 
     /// Get the `Type` instance for a given GraphQL type
@@ -1108,12 +2175,18 @@ where
         if let Some(name) = T::name(info) {
             let validated_name = name.parse::<Name>().unwrap();
             if !self.types.contains_key(name) {
+                self.see_type_source::<T>(name);
                 self.insert_placeholder(
                     validated_name.clone(),
                     Type::NonNullNamed(Cow::Owned(name.to_string())),
                 );
                 let meta = T::meta(info, self);
                 self.types.insert(validated_name, meta);
+            } else if self.see_type_source::<T>(name) {
+                // Build the meta type of this Rust type as well, to record
+                // which type builds it, as it may be a different type using
+                // the same name rather than e.g. a reference to it.
+                T::meta(info, self);
             }
             self.types[name].as_type()
         } else {
@@ -1132,6 +2205,11 @@ where
             arguments: None,
             field_type: self.get_type::<T>(info),
             deprecation_status: DeprecationStatus::Current,
+            cache_max_age: None,
+            complexity: None,
+            list_size: None,
+            buffer_size: None,
+            buffer_overflow: BufferOverflow::Wait,
         }
     }
 
@@ -1150,6 +2228,11 @@ where
             arguments: None,
             field_type: self.get_type::<I>(info),
             deprecation_status: DeprecationStatus::Current,
+            cache_max_age: None,
+            complexity: None,
+            list_size: None,
+            buffer_size: None,
+            buffer_overflow: BufferOverflow::Wait,
         }
     }
 
@@ -1174,7 +2257,32 @@ where
     where
         T: GraphQLType<S> + ToInputValue<S> + FromInputValue<S>,
     {
-        Argument::new(name, self.get_type::<Option<T>>(info)).default_value(value.to_input_value())
+        let arg_type = self.get_type::<Option<T>>(info);
+        let default_value = match self.types.get(arg_type.innermost_name()) {
+            // Enum values convert into strings, rendered as enum literals.
+            Some(&MetaType::Enum(_)) => enum_literals(value.to_input_value()),
+            _ => value.to_input_value(),
+        };
+        Argument::new(name, arg_type).default_value(default_value)
+    }
+
+    /// Create the arguments of a field from the fields of an input object
+    ///
+    /// The input object itself is not registered, so it is only part of the
+    /// schema when used as an input type elsewhere. Any other type creates no
+    /// arguments, which [`RootNode::new_validated`] reports as an error.
+    pub fn flattened_args<T>(&mut self, info: &T::TypeInfo) -> Vec<Argument<'r, S>>
+    where
+        T: GraphQLType<S> + FromInputValue<S>,
+    {
+        match T::meta(info, self) {
+            MetaType::InputObject(InputObjectMeta { input_fields, .. }) => input_fields,
+            meta => {
+                let name = meta.name().unwrap_or_else(|| std::any::type_name::<T>());
+                self.unflattenable_types.push(name.to_owned());
+                vec![]
+            }
+        }
     }
 
     fn insert_placeholder(&mut self, name: Name, of_type: Type<'r>) {
@@ -1191,6 +2299,7 @@ where
         T: FromInputValue<S> + GraphQLType<S> + ParseScalarValue<S> + 'r,
     {
         let name = T::name(info).expect("Scalar types must be named. Implement name()");
+        self.record_type_source::<T>(name);
         ScalarMeta::new::<T>(Cow::Owned(name.to_string()))
     }
 
@@ -1222,6 +2331,7 @@ where
         T: GraphQLType<S>,
     {
         let name = T::name(info).expect("Object types must be named. Implement name()");
+        self.record_type_source::<T>(name);
 
         let mut v = fields.to_vec();
         v.push(self.field::<String>("__typename", &()));
@@ -1238,6 +2348,7 @@ where
         T: FromInputValue<S> + GraphQLType<S>,
     {
         let name = T::name(info).expect("Enum types must be named. Implement name()");
+        self.record_type_source::<T>(name);
 
         EnumMeta::new::<T>(Cow::Owned(name.to_string()), values)
     }
@@ -1253,6 +2364,7 @@ where
         T: GraphQLType<S>,
     {
         let name = T::name(info).expect("Interface types must be named. Implement name()");
+        self.record_type_source::<T>(name);
 
         let mut v = fields.to_vec();
         v.push(self.field::<String>("__typename", &()));
@@ -1265,6 +2377,7 @@ where
         T: GraphQLType<S>,
     {
         let name = T::name(info).expect("Union types must be named. Implement name()");
+        self.record_type_source::<T>(name);
 
         UnionMeta::new(Cow::Owned(name.to_string()), types)
     }
@@ -1279,7 +2392,47 @@ where
         T: FromInputValue<S> + GraphQLType<S>,
     {
         let name = T::name(info).expect("Input object types must be named. Implement name()");
+        self.record_type_source::<T>(name);
 
         InputObjectMeta::new::<T>(Cow::Owned(name.to_string()), args)
     }
 }
+
+/// Rejects documents spreading fragments within themselves
+///
+/// Validation already reports them. Checking again keeps the executor, which
+/// follows every spread, from recursing forever on documents executed without
+/// being validated.
+fn reject_fragment_cycles<'a, 'd, S>(
+    schema: &'d SchemaType<S>,
+    document: &'d Document<S>,
+) -> Result<(), GraphQLError<'a>>
+where
+    S: ScalarValue,
+{
+    let mut ctx = ValidatorContext::new(schema, document);
+    visit_fragment_cycles(&mut ctx, document);
+
+    let errors = ctx.into_errors();
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(GraphQLError::ValidationError(errors))
+    }
+}
+
+/// Turns the strings of a value of an enum type into enum literals
+fn enum_literals<S>(value: InputValue<S>) -> InputValue<S>
+where
+    S: ScalarValue,
+{
+    match value {
+        InputValue::Scalar(ref s) if s.as_str().is_some() => {
+            InputValue::enum_value(s.as_str().unwrap())
+        }
+        InputValue::List(items) => {
+            InputValue::List(items.into_iter().map(|i| i.map(enum_literals)).collect())
+        }
+        value => value,
+    }
+}
@@ -5,9 +5,10 @@ use std::{
 
 use crate::{
     ast::Fragment,
-    executor::FieldPath,
+    executor::{FieldAuthorizer, FieldDirectiveHooks, FieldPath},
     parser::SourcePosition,
     schema::model::{SchemaType, TypeType},
+    types::subscriptions::{BufferPressure, ConcurrencyLimit},
     ExecutionError, Executor, Selection, Variables,
 };
 
@@ -23,6 +24,10 @@ pub struct OwnedExecutor<'a, CtxT, S> {
     pub(super) context: &'a CtxT,
     pub(super) errors: RwLock<Vec<ExecutionError<S>>>,
     pub(super) field_path: Arc<FieldPath<'a>>,
+    pub(super) field_authorizer: Option<FieldAuthorizer<'a, S>>,
+    pub(super) field_directive_hooks: Option<FieldDirectiveHooks<'a, S>>,
+    pub(super) buffer_pressure: BufferPressure,
+    pub(super) concurrency_limit: Option<ConcurrencyLimit>,
 }
 
 impl<'a, CtxT, S> Clone for OwnedExecutor<'a, CtxT, S>
@@ -40,6 +45,10 @@ where
             context: self.context,
             errors: RwLock::new(vec![]),
             field_path: self.field_path.clone(),
+            field_authorizer: self.field_authorizer.clone(),
+            field_directive_hooks: self.field_directive_hooks.clone(),
+            buffer_pressure: self.buffer_pressure.clone(),
+            concurrency_limit: self.concurrency_limit.clone(),
         }
     }
 }
@@ -67,6 +76,10 @@ where
             context: self.context,
             errors: RwLock::new(vec![]),
             field_path: self.field_path.clone(),
+            field_authorizer: self.field_authorizer.clone(),
+            field_directive_hooks: self.field_directive_hooks.clone(),
+            buffer_pressure: self.buffer_pressure.clone(),
+            concurrency_limit: self.concurrency_limit.clone(),
         }
     }
 
@@ -104,6 +117,10 @@ where
                 location,
                 Arc::clone(&self.field_path),
             )),
+            field_authorizer: self.field_authorizer.clone(),
+            field_directive_hooks: self.field_directive_hooks.clone(),
+            buffer_pressure: self.buffer_pressure.clone(),
+            concurrency_limit: self.concurrency_limit.clone(),
         }
     }
 
@@ -127,6 +144,12 @@ where
             context: self.context,
             errors: &self.errors,
             field_path: Arc::clone(&self.field_path),
+            field_authorizer: self.field_authorizer.clone(),
+            field_directive_hooks: self.field_directive_hooks.clone(),
+            tracer: None,
+            response_context: None,
+            buffer_pressure: self.buffer_pressure.clone(),
+            concurrency_limit: self.concurrency_limit.clone(),
         }
     }
 }
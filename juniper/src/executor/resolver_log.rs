@@ -0,0 +1,47 @@
+use std::{fmt, time::Duration};
+
+use crate::executor::{FieldError, FieldPath, PathSegment};
+
+/// Hook called with every field resolving slower than a threshold or to an
+/// error
+pub(crate) struct ResolverLog<S> {
+    threshold: Duration,
+    hook: Box<dyn Fn(&[PathSegment], Duration, Option<&FieldError<S>>) + Send + Sync>,
+}
+
+impl<S> ResolverLog<S> {
+    pub(crate) fn new<F>(threshold: Duration, hook: F) -> Self
+    where
+        F: Fn(&[PathSegment], Duration, Option<&FieldError<S>>) + Send + Sync + 'static,
+    {
+        ResolverLog {
+            threshold,
+            hook: Box::new(hook),
+        }
+    }
+
+    /// Calls the hook if the field at `field_path` took at least the
+    /// threshold to resolve or resolved to an error
+    pub(crate) fn record(
+        &self,
+        field_path: &FieldPath,
+        duration: Duration,
+        error: Option<&FieldError<S>>,
+    ) {
+        if duration < self.threshold && error.is_none() {
+            return;
+        }
+
+        let mut path = Vec::new();
+        field_path.construct_path(&mut path);
+        (self.hook)(&path, duration, error);
+    }
+}
+
+impl<S> fmt::Debug for ResolverLog<S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ResolverLog")
+            .field("threshold", &self.threshold)
+            .finish()
+    }
+}
@@ -0,0 +1,47 @@
+use std::sync::Mutex;
+
+/// Cache hints and HTTP headers suggested for the response of an operation
+///
+/// Resolvers write into it with
+/// [`Executor::set_cache_max_age`](crate::Executor::set_cache_max_age) and
+/// [`Executor::add_response_header`](crate::Executor::add_response_header),
+/// and integrations read it from
+/// [`GraphQLResponse::response_context`](crate::http::GraphQLResponse::response_context)
+/// after the execution.
+#[derive(Debug, Default)]
+pub struct ResponseContext {
+    cache_max_age: Mutex<Option<u32>>,
+    headers: Mutex<Vec<(String, String)>>,
+}
+
+impl ResponseContext {
+    /// Starts from the cache hint declared by the fields of the operation
+    pub(crate) fn new(cache_max_age: Option<u32>) -> Self {
+        ResponseContext {
+            cache_max_age: Mutex::new(cache_max_age),
+            headers: Mutex::default(),
+        }
+    }
+
+    /// The cache hint of the response, in seconds.
+    ///
+    /// This is the minimum of the cache hints declared by the selected fields
+    /// and the ones set by their resolvers.
+    pub fn cache_max_age(&self) -> Option<u32> {
+        *self.cache_max_age.lock().unwrap()
+    }
+
+    /// The headers suggested by the resolvers, in the order they were added
+    pub fn headers(&self) -> Vec<(String, String)> {
+        self.headers.lock().unwrap().clone()
+    }
+
+    pub(crate) fn lower_cache_max_age(&self, max_age: u32) {
+        let mut cache_max_age = self.cache_max_age.lock().unwrap();
+        *cache_max_age = Some(cache_max_age.map_or(max_age, |current| current.min(max_age)));
+    }
+
+    pub(crate) fn add_header(&self, name: String, value: String) {
+        self.headers.lock().unwrap().push((name, value));
+    }
+}
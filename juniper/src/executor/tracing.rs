@@ -0,0 +1,130 @@
+use std::{
+    sync::Mutex,
+    time::{Duration, Instant, SystemTime},
+};
+
+use crate::executor::PathSegment;
+
+/// How long resolving a field took
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldTiming {
+    /// The response path of the field
+    pub path: Vec<PathSegment>,
+    /// The name of the type the field belongs to
+    pub parent_type: String,
+    /// The name of the field
+    pub field_name: String,
+    /// The type of the field, e.g. `[String!]`
+    pub return_type: String,
+    /// When the field started resolving, since the start of the execution
+    pub start_offset: Duration,
+    /// How long the field took to resolve
+    pub duration: Duration,
+}
+
+/// Timings recorded while executing an operation with tracing enabled
+///
+/// Serializes into the [Apollo Tracing][format] format, which is how it is
+/// emitted under the `extensions.tracing` entry of a response.
+///
+/// [format]: https://github.com/apollographql/apollo-tracing
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tracing {
+    pub(crate) start_time: SystemTime,
+    pub(crate) duration: Duration,
+    pub(crate) resolvers: Vec<FieldTiming>,
+}
+
+impl Tracing {
+    /// When the execution started
+    pub fn start_time(&self) -> SystemTime {
+        self.start_time
+    }
+
+    /// When the execution ended
+    pub fn end_time(&self) -> SystemTime {
+        self.start_time + self.duration
+    }
+
+    /// How long the whole execution took
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// The timings of every resolved field, in the order they finished
+    pub fn resolvers(&self) -> &[FieldTiming] {
+        &self.resolvers
+    }
+
+    /// How long the field at `path` took to resolve
+    pub fn field_duration<P>(&self, path: &[P]) -> Option<Duration>
+    where
+        PathSegment: PartialEq<P>,
+    {
+        self.resolvers
+            .iter()
+            .find(|t| t.path.len() == path.len() && t.path.iter().zip(path).all(|(a, b)| a == b))
+            .map(|t| t.duration)
+    }
+}
+
+/// Collects the field timings of an execution
+#[derive(Debug)]
+pub(crate) struct Tracer {
+    start: Instant,
+    start_time: SystemTime,
+    resolvers: Mutex<Vec<FieldTiming>>,
+}
+
+impl Tracer {
+    pub(crate) fn new() -> Self {
+        Tracer {
+            start: Instant::now(),
+            start_time: SystemTime::now(),
+            resolvers: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub(crate) fn time_field(
+        &self,
+        path: Vec<PathSegment>,
+        parent_type: &str,
+        field_name: &str,
+        return_type: String,
+    ) -> FieldTimer {
+        FieldTimer {
+            tracer: self,
+            timing: FieldTiming {
+                path,
+                parent_type: parent_type.to_owned(),
+                field_name: field_name.to_owned(),
+                return_type,
+                start_offset: Duration::default(),
+                duration: Duration::default(),
+            },
+        }
+    }
+
+    pub(crate) fn finish(self) -> Tracing {
+        Tracing {
+            start_time: self.start_time,
+            duration: self.start.elapsed(),
+            resolvers: self.resolvers.into_inner().unwrap(),
+        }
+    }
+}
+
+/// Records the timing of a field into its [`Tracer`] once the field resolved
+pub(crate) struct FieldTimer<'r> {
+    tracer: &'r Tracer,
+    timing: FieldTiming,
+}
+
+impl<'r> FieldTimer<'r> {
+    /// Record that the field started resolving at `start` and just finished
+    pub(crate) fn finish(mut self, start: Instant) {
+        self.timing.start_offset = start.duration_since(self.tracer.start);
+        self.timing.duration = start.elapsed();
+        self.tracer.resolvers.lock().unwrap().push(self.timing);
+    }
+}
@@ -1,4 +1,12 @@
-use crate::{RootNode, Value};
+use std::{
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+
+use crate::{FieldError, FieldResult, GraphQLError, RootNode, Value};
 
 #[derive(crate::GraphQLEnumInternal)]
 enum UserKind {
@@ -112,3 +120,453 @@ async fn async_simple() {
         }),
     );
 }
+
+struct Link {
+    depth: i32,
+}
+
+#[crate::graphql_object_internal]
+impl Link {
+    fn depth(&self) -> i32 {
+        self.depth
+    }
+
+    async fn friend(&self) -> Option<Link> {
+        Some(Link {
+            depth: self.depth + 1,
+        })
+    }
+}
+
+struct ChainQuery;
+
+#[crate::graphql_object_internal]
+impl ChainQuery {
+    fn chain() -> Link {
+        Link { depth: 0 }
+    }
+}
+
+#[tokio::test]
+async fn resolves_deep_recursive_data() {
+    const DEPTH: usize = 1000;
+
+    let schema = RootNode::new(ChainQuery, Mutation, crate::EmptySubscription::new());
+    let doc = format!(
+        "{{ chain {{ {} depth {} }} }}",
+        "friend { ".repeat(DEPTH),
+        "}".repeat(DEPTH),
+    );
+
+    let vars = Default::default();
+    let (res, errs) = crate::execute(&doc, None, &schema, &vars, &())
+        .await
+        .unwrap();
+
+    assert!(errs.is_empty());
+
+    let mut value = res
+        .as_object_value()
+        .unwrap()
+        .get_field_value("chain")
+        .unwrap();
+    for _ in 0..DEPTH {
+        value = value
+            .as_object_value()
+            .unwrap()
+            .get_field_value("friend")
+            .unwrap();
+    }
+    assert_eq!(
+        value.as_object_value().unwrap().get_field_value("depth"),
+        Some(&Value::scalar(DEPTH as i32)),
+    );
+}
+
+struct Library {
+    books: Vec<String>,
+}
+
+impl crate::Context for Library {}
+
+struct Shelf {
+    prefix: String,
+}
+
+#[crate::graphql_object_internal(Context = Library)]
+impl Shelf {
+    async fn titles(&self, context: &Library, limit: i32) -> Vec<&str> {
+        let prefix = &self.prefix;
+        tokio::task::yield_now().await;
+        context
+            .books
+            .iter()
+            .filter(|b| b.starts_with(prefix.as_str()))
+            .take(limit as usize)
+            .map(|b| b.as_str())
+            .collect()
+    }
+
+    async fn prefix(&self, executor: &Executor<Library>) -> crate::FieldResult<&str> {
+        let prefix = self.prefix.as_str();
+        tokio::task::yield_now().await;
+        if executor.context().books.is_empty() {
+            Err("The library is empty")?
+        }
+        Ok(prefix)
+    }
+
+    async fn first(&self, context: &Library) -> Result<&String, crate::FieldError> {
+        tokio::task::yield_now().await;
+        context
+            .books
+            .iter()
+            .find(|b| b.starts_with(&self.prefix))
+            .ok_or_else(|| format!("No book starts with {}", self.prefix).into())
+    }
+}
+
+struct LibraryQuery;
+
+#[crate::graphql_object_internal(Context = Library)]
+impl LibraryQuery {
+    fn shelf(prefix: String) -> Option<Shelf> {
+        Some(Shelf { prefix })
+    }
+}
+
+#[tokio::test]
+async fn async_methods_borrow_self_across_await() {
+    let schema = RootNode::new(
+        LibraryQuery,
+        crate::EmptyMutation::<Library>::new(),
+        crate::EmptySubscription::<Library>::new(),
+    );
+    let library = Library {
+        books: vec!["Dune".into(), "Dracula".into(), "Emma".into()],
+    };
+    let doc = r#"{
+        d: shelf(prefix: "D") { prefix titles(limit: 5) first }
+        z: shelf(prefix: "Z") { first }
+    }"#;
+
+    let vars = Default::default();
+    let (res, errs) = crate::execute(doc, None, &schema, &vars, &library)
+        .await
+        .unwrap();
+
+    assert_eq!(
+        res,
+        crate::graphql_value!({
+            "d": {
+                "prefix": "D",
+                "titles": ["Dune", "Dracula"],
+                "first": "Dune",
+            },
+            "z": None,
+        }),
+    );
+    assert_eq!(errs.len(), 1);
+    assert_eq!(errs[0].error().message(), "No book starts with Z");
+    assert_eq!(errs[0].path(), ["z", "first"]);
+}
+
+#[test]
+fn sync_execution_rejects_async_methods() {
+    let schema = RootNode::new(
+        LibraryQuery,
+        crate::EmptyMutation::<Library>::new(),
+        crate::EmptySubscription::<Library>::new(),
+    );
+    let library = Library {
+        books: vec!["Dune".into()],
+    };
+    let doc = r#"{ shelf(prefix: "D") { first } }"#;
+
+    let vars = Default::default();
+    let (res, errs) = crate::execute_sync(doc, None, &schema, &vars, &library).unwrap();
+
+    assert_eq!(res, crate::graphql_value!({ "shelf": None }));
+    assert_eq!(errs.len(), 1);
+    assert_eq!(
+        errs[0].error().message(),
+        "Tried to resolve async field first on type Some(\"Shelf\") with a sync resolver",
+    );
+}
+
+/// Built outside of any resolver, e.g. by a loader shared between types.
+fn load_title(shelf: &Shelf) -> crate::BoxFuture<'_, String> {
+    Box::pin(async move {
+        tokio::task::yield_now().await;
+        format!("{} shelf", shelf.prefix)
+    })
+}
+
+struct Catalog {
+    shelf: Shelf,
+}
+
+#[crate::graphql_object_internal(Context = Library)]
+impl Catalog {
+    fn title(&self) -> crate::BoxFuture<'_, String> {
+        load_title(&self.shelf)
+    }
+
+    fn count(&self, context: &Library) -> crate::BoxFuture<'_, crate::FieldResult<i32>> {
+        let count = context.books.len() as i32;
+        Box::pin(async move { Ok(count) })
+    }
+
+    async fn checked_title(
+        &self,
+    ) -> std::pin::Pin<Box<dyn std::future::Future<Output = Option<String>> + Send + '_>> {
+        Box::pin(async move { Some(load_title(&self.shelf).await) })
+    }
+
+    fn shelf(&self) -> impl std::future::Future<Output = &Shelf> {
+        futures::future::ready(&self.shelf)
+    }
+}
+
+struct CatalogQuery;
+
+#[crate::graphql_object_internal(Context = Library)]
+impl CatalogQuery {
+    fn catalog() -> Catalog {
+        Catalog {
+            shelf: Shelf { prefix: "D".into() },
+        }
+    }
+}
+
+#[tokio::test]
+async fn resolves_fields_from_boxed_futures() {
+    let schema = RootNode::new(
+        CatalogQuery,
+        crate::EmptyMutation::<Library>::new(),
+        crate::EmptySubscription::<Library>::new(),
+    );
+    let library = Library {
+        books: vec!["Dune".into(), "Dracula".into()],
+    };
+    let doc = r#"{ catalog { title count checkedTitle shelf { titles(limit: 1) } } }"#;
+
+    let vars = Default::default();
+    let (res, errs) = crate::execute(doc, None, &schema, &vars, &library)
+        .await
+        .unwrap();
+
+    assert_eq!(errs, []);
+    assert_eq!(
+        res,
+        crate::graphql_value!({
+            "catalog": {
+                "title": "D shelf",
+                "count": 2,
+                "checkedTitle": "D shelf",
+                "shelf": { "titles": ["Dune"] },
+            },
+        }),
+    );
+}
+
+/// Names of the resolved fields, in the order they finished
+#[derive(Default)]
+struct Finished(Mutex<Vec<&'static str>>);
+
+impl crate::Context for Finished {}
+
+impl Finished {
+    async fn finish_after(&self, millis: u64, name: &'static str) -> &'static str {
+        tokio::time::delay_for(Duration::from_millis(millis)).await;
+        self.0.lock().unwrap().push(name);
+        name
+    }
+}
+
+struct OrderQuery;
+
+#[crate::graphql_object_internal(Context = Finished)]
+impl OrderQuery {
+    async fn first(context: &Finished) -> &'static str {
+        context.finish_after(60, "first").await
+    }
+
+    async fn second(context: &Finished) -> &'static str {
+        context.finish_after(30, "second").await
+    }
+
+    async fn third(context: &Finished) -> &'static str {
+        context.finish_after(0, "third").await
+    }
+}
+
+#[tokio::test]
+async fn fields_keep_selection_order_when_finishing_out_of_order() {
+    let schema = RootNode::new(
+        OrderQuery,
+        crate::EmptyMutation::<Finished>::new(),
+        crate::EmptySubscription::<Finished>::new(),
+    );
+
+    for (doc, keys) in vec![
+        ("{ first second third }", vec!["first", "second", "third"]),
+        (
+            "{ first kind: __typename second ...Last }
+             fragment Last on OrderQuery { third }",
+            vec!["first", "kind", "second", "third"],
+        ),
+    ] {
+        let finished = Finished::default();
+        let vars = Default::default();
+        let (res, errs) = crate::execute(doc, None, &schema, &vars, &finished)
+            .await
+            .unwrap();
+
+        assert_eq!(errs, []);
+        assert_eq!(
+            *finished.0.lock().unwrap(),
+            vec!["third", "second", "first"]
+        );
+        let obj = res.into_object().unwrap();
+        assert_eq!(
+            obj.iter().map(|(k, _)| k.as_str()).collect::<Vec<_>>(),
+            keys
+        );
+    }
+}
+
+#[derive(Default)]
+struct Dropped(AtomicBool);
+
+impl crate::Context for Dropped {}
+
+/// Flags its context once the resolver holding it is dropped
+struct DropGuard<'a>(&'a AtomicBool);
+
+impl Drop for DropGuard<'_> {
+    fn drop(&mut self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+}
+
+struct SlowQuery;
+
+#[crate::graphql_object_internal(Context = Dropped)]
+impl SlowQuery {
+    async fn slow(context: &Dropped) -> i32 {
+        let _guard = DropGuard(&context.0);
+        tokio::time::delay_for(Duration::from_secs(60)).await;
+        0
+    }
+}
+
+#[tokio::test]
+async fn deadline_cancels_slow_resolvers() {
+    let schema = RootNode::new(
+        SlowQuery,
+        crate::EmptyMutation::<Dropped>::new(),
+        crate::EmptySubscription::<Dropped>::new(),
+    );
+    let dropped = Dropped::default();
+    let vars = Default::default();
+
+    let started = Instant::now();
+    let result = crate::execute_with_deadline(
+        "{ slow }",
+        None,
+        &schema,
+        &vars,
+        &dropped,
+        tokio::time::delay_for(Duration::from_millis(50)),
+    )
+    .await;
+
+    assert_eq!(result, Err(GraphQLError::TimedOut));
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert!(dropped.0.load(Ordering::SeqCst));
+}
+
+#[derive(Default)]
+struct Resolved(AtomicUsize);
+
+impl crate::Context for Resolved {}
+
+struct Item;
+
+#[crate::graphql_object_internal(Context = Resolved)]
+impl Item {
+    async fn expensive(context: &Resolved) -> i32 {
+        tokio::time::delay_for(Duration::from_millis(50)).await;
+        context.0.fetch_add(1, Ordering::SeqCst);
+        1
+    }
+
+    async fn broken() -> FieldResult<i32> {
+        Err(FieldError::from("Broken"))
+    }
+}
+
+struct ItemQuery;
+
+#[crate::graphql_object_internal(Context = Resolved)]
+impl ItemQuery {
+    fn item() -> Option<Item> {
+        Some(Item)
+    }
+}
+
+struct ItemMutation;
+
+#[crate::graphql_object_internal(Context = Resolved)]
+impl ItemMutation {
+    async fn broken() -> FieldResult<i32> {
+        Err(FieldError::from("Broken"))
+    }
+
+    async fn record(context: &Resolved) -> i32 {
+        context.0.fetch_add(1, Ordering::SeqCst);
+        1
+    }
+}
+
+#[tokio::test]
+async fn non_null_failure_skips_remaining_siblings() {
+    let schema = RootNode::new(
+        ItemQuery,
+        ItemMutation,
+        crate::EmptySubscription::<Resolved>::new(),
+    );
+    let vars = Default::default();
+
+    let resolved = Resolved::default();
+    let (res, errs) = crate::execute(
+        "{ item { expensive broken } }",
+        None,
+        &schema,
+        &vars,
+        &resolved,
+    )
+    .await
+    .unwrap();
+    assert_eq!(errs.len(), 1);
+    assert_eq!(
+        res,
+        Value::object(vec![("item", Value::null())].into_iter().collect())
+    );
+    assert_eq!(resolved.0.load(Ordering::SeqCst), 0);
+
+    let resolved = Resolved::default();
+    let (res, errs) = crate::execute(
+        "mutation { broken record }",
+        None,
+        &schema,
+        &vars,
+        &resolved,
+    )
+    .await
+    .unwrap();
+    assert_eq!(errs.len(), 1);
+    assert_eq!(res, Value::null());
+    assert_eq!(resolved.0.load(Ordering::SeqCst), 0);
+}
@@ -0,0 +1,177 @@
+use juniper_codegen::GraphQLObjectInternal;
+
+use crate::{
+    executor::{Context, ExecutionError, FieldError},
+    parser::SourcePosition,
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    value::{DefaultScalarValue, Object, Value},
+};
+
+struct Viewer {
+    admin: bool,
+}
+
+impl Context for Viewer {}
+
+#[derive(GraphQLObjectInternal)]
+#[graphql(Context = Viewer)]
+struct User {
+    name: String,
+    email: String,
+}
+
+struct Query;
+
+#[crate::graphql_object_internal(Context = Viewer)]
+impl Query {
+    fn public() -> &str {
+        "public"
+    }
+
+    fn secret() -> Option<&str> {
+        Some("secret")
+    }
+
+    async fn secret_async() -> Option<&str> {
+        Some("secret")
+    }
+
+    fn user() -> User {
+        User {
+            name: "Alice".to_owned(),
+            email: "alice@example.com".to_owned(),
+        }
+    }
+}
+
+fn schema() -> RootNode<'static, Query, EmptyMutation<Viewer>, EmptySubscription<Viewer>> {
+    RootNode::new(
+        Query,
+        EmptyMutation::<Viewer>::new(),
+        EmptySubscription::<Viewer>::new(),
+    )
+    .authorize_fields(|parent_type, field_name, viewer: &Viewer| {
+        match (parent_type, field_name) {
+            ("Query", "secret") | ("Query", "secretAsync") | ("User", "email") if !viewer.admin => {
+                Err(FieldError::new("Forbidden", Value::null()))
+            }
+            _ => Ok(()),
+        }
+    })
+}
+
+fn forbidden(index: usize, path: &[&str]) -> ExecutionError<DefaultScalarValue> {
+    ExecutionError::new(
+        SourcePosition::new(index, 0, index),
+        path,
+        FieldError::new("Forbidden", Value::null()),
+    )
+}
+
+#[test]
+fn denied_field_resolves_null_with_error() {
+    let schema = schema();
+    let result = crate::execute_sync(
+        "{ public secret }",
+        None,
+        &schema,
+        &Default::default(),
+        &Viewer { admin: false },
+    );
+
+    assert_eq!(
+        result,
+        Ok((
+            Value::object(
+                vec![
+                    ("public", Value::scalar("public")),
+                    ("secret", Value::null()),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            vec![forbidden(9, &["secret"])]
+        ))
+    );
+}
+
+#[test]
+fn allowed_field_resolves() {
+    let schema = schema();
+    let result = crate::execute_sync(
+        "{ public secret }",
+        None,
+        &schema,
+        &Default::default(),
+        &Viewer { admin: true },
+    );
+
+    assert_eq!(
+        result,
+        Ok((
+            Value::object(
+                vec![
+                    ("public", Value::scalar("public")),
+                    ("secret", Value::scalar("secret")),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            vec![]
+        ))
+    );
+}
+
+#[test]
+fn denied_non_null_field_nulls_parent() {
+    let schema = schema();
+    let result = crate::execute_sync(
+        "{ public user { name email } }",
+        None,
+        &schema,
+        &Default::default(),
+        &Viewer { admin: false },
+    );
+
+    assert_eq!(
+        result,
+        Ok((Value::null(), vec![forbidden(21, &["user", "email"])]))
+    );
+}
+
+#[tokio::test]
+async fn denied_field_resolves_null_with_error_async() {
+    let schema = schema();
+    let result = crate::execute(
+        "{ public secretAsync user { name } }",
+        None,
+        &schema,
+        &Default::default(),
+        &Viewer { admin: false },
+    )
+    .await;
+
+    assert_eq!(
+        result,
+        Ok((
+            Value::object(
+                vec![
+                    ("public", Value::scalar("public")),
+                    ("secretAsync", Value::null()),
+                    (
+                        "user",
+                        Value::object(
+                            vec![("name", Value::scalar("Alice"))]
+                                .into_iter()
+                                .collect::<Object<_>>(),
+                        ),
+                    ),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            vec![forbidden(9, &["secretAsync"])]
+        ))
+    );
+}
@@ -0,0 +1,172 @@
+use crate::{
+    http::{GraphQLRequest, GraphQLResponse},
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+};
+
+struct Inner;
+
+#[crate::graphql_object_internal]
+impl Inner {
+    #[graphql(cache_control(max_age = 10))]
+    fn short() -> i32 {
+        1
+    }
+
+    fn uncached() -> i32 {
+        2
+    }
+}
+
+struct TestType;
+
+#[crate::graphql_object_internal]
+impl TestType {
+    #[graphql(cache_control(max_age = 60))]
+    fn a() -> &str {
+        "a"
+    }
+
+    #[graphql(cache_control(max_age = 30))]
+    fn b() -> &str {
+        "b"
+    }
+
+    #[graphql(cache_control(max_age = 120))]
+    fn inner() -> Inner {
+        Inner
+    }
+
+    fn uncached() -> &str {
+        "uncached"
+    }
+
+    fn personalized(executor: &Executor<()>) -> &str {
+        executor.set_cache_max_age(5);
+        executor.add_response_header("Vary", "Authorization");
+        "personalized"
+    }
+}
+
+async fn run_query(query: &str) -> Option<u32> {
+    let schema = RootNode::new(
+        TestType,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    )
+    .enable_cache_control();
+
+    let request = GraphQLRequest::new(query.to_owned(), None, None);
+    let response = request.execute(&schema, &()).await;
+
+    assert!(response.is_ok());
+
+    response.cache_max_age()
+}
+
+#[tokio::test]
+async fn max_age_is_minimum_of_selected_fields() {
+    assert_eq!(run_query("{ a b }").await, Some(30));
+    assert_eq!(run_query("{ a uncached }").await, Some(60));
+    assert_eq!(run_query("{ a inner { uncached } }").await, Some(60));
+    assert_eq!(run_query("{ a inner { short } }").await, Some(10));
+}
+
+#[tokio::test]
+async fn max_age_includes_fragments() {
+    assert_eq!(
+        run_query("{ a ...Frag } fragment Frag on TestType { b }").await,
+        Some(30)
+    );
+    assert_eq!(run_query("{ a ... on TestType { b } }").await, Some(30));
+}
+
+#[tokio::test]
+async fn max_age_ignores_excluded_fields() {
+    assert_eq!(run_query("{ a b @skip(if: true) }").await, Some(60));
+}
+
+#[tokio::test]
+async fn max_age_honors_directives() {
+    assert_eq!(
+        run_query("query @cacheControl(maxAge: 5) { a }").await,
+        Some(5)
+    );
+    assert_eq!(
+        run_query("{ a uncached @cacheControl(maxAge: 15) }").await,
+        Some(15)
+    );
+}
+
+#[tokio::test]
+async fn directive_requires_opt_in() {
+    let schema = RootNode::new(
+        TestType,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    assert!(schema.schema.directive_by_name("cacheControl").is_none());
+
+    let request = GraphQLRequest::new("{ a @cacheControl(maxAge: 5) }".to_owned(), None, None);
+    let response = request.execute(&schema, &()).await;
+    assert!(!response.is_ok());
+
+    let request = GraphQLRequest::new("{ a b }".to_owned(), None, None);
+    let response = request.execute(&schema, &()).await;
+    assert_eq!(response.cache_max_age(), Some(30));
+}
+
+#[tokio::test]
+async fn max_age_is_none_without_hints() {
+    assert_eq!(run_query("{ uncached }").await, None);
+}
+
+#[test]
+fn max_age_sync() {
+    let schema = RootNode::new(
+        TestType,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let request = GraphQLRequest::new("{ a b }".to_owned(), None, None);
+    let response = request.execute_sync(&schema, &());
+
+    assert_eq!(response.cache_max_age(), Some(30));
+}
+
+#[tokio::test]
+async fn max_age_includes_resolver_hints() {
+    assert_eq!(run_query("{ personalized }").await, Some(5));
+    assert_eq!(run_query("{ a personalized }").await, Some(5));
+    assert_eq!(
+        run_query("{ personalized @cacheControl(maxAge: 2) }").await,
+        Some(2)
+    );
+}
+
+fn assert_vary_header(response: &GraphQLResponse) {
+    assert_eq!(
+        response.response_context().headers(),
+        vec![("Vary".to_owned(), "Authorization".to_owned())],
+    );
+}
+
+#[tokio::test]
+async fn resolvers_suggest_headers() {
+    let schema = RootNode::new(
+        TestType,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let request = GraphQLRequest::new("{ a personalized }".to_owned(), None, None);
+
+    assert_vary_header(&request.execute(&schema, &()).await);
+    assert_vary_header(&request.execute_sync(&schema, &()));
+
+    let request = GraphQLRequest::new("{ a }".to_owned(), None, None);
+    let response = request.execute(&schema, &()).await;
+    assert!(response.response_context().headers().is_empty());
+}
@@ -0,0 +1,125 @@
+use crate::{
+    ast::{Definition, Document},
+    executor::operation_complexity,
+    parser::{parse_document_source, SourcePosition},
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    validation::RuleError,
+    value::DefaultScalarValue,
+    GraphQLError,
+};
+
+struct Item;
+
+#[crate::graphql_object_internal]
+impl Item {
+    fn id() -> i32 {
+        1
+    }
+
+    #[graphql(complexity(cost = 5))]
+    fn details() -> &str {
+        "details"
+    }
+
+    #[graphql(complexity(list_size = 3))]
+    fn tags() -> Vec<&str> {
+        vec!["a", "b", "c"]
+    }
+}
+
+struct Query;
+
+#[crate::graphql_object_internal]
+impl Query {
+    fn item() -> Item {
+        Item
+    }
+
+    fn items() -> Vec<Item> {
+        vec![Item, Item]
+    }
+
+    #[graphql(complexity(cost = 2, list_size = 100))]
+    fn all_items() -> Vec<Item> {
+        vec![Item]
+    }
+}
+
+type Schema = RootNode<'static, Query, EmptyMutation<()>, EmptySubscription<()>>;
+
+fn schema() -> Schema {
+    RootNode::new(
+        Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    )
+}
+
+fn complexity(query: &str) -> usize {
+    let schema = schema();
+    let document: Document<DefaultScalarValue> =
+        parse_document_source(query, &schema.schema).expect("Parse error");
+    let operation = document
+        .iter()
+        .find_map(|def| match *def {
+            Definition::Operation(ref op) => Some(op),
+            _ => None,
+        })
+        .expect("No operation");
+
+    operation_complexity(&document, operation, &schema.schema, &Default::default())
+}
+
+#[test]
+fn scalar_fields_cost_one() {
+    assert_eq!(complexity("{ item { id } }"), 2);
+}
+
+#[test]
+fn field_cost_is_configurable() {
+    assert_eq!(complexity("{ item { id details } }"), 7);
+}
+
+#[test]
+fn list_fields_multiply_by_estimated_size() {
+    // items: (1 + id + tags * 3) * 10
+    assert_eq!(complexity("{ items { id tags } }"), 50);
+    // allItems: (2 + details) * 100
+    assert_eq!(complexity("{ allItems { details } }"), 700);
+}
+
+#[test]
+fn fragments_and_directives() {
+    assert_eq!(
+        complexity(
+            r#"
+            {
+                item { ...details ... on Item { id } }
+                items @skip(if: true) { id }
+            }
+
+            fragment details on Item { details }
+            "#
+        ),
+        7
+    );
+}
+
+#[test]
+fn rejects_operations_over_budget() {
+    let schema = schema().max_query_complexity(50);
+    let vars = Default::default();
+
+    let at_budget = crate::execute_sync("{ items { id tags } }", None, &schema, &vars, &());
+    assert!(at_budget.is_ok());
+
+    let over_budget = crate::execute_sync("{ allItems { id } }", None, &schema, &vars, &());
+    assert_eq!(
+        over_budget,
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            "Operation has a complexity of 300, which exceeds the maximum complexity of 50",
+            &[SourcePosition::new(0, 0, 0)],
+        )]))
+    );
+}
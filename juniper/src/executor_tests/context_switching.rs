@@ -0,0 +1,110 @@
+use crate::{
+    executor::{Context, ExecutionResult, Executor, Registry},
+    schema::{meta::MetaType, model::RootNode},
+    types::{
+        base::{Arguments, GraphQLType},
+        scalars::{EmptyMutation, EmptySubscription},
+    },
+    value::{DefaultScalarValue, Value},
+};
+
+struct Tenant {
+    name: Option<String>,
+}
+
+impl Context for Tenant {}
+
+/// Query resolving `scoped` with the context narrowed to a single tenant
+struct Query;
+
+impl GraphQLType for Query {
+    type Context = Tenant;
+    type TypeInfo = ();
+
+    fn name(_: &()) -> Option<&'static str> {
+        Some("Query")
+    }
+
+    fn meta<'r>(_: &(), registry: &mut Registry<'r>) -> MetaType<'r>
+    where
+        DefaultScalarValue: 'r,
+    {
+        let fields = &[
+            registry.field::<Scoped>("unscoped", &()),
+            registry
+                .field::<Scoped>("scoped", &())
+                .argument(registry.arg::<String>("tenant", &())),
+        ];
+        registry.build_object_type::<Query>(&(), fields).into_meta()
+    }
+
+    fn resolve_field(
+        &self,
+        info: &(),
+        field_name: &str,
+        args: &Arguments,
+        executor: &Executor<Tenant>,
+    ) -> ExecutionResult {
+        match field_name {
+            "unscoped" => executor.resolve(info, &Scoped),
+            "scoped" => {
+                let narrowed = Tenant {
+                    name: args.get("tenant"),
+                };
+                let executor = executor.with_context(&narrowed);
+                executor.resolve(info, &Scoped)
+            }
+            _ => panic!("Field {} not found on type Query", field_name),
+        }
+    }
+}
+
+struct Scoped;
+
+#[crate::graphql_object_internal(Context = Tenant)]
+impl Scoped {
+    fn tenant(context: &Tenant) -> Option<String> {
+        context.name.clone()
+    }
+}
+
+#[test]
+fn child_sees_narrowed_context() {
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<Tenant>::new(),
+        EmptySubscription::<Tenant>::new(),
+    );
+
+    let (result, errors) = crate::execute_sync(
+        r#"{ unscoped { tenant } scoped(tenant: "acme") { tenant } }"#,
+        None,
+        &schema,
+        &Default::default(),
+        &Tenant { name: None },
+    )
+    .unwrap();
+
+    assert_eq!(errors, []);
+    assert_eq!(
+        result,
+        Value::object(
+            vec![
+                (
+                    "unscoped",
+                    Value::object(vec![("tenant", Value::null())].into_iter().collect()),
+                ),
+                (
+                    "scoped",
+                    Value::object(
+                        vec![("tenant", Value::scalar("acme"))]
+                            .into_iter()
+                            .collect(),
+                    ),
+                ),
+            ]
+            .into_iter()
+            .collect()
+        )
+    );
+}
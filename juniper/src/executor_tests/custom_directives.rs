@@ -0,0 +1,291 @@
+use std::{
+    pin::Pin,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use futures::{stream, Stream, StreamExt};
+
+use crate::{
+    ast::Type,
+    executor::{Context, ExecutionError, FieldError},
+    parser::SourcePosition,
+    schema::{
+        meta::Argument,
+        model::{DirectiveLocation, DirectiveType, RootNode},
+    },
+    types::scalars::EmptyMutation,
+    validation::RuleError,
+    value::Value,
+    GraphQLError,
+};
+
+struct Viewer {
+    role: String,
+    resolved: AtomicUsize,
+}
+
+impl Context for Viewer {}
+
+struct Query;
+
+#[crate::graphql_object_internal(Context = Viewer)]
+impl Query {
+    fn greeting() -> &str {
+        "hello"
+    }
+
+    async fn greeting_async() -> &str {
+        "hello"
+    }
+
+    fn count() -> i32 {
+        3
+    }
+
+    fn secret(context: &Viewer) -> &str {
+        context.resolved.fetch_add(1, Ordering::SeqCst);
+        "secret"
+    }
+}
+
+struct Subscription;
+
+#[crate::graphql_subscription_internal(Context = Viewer)]
+impl Subscription {
+    async fn greetings(context: &Viewer) -> Pin<Box<dyn Stream<Item = String> + Send>> {
+        context.resolved.fetch_add(1, Ordering::SeqCst);
+        Box::pin(stream::iter(vec!["hello".to_owned(), "bye".to_owned()]))
+    }
+}
+
+fn schema() -> RootNode<'static, Query, EmptyMutation<Viewer>, Subscription> {
+    RootNode::new(Query, EmptyMutation::<Viewer>::new(), Subscription)
+        .field_directive(
+            DirectiveType::new("uppercase", &[DirectiveLocation::Field], &[]),
+            |_, value, _: &Viewer| {
+                Ok(match value.as_string_value() {
+                    Some(s) => Value::scalar(s.to_uppercase()),
+                    None => value,
+                })
+            },
+        )
+        .field_directive_guard(
+            DirectiveType::new(
+                "requireRole",
+                &[DirectiveLocation::Field],
+                &[Argument::new("role", Type::NonNullNamed("String".into()))],
+            ),
+            |args, viewer: &Viewer| {
+                if args.get::<String>("role") == Some(viewer.role.clone()) {
+                    Ok(())
+                } else {
+                    Err(FieldError::new("Forbidden", Value::null()))
+                }
+            },
+        )
+}
+
+fn viewer(role: &str) -> Viewer {
+    Viewer {
+        role: role.to_owned(),
+        resolved: AtomicUsize::new(0),
+    }
+}
+
+#[test]
+fn uppercase_transforms_string_field() {
+    let schema = schema();
+    let result = crate::execute_sync(
+        "{ greeting @uppercase plain: greeting count @uppercase }",
+        None,
+        &schema,
+        &Default::default(),
+        &viewer("user"),
+    );
+
+    assert_eq!(
+        result,
+        Ok((
+            Value::object(
+                vec![
+                    ("greeting", Value::scalar("HELLO")),
+                    ("plain", Value::scalar("hello")),
+                    ("count", Value::scalar(3)),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            vec![]
+        ))
+    );
+}
+
+#[tokio::test]
+async fn uppercase_transforms_string_field_async() {
+    let schema = schema();
+    let result = crate::execute(
+        "{ greetingAsync @uppercase }",
+        None,
+        &schema,
+        &Default::default(),
+        &viewer("user"),
+    )
+    .await;
+
+    assert_eq!(
+        result,
+        Ok((
+            Value::object(
+                vec![("greetingAsync", Value::scalar("HELLO"))]
+                    .into_iter()
+                    .collect(),
+            ),
+            vec![]
+        ))
+    );
+}
+
+#[test]
+fn directive_arguments_can_deny_access() {
+    let schema = schema();
+    let query = r#"{ greeting @requireRole(role: "admin") @uppercase }"#;
+
+    let denied = crate::execute_sync(query, None, &schema, &Default::default(), &viewer("user"));
+    assert_eq!(
+        denied,
+        Ok((
+            Value::null(),
+            vec![ExecutionError::new(
+                SourcePosition::new(2, 0, 2),
+                &["greeting"],
+                FieldError::new("Forbidden", Value::null()),
+            )]
+        ))
+    );
+
+    let allowed = crate::execute_sync(query, None, &schema, &Default::default(), &viewer("admin"));
+    assert_eq!(
+        allowed,
+        Ok((
+            Value::object(
+                vec![("greeting", Value::scalar("HELLO"))]
+                    .into_iter()
+                    .collect(),
+            ),
+            vec![]
+        ))
+    );
+}
+
+#[test]
+fn denied_field_is_not_resolved() {
+    let schema = schema();
+    let viewer = viewer("user");
+
+    let result = crate::execute_sync(
+        r#"{ secret @requireRole(role: "admin") }"#,
+        None,
+        &schema,
+        &Default::default(),
+        &viewer,
+    );
+
+    assert_eq!(
+        result,
+        Ok((
+            Value::null(),
+            vec![ExecutionError::new(
+                SourcePosition::new(2, 0, 2),
+                &["secret"],
+                FieldError::new("Forbidden", Value::null()),
+            )]
+        ))
+    );
+    assert_eq!(viewer.resolved.load(Ordering::SeqCst), 0);
+}
+
+#[tokio::test]
+async fn subscription_directives_guard_and_map_events() {
+    let schema = schema();
+
+    let viewer = viewer("user");
+    let (value, errors) = crate::resolve_into_stream(
+        r#"subscription { greetings @requireRole(role: "admin") }"#,
+        None,
+        &schema,
+        &Default::default(),
+        &viewer,
+    )
+    .await
+    .expect("Subscription failed");
+    match value {
+        Value::Null => {}
+        _ => panic!("Expected a null subscription result"),
+    }
+    assert_eq!(
+        errors,
+        vec![ExecutionError::new(
+            SourcePosition::new(15, 0, 15),
+            &["greetings"],
+            FieldError::new("Forbidden", Value::null()),
+        )]
+    );
+    assert_eq!(viewer.resolved.load(Ordering::SeqCst), 0);
+
+    let viewer = self::viewer("admin");
+    let (value, errors) = crate::resolve_into_stream(
+        r#"subscription { greetings @requireRole(role: "admin") @uppercase }"#,
+        None,
+        &schema,
+        &Default::default(),
+        &viewer,
+    )
+    .await
+    .expect("Subscription failed");
+    assert_eq!(errors, []);
+
+    let stream = match value {
+        Value::Object(fields) => match fields.into_iter().next() {
+            Some((name, Value::Scalar(stream))) if name == "greetings" => stream,
+            _ => panic!("Expected a stream of greetings"),
+        },
+        _ => panic!("Expected an object"),
+    };
+    assert_eq!(
+        stream.collect::<Vec<_>>().await,
+        vec![Ok(Value::scalar("HELLO")), Ok(Value::scalar("BYE"))]
+    );
+}
+
+#[test]
+fn unregistered_directive_is_rejected() {
+    let schema = schema();
+    let result = crate::execute_sync(
+        "{ greeting @lowercase }",
+        None,
+        &schema,
+        &Default::default(),
+        &viewer("user"),
+    );
+
+    assert_eq!(
+        result,
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            r#"Unknown directive "lowercase""#,
+            &[SourcePosition::new(11, 0, 11)],
+        )]))
+    );
+}
+
+#[test]
+fn registered_directives_are_part_of_the_schema() {
+    let schema = schema();
+
+    assert!(schema.schema.directive_by_name("uppercase").is_some());
+    let require_role = schema
+        .schema
+        .directive_by_name("requireRole")
+        .expect("directive should be registered");
+    assert_eq!(require_role.locations, vec![DirectiveLocation::Field]);
+    assert_eq!(require_role.arguments[0].name, "role");
+}
@@ -0,0 +1,203 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc, Mutex,
+};
+
+use futures::{future, poll};
+use juniper_codegen::GraphQLObjectInternal;
+
+use crate::{
+    dataloader::{DataLoader, DataLoaderError},
+    executor::{Context, FieldResult},
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    value::{DefaultScalarValue, Object, Value},
+};
+
+struct Loaders {
+    users: DataLoader<i32, User>,
+    batches: Arc<Mutex<Vec<Vec<i32>>>>,
+}
+
+impl Context for Loaders {}
+
+impl Loaders {
+    fn new() -> Self {
+        let batches = Arc::new(Mutex::new(vec![]));
+        let recorded = batches.clone();
+
+        Loaders {
+            users: DataLoader::new(move |ids: Vec<i32>| {
+                recorded.lock().unwrap().push(ids.clone());
+                async move {
+                    ids.into_iter()
+                        .map(|id| User {
+                            id,
+                            name: format!("user{}", id),
+                        })
+                        .collect()
+                }
+            }),
+            batches,
+        }
+    }
+
+    fn batches(&self) -> Vec<Vec<i32>> {
+        self.batches.lock().unwrap().clone()
+    }
+}
+
+#[derive(Clone, GraphQLObjectInternal)]
+#[graphql(Context = Loaders)]
+struct User {
+    id: i32,
+    name: String,
+}
+
+struct Query;
+
+#[crate::graphql_object_internal(Context = Loaders)]
+impl Query {
+    async fn user(id: i32, context: &Loaders) -> FieldResult<User> {
+        Ok(context.users.load(id).await?)
+    }
+
+    async fn users(ids: Vec<i32>, context: &Loaders) -> FieldResult<Vec<User>> {
+        future::join_all(ids.into_iter().map(|id| context.users.load(id)))
+            .await
+            .into_iter()
+            .map(|user| Ok(user?))
+            .collect()
+    }
+}
+
+fn schema() -> RootNode<'static, Query, EmptyMutation<Loaders>, EmptySubscription<Loaders>> {
+    RootNode::new(
+        Query,
+        EmptyMutation::<Loaders>::new(),
+        EmptySubscription::<Loaders>::new(),
+    )
+}
+
+fn user_name(name: &str) -> Value<DefaultScalarValue> {
+    Value::object(
+        vec![("name", Value::scalar(name))]
+            .into_iter()
+            .collect::<Object<_>>(),
+    )
+}
+
+#[tokio::test]
+async fn sibling_fields_load_in_one_batch() {
+    let schema = schema();
+    let context = Loaders::new();
+
+    let result = crate::execute(
+        "{ first: user(id: 1) { name } second: user(id: 2) { name } }",
+        None,
+        &schema,
+        &Default::default(),
+        &context,
+    )
+    .await;
+
+    assert_eq!(
+        result,
+        Ok((
+            Value::object(
+                vec![
+                    ("first", user_name("user1")),
+                    ("second", user_name("user2"))
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            vec![]
+        ))
+    );
+    assert_eq!(context.batches(), vec![vec![1, 2]]);
+}
+
+#[tokio::test]
+async fn repeated_keys_are_loaded_once() {
+    let schema = schema();
+    let context = Loaders::new();
+
+    let result = crate::execute(
+        "{ users(ids: [3, 4, 3]) { name } again: user(id: 4) { name } }",
+        None,
+        &schema,
+        &Default::default(),
+        &context,
+    )
+    .await;
+
+    assert_eq!(
+        result,
+        Ok((
+            Value::object(
+                vec![
+                    (
+                        "users",
+                        Value::list(vec![
+                            user_name("user3"),
+                            user_name("user4"),
+                            user_name("user3"),
+                        ]),
+                    ),
+                    ("again", user_name("user4")),
+                ]
+                .into_iter()
+                .collect(),
+            ),
+            vec![]
+        ))
+    );
+    assert_eq!(context.batches(), vec![vec![3, 4]]);
+
+    let cached = crate::execute(
+        "{ user(id: 3) { name } }",
+        None,
+        &schema,
+        &Default::default(),
+        &context,
+    )
+    .await;
+
+    assert!(cached.is_ok());
+    assert_eq!(context.batches(), vec![vec![3, 4]]);
+}
+
+#[tokio::test]
+async fn mismatched_batch_is_an_error() {
+    let loader = DataLoader::new(|_: Vec<i32>| async { vec![0] });
+
+    let (first, second) = future::join(loader.load(1), loader.load(2)).await;
+
+    let error = DataLoaderError::BatchSize { keys: 2, values: 1 };
+    assert_eq!(first, Err(error.clone()));
+    assert_eq!(second, Err(error));
+}
+
+#[tokio::test]
+async fn dropped_batch_does_not_stall_later_loads() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counted = calls.clone();
+    let loader = DataLoader::new(move |ids: Vec<i32>| {
+        let first = counted.fetch_add(1, Ordering::SeqCst) == 0;
+        async move {
+            if first {
+                future::pending::<()>().await;
+            }
+            ids
+        }
+    });
+
+    let mut running = Box::pin(loader.load(1));
+    assert!(poll!(running.as_mut()).is_pending());
+    assert!(poll!(running.as_mut()).is_pending());
+    drop(running);
+
+    assert_eq!(loader.load(1).await, Ok(1));
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
@@ -1,8 +1,12 @@
 use crate::{
+    ast::InputValue,
     executor::Variables,
+    parser::SourcePosition,
     schema::model::RootNode,
     types::scalars::{EmptyMutation, EmptySubscription},
+    validation::RuleError,
     value::{DefaultScalarValue, Object, Value},
+    GraphQLError,
 };
 
 struct TestType;
@@ -41,6 +45,22 @@ where
     f(obj);
 }
 
+async fn run_invalid_variable_query(
+    query: &str,
+    vars: Variables<DefaultScalarValue>,
+) -> Vec<RuleError> {
+    let schema = RootNode::new(
+        TestType,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    match crate::execute(query, None, &schema, &vars, &()).await {
+        Err(GraphQLError::ValidationError(errors)) => errors,
+        result => panic!("Expected validation errors, got {:#?}", result),
+    }
+}
+
 async fn run_query<F>(query: &str, f: F)
 where
     F: Fn(&Object<DefaultScalarValue>) -> (),
@@ -245,3 +265,112 @@ async fn scalar_include_false_skip_false() {
     })
     .await;
 }
+
+#[tokio::test]
+async fn include_with_missing_variable() {
+    let errors = run_invalid_variable_query(
+        "query($flag: Boolean!) { a, b @include(if: $flag) }",
+        Variables::new(),
+    )
+    .await;
+
+    assert_eq!(
+        errors,
+        vec![RuleError::new(
+            r#"Variable "$flag" of required type "Boolean!" was not provided."#,
+            &[SourcePosition::new(6, 0, 6)],
+        )]
+    );
+}
+
+#[tokio::test]
+async fn include_with_null_variable() {
+    let errors = run_invalid_variable_query(
+        "query($flag: Boolean!) { a, b @include(if: $flag) }",
+        vec![("flag".to_owned(), InputValue::null())]
+            .into_iter()
+            .collect(),
+    )
+    .await;
+
+    assert_eq!(
+        errors,
+        vec![RuleError::new(
+            r#"Variable "$flag" of required type "Boolean!" was not provided."#,
+            &[SourcePosition::new(6, 0, 6)],
+        )]
+    );
+}
+
+#[tokio::test]
+async fn include_with_non_boolean_variable() {
+    let errors = run_invalid_variable_query(
+        "query($flag: Boolean!) { a, b @include(if: $flag) }",
+        vec![("flag".to_owned(), InputValue::scalar("yes"))]
+            .into_iter()
+            .collect(),
+    )
+    .await;
+
+    assert_eq!(
+        errors,
+        vec![RuleError::new(
+            r#"Variable "$flag" got invalid value; expected type "Boolean!". Expected "Boolean", found "yes"."#,
+            &[SourcePosition::new(6, 0, 6)],
+        )]
+    );
+}
+
+#[tokio::test]
+async fn include_with_defaulted_variable() {
+    run_variable_query(
+        "query($flag: Boolean = false) { a, b @include(if: $flag) }",
+        Variables::new(),
+        |result| {
+            assert_eq!(result.get_field_value("a"), Some(&Value::scalar("a")));
+            assert_eq!(result.get_field_value("b"), None);
+        },
+    )
+    .await;
+}
+
+#[tokio::test]
+async fn include_with_explicit_null_overriding_default() {
+    let errors = run_invalid_variable_query(
+        "query($flag: Boolean = true) { a, b @include(if: $flag) }",
+        vec![("flag".to_owned(), InputValue::null())]
+            .into_iter()
+            .collect(),
+    )
+    .await;
+
+    assert_eq!(
+        errors,
+        vec![RuleError::new(
+            r#"Variable "$flag" got invalid value; expected type "Boolean!". In argument "if" of directive "@include": Expected "Boolean!", found null."#,
+            &[SourcePosition::new(49, 0, 49)],
+        )]
+    );
+}
+
+#[tokio::test]
+async fn skip_in_fragment_with_explicit_null_overriding_default() {
+    let errors = run_invalid_variable_query(
+        r#"
+            query($flag: Boolean = true) { a, ...Frag }
+            fragment Frag on TestType { b @skip(if: $flag) }
+        "#,
+        vec![("flag".to_owned(), InputValue::null())]
+            .into_iter()
+            .collect(),
+    )
+    .await;
+
+    assert_eq!(
+        errors,
+        vec![RuleError::new(
+            r#"Variable "$flag" got invalid value; expected type "Boolean!". In argument "if" of directive "@skip": Expected "Boolean!", found null."#,
+            &[SourcePosition::new(109, 2, 52)],
+        )]
+    );
+}
@@ -0,0 +1,207 @@
+use crate::{
+    ast::InputValue,
+    executor::{PlannedResolver, Variables},
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    value::DefaultScalarValue,
+    GraphQLError,
+};
+
+struct Author;
+
+#[crate::graphql_object_internal]
+impl Author {
+    fn name() -> &str {
+        "Jane"
+    }
+}
+
+struct Book;
+
+#[crate::graphql_object_internal]
+impl Book {
+    fn title() -> &str {
+        "Title"
+    }
+
+    fn author() -> Author {
+        Author
+    }
+
+    #[graphql(complexity(list_size = 3), arguments(limit(default = 5)))]
+    fn reviews(limit: i32) -> Vec<&str> {
+        vec!["good"; limit as usize]
+    }
+}
+
+struct Query;
+
+#[crate::graphql_object_internal]
+impl Query {
+    fn book(id: i32) -> Book {
+        let _ = id;
+        Book
+    }
+
+    #[graphql(complexity(list_size = 4))]
+    fn books() -> Vec<Book> {
+        vec![Book]
+    }
+
+    fn authors() -> Vec<Author> {
+        vec![Author]
+    }
+
+    fn unexecuted() -> bool {
+        panic!("Resolvers must not run during a dry run")
+    }
+}
+
+fn schema() -> RootNode<'static, Query, EmptyMutation<()>, EmptySubscription<()>> {
+    RootNode::new(
+        Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    )
+}
+
+fn planned(
+    parent_type: &str,
+    field_name: &str,
+    arguments: Vec<(&str, InputValue<DefaultScalarValue>)>,
+    count: usize,
+) -> PlannedResolver {
+    PlannedResolver {
+        parent_type: parent_type.to_owned(),
+        field_name: field_name.to_owned(),
+        arguments: arguments
+            .into_iter()
+            .map(|(name, value)| (name.to_owned(), value))
+            .collect(),
+        count,
+    }
+}
+
+#[test]
+fn enumerates_resolvers_of_a_nested_query() {
+    let schema = schema();
+    let vars: Variables = vec![("id".to_owned(), InputValue::scalar(7))]
+        .into_iter()
+        .collect();
+
+    let plan = schema
+        .dry_run(
+            r#"query($id: Int!) {
+                book(id: $id) { title author { name } }
+                books { ...BookFields }
+                __typename
+            }
+
+            fragment BookFields on Book {
+                reviews(limit: 2)
+                author { name }
+            }"#,
+            None,
+            &vars,
+            10,
+        )
+        .expect("Dry run failed");
+
+    assert_eq!(
+        plan,
+        vec![
+            planned("Query", "book", vec![("id", InputValue::scalar(7))], 1),
+            planned("Book", "title", vec![], 1),
+            planned("Book", "author", vec![], 1),
+            planned("Author", "name", vec![], 1),
+            planned("Query", "books", vec![], 1),
+            planned("Book", "reviews", vec![("limit", InputValue::scalar(2))], 4),
+            planned("Book", "author", vec![], 4),
+            planned("Author", "name", vec![], 4),
+        ]
+    );
+}
+
+#[test]
+fn applies_default_arguments() {
+    let plan = schema()
+        .dry_run("{ books { reviews } }", None, &Variables::new(), 10)
+        .expect("Dry run failed");
+
+    assert_eq!(
+        plan,
+        vec![
+            planned("Query", "books", vec![], 1),
+            planned("Book", "reviews", vec![("limit", InputValue::scalar(5))], 4),
+        ]
+    );
+}
+
+#[test]
+fn does_not_run_resolvers() {
+    let plan = schema()
+        .dry_run("{ unexecuted }", None, &Variables::new(), 10)
+        .expect("Dry run failed");
+
+    assert_eq!(plan, vec![planned("Query", "unexecuted", vec![], 1)]);
+}
+
+#[test]
+fn skips_excluded_selections() {
+    let plan = schema()
+        .dry_run(
+            "{ books @skip(if: true) { title } unexecuted @include(if: false) }",
+            None,
+            &Variables::new(),
+            10,
+        )
+        .expect("Dry run failed");
+
+    assert_eq!(plan, vec![]);
+}
+
+#[test]
+fn rejects_invalid_queries() {
+    let result = schema().dry_run("{ book { title } }", None, &Variables::new(), 10);
+
+    match result {
+        Err(GraphQLError::ValidationError(ref errors)) => assert_eq!(errors.len(), 1),
+        _ => panic!("Expected a validation error, got {:?}", result),
+    }
+}
+
+#[test]
+fn plans_the_given_operation() {
+    let plan = schema()
+        .dry_run(
+            "query A { books { title } } query B { unexecuted }",
+            Some("B"),
+            &Variables::new(),
+            10,
+        )
+        .expect("Dry run failed");
+
+    assert_eq!(plan, vec![planned("Query", "unexecuted", vec![], 1)]);
+}
+
+#[test]
+fn assumes_list_size_for_unhinted_lists() {
+    let plan = schema()
+        .dry_run(
+            "{ authors { name } books { title } }",
+            None,
+            &Variables::new(),
+            7,
+        )
+        .expect("Dry run failed");
+
+    assert_eq!(
+        plan,
+        vec![
+            planned("Query", "authors", vec![], 1),
+            planned("Author", "name", vec![], 7),
+            planned("Query", "books", vec![], 1),
+            planned("Book", "title", vec![], 4),
+        ]
+    );
+}
@@ -28,6 +28,11 @@ impl TestType {
     fn a_color() -> Color {
         Color::Red
     }
+
+    #[graphql(arguments(color(default = Color::Green)))]
+    fn with_default(color: Color) -> String {
+        format!("Color::{:?}", color)
+    }
 }
 
 async fn run_variable_query<F>(query: &str, vars: Variables<DefaultScalarValue>, f: F)
@@ -100,12 +105,77 @@ async fn does_not_accept_string_literals() {
     assert_eq!(
         error,
         ValidationError(vec![RuleError::new(
-            r#"Invalid value for argument "color", expected type "Color!""#,
+            r#"Invalid value for argument "color", expected type "Color!": one of RED, GREEN, BLUE"#,
             &[SourcePosition::new(18, 0, 18)],
         )])
     );
 }
 
+#[tokio::test]
+async fn does_not_accept_unknown_enum_literals() {
+    let schema = RootNode::new(
+        TestType,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let query = r#"{ toString(color: BLURPLE) }"#;
+    let vars = vec![].into_iter().collect();
+
+    let error = crate::execute(query, None, &schema, &vars, &())
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        ValidationError(vec![RuleError::new(
+            r#"Invalid value for argument "color", expected type "Color!": one of RED, GREEN, BLUE"#,
+            &[SourcePosition::new(18, 0, 18)],
+        )])
+    );
+}
+
+#[tokio::test]
+async fn uses_default_when_argument_is_omitted() {
+    run_query("{ withDefault }", |result| {
+        assert_eq!(
+            result.get_field_value("withDefault"),
+            Some(&Value::scalar("Color::Green"))
+        );
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn renders_default_as_enum_value() {
+    run_query(
+        r#"{ __type(name: "TestType") { fields { name args { defaultValue } } } }"#,
+        |result| {
+            let fields = result
+                .get_field_value("__type")
+                .and_then(|t| t.as_object_value())
+                .and_then(|t| t.get_field_value("fields"))
+                .and_then(|f| f.as_list_value())
+                .expect("fields not a list");
+            let field = fields
+                .iter()
+                .filter_map(|f| f.as_object_value())
+                .find(|f| f.get_field_value("name") == Some(&Value::scalar("withDefault")))
+                .expect("withDefault field not found");
+
+            assert_eq!(
+                field.get_field_value("args"),
+                Some(&Value::list(vec![Value::object(
+                    vec![("defaultValue", Value::scalar("GREEN"))]
+                        .into_iter()
+                        .collect(),
+                )])),
+            );
+        },
+    )
+    .await;
+}
+
 #[tokio::test]
 async fn accepts_strings_in_variables() {
     run_variable_query(
@@ -143,7 +213,7 @@ async fn does_not_accept_incorrect_enum_name_in_variables() {
     assert_eq!(
         error,
         ValidationError(vec![RuleError::new(
-            r#"Variable "$color" got invalid value. Invalid value for enum "Color"."#,
+            r#"Variable "$color" got invalid value; expected type "Color!". Invalid value for enum "Color", expected one of RED, GREEN, BLUE."#,
             &[SourcePosition::new(8, 0, 8)],
         )])
     );
@@ -169,7 +239,7 @@ async fn does_not_accept_incorrect_type_in_variables() {
     assert_eq!(
         error,
         ValidationError(vec![RuleError::new(
-            r#"Variable "$color" got invalid value. Expected "Color", found not a string or enum."#,
+            r#"Variable "$color" got invalid value; expected type "Color!". Expected "Color", found not a string or enum."#,
             &[SourcePosition::new(8, 0, 8)],
         )])
     );
@@ -0,0 +1,131 @@
+use crate::{
+    executor::{ExecutionError, FieldError},
+    parser::SourcePosition,
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    value::Value,
+    GraphQLError,
+};
+
+struct Query;
+
+#[crate::graphql_object_internal]
+impl Query {
+    fn a() -> &str {
+        "a"
+    }
+
+    /// Imports the valid items, reporting the invalid ones
+    fn import(executor: &Executor, items: Vec<i32>) -> i32 {
+        let (valid, invalid): (Vec<_>, Vec<_>) = items.into_iter().partition(|i| *i >= 0);
+        executor.push_errors(
+            invalid
+                .into_iter()
+                .map(|i| FieldError::new(format!("Invalid item {}", i), Value::null())),
+        );
+        valid.len() as i32
+    }
+}
+
+fn execute_err(doc: &str, operation_name: Option<&str>) -> String {
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    crate::execute_sync(doc, operation_name, &schema, &Default::default(), &())
+        .unwrap_err()
+        .to_string()
+}
+
+#[test]
+fn formats_parse_error_with_position() {
+    assert_eq!(
+        execute_err("{ a", None),
+        "Parse error: Unexpected end of input. At 0:3",
+    );
+}
+
+#[test]
+fn formats_every_validation_error() {
+    assert_eq!(
+        execute_err("{ b c }", None),
+        r#"Validation failed: Unknown field "b" on type "Query". At 0:2; Unknown field "c" on type "Query". At 0:4"#,
+    );
+}
+
+#[test]
+fn formats_operation_errors() {
+    let doc = "query First { a } query Second { a }";
+
+    assert_eq!(
+        execute_err(doc, None),
+        "Must provide operation name if query contains multiple operations",
+    );
+    assert_eq!(
+        execute_err(doc, Some("Third")),
+        r#"Unknown operation named "Third""#,
+    );
+}
+
+#[test]
+fn is_a_std_error() {
+    fn describe(error: &dyn std::error::Error) -> String {
+        error.to_string()
+    }
+
+    assert_eq!(
+        describe(&GraphQLError::NoOperationProvided),
+        "Must provide an operation",
+    );
+    assert_eq!(
+        describe(&GraphQLError::IsSubscription),
+        "Expected query, got subscription",
+    );
+    assert_eq!(
+        describe(&GraphQLError::NotSubscription),
+        "Expected subscription, got query",
+    );
+    assert_eq!(describe(&GraphQLError::TimedOut), "Execution timed out");
+    assert_eq!(
+        describe(&GraphQLError::MalformedRequest),
+        r#"Expected a GraphQL request object with a "query" string"#,
+    );
+}
+
+#[test]
+fn resolver_pushes_several_errors() {
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let result = crate::execute_sync(
+        "{ a import(items: [1, -2, 3, -4]) }",
+        None,
+        &schema,
+        &Default::default(),
+        &(),
+    );
+
+    let error = |message: &str| {
+        ExecutionError::new(
+            SourcePosition::new(4, 0, 4),
+            &["import"],
+            FieldError::new(message, Value::null()),
+        )
+    };
+    assert_eq!(
+        result,
+        Ok((
+            Value::object(
+                vec![("a", Value::scalar("a")), ("import", Value::scalar(2))]
+                    .into_iter()
+                    .collect()
+            ),
+            vec![error("Invalid item -2"), error("Invalid item -4")],
+        ))
+    );
+}
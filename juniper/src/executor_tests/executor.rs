@@ -827,7 +827,7 @@ mod dynamic_context_switching {
 
 mod propagates_errors_to_nullable_fields {
     use crate::{
-        executor::{ExecutionError, FieldError, FieldResult, IntoFieldError},
+        executor::{ExecutionError, FieldError, FieldResult, IntoFieldError, PathSegment},
         parser::SourcePosition,
         schema::model::RootNode,
         types::scalars::{EmptyMutation, EmptySubscription},
@@ -1095,7 +1095,11 @@ mod propagates_errors_to_nullable_fields {
             errs,
             vec![ExecutionError::new(
                 SourcePosition::new(11, 0, 11),
-                &["inners", "nonNullableErrorField"],
+                &[
+                    PathSegment::from("inners"),
+                    0.into(),
+                    "nonNullableErrorField".into(),
+                ],
                 FieldError::new("Error for nonNullableErrorField", Value::null()),
             )]
         );
@@ -1128,36 +1132,679 @@ mod propagates_errors_to_nullable_fields {
             vec![
                 ExecutionError::new(
                     SourcePosition::new(19, 0, 19),
-                    &["nullableInners", "nonNullableErrorField"],
+                    &[
+                        PathSegment::from("nullableInners"),
+                        0.into(),
+                        "nonNullableErrorField".into(),
+                    ],
                     FieldError::new("Error for nonNullableErrorField", Value::null()),
                 ),
                 ExecutionError::new(
                     SourcePosition::new(19, 0, 19),
-                    &["nullableInners", "nonNullableErrorField"],
+                    &[
+                        PathSegment::from("nullableInners"),
+                        1.into(),
+                        "nonNullableErrorField".into(),
+                    ],
                     FieldError::new("Error for nonNullableErrorField", Value::null()),
                 ),
                 ExecutionError::new(
                     SourcePosition::new(19, 0, 19),
-                    &["nullableInners", "nonNullableErrorField"],
+                    &[
+                        PathSegment::from("nullableInners"),
+                        2.into(),
+                        "nonNullableErrorField".into(),
+                    ],
                     FieldError::new("Error for nonNullableErrorField", Value::null()),
                 ),
                 ExecutionError::new(
                     SourcePosition::new(19, 0, 19),
-                    &["nullableInners", "nonNullableErrorField"],
+                    &[
+                        PathSegment::from("nullableInners"),
+                        3.into(),
+                        "nonNullableErrorField".into(),
+                    ],
                     FieldError::new("Error for nonNullableErrorField", Value::null()),
                 ),
                 ExecutionError::new(
                     SourcePosition::new(19, 0, 19),
-                    &["nullableInners", "nonNullableErrorField"],
+                    &[
+                        PathSegment::from("nullableInners"),
+                        4.into(),
+                        "nonNullableErrorField".into(),
+                    ],
                     FieldError::new("Error for nonNullableErrorField", Value::null()),
                 ),
             ]
         );
     }
+
+    #[tokio::test]
+    async fn non_nullable_deep_level_stops_at_nearest_nullable() {
+        let schema = RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+        let doc = r"{
+            inner {
+                nullableField { nonNullableField { nonNullableErrorField } }
+                nonNullableField { nullableField { __typename } }
+            }
+        }";
+
+        let vars = vec![].into_iter().collect();
+
+        let (result, errs) = crate::execute(doc, None, &schema, &vars, &())
+            .await
+            .expect("Execution failed");
+
+        assert_eq!(
+            result,
+            graphql_value!({
+                "inner": {
+                    "nullableField": None,
+                    "nonNullableField": { "nullableField": { "__typename": "Inner" } },
+                },
+            })
+        );
+
+        assert_eq!(
+            errs,
+            vec![ExecutionError::new(
+                SourcePosition::new(73, 2, 51),
+                &[
+                    "inner",
+                    "nullableField",
+                    "nonNullableField",
+                    "nonNullableErrorField",
+                ],
+                FieldError::new("Error for nonNullableErrorField", Value::null()),
+            )]
+        );
+    }
+
+    #[test]
+    fn disabled_propagation_nulls_in_place() {
+        let schema = RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        )
+        .disable_null_propagation();
+        let doc = r"{ inner { nonNullableField { nonNullableErrorField nullableField { __typename } } } }";
+
+        let vars = vec![].into_iter().collect();
+
+        let (result, errs) =
+            crate::execute_sync(doc, None, &schema, &vars, &()).expect("Execution failed");
+
+        assert_eq!(
+            result,
+            graphql_value!({
+                "inner": {
+                    "nonNullableField": {
+                        "nonNullableErrorField": None,
+                        "nullableField": { "__typename": "Inner" },
+                    },
+                },
+            })
+        );
+        assert_eq!(errs.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn disabled_propagation_nulls_list_items_in_place() {
+        let schema = RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        )
+        .disable_null_propagation();
+        let doc = r"{ inners { nonNullableErrorField } inner { __typename } }";
+
+        let vars = vec![].into_iter().collect();
+
+        let (result, errs) = crate::execute(doc, None, &schema, &vars, &())
+            .await
+            .expect("Execution failed");
+
+        assert_eq!(
+            result,
+            graphql_value!({
+                "inners": [
+                    { "nonNullableErrorField": None },
+                    { "nonNullableErrorField": None },
+                    { "nonNullableErrorField": None },
+                    { "nonNullableErrorField": None },
+                    { "nonNullableErrorField": None },
+                ],
+                "inner": { "__typename": "Inner" },
+            })
+        );
+        assert_eq!(errs.len(), 5);
+    }
+}
+
+mod error_sources {
+    use std::{error::Error, fmt, num::ParseIntError};
+
+    use crate::{
+        executor::{FieldError, FieldResult},
+        http::GraphQLResponse,
+        schema::model::RootNode,
+        types::scalars::{EmptyMutation, EmptySubscription},
+    };
+
+    #[derive(Debug)]
+    struct LoadError(ParseIntError);
+
+    impl fmt::Display for LoadError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "Failed to load age")
+        }
+    }
+
+    impl Error for LoadError {
+        fn source(&self) -> Option<&(dyn Error + 'static)> {
+            Some(&self.0)
+        }
+    }
+
+    struct Schema;
+
+    #[crate::graphql_object_internal]
+    impl Schema {
+        fn age() -> FieldResult<Option<i32>> {
+            let age = "forty"
+                .parse()
+                .map_err(LoadError)
+                .map_err(FieldError::from_error)?;
+            Ok(Some(age))
+        }
+
+        fn plain() -> FieldResult<Option<i32>> {
+            Err("Plain error")?
+        }
+    }
+
+    #[test]
+    fn source_chain_is_retained_but_not_serialized() {
+        let schema = RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+
+        let (_, errs) =
+            crate::execute_sync("{ age plain }", None, &schema, &Default::default(), &())
+                .expect("Execution failed");
+
+        assert_eq!(errs.len(), 2);
+        assert_eq!(
+            errs[0]
+                .error()
+                .source_chain()
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>(),
+            vec!["Failed to load age", "invalid digit found in string"]
+        );
+        assert!(errs[1].error().source().is_none());
+        assert_eq!(errs[1].error().source_chain().count(), 0);
+
+        assert_eq!(
+            serde_json::to_value(&errs[0]).unwrap(),
+            serde_json::json!({
+                "message": "Failed to load age",
+                "locations": [{"line": 1, "column": 3}],
+                "path": ["age"],
+            })
+        );
+    }
+
+    #[test]
+    fn source_chain_is_serialized_when_exposed() {
+        let schema = RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+
+        let response = GraphQLResponse::from_result(crate::execute_sync(
+            "{ age plain }",
+            None,
+            &schema,
+            &Default::default(),
+            &(),
+        ))
+        .expose_error_sources();
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap()["errors"],
+            serde_json::json!([
+                {
+                    "message": "Failed to load age",
+                    "locations": [{"line": 1, "column": 3}],
+                    "path": ["age"],
+                    "extensions": {
+                        "sources": ["Failed to load age", "invalid digit found in string"],
+                    },
+                },
+                {
+                    "message": "Plain error",
+                    "locations": [{"line": 1, "column": 7}],
+                    "path": ["plain"],
+                },
+            ])
+        );
+    }
+}
+
+mod displayed {
+    use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+
+    use crate::{
+        schema::model::RootNode,
+        types::scalars::{Displayed, EmptyMutation, EmptySubscription},
+    };
+
+    struct Schema;
+
+    #[crate::graphql_object_internal]
+    impl Schema {
+        fn address() -> Displayed<IpAddr> {
+            Displayed(IpAddr::V4(Ipv4Addr::new(192, 168, 0, 1)))
+        }
+
+        fn fallbacks() -> Vec<Displayed<IpAddr>> {
+            vec![Displayed(IpAddr::V6(Ipv6Addr::LOCALHOST))]
+        }
+    }
+
+    #[test]
+    fn resolves_as_a_string() {
+        let schema = RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+
+        let result = crate::execute_sync(
+            "{ address fallbacks }",
+            None,
+            &schema,
+            &Default::default(),
+            &(),
+        );
+
+        assert_eq!(
+            result,
+            Ok((
+                graphql_value!({ "address": "192.168.0.1", "fallbacks": ["::1"] }),
+                vec![],
+            )),
+        );
+    }
+
+    #[test]
+    fn is_typed_as_a_string() {
+        let schema = RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+
+        assert!(schema
+            .as_sdl()
+            .contains("address: String!\n  fallbacks: [String!]!"));
+    }
+}
+
+mod error_extensions {
+    use crate::{
+        executor::{FieldError, FieldResult},
+        http::GraphQLResponse,
+        schema::model::RootNode,
+        types::scalars::{EmptyMutation, EmptySubscription},
+    };
+
+    struct Schema;
+
+    #[crate::graphql_object_internal]
+    impl Schema {
+        fn search() -> FieldResult<Option<i32>> {
+            Err(FieldError::with_code("Rate limit exceeded", "RATE_LIMITED"))
+        }
+
+        fn upload() -> FieldResult<Option<i32>> {
+            Err(FieldError::with_extensions(
+                "File too large",
+                graphql_value!({ "code": "TOO_LARGE", "maxBytes": 1024 }),
+            ))
+        }
+    }
+
+    #[test]
+    fn serializes_codes_and_structured_data_under_extensions() {
+        let schema = RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+
+        let response = GraphQLResponse::from_result(crate::execute_sync(
+            "{ search upload }",
+            None,
+            &schema,
+            &Default::default(),
+            &(),
+        ));
+
+        assert_eq!(
+            serde_json::to_string(&response).unwrap(),
+            concat!(
+                r#"{"data":{"search":null,"upload":null},"errors":["#,
+                r#"{"message":"Rate limit exceeded","locations":[{"line":1,"column":3}],"#,
+                r#""path":["search"],"extensions":{"code":"RATE_LIMITED"}},"#,
+                r#"{"message":"File too large","locations":[{"line":1,"column":10}],"#,
+                r#""path":["upload"],"extensions":{"code":"TOO_LARGE","maxBytes":1024}}]}"#,
+            ),
+        );
+    }
+
+    #[test]
+    fn wraps_extensions_that_are_not_an_object() {
+        let error: FieldError = FieldError::with_extensions("Oops", graphql_value!("TOO_LARGE"));
+
+        assert_eq!(
+            error.extensions(),
+            &graphql_value!({ "value": "TOO_LARGE" }),
+        );
+    }
+}
+
+mod error_paths {
+    use crate::{
+        executor::{FieldError, FieldResult},
+        http::GraphQLResponse,
+        schema::model::RootNode,
+        types::scalars::{EmptyMutation, EmptySubscription},
+    };
+
+    struct Character {
+        id: i32,
+    }
+
+    #[crate::graphql_object_internal]
+    impl Character {
+        fn name(&self) -> FieldResult<Option<String>> {
+            if self.id == 121 {
+                Err(FieldError::from("Name is classified"))
+            } else {
+                Ok(Some(format!("Character {}", self.id)))
+            }
+        }
+
+        fn friends(&self) -> Vec<Character> {
+            (0..3)
+                .map(|i| Character {
+                    id: self.id * 10 + i,
+                })
+                .collect()
+        }
+    }
+
+    struct Schema;
+
+    #[crate::graphql_object_internal]
+    impl Schema {
+        fn hero() -> Character {
+            Character { id: 1 }
+        }
+    }
+
+    const QUERY: &str = "{ hero { friends { allies: friends { name } } } }";
+
+    const EXPECTED: &str = concat!(
+        r#"{"message":"Name is classified","locations":[{"line":1,"column":38}],"#,
+        r#""path":["hero","friends",2,"allies",1,"name"]}"#,
+    );
+
+    fn schema() -> RootNode<'static, Schema, EmptyMutation<()>, EmptySubscription<()>> {
+        RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        )
+    }
+
+    #[test]
+    fn include_list_indices() {
+        let (_, errors) =
+            crate::execute_sync(QUERY, None, &schema(), &Default::default(), &()).unwrap();
+
+        assert_eq!(errors.len(), 1);
+        assert_eq!(serde_json::to_string(&errors[0]).unwrap(), EXPECTED);
+    }
+
+    #[tokio::test]
+    async fn include_list_indices_async() {
+        let schema = schema();
+        let response = GraphQLResponse::from_result(
+            crate::execute(QUERY, None, &schema, &Default::default(), &()).await,
+        );
+
+        let json = serde_json::to_value(&response).unwrap();
+        assert_eq!(json["errors"].as_array().unwrap().len(), 1);
+        assert_eq!(json["errors"][0].to_string(), EXPECTED);
+    }
+}
+
+mod unknown_fields {
+    use crate::{
+        executor::{execute_validated_query, execute_validated_query_async, get_operation},
+        parser::parse_document_source,
+        schema::model::RootNode,
+        types::scalars::{EmptyMutation, EmptySubscription},
+        value::{DefaultScalarValue, Object, Value},
+    };
+
+    struct Hero;
+
+    #[crate::graphql_object_internal]
+    impl Hero {
+        fn name() -> &str {
+            "Luke"
+        }
+    }
+
+    struct Schema;
+
+    #[crate::graphql_object_internal]
+    impl Schema {
+        fn hero() -> Hero {
+            Hero
+        }
+    }
+
+    // Never validated, so the unknown field inside the fragment reaches the
+    // executor
+    const QUERY: &str = "{ hero { ...HeroFields } } fragment HeroFields on Hero { name codename }";
+
+    const EXPECTED_ERROR: &str = concat!(
+        r#"{"message":"Unknown field \"codename\" on type \"Hero\"","#,
+        r#""locations":[{"line":1,"column":63}],"path":["hero","codename"]}"#,
+    );
+
+    fn schema() -> RootNode<'static, Schema, EmptyMutation<()>, EmptySubscription<()>> {
+        RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        )
+    }
+
+    fn expected_value() -> Value<DefaultScalarValue> {
+        let hero: Object<_> = vec![("name", Value::scalar("Luke")), ("codename", Value::null())]
+            .into_iter()
+            .collect();
+        Value::object(vec![("hero", Value::object(hero))].into_iter().collect())
+    }
+
+    #[test]
+    fn resolve_to_null_with_an_error() {
+        let schema = schema();
+        let document = parse_document_source(QUERY, &schema.schema).unwrap();
+        let operation = get_operation(&document, None).unwrap();
+
+        let (value, errors) =
+            execute_validated_query(&document, operation, &schema, &Default::default(), &())
+                .unwrap();
+
+        assert_eq!(value, expected_value());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(serde_json::to_string(&errors[0]).unwrap(), EXPECTED_ERROR);
+    }
+
+    #[tokio::test]
+    async fn resolve_to_null_with_an_error_async() {
+        let schema = schema();
+        let document = parse_document_source(QUERY, &schema.schema).unwrap();
+        let operation = get_operation(&document, None).unwrap();
+
+        let (value, errors) =
+            execute_validated_query_async(&document, operation, &schema, &Default::default(), &())
+                .await
+                .unwrap();
+
+        assert_eq!(value, expected_value());
+        assert_eq!(errors.len(), 1);
+        assert_eq!(serde_json::to_string(&errors[0]).unwrap(), EXPECTED_ERROR);
+    }
+}
+
+mod misconfigured_leaf_types {
+    use crate::{
+        ast::{FromInputValue, InputValue},
+        executor::{ExecutionError, Registry},
+        parser::SourcePosition,
+        schema::{
+            meta::{EnumValue, MetaType},
+            model::RootNode,
+        },
+        types::{
+            async_await::GraphQLTypeAsync,
+            base::GraphQLType,
+            scalars::{EmptyMutation, EmptySubscription},
+        },
+        value::{DefaultScalarValue, ScalarValue, Value},
+        FieldError,
+    };
+
+    /// An enum that forgets to implement `resolve`
+    struct Broken;
+
+    impl<S: ScalarValue> GraphQLType<S> for Broken {
+        type Context = ();
+        type TypeInfo = ();
+
+        fn name(_: &()) -> Option<&str> {
+            Some("Broken")
+        }
+
+        fn meta<'r>(info: &(), registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+        where
+            S: 'r,
+        {
+            registry
+                .build_enum_type::<Self>(info, &[EnumValue::new("BROKEN")])
+                .into_meta()
+        }
+    }
+
+    impl<S: ScalarValue + Send + Sync> GraphQLTypeAsync<S> for Broken {}
+
+    impl<S: ScalarValue> FromInputValue<S> for Broken {
+        fn from_input_value(_: &InputValue<S>) -> Option<Self> {
+            Some(Broken)
+        }
+    }
+
+    struct Schema;
+
+    #[crate::graphql_object_internal]
+    impl Schema {
+        fn broken() -> Option<Broken> {
+            Some(Broken)
+        }
+
+        fn healthy() -> i32 {
+            1
+        }
+    }
+
+    fn expected() -> (Value, Vec<ExecutionError<DefaultScalarValue>>) {
+        (
+            Value::object(
+                vec![("broken", Value::null()), ("healthy", Value::scalar(1))]
+                    .into_iter()
+                    .collect(),
+            ),
+            vec![ExecutionError::new(
+                SourcePosition::new(2, 0, 2),
+                &["broken"],
+                FieldError::new(
+                    "resolve() must be implemented by non-object output types",
+                    Value::null(),
+                ),
+            )],
+        )
+    }
+
+    #[test]
+    fn errors_instead_of_panicking() {
+        let schema = RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+
+        let result = crate::execute_sync(
+            "{ broken healthy }",
+            None,
+            &schema,
+            &Default::default(),
+            &(),
+        );
+
+        assert_eq!(result, Ok(expected()));
+    }
+
+    #[tokio::test]
+    async fn errors_instead_of_panicking_async() {
+        let schema = RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+
+        let result = crate::execute(
+            "{ broken healthy }",
+            None,
+            &schema,
+            &Default::default(),
+            &(),
+        )
+        .await;
+
+        assert_eq!(result, Ok(expected()));
+    }
 }
 
 mod named_operations {
     use crate::{
+        executor::get_operation,
+        parser::parse_document_source,
         schema::model::RootNode,
         types::scalars::{EmptyMutation, EmptySubscription},
         value::Value,
@@ -1277,6 +1924,98 @@ mod named_operations {
             .await
             .unwrap_err();
 
-        assert_eq!(err, GraphQLError::UnknownOperationName);
+        assert_eq!(
+            err,
+            GraphQLError::UnknownOperationName("UnknownExample".to_owned())
+        );
+    }
+
+    #[test]
+    fn error_if_no_operation_provided() {
+        let schema = RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+        let doc = r"fragment F on Schema { a }";
+
+        let document = parse_document_source(doc, &schema.schema).unwrap();
+        let err = get_operation(&document, None).unwrap_err();
+
+        assert_eq!(err, GraphQLError::NoOperationProvided);
+    }
+}
+
+mod fragment_cycles {
+    use crate::{
+        executor::{execute_validated_query, execute_validated_query_async, get_operation},
+        parser::{parse_document_source, SourcePosition},
+        schema::model::RootNode,
+        types::scalars::{EmptyMutation, EmptySubscription},
+        validation::RuleError,
+        GraphQLError,
+    };
+
+    struct Hero;
+
+    #[crate::graphql_object_internal]
+    impl Hero {
+        fn name() -> &str {
+            "Luke"
+        }
+    }
+
+    struct Schema;
+
+    #[crate::graphql_object_internal]
+    impl Schema {
+        fn hero() -> Hero {
+            Hero
+        }
+    }
+
+    // Never validated, so only the executor stands between the spread of
+    // `HeroFields` within itself and an endless recursion
+    const QUERY: &str =
+        "{ hero { ...HeroFields } } fragment HeroFields on Hero { name ...HeroFields }";
+
+    fn schema() -> RootNode<'static, Schema, EmptyMutation<()>, EmptySubscription<()>> {
+        RootNode::new(
+            Schema,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        )
+    }
+
+    fn expected_error() -> GraphQLError<'static> {
+        GraphQLError::ValidationError(vec![RuleError::new(
+            r#"Cannot spread fragment "HeroFields" within itself"#,
+            &[SourcePosition::new(62, 0, 62)],
+        )])
+    }
+
+    #[test]
+    fn rejected_before_execution() {
+        let schema = schema();
+        let document = parse_document_source(QUERY, &schema.schema).unwrap();
+        let operation = get_operation(&document, None).unwrap();
+
+        let result =
+            execute_validated_query(&document, operation, &schema, &Default::default(), &());
+
+        assert_eq!(result.unwrap_err(), expected_error());
+    }
+
+    #[tokio::test]
+    async fn rejected_before_async_execution() {
+        let schema = schema();
+        let document = parse_document_source(QUERY, &schema.schema).unwrap();
+        let operation = get_operation(&document, None).unwrap();
+
+        let result =
+            execute_validated_query_async(&document, operation, &schema, &Default::default(), &())
+                .await;
+
+        assert_eq!(result.unwrap_err(), expected_error());
     }
 }
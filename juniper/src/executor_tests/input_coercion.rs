@@ -0,0 +1,165 @@
+use crate::{
+    ast::{FromInputValue, InputValue},
+    executor::{Registry, Variables},
+    schema::{
+        meta::{Argument, MetaType},
+        model::RootNode,
+    },
+    types::{
+        base::GraphQLType,
+        scalars::{EmptyMutation, EmptySubscription},
+    },
+    validation::RuleError,
+    value::{DefaultScalarValue, Value},
+    GraphQLError,
+};
+
+/// Input object reading its fields as they are, relying on the executor to
+/// fill in their defaults
+struct Paging {
+    offset: i32,
+    size: i32,
+}
+
+impl GraphQLType for Paging {
+    type Context = ();
+    type TypeInfo = ();
+
+    fn name(_: &()) -> Option<&'static str> {
+        Some("Paging")
+    }
+
+    fn meta<'r>(_: &(), registry: &mut Registry<'r>) -> MetaType<'r>
+    where
+        DefaultScalarValue: 'r,
+    {
+        let fields = &[
+            Argument::new("offset", registry.get_type::<i32>(&()))
+                .default_value(InputValue::scalar(0)),
+            registry.arg_with_default::<i32>("size", &10, &()),
+        ];
+        registry
+            .build_input_object_type::<Paging>(&(), fields)
+            .into_meta()
+    }
+}
+
+impl FromInputValue for Paging {
+    fn from_input_value(v: &InputValue) -> Option<Self> {
+        let obj = v.to_object_value()?;
+        Some(Paging {
+            offset: obj.get("offset")?.convert()?,
+            size: obj.get("size")?.convert()?,
+        })
+    }
+}
+
+struct Query;
+
+#[crate::graphql_object_internal]
+impl Query {
+    fn page(paging: Paging) -> String {
+        format!("{}..{}", paging.offset, paging.offset + paging.size)
+    }
+
+    fn pages(pagings: Vec<Paging>) -> Vec<String> {
+        pagings
+            .iter()
+            .map(|p| format!("{}..{}", p.offset, p.offset + p.size))
+            .collect()
+    }
+}
+
+fn execute(query: &str, vars: &Variables) -> Result<Value, Vec<RuleError>> {
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    match crate::execute_sync(query, None, &schema, vars, &()) {
+        Ok((value, errors)) => {
+            assert_eq!(errors, []);
+            Ok(value)
+        }
+        Err(GraphQLError::ValidationError(errors)) => Err(errors),
+        Err(e) => panic!("Expected a validation error, got {:?}", e),
+    }
+}
+
+fn assert_rejected(result: Result<Value, Vec<RuleError>>) {
+    assert!(
+        result.is_err(),
+        "Expected a validation error, got {:?}",
+        result,
+    );
+}
+
+#[test]
+fn fills_absent_fields_with_defaults() {
+    assert_eq!(
+        execute(r#"{ page(paging: {size: 5}) }"#, &Variables::new()),
+        Ok(Value::object(
+            vec![("page", Value::scalar("0..5"))].into_iter().collect()
+        )),
+    );
+    assert_eq!(
+        execute(r#"{ page(paging: {offset: 20}) }"#, &Variables::new()),
+        Ok(Value::object(
+            vec![("page", Value::scalar("20..30"))]
+                .into_iter()
+                .collect()
+        )),
+    );
+}
+
+#[test]
+fn fills_absent_fields_of_list_items_and_variables() {
+    let vars = vec![(
+        "pagings".to_owned(),
+        InputValue::list(vec![
+            InputValue::object(
+                vec![("offset", InputValue::scalar(5))]
+                    .into_iter()
+                    .collect(),
+            ),
+            InputValue::object(vec![("size", InputValue::scalar(1))].into_iter().collect()),
+        ]),
+    )]
+    .into_iter()
+    .collect();
+
+    assert_eq!(
+        execute(
+            r#"query q($pagings: [Paging!]!) { pages(pagings: $pagings) }"#,
+            &vars
+        ),
+        Ok(Value::object(
+            vec![(
+                "pages",
+                Value::list(vec![Value::scalar("5..15"), Value::scalar("0..1")]),
+            )]
+            .into_iter()
+            .collect()
+        )),
+    );
+}
+
+#[test]
+fn rejects_unknown_fields() {
+    assert_rejected(execute(
+        r#"{ page(paging: {limit: 5}) }"#,
+        &Variables::new(),
+    ));
+
+    let vars = vec![(
+        "paging".to_owned(),
+        InputValue::object(vec![("limit", InputValue::scalar(5))].into_iter().collect()),
+    )]
+    .into_iter()
+    .collect();
+    assert_rejected(execute(
+        r#"query q($paging: Paging!) { page(paging: $paging) }"#,
+        &vars,
+    ));
+}
@@ -0,0 +1,142 @@
+use futures::StreamExt;
+use juniper_codegen::GraphQLObjectInternal;
+
+use crate::{
+    executor::Context,
+    schema::model::RootNode,
+    types::{live::Live, scalars::EmptyMutation},
+    value::{DefaultScalarValue, Object, Value},
+};
+
+struct Thermostat {
+    reading: Live<Reading>,
+}
+
+impl Context for Thermostat {}
+
+#[derive(Clone, GraphQLObjectInternal)]
+#[graphql(Context = Thermostat)]
+struct Reading {
+    celsius: i32,
+}
+
+struct Query;
+
+#[crate::graphql_object_internal(Context = Thermostat)]
+impl Query {
+    fn reading(context: &Thermostat) -> Live<Reading> {
+        context.reading.clone()
+    }
+}
+
+struct Subscription;
+
+#[crate::graphql_subscription_internal(Context = Thermostat)]
+impl Subscription {
+    async fn reading(context: &Thermostat) -> Live<Reading> {
+        context.reading.clone()
+    }
+}
+
+fn schema() -> RootNode<'static, Query, EmptyMutation<Thermostat>, Subscription> {
+    RootNode::new(Query, EmptyMutation::<Thermostat>::new(), Subscription)
+}
+
+fn reading(celsius: i32) -> Value<DefaultScalarValue> {
+    Value::object(
+        vec![(
+            "reading",
+            Value::object(
+                vec![("celsius", Value::scalar(celsius))]
+                    .into_iter()
+                    .collect::<Object<_>>(),
+            ),
+        )]
+        .into_iter()
+        .collect(),
+    )
+}
+
+#[tokio::test]
+async fn query_resolves_current_value() {
+    let schema = schema();
+    let context = Thermostat {
+        reading: Live::new(Reading { celsius: 20 }),
+    };
+
+    let result = crate::execute(
+        "{ reading { celsius } }",
+        None,
+        &schema,
+        &Default::default(),
+        &context,
+    )
+    .await;
+    assert_eq!(result, Ok((reading(20), vec![])));
+
+    context.reading.set(Reading { celsius: 21 });
+
+    let result = crate::execute_sync(
+        "{ reading { celsius } }",
+        None,
+        &schema,
+        &Default::default(),
+        &context,
+    );
+    assert_eq!(result, Ok((reading(21), vec![])));
+}
+
+#[tokio::test]
+async fn subscription_streams_updates_of_the_queried_value() {
+    let schema = schema();
+    let context = Thermostat {
+        reading: Live::new(Reading { celsius: 20 }),
+    };
+
+    let (value, errors) = crate::resolve_into_stream(
+        "subscription { reading { celsius } }",
+        None,
+        &schema,
+        &Default::default(),
+        &context,
+    )
+    .await
+    .expect("Subscription failed");
+    assert_eq!(errors, []);
+
+    let mut stream = match value {
+        Value::Object(fields) => match fields.into_iter().next() {
+            Some((name, Value::Scalar(stream))) if name == "reading" => stream,
+            _ => panic!("Expected a stream of readings"),
+        },
+        _ => panic!("Expected an object"),
+    };
+
+    let celsius = |celsius: i32| {
+        Ok(Value::object(
+            vec![("celsius", Value::scalar(celsius))]
+                .into_iter()
+                .collect::<Object<_>>(),
+        ))
+    };
+
+    assert_eq!(stream.next().await, Some(celsius(20)));
+
+    context.reading.set(Reading { celsius: 22 });
+    assert_eq!(stream.next().await, Some(celsius(22)));
+
+    context.reading.set(Reading { celsius: 23 });
+    context.reading.set(Reading { celsius: 24 });
+    context.reading.close();
+    assert_eq!(stream.collect::<Vec<_>>().await, vec![celsius(24)]);
+
+    let result = crate::execute(
+        "{ reading { celsius } }",
+        None,
+        &schema,
+        &Default::default(),
+        &context,
+    )
+    .await;
+    assert_eq!(result, Ok((reading(24), vec![])));
+}
@@ -0,0 +1,187 @@
+use std::sync::Mutex;
+
+use juniper_codegen::GraphQLObjectInternal;
+
+use crate::{
+    ast::InputValue,
+    executor::{Context, LookAheadMethods, LookAheadValue},
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    value::{DefaultScalarValue, ScalarValue, Value},
+};
+
+#[derive(Default)]
+struct Database {
+    queries: Mutex<Vec<String>>,
+}
+
+impl Context for Database {}
+
+#[derive(GraphQLObjectInternal)]
+#[graphql(Context = Database)]
+struct Post {
+    title: String,
+}
+
+struct User {
+    id: i32,
+    name: String,
+}
+
+#[crate::graphql_object_internal(Context = Database)]
+impl User {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn email(&self) -> String {
+        format!("{}@example.com", self.name)
+    }
+
+    fn posts(&self, first: Option<i32>) -> Vec<Post> {
+        (0..first.unwrap_or(1))
+            .map(|i| Post {
+                title: format!("post{}", i),
+            })
+            .collect()
+    }
+}
+
+struct Query;
+
+#[crate::graphql_object_internal(Context = Database)]
+impl Query {
+    fn users(limit: i32, executor: &Executor<Database>) -> Vec<User> {
+        let look_ahead = executor.look_ahead();
+
+        let columns = look_ahead
+            .children()
+            .into_iter()
+            .filter(|child| !child.has_children())
+            .map(|child| child.field_name())
+            .collect::<Vec<_>>();
+        let posts_limit = look_ahead
+            .select_child("posts")
+            .and_then(|posts| posts.argument("first"))
+            .map(|first| match *first.value() {
+                LookAheadValue::Scalar(s) => s.as_int().unwrap(),
+                _ => panic!("Expected an integer"),
+            });
+
+        executor.context().queries.lock().unwrap().push(format!(
+            "SELECT {} FROM users LIMIT {}; posts: {:?}",
+            columns.join(", "),
+            limit,
+            posts_limit,
+        ));
+
+        (0..limit)
+            .map(|id| User {
+                id,
+                name: format!("user{}", id),
+            })
+            .collect()
+    }
+}
+
+fn schema() -> RootNode<'static, Query, EmptyMutation<Database>, EmptySubscription<Database>> {
+    RootNode::new(
+        Query,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    )
+}
+
+#[test]
+fn resolver_selects_requested_columns() {
+    let schema = schema();
+    let database = Database::default();
+
+    let vars = vec![("limit".to_owned(), InputValue::scalar(2))]
+        .into_iter()
+        .collect();
+    let (result, errors) = crate::execute_sync(
+        "query($limit: Int!) { users(limit: $limit) { id name posts(first: 3) { title } } }",
+        None,
+        &schema,
+        &vars,
+        &database,
+    )
+    .expect("Execution failed");
+
+    assert_eq!(errors, []);
+    assert_eq!(
+        result
+            .as_object_value()
+            .and_then(|o| o.get_field_value("users"))
+            .and_then(Value::as_list_value)
+            .map(Vec::len),
+        Some(2)
+    );
+    assert_eq!(
+        *database.queries.lock().unwrap(),
+        vec!["SELECT id, name FROM users LIMIT 2; posts: Some(3)".to_owned()]
+    );
+}
+
+#[test]
+fn look_ahead_substitutes_variables() {
+    struct Inspect;
+
+    #[crate::graphql_object_internal(Context = Database)]
+    impl Inspect {
+        fn users(executor: &Executor<Database>) -> Vec<User> {
+            let look_ahead = executor.look_ahead();
+            assert_eq!(look_ahead.field_name(), "users");
+            assert_eq!(
+                look_ahead
+                    .children()
+                    .into_iter()
+                    .map(|c| c.field_name())
+                    .collect::<Vec<_>>(),
+                vec!["email", "posts"]
+            );
+
+            let posts = look_ahead.select_child("posts").expect("posts selected");
+            assert_eq!(posts.child_names(), vec!["title"]);
+            assert_eq!(
+                posts.argument("first").map(|a| a.value()),
+                Some(&LookAheadValue::Scalar(&DefaultScalarValue::Int(5)))
+            );
+
+            executor
+                .context()
+                .queries
+                .lock()
+                .unwrap()
+                .push("inspected".to_owned());
+            vec![]
+        }
+    }
+
+    let schema = RootNode::new(
+        Inspect,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    );
+    let database = Database::default();
+
+    let vars = vec![("first".to_owned(), InputValue::scalar(5))]
+        .into_iter()
+        .collect();
+    let (_, errors) = crate::execute_sync(
+        "query($first: Int) { users { email posts(first: $first) { title } } }",
+        None,
+        &schema,
+        &vars,
+        &database,
+    )
+    .expect("Execution failed");
+
+    assert_eq!(errors, []);
+    assert_eq!(*database.queries.lock().unwrap(), vec!["inspected"]);
+}
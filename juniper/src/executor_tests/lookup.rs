@@ -0,0 +1,160 @@
+use juniper_codegen::GraphQLObjectInternal;
+
+use crate::{
+    executor::{ExecutionError, FieldError, Lookup},
+    parser::SourcePosition,
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    value::{DefaultScalarValue, Object, Value},
+};
+
+#[derive(GraphQLObjectInternal)]
+struct User {
+    id: i32,
+    name: String,
+}
+
+struct Query;
+
+#[crate::graphql_object_internal]
+impl Query {
+    fn user(id: i32) -> Lookup<User> {
+        if id == 1 {
+            Lookup::Found(User {
+                id,
+                name: "Alice".to_owned(),
+            })
+        } else {
+            Lookup::NotFound
+        }
+    }
+
+    async fn user_async(id: i32) -> Lookup<User> {
+        Some(User {
+            id,
+            name: "Bob".to_owned(),
+        })
+        .filter(|u| u.id == 2)
+        .into()
+    }
+}
+
+fn schema() -> RootNode<'static, Query, EmptyMutation<()>, EmptySubscription<()>> {
+    RootNode::new(
+        Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    )
+}
+
+fn user(id: i32, name: &str) -> Value<DefaultScalarValue> {
+    Value::object(
+        vec![("id", Value::scalar(id)), ("name", Value::scalar(name))]
+            .into_iter()
+            .collect::<Object<_>>(),
+    )
+}
+
+fn not_found(path: &str) -> ExecutionError<DefaultScalarValue> {
+    ExecutionError::new(
+        SourcePosition::new(2, 0, 2),
+        &[path],
+        FieldError::new(
+            "Not found",
+            Value::object(
+                vec![("code", Value::scalar("NOT_FOUND"))]
+                    .into_iter()
+                    .collect(),
+            ),
+        ),
+    )
+}
+
+#[test]
+fn found_lookup_resolves_object() {
+    let schema = schema();
+    let result = crate::execute_sync(
+        "{ user(id: 1) { id name } }",
+        None,
+        &schema,
+        &Default::default(),
+        &(),
+    );
+
+    assert_eq!(
+        result,
+        Ok((
+            Value::object(vec![("user", user(1, "Alice"))].into_iter().collect()),
+            vec![]
+        ))
+    );
+}
+
+#[test]
+fn missing_lookup_resolves_null_with_not_found_code() {
+    let schema = schema();
+    let result = crate::execute_sync(
+        "{ user(id: 2) { id name } }",
+        None,
+        &schema,
+        &Default::default(),
+        &(),
+    );
+
+    assert_eq!(
+        result,
+        Ok((
+            Value::object(vec![("user", Value::null())].into_iter().collect()),
+            vec![not_found("user")]
+        ))
+    );
+}
+
+#[tokio::test]
+async fn async_lookup() {
+    let schema = schema();
+
+    let found = crate::execute(
+        "{ userAsync(id: 2) { id name } }",
+        None,
+        &schema,
+        &Default::default(),
+        &(),
+    )
+    .await;
+    assert_eq!(
+        found,
+        Ok((
+            Value::object(vec![("userAsync", user(2, "Bob"))].into_iter().collect()),
+            vec![]
+        ))
+    );
+
+    let missing = crate::execute(
+        "{ userAsync(id: 1) { id name } }",
+        None,
+        &schema,
+        &Default::default(),
+        &(),
+    )
+    .await;
+    assert_eq!(
+        missing,
+        Ok((
+            Value::object(vec![("userAsync", Value::null())].into_iter().collect()),
+            vec![not_found("userAsync")]
+        ))
+    );
+}
+
+#[test]
+fn lookup_field_is_nullable() {
+    let schema = schema();
+    let field = schema
+        .schema
+        .concrete_query_type()
+        .field_by_name("user")
+        .unwrap();
+
+    assert_eq!(field.field_type.to_string(), "User");
+}
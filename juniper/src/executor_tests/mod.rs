@@ -1,7 +1,24 @@
+mod authorization;
+mod cache_control;
+mod complexity;
+mod context_switching;
+mod custom_directives;
+mod dataloader;
 mod directives;
+mod dry_run;
 mod enums;
+mod errors;
 mod executor;
+mod input_coercion;
 mod introspection;
+mod live;
+mod look_ahead;
+mod lookup;
+mod operation_inspection;
+#[cfg(feature = "relay")]
+mod relay;
+mod resolver_log;
+mod tracing;
 mod variables;
 
 // FIXME: re-enable
@@ -0,0 +1,121 @@
+use std::sync::Mutex;
+
+use crate::{
+    ast::Selection,
+    executor::Context,
+    parser::SourcePosition,
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    validation::RuleError,
+    value::Value,
+    GraphQLError,
+};
+
+/// Records the names of the inspected operations
+#[derive(Default)]
+struct Inspected(Mutex<Vec<Option<String>>>);
+
+impl Context for Inspected {}
+
+struct Query;
+
+#[crate::graphql_object_internal(Context = Inspected)]
+impl Query {
+    fn public() -> &str {
+        "public"
+    }
+
+    fn secret() -> &str {
+        "secret"
+    }
+}
+
+fn schema() -> RootNode<'static, Query, EmptyMutation<Inspected>, EmptySubscription<Inspected>> {
+    RootNode::new(
+        Query,
+        EmptyMutation::<Inspected>::new(),
+        EmptySubscription::<Inspected>::new(),
+    )
+    .inspect_operations(|_, operation, inspected: &Inspected| {
+        inspected
+            .0
+            .lock()
+            .unwrap()
+            .push(operation.name.as_ref().map(|n| n.item.to_owned()));
+
+        for selection in &operation.selection_set {
+            if let Selection::Field(ref field) = *selection {
+                if field.item.name.item == "secret" {
+                    return Err(RuleError::new(
+                        "Field \"secret\" is not allowed",
+                        &[field.start],
+                    ));
+                }
+            }
+        }
+        Ok(())
+    })
+}
+
+#[test]
+fn rejects_blacklisted_top_level_field() {
+    let schema = schema();
+    let inspected = Inspected::default();
+
+    let result = crate::execute_sync(
+        "query Leak { public secret }",
+        None,
+        &schema,
+        &Default::default(),
+        &inspected,
+    );
+
+    assert_eq!(
+        result,
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            "Field \"secret\" is not allowed",
+            &[SourcePosition::new(20, 0, 20)],
+        )]))
+    );
+    assert_eq!(*inspected.0.lock().unwrap(), vec![Some("Leak".to_owned())]);
+}
+
+#[tokio::test]
+async fn inspects_operations_before_executing_them() {
+    let schema = schema();
+    let inspected = Inspected::default();
+
+    let result = crate::execute(
+        "query Open { public } query Leak { secret }",
+        Some("Open"),
+        &schema,
+        &Default::default(),
+        &inspected,
+    )
+    .await;
+    assert_eq!(
+        result,
+        Ok((
+            Value::object(
+                vec![("public", Value::scalar("public"))]
+                    .into_iter()
+                    .collect()
+            ),
+            vec![],
+        ))
+    );
+
+    let result = crate::execute("{ secret }", None, &schema, &Default::default(), &inspected).await;
+    assert_eq!(
+        result,
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            "Field \"secret\" is not allowed",
+            &[SourcePosition::new(2, 0, 2)],
+        )]))
+    );
+
+    assert_eq!(
+        *inspected.0.lock().unwrap(),
+        vec![Some("Open".to_owned()), None]
+    );
+}
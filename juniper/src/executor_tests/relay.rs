@@ -0,0 +1,155 @@
+use juniper_codegen::GraphQLObjectInternal;
+use serde_json::json;
+
+use crate::{
+    executor::FieldResult,
+    relay::{Connection, ConnectionArguments, ConnectionNode},
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+};
+
+#[derive(GraphQLObjectInternal)]
+struct User {
+    id: i32,
+}
+
+impl ConnectionNode for User {
+    const CONNECTION_TYPE_NAME: &'static str = "UserConnection";
+    const EDGE_TYPE_NAME: &'static str = "UserEdge";
+}
+
+struct Query;
+
+#[crate::graphql_object_internal]
+impl Query {
+    fn users(
+        first: Option<i32>,
+        after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+    ) -> FieldResult<Connection<User>> {
+        let users = (0..5).map(|id| User { id }).collect();
+        let args = ConnectionArguments {
+            first,
+            after,
+            last,
+            before,
+        };
+        Ok(Connection::from_nodes(users, |u| u.id.to_string()).paginate(&args)?)
+    }
+}
+
+fn schema() -> RootNode<'static, Query, EmptyMutation<()>, EmptySubscription<()>> {
+    RootNode::new(
+        Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    )
+}
+
+fn query(args: &str) -> String {
+    format!(
+        "{{ users({}) {{ \
+            edges {{ node {{ id }} }} \
+            pageInfo {{ hasNextPage hasPreviousPage startCursor endCursor }} \
+        }} }}",
+        args,
+    )
+}
+
+fn page(args: &str) -> serde_json::Value {
+    let schema = schema();
+    let (result, errors) =
+        crate::execute_sync(&query(args), None, &schema, &Default::default(), &())
+            .expect("Execution failed");
+
+    assert_eq!(errors, []);
+    let users = serde_json::to_value(&result).unwrap()["users"].take();
+    json!({
+        "ids": users["edges"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|e| e["node"]["id"].clone())
+            .collect::<Vec<_>>(),
+        "pageInfo": users["pageInfo"],
+    })
+}
+
+fn expected(ids: &[i32], next: bool, previous: bool, start: &str, end: &str) -> serde_json::Value {
+    json!({
+        "ids": ids,
+        "pageInfo": {
+            "hasNextPage": next,
+            "hasPreviousPage": previous,
+            "startCursor": start,
+            "endCursor": end,
+        },
+    })
+}
+
+#[test]
+fn paginates_forward() {
+    assert_eq!(
+        page("first: 2"),
+        expected(&[0, 1], true, false, "MA==", "MQ=="),
+    );
+    assert_eq!(
+        page(r#"first: 2, after: "MQ==""#),
+        expected(&[2, 3], true, true, "Mg==", "Mw=="),
+    );
+    assert_eq!(
+        page(r#"first: 2, after: "Mw==""#),
+        expected(&[4], false, true, "NA==", "NA=="),
+    );
+}
+
+#[test]
+fn paginates_backward() {
+    assert_eq!(
+        page("last: 2"),
+        expected(&[3, 4], false, true, "Mw==", "NA=="),
+    );
+    assert_eq!(
+        page(r#"last: 2, before: "Mw==""#),
+        expected(&[1, 2], true, true, "MQ==", "Mg=="),
+    );
+    assert_eq!(
+        page(r#"last: 2, before: "MQ==""#),
+        expected(&[0], true, false, "MA==", "MA=="),
+    );
+}
+
+#[tokio::test]
+async fn resolves_async_and_rejects_invalid_arguments() {
+    let schema = schema();
+
+    let (result, errors) = crate::execute(
+        &query(r#"after: "MA==", before: "NA==""#),
+        None,
+        &schema,
+        &Default::default(),
+        &(),
+    )
+    .await
+    .expect("Execution failed");
+    assert_eq!(errors, []);
+    assert_eq!(
+        serde_json::to_value(&result).unwrap()["users"]["edges"],
+        json!([{"node": {"id": 1}}, {"node": {"id": 2}}, {"node": {"id": 3}}]),
+    );
+
+    for (args, message) in &[
+        (
+            r#"after: "not a cursor""#,
+            r#"Invalid cursor "not a cursor""#,
+        ),
+        ("first: -1", r#"Argument "first" must not be negative"#),
+    ] {
+        let (_, errors) = crate::execute(&query(args), None, &schema, &Default::default(), &())
+            .await
+            .expect("Execution failed");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].error().message(), *message);
+    }
+}
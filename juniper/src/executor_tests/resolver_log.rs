@@ -0,0 +1,92 @@
+use std::{
+    sync::{Arc, Mutex},
+    thread,
+    time::Duration,
+};
+
+use crate::{
+    executor::{FieldError, FieldResult, PathSegment},
+    http::GraphQLRequest,
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    value::Value,
+};
+
+struct Query;
+
+#[crate::graphql_object_internal]
+impl Query {
+    fn fast() -> i32 {
+        1
+    }
+
+    fn slow() -> i32 {
+        thread::sleep(Duration::from_millis(50));
+        2
+    }
+
+    fn broken() -> FieldResult<i32> {
+        Err(FieldError::new("Broken", Value::null()))
+    }
+}
+
+type Logged = Arc<Mutex<Vec<(Vec<PathSegment>, Duration, Option<String>)>>>;
+
+fn schema() -> (
+    RootNode<'static, Query, EmptyMutation<()>, EmptySubscription<()>>,
+    Logged,
+) {
+    let logged = Logged::default();
+    let log = logged.clone();
+    let schema = RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new())
+        .log_resolvers(Duration::from_millis(20), move |path, duration, error| {
+            log.lock().unwrap().push((
+                path.to_vec(),
+                duration,
+                error.map(|e| e.message().to_owned()),
+            ))
+        });
+    (schema, logged)
+}
+
+fn assert_logged_slow_and_broken(logged: &Logged) {
+    let mut logged = logged.lock().unwrap().clone();
+    logged.sort_by(|a, b| a.0.cmp(&b.0));
+
+    assert_eq!(logged.len(), 2);
+    assert_eq!(logged[0].0, vec![PathSegment::from("broken")]);
+    assert_eq!(logged[0].2, Some("Broken".to_owned()));
+    assert_eq!(logged[1].0, vec![PathSegment::from("slow")]);
+    assert!(logged[1].1 >= Duration::from_millis(50));
+    assert_eq!(logged[1].2, None);
+}
+
+#[test]
+fn logs_slow_and_failed_sync_resolvers() {
+    let (schema, logged) = schema();
+
+    let (_, errors) = crate::execute_sync(
+        "{ fast slow broken }",
+        None,
+        &schema,
+        &Default::default(),
+        &(),
+    )
+    .unwrap();
+
+    assert_eq!(errors.len(), 1);
+    assert_logged_slow_and_broken(&logged);
+}
+
+#[tokio::test]
+async fn logs_slow_and_failed_async_resolvers() {
+    let (schema, logged) = schema();
+    let request = GraphQLRequest::new("{ fast slow broken }".to_owned(), None, None);
+
+    let response = request.execute(&schema, &()).await;
+
+    assert!(response.is_ok());
+    let json = serde_json::to_value(&response).unwrap();
+    assert_eq!(json["errors"].as_array().map(Vec::len), Some(1));
+    assert_logged_slow_and_broken(&logged);
+}
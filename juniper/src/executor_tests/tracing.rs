@@ -0,0 +1,123 @@
+use std::time::Duration;
+
+use crate::{
+    executor::PathSegment,
+    http::GraphQLRequest,
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+};
+
+struct Query;
+
+#[crate::graphql_object_internal]
+impl Query {
+    async fn fast() -> i32 {
+        1
+    }
+
+    async fn slow() -> i32 {
+        tokio::time::delay_for(Duration::from_millis(50)).await;
+        2
+    }
+
+    fn items() -> Vec<Item> {
+        vec![Item, Item]
+    }
+}
+
+struct Item;
+
+#[crate::graphql_object_internal]
+impl Item {
+    fn name() -> &str {
+        "item"
+    }
+}
+
+fn schema() -> RootNode<'static, Query, EmptyMutation<()>, EmptySubscription<()>> {
+    RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new())
+}
+
+#[tokio::test]
+async fn slow_field_takes_longer_than_fast_sibling() {
+    let schema = schema().enable_tracing();
+    let request = GraphQLRequest::new("{ fast slow }".to_owned(), None, None);
+
+    let response = request.execute(&schema, &()).await;
+    let tracing = response.tracing().expect("tracing should be recorded");
+
+    let fast = tracing.field_duration(&["fast"]).unwrap();
+    let slow = tracing.field_duration(&["slow"]).unwrap();
+    assert!(slow >= Duration::from_millis(50));
+    assert!(slow > fast);
+    assert!(tracing.duration() >= slow);
+}
+
+#[tokio::test]
+async fn records_paths_of_nested_fields() {
+    let schema = schema().enable_tracing();
+    let request = GraphQLRequest::new("{ items { name } }".to_owned(), None, None);
+
+    let response = request.execute(&schema, &()).await;
+    let tracing = response.tracing().unwrap();
+
+    let mut paths = tracing
+        .resolvers()
+        .iter()
+        .map(|t| t.path.clone())
+        .collect::<Vec<_>>();
+    paths.sort();
+    assert_eq!(
+        paths,
+        vec![
+            vec![PathSegment::from("items")],
+            vec!["items".into(), 0.into(), "name".into()],
+            vec!["items".into(), 1.into(), "name".into()],
+        ],
+    );
+
+    let items = tracing
+        .resolvers()
+        .iter()
+        .find(|t| t.field_name == "items")
+        .unwrap();
+    assert_eq!(items.parent_type, "Query");
+    assert_eq!(items.return_type, "[Item!]!");
+}
+
+#[tokio::test]
+async fn emits_apollo_tracing_extension() {
+    let schema = schema().enable_tracing();
+    let request = GraphQLRequest::new("{ fast }".to_owned(), None, None);
+
+    let response = request.execute(&schema, &()).await;
+    let json = serde_json::to_value(&response).unwrap();
+
+    assert_eq!(json["data"], serde_json::json!({"fast": 1}));
+    let tracing = &json["extensions"]["tracing"];
+    assert_eq!(tracing["version"], 1);
+    assert!(tracing["startTime"].as_str().unwrap().ends_with('Z'));
+    assert!(tracing["duration"].is_u64());
+
+    let resolver = &tracing["execution"]["resolvers"][0];
+    assert_eq!(resolver["path"], serde_json::json!(["fast"]));
+    assert_eq!(resolver["parentType"], "Query");
+    assert_eq!(resolver["fieldName"], "fast");
+    assert_eq!(resolver["returnType"], "Int!");
+    assert!(resolver["startOffset"].is_u64());
+    assert!(resolver["duration"].is_u64());
+}
+
+#[tokio::test]
+async fn untraced_by_default() {
+    let schema = schema();
+    let request = GraphQLRequest::new("{ fast }".to_owned(), None, None);
+
+    let response = request.execute(&schema, &()).await;
+
+    assert!(response.tracing().is_none());
+    assert_eq!(
+        serde_json::to_value(&response).unwrap(),
+        serde_json::json!({"data": {"fast": 1}}),
+    );
+}
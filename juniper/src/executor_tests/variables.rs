@@ -124,6 +124,10 @@ impl TestType {
     fn float_input(value: f64) -> String {
         format!("value: {}", value)
     }
+
+    fn long_input(value: i64) -> i64 {
+        value
+    }
 }
 
 async fn run_variable_query<F>(query: &str, vars: Variables<DefaultScalarValue>, f: F)
@@ -318,7 +322,7 @@ async fn variable_error_on_nested_non_null() {
     assert_eq!(
         error,
         ValidationError(vec![RuleError::new(
-            r#"Variable "$input" got invalid value. In field "c": Expected "String!", found null."#,
+            r#"Variable "$input" got invalid value; expected type "TestInputObject". In field "c": Expected "String!", found null."#,
             &[SourcePosition::new(8, 0, 8)],
         )])
     );
@@ -344,7 +348,7 @@ async fn variable_error_on_incorrect_type() {
     assert_eq!(
         error,
         ValidationError(vec![RuleError::new(
-            r#"Variable "$input" got invalid value. Expected "TestInputObject", found not an object."#,
+            r#"Variable "$input" got invalid value; expected type "TestInputObject". Expected "TestInputObject", found not an object."#,
             &[SourcePosition::new(8, 0, 8)],
         ),])
     );
@@ -380,7 +384,7 @@ async fn variable_error_on_omit_non_null() {
     assert_eq!(
         error,
         ValidationError(vec![RuleError::new(
-            r#"Variable "$input" got invalid value. In field "c": Expected "String!", found null."#,
+            r#"Variable "$input" got invalid value; expected type "TestInputObject". In field "c": Expected "String!", found null."#,
             &[SourcePosition::new(8, 0, 8)],
         )])
     );
@@ -418,11 +422,11 @@ async fn variable_multiple_errors_with_nesting() {
         error,
         ValidationError(vec![
             RuleError::new(
-                r#"Variable "$input" got invalid value. In field "na": In field "c": Expected "String!", found null."#,
+                r#"Variable "$input" got invalid value; expected type "TestNestedInputObject". In field "na": In field "c": Expected "String!", found null."#,
                 &[SourcePosition::new(8, 0, 8)],
             ),
             RuleError::new(
-                r#"Variable "$input" got invalid value. In field "nb": Expected "String!", found null."#,
+                r#"Variable "$input" got invalid value; expected type "TestNestedInputObject". In field "nb": Expected "String!", found null."#,
                 &[SourcePosition::new(8, 0, 8)],
             ),
         ])
@@ -461,7 +465,7 @@ async fn variable_error_on_additional_field() {
     assert_eq!(
         error,
         ValidationError(vec![RuleError::new(
-            r#"Variable "$input" got invalid value. In field "extra": Unknown field."#,
+            r#"Variable "$input" got invalid value; expected type "TestInputObject". In field "extra": Unknown field."#,
             &[SourcePosition::new(8, 0, 8)],
         )])
     );
@@ -832,7 +836,7 @@ async fn does_not_allow_lists_of_non_null_to_contain_null() {
     assert_eq!(
         error,
         ValidationError(vec![RuleError::new(
-            r#"Variable "$input" got invalid value. In element #1: Expected "String!", found null."#,
+            r#"Variable "$input" got invalid value; expected type "[String!]". In element #1: Expected "String!", found null."#,
             &[SourcePosition::new(8, 0, 8)],
         ),])
     );
@@ -865,7 +869,7 @@ async fn does_not_allow_non_null_lists_of_non_null_to_contain_null() {
     assert_eq!(
         error,
         ValidationError(vec![RuleError::new(
-            r#"Variable "$input" got invalid value. In element #1: Expected "String!", found null."#,
+            r#"Variable "$input" got invalid value; expected type "[String!]!". In element #1: Expected "String!", found null."#,
             &[SourcePosition::new(8, 0, 8)],
         ),])
     );
@@ -1119,6 +1123,67 @@ async fn does_not_allow_null_variable_for_required_field() {
     );
 }
 
+#[tokio::test]
+async fn reports_offending_variable_of_type_mismatch() {
+    let schema = RootNode::new(
+        TestType,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let query = r#"query q($name: String, $id: Int!) {
+        fieldWithNullableStringInput(input: $name)
+        integerInput(value: $id)
+    }"#;
+    let vars = vec![
+        ("name".to_owned(), InputValue::scalar("R2-D2")),
+        ("id".to_owned(), InputValue::scalar("abc")),
+    ]
+    .into_iter()
+    .collect();
+
+    let error = crate::execute(query, None, &schema, &vars, &())
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        ValidationError(vec![RuleError::new(
+            r#"Variable "$id" got invalid value; expected type "Int!". Expected "Int", found "abc"."#,
+            &[SourcePosition::new(23, 0, 23)],
+        )])
+    );
+}
+
+#[tokio::test]
+async fn reports_offending_variable_when_required_one_is_missing() {
+    let schema = RootNode::new(
+        TestType,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let query = r#"query q($name: String, $id: Int!) {
+        fieldWithNullableStringInput(input: $name)
+        integerInput(value: $id)
+    }"#;
+    let vars = vec![("name".to_owned(), InputValue::scalar("R2-D2"))]
+        .into_iter()
+        .collect();
+
+    let error = crate::execute(query, None, &schema, &vars, &())
+        .await
+        .unwrap_err();
+
+    assert_eq!(
+        error,
+        ValidationError(vec![RuleError::new(
+            r#"Variable "$id" of required type "Int!" was not provided."#,
+            &[SourcePosition::new(23, 0, 23)],
+        )])
+    );
+}
+
 #[tokio::test]
 async fn input_object_with_default_values() {
     run_query(r#"{ inputWithDefaults(arg: {a: 1}) }"#, |result| {
@@ -1224,7 +1289,7 @@ mod integers {
         assert_eq!(
             error,
             ValidationError(vec![RuleError::new(
-                r#"Variable "$var" got invalid value. Expected "Int"."#,
+                r#"Variable "$var" got invalid value; expected type "Int!". Expected "Int", found 10."#,
                 &[SourcePosition::new(8, 0, 8)],
             )])
         );
@@ -1250,7 +1315,130 @@ mod integers {
         assert_eq!(
             error,
             ValidationError(vec![RuleError::new(
-                r#"Variable "$var" got invalid value. Expected "Int"."#,
+                r#"Variable "$var" got invalid value; expected type "Int!". Expected "Int", found "10"."#,
+                &[SourcePosition::new(8, 0, 8)],
+            )])
+        );
+    }
+}
+
+mod longs {
+    use super::*;
+
+    #[tokio::test]
+    async fn extreme_values_round_trip() {
+        for &value in &[i64::max_value(), i64::min_value()] {
+            run_variable_query(
+                r#"query q($count: Long!) { longInput(value: $count) }"#,
+                vec![("count".to_owned(), InputValue::scalar(value))]
+                    .into_iter()
+                    .collect(),
+                |result| {
+                    assert_eq!(
+                        result.get_field_value("longInput"),
+                        Some(&Value::scalar(value))
+                    );
+                },
+            )
+            .await;
+        }
+    }
+
+    #[tokio::test]
+    async fn max_value_round_trips_through_json() {
+        let schema = RootNode::new(
+            TestType,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+
+        let query = r#"query q($count: Long!) { longInput(value: $count) }"#;
+        let vars: InputValue<DefaultScalarValue> =
+            serde_json::from_str(r#"{"count": 9223372036854775807}"#).unwrap();
+        let vars = vars
+            .to_object_value()
+            .unwrap()
+            .into_iter()
+            .map(|(k, v)| (k.to_owned(), v.clone()))
+            .collect();
+
+        let (result, errs) = crate::execute(query, None, &schema, &vars, &())
+            .await
+            .expect("Execution failed");
+
+        assert_eq!(errs, []);
+        assert_eq!(
+            serde_json::to_string(&result).unwrap(),
+            r#"{"longInput":9223372036854775807}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn accepts_int_values() {
+        run_variable_query(
+            r#"query q($count: Long!) { longInput(value: $count) }"#,
+            vec![("count".to_owned(), InputValue::scalar(-1))]
+                .into_iter()
+                .collect(),
+            |result| {
+                assert_eq!(
+                    result.get_field_value("longInput"),
+                    Some(&Value::scalar(-1i64))
+                );
+            },
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn inline_values_should_work() {
+        run_query(r#"{ longInput(value: 9223372036854775807) }"#, |result| {
+            assert_eq!(
+                result.get_field_value("longInput"),
+                Some(&Value::scalar(i64::max_value()))
+            );
+        })
+        .await;
+    }
+
+    #[tokio::test]
+    async fn floats_accept_large_integers() {
+        run_variable_query(
+            r#"query q($var: Float!) { floatInput(value: $var) }"#,
+            vec![("var".to_owned(), InputValue::scalar(123_567_890_123i64))]
+                .into_iter()
+                .collect(),
+            |result| {
+                assert_eq!(
+                    result.get_field_value("floatInput"),
+                    Some(&Value::scalar(r#"value: 123567890123"#))
+                );
+            },
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn ints_do_not_accept_large_integers() {
+        let schema = RootNode::new(
+            TestType,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+
+        let query = r#"query q($var: Int!) { integerInput(value: $var) }"#;
+        let vars = vec![("var".to_owned(), InputValue::scalar(i64::max_value()))]
+            .into_iter()
+            .collect();
+
+        let error = crate::execute(query, None, &schema, &vars, &())
+            .await
+            .unwrap_err();
+
+        assert_eq!(
+            error,
+            ValidationError(vec![RuleError::new(
+                r#"Variable "$var" got invalid value; expected type "Int!". Expected "Int", found 9223372036854775807."#,
                 &[SourcePosition::new(8, 0, 8)],
             )])
         );
@@ -1314,7 +1502,7 @@ mod floats {
         assert_eq!(
             error,
             ValidationError(vec![RuleError::new(
-                r#"Variable "$var" got invalid value. Expected "Float"."#,
+                r#"Variable "$var" got invalid value; expected type "Float!". Expected "Float", found "10"."#,
                 &[SourcePosition::new(8, 0, 8)],
             )])
         );
@@ -1,7 +1,70 @@
 //! Utility module to generate a GraphiQL interface
 
+use super::{escape_html, js_string_literal};
+
+/// The GraphiQL version the generated page loads when none is pinned
+pub const DEFAULT_GRAPHIQL_VERSION: &str = "0.17.5";
+
+/// Options to customize the page generated by [`graphiql_source_with`]
+///
+/// [`graphiql_source_with`]: fn.graphiql_source_with.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct GraphiQLOptions {
+    title: String,
+    default_query: Option<String>,
+    headers: Vec<(String, String)>,
+    version: String,
+}
+
+impl Default for GraphiQLOptions {
+    fn default() -> Self {
+        GraphiQLOptions {
+            title: "GraphQL".to_owned(),
+            default_query: None,
+            headers: Vec::new(),
+            version: DEFAULT_GRAPHIQL_VERSION.to_owned(),
+        }
+    }
+}
+
+impl GraphiQLOptions {
+    /// Construct the default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the page title
+    pub fn title<T: Into<String>>(mut self, title: T) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Pre-fill the editor with a query
+    pub fn default_query<T: Into<String>>(mut self, query: T) -> Self {
+        self.default_query = Some(query.into());
+        self
+    }
+
+    /// Add an HTTP header to send along with every request
+    pub fn header<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Pin the version of the GraphiQL assets loaded from the CDN
+    pub fn version<T: Into<String>>(mut self, version: T) -> Self {
+        self.version = version.into();
+        self
+    }
+}
+
 /// Generate the HTML source to show a GraphiQL interface
 pub fn graphiql_source(graphql_endpoint_url: &str) -> String {
+    graphiql_source_with(graphql_endpoint_url, &GraphiQLOptions::default())
+}
+
+/// Generate the HTML source to show a GraphiQL interface, customized by `options`
+pub fn graphiql_source_with(graphql_endpoint_url: &str, options: &GraphiQLOptions) -> String {
     let stylesheet_source = r#"
     <style>
         html, body, #app {
@@ -15,12 +78,16 @@ pub fn graphiql_source(graphql_endpoint_url: &str) -> String {
     let fetcher_source = r#"
     <script>
         function graphQLFetcher(params) {
+            var headers = {
+                'Accept': 'application/json',
+                'Content-Type': 'application/json',
+            };
+            for (var name in GRAPHQL_HEADERS) {
+                headers[name] = GRAPHQL_HEADERS[name];
+            }
             return fetch(GRAPHQL_URL, {
                 method: 'post',
-                headers: {
-                    'Accept': 'application/json',
-                    'Content-Type': 'application/json',
-                },
+                headers: headers,
                 credentials: 'include',
                 body: JSON.stringify(params)
             }).then(function (response) {
@@ -36,33 +103,85 @@ pub fn graphiql_source(graphql_endpoint_url: &str) -> String {
         ReactDOM.render(
             React.createElement(GraphiQL, {
                 fetcher: graphQLFetcher,
+                defaultQuery: GRAPHQL_DEFAULT_QUERY,
             }),
             document.querySelector('#app'));
     </script>
     "#;
 
+    let headers = options
+        .headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}", js_string_literal(name), js_string_literal(value)))
+        .collect::<Vec<_>>()
+        .join(", ");
+    let default_query = options
+        .default_query
+        .as_ref()
+        .map(|q| js_string_literal(q))
+        .unwrap_or_else(|| "undefined".to_owned());
+
     format!(
         r#"
 <!DOCTYPE html>
 <html>
 <head>
-    <title>GraphQL</title>
+    <title>{title}</title>
     {stylesheet_source}
-    <link rel="stylesheet" type="text/css" href="//cdn.jsdelivr.net/npm/graphiql@0.17.5/graphiql.min.css">
+    <link rel="stylesheet" type="text/css" href="//cdn.jsdelivr.net/npm/graphiql@{version}/graphiql.min.css">
 </head>
 <body>
     <div id="app"></div>
     <script src="//cdnjs.cloudflare.com/ajax/libs/fetch/2.0.3/fetch.js"></script>
     <script src="//cdnjs.cloudflare.com/ajax/libs/react/16.10.2/umd/react.production.min.js"></script>
     <script src="//cdnjs.cloudflare.com/ajax/libs/react-dom/16.10.2/umd/react-dom.production.min.js"></script>
-    <script src="//cdn.jsdelivr.net/npm/graphiql@0.17.5/graphiql.min.js"></script>
+    <script src="//cdn.jsdelivr.net/npm/graphiql@{version}/graphiql.min.js"></script>
     <script>var GRAPHQL_URL = '{graphql_url}';</script>
+    <script>
+        var GRAPHQL_HEADERS = {{{headers}}};
+        var GRAPHQL_DEFAULT_QUERY = {default_query};
+    </script>
     {fetcher_source}
 </body>
 </html>
 "#,
+        title = escape_html(&options.title),
+        version = escape_html(&options.version),
         graphql_url = graphql_endpoint_url,
+        headers = headers,
+        default_query = default_query,
         stylesheet_source = stylesheet_source,
         fetcher_source = fetcher_source
     )
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{graphiql_source, graphiql_source_with, GraphiQLOptions};
+
+    #[test]
+    fn default_source_uses_default_options() {
+        let html = graphiql_source("/graphql");
+
+        assert!(html.contains("<title>GraphQL</title>"));
+        assert!(html.contains("graphiql@0.17.5/graphiql.min.js"));
+        assert!(html.contains("<script>var GRAPHQL_URL = '/graphql';</script>"));
+        assert!(html.contains("var GRAPHQL_DEFAULT_QUERY = undefined;"));
+    }
+
+    #[test]
+    fn injects_options() {
+        let options = GraphiQLOptions::new()
+            .title("My <API>")
+            .default_query("{\n  hero { name }\n}")
+            .header("Authorization", "Bearer 'token'")
+            .version("1.0.0");
+        let html = graphiql_source_with("/graphql", &options);
+
+        assert!(html.contains("<title>My &lt;API&gt;</title>"));
+        assert!(html.contains("var GRAPHQL_DEFAULT_QUERY = \"{\\n  hero { name }\\n}\";"));
+        assert!(html.contains("var GRAPHQL_HEADERS = {\"Authorization\": \"Bearer 'token'\"};"));
+        assert!(html.contains("graphiql@1.0.0/graphiql.min.css"));
+        assert!(!html.contains("0.17.5"));
+    }
+}
@@ -1,18 +1,26 @@
 //! Utilities for building HTTP endpoints in a library-agnostic manner
 
 pub mod graphiql;
+#[cfg(feature = "multipart")]
+pub mod multipart;
+#[cfg(feature = "ndjson")]
+pub mod ndjson;
+#[cfg(feature = "persisted-queries")]
+pub mod persisted_queries;
 pub mod playground;
 
+use std::convert::TryFrom;
+
 use serde::{
-    de::Deserialize,
+    de::{Deserialize, IgnoredAny},
     ser::{self, Serialize, SerializeMap},
 };
 use serde_derive::{Deserialize, Serialize};
 
 use crate::{
     ast::InputValue,
-    executor::{ExecutionError, ValuesStream},
-    value::{DefaultScalarValue, ScalarValue},
+    executor::{ExecutionError, ResponseContext, Tracing, ValuesStream},
+    value::{sort_value_by_field, DefaultScalarValue, ScalarValue},
     FieldError, GraphQLError, GraphQLSubscriptionType, GraphQLType, GraphQLTypeAsync, RootNode,
     Value, Variables,
 };
@@ -24,7 +32,15 @@ use crate::{
 ///
 /// For GET, you will need to parse the query string and extract "query",
 /// "operationName", and "variables" manually.
+///
+/// The `query` may be left out if the request carries the hash of an
+/// [automatic persisted query][apq] in `extensions.persistedQuery.sha256Hash`
+/// instead, see [`GraphQLRequest::load_persisted_query`].
+///
+/// [apq]: https://www.apollographql.com/docs/apollo-server/performance/apq/
 #[derive(Deserialize, Clone, Serialize, PartialEq, Debug)]
+#[serde(try_from = "RawGraphQLRequest<S>")]
+#[serde(bound(deserialize = "InputValue<S>: Deserialize<'de>"))]
 pub struct GraphQLRequest<S = DefaultScalarValue>
 where
     S: ScalarValue,
@@ -32,8 +48,87 @@ where
     query: String,
     #[serde(rename = "operationName")]
     operation_name: Option<String>,
-    #[serde(bound(deserialize = "InputValue<S>: Deserialize<'de> + Serialize"))]
     variables: Option<InputValue<S>>,
+    #[cfg(feature = "persisted-queries")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    extensions: Option<RequestExtensions>,
+    /// The message and extensions of the error loading the persisted query
+    /// of this request failed with, responded with instead of executing it
+    #[cfg(feature = "persisted-queries")]
+    #[serde(skip)]
+    persisted_query_error: Option<(String, Value<S>)>,
+}
+
+/// A [`GraphQLRequest`] as sent by a client, before checking that it carries
+/// either a query or the hash of a persisted query
+#[derive(Deserialize)]
+#[serde(bound(deserialize = "InputValue<S>: Deserialize<'de>"))]
+struct RawGraphQLRequest<S>
+where
+    S: ScalarValue,
+{
+    query: Option<String>,
+    #[serde(rename = "operationName")]
+    operation_name: Option<String>,
+    variables: Option<InputValue<S>>,
+    #[cfg(feature = "persisted-queries")]
+    extensions: Option<RequestExtensions>,
+}
+
+impl<S> TryFrom<RawGraphQLRequest<S>> for GraphQLRequest<S>
+where
+    S: ScalarValue,
+{
+    type Error = &'static str;
+
+    fn try_from(raw: RawGraphQLRequest<S>) -> Result<Self, Self::Error> {
+        #[cfg(feature = "persisted-queries")]
+        let persisted = raw
+            .extensions
+            .as_ref()
+            .and_then(|e| e.persisted_query.as_ref())
+            .is_some();
+        #[cfg(not(feature = "persisted-queries"))]
+        let persisted = false;
+        let query = match raw.query {
+            Some(query) => query,
+            None if persisted => String::new(),
+            None => return Err("missing field `query`"),
+        };
+
+        Ok(GraphQLRequest {
+            query,
+            operation_name: raw.operation_name,
+            variables: raw.variables,
+            #[cfg(feature = "persisted-queries")]
+            extensions: raw.extensions,
+            #[cfg(feature = "persisted-queries")]
+            persisted_query_error: None,
+        })
+    }
+}
+
+/// The `extensions` of a [`GraphQLRequest`] understood by Juniper
+#[cfg(feature = "persisted-queries")]
+#[derive(Deserialize, Clone, Serialize, PartialEq, Debug)]
+struct RequestExtensions {
+    #[serde(rename = "persistedQuery", skip_serializing_if = "Option::is_none")]
+    persisted_query: Option<PersistedQueryExtension>,
+}
+
+/// The `extensions.persistedQuery` of an automatic persisted query request
+#[cfg(feature = "persisted-queries")]
+#[derive(Deserialize, Clone, Serialize, PartialEq, Debug)]
+struct PersistedQueryExtension {
+    #[serde(default = "persisted_query_version")]
+    version: i32,
+    #[serde(rename = "sha256Hash")]
+    sha256_hash: String,
+}
+
+#[cfg(feature = "persisted-queries")]
+fn persisted_query_version() -> i32 {
+    1
 }
 
 impl<S> GraphQLRequest<S>
@@ -68,6 +163,27 @@ where
             query,
             operation_name,
             variables,
+            #[cfg(feature = "persisted-queries")]
+            extensions: None,
+            #[cfg(feature = "persisted-queries")]
+            persisted_query_error: None,
+        }
+    }
+
+    /// The response to send instead of executing this request, if loading
+    /// its persisted query failed
+    fn failed_response<'a>(&self) -> Option<GraphQLResponse<'a, S>> {
+        #[cfg(feature = "persisted-queries")]
+        {
+            self.persisted_query_error
+                .as_ref()
+                .map(|&(ref message, ref extensions)| {
+                    GraphQLResponse::error(FieldError::new(message, extensions.clone()))
+                })
+        }
+        #[cfg(not(feature = "persisted-queries"))]
+        {
+            None
         }
     }
 
@@ -86,13 +202,19 @@ where
         MutationT: GraphQLType<S, Context = CtxT>,
         SubscriptionT: GraphQLType<S, Context = CtxT>,
     {
-        GraphQLResponse(crate::execute_sync(
-            &self.query,
-            self.operation_name(),
-            root_node,
-            &self.variables(),
-            context,
-        ))
+        if let Some(response) = self.failed_response() {
+            return response;
+        }
+
+        GraphQLResponse::from_result_with_response_context(
+            crate::execute_sync_with_response_context(
+                &self.query,
+                self.operation_name(),
+                root_node,
+                &self.variables(),
+                context,
+            ),
+        )
     }
 
     /// Execute a GraphQL request using the specified schema and context
@@ -114,10 +236,352 @@ where
         SubscriptionT::TypeInfo: Send + Sync,
         CtxT: Send + Sync,
     {
+        if let Some(response) = self.failed_response() {
+            return response;
+        }
+
         let op = self.operation_name();
         let vars = &self.variables();
-        let res = crate::execute(&self.query, op, root_node, vars, context).await;
-        GraphQLResponse(res)
+        match crate::execute_with_response_context(&self.query, op, root_node, vars, context).await
+        {
+            Ok((value, errors, response_context, tracing)) => GraphQLResponse {
+                result: Ok((value, errors)),
+                response_context,
+                tracing,
+            },
+            Err(e) => GraphQLResponse::from_result(Err(e)),
+        }
+    }
+
+    /// Execute a GraphQL request asynchronously, giving up once `deadline`
+    /// completes
+    ///
+    /// See [`execute_with_deadline`](../fn.execute_with_deadline.html).
+    pub async fn execute_with_deadline<'a, CtxT, QueryT, MutationT, SubscriptionT, D>(
+        &'a self,
+        root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+        context: &'a CtxT,
+        deadline: D,
+    ) -> GraphQLResponse<'a, S>
+    where
+        S: ScalarValue + Send + Sync,
+        QueryT: crate::GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+        QueryT::TypeInfo: Send + Sync,
+        MutationT: crate::GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+        MutationT::TypeInfo: Send + Sync,
+        SubscriptionT: GraphQLType<S, Context = CtxT> + Send + Sync,
+        SubscriptionT::TypeInfo: Send + Sync,
+        CtxT: Send + Sync,
+        D: std::future::Future<Output = ()>,
+    {
+        use futures::future::{self, Either};
+
+        let execution = self.execute(root_node, context);
+        futures::pin_mut!(execution, deadline);
+
+        match future::select(execution, deadline).await {
+            Either::Left((response, _)) => response,
+            Either::Right(((), _)) => GraphQLResponse::from_result(Err(GraphQLError::TimedOut)),
+        }
+    }
+}
+
+/// An entry of a batched request
+///
+/// An entry that is not a valid request, e.g. because it lacks a `query`,
+/// still deserializes and executes into an error response, so a malformed
+/// entry doesn't fail the other entries of its batch.
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(transparent)]
+#[serde(bound = "InputValue<S>: Deserialize<'de>")]
+pub struct GraphQLBatchEntry<S = DefaultScalarValue>(BatchEntry<S>)
+where
+    S: ScalarValue;
+
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(untagged)]
+#[serde(bound = "InputValue<S>: Deserialize<'de>")]
+enum BatchEntry<S>
+where
+    S: ScalarValue,
+{
+    Request(GraphQLRequest<S>),
+    Malformed(IgnoredAny),
+}
+
+impl<S> GraphQLBatchEntry<S>
+where
+    S: ScalarValue,
+{
+    /// The request of this entry, or `None` if the entry is malformed
+    pub fn request(&self) -> Option<&GraphQLRequest<S>> {
+        match self.0 {
+            BatchEntry::Request(ref request) => Some(request),
+            BatchEntry::Malformed(_) => None,
+        }
+    }
+
+    /// Returns the `operation_name` of the request of this entry.
+    pub fn operation_name(&self) -> Option<&str> {
+        self.request().and_then(GraphQLRequest::operation_name)
+    }
+
+    #[cfg(feature = "persisted-queries")]
+    fn request_mut(&mut self) -> Option<&mut GraphQLRequest<S>> {
+        match self.0 {
+            BatchEntry::Request(ref mut request) => Some(request),
+            BatchEntry::Malformed(_) => None,
+        }
+    }
+
+    /// Execute the request of this entry synchronously using the specified
+    /// schema and context
+    pub fn execute_sync<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+        &'a self,
+        root_node: &'a RootNode<QueryT, MutationT, SubscriptionT, S>,
+        context: &CtxT,
+    ) -> GraphQLResponse<'a, S>
+    where
+        S: ScalarValue,
+        QueryT: GraphQLType<S, Context = CtxT>,
+        MutationT: GraphQLType<S, Context = CtxT>,
+        SubscriptionT: GraphQLType<S, Context = CtxT>,
+    {
+        match self.0 {
+            BatchEntry::Request(ref request) => request.execute_sync(root_node, context),
+            BatchEntry::Malformed(_) => GraphQLResponse::malformed_request(),
+        }
+    }
+
+    /// Execute the request of this entry using the specified schema and
+    /// context
+    pub async fn execute<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+        &'a self,
+        root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+        context: &'a CtxT,
+    ) -> GraphQLResponse<'a, S>
+    where
+        S: ScalarValue + Send + Sync,
+        QueryT: crate::GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+        QueryT::TypeInfo: Send + Sync,
+        MutationT: crate::GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+        MutationT::TypeInfo: Send + Sync,
+        SubscriptionT: GraphQLType<S, Context = CtxT> + Send + Sync,
+        SubscriptionT::TypeInfo: Send + Sync,
+        CtxT: Send + Sync,
+    {
+        match self.0 {
+            BatchEntry::Request(ref request) => request.execute(root_node, context).await,
+            BatchEntry::Malformed(_) => GraphQLResponse::malformed_request(),
+        }
+    }
+}
+
+impl<S> From<GraphQLRequest<S>> for GraphQLBatchEntry<S>
+where
+    S: ScalarValue,
+{
+    fn from(request: GraphQLRequest<S>) -> Self {
+        GraphQLBatchEntry(BatchEntry::Request(request))
+    }
+}
+
+/// Execute the entries of a batched request concurrently using the specified
+/// schema and context
+///
+/// All entries share the schema and the context, and at most
+/// [`RootNode::batch_concurrency_limit`](../struct.RootNode.html#method.batch_concurrency_limit)
+/// of them are executed at once. The responses are in the order of `entries`,
+/// whichever entry finishes first.
+pub async fn execute_batch<'a, CtxT, QueryT, MutationT, SubscriptionT, S>(
+    entries: &'a [GraphQLBatchEntry<S>],
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    context: &'a CtxT,
+) -> Vec<GraphQLResponse<'a, S>>
+where
+    S: ScalarValue + Send + Sync,
+    QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLType<S, Context = CtxT> + Send + Sync,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Send + Sync,
+{
+    use futures::stream::{self, StreamExt as _};
+
+    let limit = root_node
+        .schema
+        .batch_concurrency_limit
+        .unwrap_or_else(|| entries.len().max(1));
+
+    stream::iter(entries)
+        .map(|entry| entry.execute(root_node, context))
+        .buffered(limit)
+        .collect()
+        .await
+}
+
+/// A single GraphQL request or a batch of them, as sent in the body of a
+/// `POST` request
+#[derive(Deserialize, Clone, PartialEq, Debug)]
+#[serde(untagged)]
+#[serde(bound = "InputValue<S>: Deserialize<'de>")]
+pub enum GraphQLBatchRequest<S = DefaultScalarValue>
+where
+    S: ScalarValue,
+{
+    /// A single request
+    Single(GraphQLRequest<S>),
+    /// A batch of requests, answered with a list of responses
+    Batch(Vec<GraphQLBatchEntry<S>>),
+}
+
+impl<S> GraphQLBatchRequest<S>
+where
+    S: ScalarValue,
+{
+    /// Load the [automatic persisted queries][apq] of the requests from the
+    /// store set with `RootNode::persisted_queries`.
+    ///
+    /// A request whose query can't be loaded is answered with the error when
+    /// executed. This does nothing unless the `persisted-queries` feature is
+    /// enabled.
+    ///
+    /// [apq]: persisted_queries/index.html
+    pub fn load_persisted_queries<QueryT, MutationT, SubscriptionT>(
+        &mut self,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
+    ) where
+        QueryT: GraphQLType<S>,
+        MutationT: GraphQLType<S>,
+        SubscriptionT: GraphQLType<S>,
+    {
+        #[cfg(feature = "persisted-queries")]
+        {
+            let store = root_node.persisted_query_store();
+            match *self {
+                GraphQLBatchRequest::Single(ref mut request) => {
+                    request.load_persisted_query_from(store)
+                }
+                GraphQLBatchRequest::Batch(ref mut entries) => {
+                    for request in entries.iter_mut().filter_map(|e| e.request_mut()) {
+                        request.load_persisted_query_from(store);
+                    }
+                }
+            }
+        }
+        #[cfg(not(feature = "persisted-queries"))]
+        {
+            let _ = root_node;
+        }
+    }
+
+    /// Execute the requests synchronously using the specified schema and
+    /// context, one after the other
+    pub fn execute_sync<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+        &'a self,
+        root_node: &'a RootNode<QueryT, MutationT, SubscriptionT, S>,
+        context: &CtxT,
+    ) -> GraphQLBatchResponse<'a, S>
+    where
+        QueryT: GraphQLType<S, Context = CtxT>,
+        MutationT: GraphQLType<S, Context = CtxT>,
+        SubscriptionT: GraphQLType<S, Context = CtxT>,
+    {
+        match *self {
+            GraphQLBatchRequest::Single(ref request) => {
+                GraphQLBatchResponse::Single(request.execute_sync(root_node, context))
+            }
+            GraphQLBatchRequest::Batch(ref entries) => GraphQLBatchResponse::Batch(
+                entries
+                    .iter()
+                    .map(|entry| entry.execute_sync(root_node, context))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// Execute the requests using the specified schema and context, the ones
+    /// of a batch with [`execute_batch`](fn.execute_batch.html)
+    pub async fn execute<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+        &'a self,
+        root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+        context: &'a CtxT,
+    ) -> GraphQLBatchResponse<'a, S>
+    where
+        S: Send + Sync,
+        QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+        QueryT::TypeInfo: Send + Sync,
+        MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+        MutationT::TypeInfo: Send + Sync,
+        SubscriptionT: GraphQLType<S, Context = CtxT> + Send + Sync,
+        SubscriptionT::TypeInfo: Send + Sync,
+        CtxT: Send + Sync,
+    {
+        match *self {
+            GraphQLBatchRequest::Single(ref request) => {
+                GraphQLBatchResponse::Single(request.execute(root_node, context).await)
+            }
+            GraphQLBatchRequest::Batch(ref entries) => {
+                GraphQLBatchResponse::Batch(execute_batch(entries, root_node, context).await)
+            }
+        }
+    }
+
+    /// The operation names of the requests.
+    pub fn operation_names(&self) -> Vec<Option<&str>> {
+        match *self {
+            GraphQLBatchRequest::Single(ref request) => vec![request.operation_name()],
+            GraphQLBatchRequest::Batch(ref entries) => entries
+                .iter()
+                .map(GraphQLBatchEntry::operation_name)
+                .collect(),
+        }
+    }
+}
+
+/// The response to a [`GraphQLBatchRequest`], serialized as a single response
+/// or a list of them
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum GraphQLBatchResponse<'a, S = DefaultScalarValue>
+where
+    S: ScalarValue,
+{
+    /// The response to a single request
+    Single(GraphQLResponse<'a, S>),
+    /// The responses to a batch of requests, in the order of the requests
+    Batch(Vec<GraphQLResponse<'a, S>>),
+}
+
+impl<'a, S> GraphQLBatchResponse<'a, S>
+where
+    S: ScalarValue,
+{
+    /// Were all the requests successful?
+    ///
+    /// See [`GraphQLResponse::is_ok`].
+    pub fn is_ok(&self) -> bool {
+        match *self {
+            GraphQLBatchResponse::Single(ref response) => response.is_ok(),
+            GraphQLBatchResponse::Batch(ref responses) => responses.iter().all(|r| r.is_ok()),
+        }
+    }
+
+    /// The conventional HTTP status code to send these responses with.
+    ///
+    /// This is the [`GraphQLResponse::status_code`] of a single response, and
+    /// the highest status code of the responses of a batch.
+    pub fn status_code(&self) -> u16 {
+        match *self {
+            GraphQLBatchResponse::Single(ref response) => response.status_code(),
+            GraphQLBatchResponse::Batch(ref responses) => responses
+                .iter()
+                .map(GraphQLResponse::status_code)
+                .max()
+                .unwrap_or(200),
+        }
     }
 }
 
@@ -154,9 +618,11 @@ where
 /// This struct implements Serialize, so you can simply serialize this
 /// to JSON and send it over the wire. Use the `is_ok` method to determine
 /// whether to send a 200 or 400 HTTP status code.
-pub struct GraphQLResponse<'a, S = DefaultScalarValue>(
-    Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>,
-);
+pub struct GraphQLResponse<'a, S = DefaultScalarValue> {
+    result: Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>,
+    response_context: ResponseContext,
+    tracing: Option<Tracing>,
+}
 
 impl<'a, S> GraphQLResponse<'a, S>
 where
@@ -164,12 +630,59 @@ where
 {
     /// Constructs new `GraphQLResponse` using the given result
     pub fn from_result(r: Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>) -> Self {
-        Self(r)
+        GraphQLResponse {
+            result: r,
+            response_context: ResponseContext::default(),
+            tracing: None,
+        }
+    }
+
+    fn from_result_with_response_context(
+        r: Result<(Value<S>, Vec<ExecutionError<S>>, ResponseContext), GraphQLError<'a>>,
+    ) -> Self {
+        match r {
+            Ok((value, errors, response_context)) => GraphQLResponse {
+                result: Ok((value, errors)),
+                response_context,
+                tracing: None,
+            },
+            Err(e) => Self::from_result(Err(e)),
+        }
     }
 
     /// Constructs an error response outside of the normal execution flow
     pub fn error(error: FieldError<S>) -> Self {
-        GraphQLResponse(Ok((Value::null(), vec![ExecutionError::at_origin(error)])))
+        Self::from_result(Ok((Value::null(), vec![ExecutionError::at_origin(error)])))
+    }
+
+    fn malformed_request() -> Self {
+        Self::from_result(Err(GraphQLError::MalformedRequest))
+    }
+
+    /// The cache hint of the executed operation, in seconds.
+    ///
+    /// This is the minimum `maxAge` of the `@cacheControl` directives and
+    /// field cache hints across the selected fields, and of the cache hints
+    /// set by their resolvers with
+    /// [`Executor::set_cache_max_age`](../struct.Executor.html#method.set_cache_max_age).
+    /// It can be turned into a `Cache-Control: max-age=...` header. Returns
+    /// `None` if no hint applies or the request failed.
+    pub fn cache_max_age(&self) -> Option<u32> {
+        self.response_context.cache_max_age()
+    }
+
+    /// The cache hints and HTTP headers suggested by the resolvers of the
+    /// executed operation, for integrations to apply to the HTTP response.
+    pub fn response_context(&self) -> &ResponseContext {
+        &self.response_context
+    }
+
+    /// How long every field took to resolve, if the schema enables tracing.
+    ///
+    /// Only recorded by asynchronous execution, see
+    /// [`RootNode::enable_tracing`](../struct.RootNode.html#method.enable_tracing).
+    pub fn tracing(&self) -> Option<&Tracing> {
+        self.tracing.as_ref()
     }
 
     /// Was the request successful or not?
@@ -177,7 +690,99 @@ where
     /// Note that there still might be errors in the response even though it's
     /// considered OK. This is by design in GraphQL.
     pub fn is_ok(&self) -> bool {
-        self.0.is_ok()
+        self.result.is_ok()
+    }
+
+    /// Were errors reported, whether the request failed as a whole or some of
+    /// its fields failed?
+    pub fn has_errors(&self) -> bool {
+        match self.result {
+            Ok((_, ref errors)) => !errors.is_empty(),
+            Err(_) => true,
+        }
+    }
+
+    /// Did the request execute with only part of its data, because some of
+    /// its fields failed?
+    ///
+    /// Requests failing as a whole, e.g. on validation, are not partial.
+    pub fn is_partial(&self) -> bool {
+        match self.result {
+            Ok((ref data, ref errors)) => !errors.is_empty() && !data.is_null(),
+            Err(_) => false,
+        }
+    }
+
+    /// The conventional HTTP status code to send this response with.
+    ///
+    /// - `400 Bad Request` if the request could not be executed, e.g. because
+    ///   of a parse or validation error.
+    /// - `503 Service Unavailable` if the execution did not complete before
+    ///   its deadline.
+    /// - `200 OK` otherwise, including when field errors were reported in the
+    ///   `errors` list of the response.
+    pub fn status_code(&self) -> u16 {
+        match self.result {
+            Ok(_) => 200,
+            Err(GraphQLError::TimedOut) => 503,
+            Err(_) => 400,
+        }
+    }
+
+    /// Sort the keys of every object of the data alphabetically.
+    ///
+    /// The fields of objects otherwise follow the order the query selects
+    /// them in, whatever order they resolve in. Sorted keys keep snapshots
+    /// of responses stable when the selection order of queries changes.
+    pub fn sort_keys(mut self) -> Self {
+        if let Ok((ref mut data, _)) = self.result {
+            sort_value_by_field(data);
+        }
+        self
+    }
+
+    /// Add the source chain of each field error to its `"extensions"`, as a
+    /// `"sources"` list of messages from the outermost to the innermost cause.
+    ///
+    /// Meant for debugging only: sources are internal Rust errors whose
+    /// messages may leak implementation details to clients, which is why they
+    /// are not serialized by default. Log
+    /// [`FieldError::source_chain`](crate::FieldError::source_chain) instead
+    /// in production.
+    pub fn expose_error_sources(mut self) -> Self {
+        if let Ok((_, ref mut errors)) = self.result {
+            for error in errors {
+                error.error_mut().expose_source_chain();
+            }
+        }
+        self
+    }
+
+    /// Serialize this response with the errors transformed by `formatter`.
+    pub fn with_error_formatter<'r>(
+        &'r self,
+        formatter: &'r dyn ErrorFormatter<S>,
+    ) -> FormattedGraphQLResponse<'r, 'a, S> {
+        FormattedGraphQLResponse {
+            response: self,
+            formatter,
+        }
+    }
+
+    /// Serialize this response into a JSON string, indented over several lines
+    /// if `pretty` is set or on a single compact line otherwise.
+    ///
+    /// # Panics
+    ///
+    /// If the `Serialize` implementation of `S` fails.
+    #[cfg(any(test, feature = "serde_json"))]
+    pub fn to_json_string(&self, pretty: bool) -> String {
+        let json = if pretty {
+            serde_json::to_string_pretty(self)
+        } else {
+            serde_json::to_string(self)
+        };
+        json.expect("GraphQL responses serialize to JSON")
     }
 }
 
@@ -192,7 +797,7 @@ where
     where
         S: ser::Serializer,
     {
-        match self.0 {
+        match self.result {
             Ok((ref res, ref err)) => {
                 let mut map = serializer.serialize_map(None)?;
 
@@ -204,6 +809,10 @@ where
                     map.serialize_value(err)?;
                 }
 
+                if let Some(ref tracing) = self.tracing {
+                    map.serialize_entry("extensions", &TracingExtensions { tracing })?;
+                }
+
                 map.end()
             }
             Err(ref err) => {
@@ -216,11 +825,123 @@ where
     }
 }
 
+/// The `extensions` entry of a traced response
+#[derive(Serialize)]
+struct TracingExtensions<'t> {
+    tracing: &'t Tracing,
+}
+
+/// Transforms the errors of a response into the shape expected by clients
+///
+/// Integrations serialize responses in the spec-compliant format by default.
+/// Passing an `ErrorFormatter` to them replaces the value of the `errors`
+/// entry, e.g. to match an existing error contract.
+pub trait ErrorFormatter<S = DefaultScalarValue>: Send + Sync {
+    /// Formats the field errors raised while executing an operation.
+    ///
+    /// Only called if there was at least one error.
+    fn format_execution_errors(&self, errors: &[ExecutionError<S>]) -> Value<S>;
+
+    /// Formats the error of a request that could not be executed, e.g.
+    /// because of a parse or validation error.
+    fn format_request_error(&self, error: &GraphQLError) -> Value<S>;
+}
+
+/// A [`GraphQLResponse`] serialized with the errors transformed by an
+/// [`ErrorFormatter`]
+///
+/// Returned by [`GraphQLResponse::with_error_formatter`].
+pub struct FormattedGraphQLResponse<'r, 'a, S> {
+    response: &'r GraphQLResponse<'a, S>,
+    formatter: &'r dyn ErrorFormatter<S>,
+}
+
+impl<'r, 'a, T> Serialize for FormattedGraphQLResponse<'r, 'a, T>
+where
+    T: Serialize + ScalarValue,
+    Value<T>: Serialize,
+{
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match self.response.result {
+            Ok((ref res, ref err)) => {
+                let mut map = serializer.serialize_map(None)?;
+
+                map.serialize_key("data")?;
+                map.serialize_value(res)?;
+
+                if !err.is_empty() {
+                    map.serialize_key("errors")?;
+                    map.serialize_value(&self.formatter.format_execution_errors(err))?;
+                }
+
+                if let Some(ref tracing) = self.response.tracing {
+                    map.serialize_entry("extensions", &TracingExtensions { tracing })?;
+                }
+
+                map.end()
+            }
+            Err(ref err) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_key("errors")?;
+                map.serialize_value(&self.formatter.format_request_error(err))?;
+                map.end()
+            }
+        }
+    }
+}
+
+/// Render `s` as a double-quoted JavaScript string literal that is safe to
+/// embed inside a `<script>` element.
+fn js_string_literal(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            '<' => out.push_str("\\u003c"),
+            '>' => out.push_str("\\u003e"),
+            '&' => out.push_str("\\u0026"),
+            '\u{2028}' => out.push_str("\\u2028"),
+            '\u{2029}' => out.push_str("\\u2029"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// Escape `s` for use as HTML text or a double-quoted attribute value.
+fn escape_html(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
 #[cfg(any(test, feature = "expose-test-schema"))]
 #[allow(missing_docs)]
 pub mod tests {
     use serde_json::{self, Value as Json};
 
+    use super::ErrorFormatter;
+    use crate::{DefaultScalarValue, ExecutionError, GraphQLError, Value};
+
     /// Normalized response content we expect to get back from
     /// the http framework integration we are testing.
     #[derive(Debug)]
@@ -237,6 +958,33 @@ pub mod tests {
         fn post(&self, url: &str, body: &str) -> TestResponse;
     }
 
+    /// Error formatter rendering the errors of a response as a list of their
+    /// messages, to test integrations accepting an [`ErrorFormatter`].
+    pub struct LegacyErrors;
+
+    impl ErrorFormatter for LegacyErrors {
+        fn format_execution_errors(&self, errors: &[ExecutionError<DefaultScalarValue>]) -> Value {
+            Value::list(
+                errors
+                    .iter()
+                    .map(|e| Value::scalar(e.error().message().to_owned()))
+                    .collect(),
+            )
+        }
+
+        fn format_request_error(&self, error: &GraphQLError) -> Value {
+            match error {
+                GraphQLError::ValidationError(errors) => Value::list(
+                    errors
+                        .iter()
+                        .map(|e| Value::scalar(e.message().to_owned()))
+                        .collect(),
+                ),
+                e => Value::list(vec![Value::scalar(e.to_string())]),
+            }
+        }
+    }
+
     #[allow(missing_docs)]
     pub fn run_http_test_suite<T: HTTPIntegration>(integration: &T) {
         println!("Running HTTP Test suite for integration");
@@ -256,12 +1004,21 @@ pub mod tests {
         println!("  - test_batched_post");
         test_batched_post(integration);
 
+        println!("  - test_batched_post_with_malformed_entry");
+        test_batched_post_with_malformed_entry(integration);
+
+        println!("  - test_operation_name");
+        test_operation_name(integration);
+
         println!("  - test_invalid_json");
         test_invalid_json(integration);
 
         println!("  - test_invalid_field");
         test_invalid_field(integration);
 
+        println!("  - test_multiple_invalid_fields");
+        test_multiple_invalid_fields(integration);
+
         println!("  - test_duplicate_keys");
         test_duplicate_keys(integration);
     }
@@ -382,6 +1139,79 @@ pub mod tests {
         );
     }
 
+    fn test_batched_post_with_malformed_entry<T: HTTPIntegration>(integration: &T) {
+        let response = integration.post(
+            "/",
+            r#"[{"query": "{hero{name}}"}, {"qeury": "{hero{name}}"}, {"query": "{hero{id}}"}]"#,
+        );
+
+        assert_eq!(response.status_code, 400);
+        assert_eq!(response.content_type, "application/json");
+
+        assert_eq!(
+            unwrap_json_response(&response),
+            serde_json::from_str::<Json>(
+                r#"[
+                    {"data": {"hero": {"name": "R2-D2"}}},
+                    {
+                        "errors": [{
+                            "message": "Expected a GraphQL request object with a \"query\" string"
+                        }]
+                    },
+                    {"data": {"hero": {"id": "2001"}}}
+                ]"#
+            )
+            .expect("Invalid JSON constant in test")
+        );
+    }
+
+    fn test_operation_name<T: HTTPIntegration>(integration: &T) {
+        let response = integration.post(
+            "/",
+            r#"{"query": "query A { hero { name } } query B { hero { id } }"}"#,
+        );
+        assert_eq!(response.status_code, 400);
+        assert_eq!(
+            unwrap_json_response(&response),
+            serde_json::from_str::<Json>(
+                r#"{"errors": [{
+                    "message": "Must provide operation name if query contains multiple operations"
+                }]}"#
+            )
+            .expect("Invalid JSON constant in test")
+        );
+
+        let response = integration.post(
+            "/",
+            r#"{
+                "query": "query A { hero { name } } query B { hero { id } }",
+                "operationName": "B"
+            }"#,
+        );
+        assert_eq!(response.status_code, 200);
+        assert_eq!(
+            unwrap_json_response(&response),
+            serde_json::from_str::<Json>(r#"{"data": {"hero": {"id": "2001"}}}"#)
+                .expect("Invalid JSON constant in test")
+        );
+
+        let response = integration.post(
+            "/",
+            r#"{
+                "query": "query A { hero { name } } query B { hero { id } }",
+                "operationName": "C"
+            }"#,
+        );
+        assert_eq!(response.status_code, 400);
+        assert_eq!(
+            unwrap_json_response(&response),
+            serde_json::from_str::<Json>(
+                r#"{"errors": [{"message": "Unknown operation named \"C\""}]}"#
+            )
+            .expect("Invalid JSON constant in test")
+        );
+    }
+
     fn test_invalid_json<T: HTTPIntegration>(integration: &T) {
         let response = integration.get("/?query=blah");
         assert_eq!(response.status_code, 400);
@@ -397,6 +1227,30 @@ pub mod tests {
         assert_eq!(response.status_code, 400);
     }
 
+    fn test_multiple_invalid_fields<T: HTTPIntegration>(integration: &T) {
+        let response = integration.post("/", r#"{"query": "{hero{blah name bleh}}"}"#);
+
+        assert_eq!(response.status_code, 400);
+        assert_eq!(response.content_type, "application/json");
+
+        assert_eq!(
+            unwrap_json_response(&response),
+            serde_json::from_str::<Json>(
+                r#"{"errors": [
+                    {
+                        "message": "Unknown field \"blah\" on type \"Character\"",
+                        "locations": [{"line": 1, "column": 7}]
+                    },
+                    {
+                        "message": "Unknown field \"bleh\" on type \"Character\"",
+                        "locations": [{"line": 1, "column": 17}]
+                    }
+                ]}"#
+            )
+            .expect("Invalid JSON constant in test")
+        );
+    }
+
     fn test_duplicate_keys<T: HTTPIntegration>(integration: &T) {
         // {hero{name}}
         let response = integration.get("/?query=%7B%22query%22%3A%20%22%7Bhero%7Bname%7D%7D%22%2C%20%22query%22%3A%20%22%7Bhero%7Bname%7D%7D%22%7D");
@@ -0,0 +1,520 @@
+//! Parsing of `multipart/form-data` requests uploading files, following the
+//! [GraphQL multipart request specification][spec]
+//!
+//! A multipart request has an `operations` part holding the JSON request, a
+//! `map` part naming the variables each file is uploaded to, and one part per
+//! file. [`parse_request`] deserializes the operations with the value of every
+//! mapped variable set to the name of its file part, which resolvers receive
+//! as an [`Upload`] argument and look up in the returned [`Uploads`], usually
+//! through the context.
+//!
+//! ```rust
+//! use juniper::{
+//!     http::{
+//!         multipart::{self, Upload, Uploads},
+//!         GraphQLRequest,
+//!     },
+//!     EmptyMutation, EmptySubscription, FieldResult, RootNode,
+//! };
+//!
+//! struct Context {
+//!     uploads: Uploads,
+//! }
+//!
+//! impl juniper::Context for Context {}
+//!
+//! struct Query;
+//!
+//! #[juniper::graphql_object(Context = Context)]
+//! impl Query {
+//!     fn file_size(context: &Context, file: Upload) -> FieldResult<i32> {
+//!         let file = context.uploads.get(&file).ok_or("File not uploaded")?;
+//!         Ok(file.contents().len() as i32)
+//!     }
+//! }
+//!
+//! let body = concat!(
+//!     "--X\r\n",
+//!     "Content-Disposition: form-data; name=\"operations\"\r\n\r\n",
+//!     r#"{"query": "query($file: Upload!) { fileSize(file: $file) }", "variables": {"file": null}}"#,
+//!     "\r\n--X\r\n",
+//!     "Content-Disposition: form-data; name=\"map\"\r\n\r\n",
+//!     r#"{"0": ["variables.file"]}"#,
+//!     "\r\n--X\r\n",
+//!     "Content-Disposition: form-data; name=\"0\"; filename=\"a.txt\"\r\n\r\n",
+//!     "abc",
+//!     "\r\n--X--\r\n",
+//! );
+//!
+//! let multipart = multipart::parse_request::<GraphQLRequest>(
+//!     "multipart/form-data; boundary=X",
+//!     body.as_bytes(),
+//! )
+//! .unwrap();
+//!
+//! let schema = RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new());
+//! let context = Context { uploads: multipart.uploads };
+//! let response = multipart.operations.execute_sync(&schema, &context);
+//!
+//! assert_eq!(
+//!     serde_json::to_string(&response).unwrap(),
+//!     r#"{"data":{"fileSize":3}}"#,
+//! );
+//! ```
+//!
+//! [spec]: https://github.com/jaydenseric/graphql-multipart-request-spec
+
+use std::{collections::HashMap, fmt, io::Read};
+
+use serde::de::DeserializeOwned;
+
+use crate::{
+    parser::{ParseError, ScalarToken, Token},
+    value::{ParseScalarResult, ScalarValue},
+    Value,
+};
+
+/// The value of an `Upload` argument, referring to a file of a multipart
+/// request by the name of its part
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Upload(String);
+
+impl Upload {
+    /// The name of the part holding the file
+    pub fn name(&self) -> &str {
+        &self.0
+    }
+}
+
+#[crate::graphql_scalar_internal(
+    name = "Upload",
+    description = "A file uploaded through a multipart request"
+)]
+impl<S> GraphQLScalar for Upload
+where
+    S: ScalarValue,
+{
+    fn resolve(&self) -> Value {
+        Value::scalar(self.0.clone())
+    }
+
+    fn from_input_value(v: &InputValue) -> Option<Upload> {
+        v.as_string_value().map(|name| Upload(name.to_owned()))
+    }
+
+    fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
+        if let ScalarToken::String(value) = value {
+            Ok(S::from(value.to_owned()))
+        } else {
+            Err(ParseError::UnexpectedToken(Token::Scalar(value)))
+        }
+    }
+}
+
+/// A file uploaded through a multipart request
+#[derive(Clone, Debug, PartialEq)]
+pub struct UploadedFile {
+    filename: Option<String>,
+    content_type: Option<String>,
+    contents: Vec<u8>,
+}
+
+impl UploadedFile {
+    /// The name of the file on the client, if provided
+    pub fn filename(&self) -> Option<&str> {
+        self.filename.as_deref()
+    }
+
+    /// The content type of the file, if provided
+    pub fn content_type(&self) -> Option<&str> {
+        self.content_type.as_deref()
+    }
+
+    /// The contents of the file
+    pub fn contents(&self) -> &[u8] {
+        &self.contents
+    }
+
+    /// Reads the contents of the file
+    pub fn reader(&self) -> impl Read + '_ {
+        &self.contents[..]
+    }
+
+    /// Takes the contents of the file
+    pub fn into_contents(self) -> Vec<u8> {
+        self.contents
+    }
+}
+
+/// The files uploaded through a multipart request, by the name of their part
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Uploads {
+    files: HashMap<String, UploadedFile>,
+}
+
+impl Uploads {
+    /// The file an `Upload` argument refers to
+    ///
+    /// Returns `None` if the request uploaded no such file, e.g. when the
+    /// argument was passed as a string in the query.
+    pub fn get(&self, upload: &Upload) -> Option<&UploadedFile> {
+        self.files.get(upload.name())
+    }
+
+    /// The number of uploaded files
+    pub fn len(&self) -> usize {
+        self.files.len()
+    }
+
+    /// Whether no file was uploaded
+    pub fn is_empty(&self) -> bool {
+        self.files.is_empty()
+    }
+}
+
+/// A multipart request, split into its operations and uploaded files
+#[derive(Debug)]
+pub struct MultipartRequest<T> {
+    /// The operations, with the files set as the value of the variables they
+    /// are mapped to
+    pub operations: T,
+    /// The uploaded files
+    pub uploads: Uploads,
+}
+
+/// An error preventing a multipart request from being parsed
+#[derive(Debug)]
+pub enum MultipartError {
+    /// The content type is not `multipart/form-data` with a boundary
+    InvalidContentType,
+    /// The body is not made of parts delimited by the boundary
+    MalformedBody,
+    /// The `operations` or `map` part is missing
+    MissingPart(&'static str),
+    /// The `operations` or `map` part is not valid JSON of the expected shape
+    InvalidJson(&'static str, serde_json::Error),
+    /// The map refers to a file part that doesn't exist
+    MissingFile(String),
+    /// The map refers to a location that doesn't exist in the operations
+    InvalidPath(String),
+}
+
+impl fmt::Display for MultipartError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            MultipartError::InvalidContentType => {
+                write!(
+                    f,
+                    "Expected a multipart/form-data content type with a boundary"
+                )
+            }
+            MultipartError::MalformedBody => write!(f, "Malformed multipart body"),
+            MultipartError::MissingPart(name) => write!(f, "Missing the {:?} part", name),
+            MultipartError::InvalidJson(name, err) => {
+                write!(f, "Invalid JSON in the {:?} part: {}", name, err)
+            }
+            MultipartError::MissingFile(name) => {
+                write!(f, "The map refers to a missing file part {:?}", name)
+            }
+            MultipartError::InvalidPath(path) => {
+                write!(f, "The map refers to a missing operations path {:?}", path)
+            }
+        }
+    }
+}
+
+impl std::error::Error for MultipartError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MultipartError::InvalidJson(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+/// Parses a multipart request uploading files
+///
+/// `content_type` is the value of the `Content-Type` header of the request.
+/// The operations are deserialized into `T`, e.g. a `GraphQLRequest` or the
+/// batch request type of an integration.
+pub fn parse_request<T>(
+    content_type: &str,
+    body: &[u8],
+) -> Result<MultipartRequest<T>, MultipartError>
+where
+    T: DeserializeOwned,
+{
+    let boundary = boundary(content_type).ok_or(MultipartError::InvalidContentType)?;
+
+    let (mut operations, mut map) = (None, None);
+    let mut files = HashMap::new();
+    for part in parts(body, &boundary)? {
+        match part.name.as_str() {
+            "operations" => {
+                operations = Some(
+                    serde_json::from_slice::<serde_json::Value>(part.body)
+                        .map_err(|e| MultipartError::InvalidJson("operations", e))?,
+                )
+            }
+            "map" => {
+                map = Some(
+                    serde_json::from_slice::<HashMap<String, Vec<String>>>(part.body)
+                        .map_err(|e| MultipartError::InvalidJson("map", e))?,
+                )
+            }
+            _ => {
+                let file = UploadedFile {
+                    filename: part.filename,
+                    content_type: part.content_type,
+                    contents: part.body.to_vec(),
+                };
+                files.insert(part.name, file);
+            }
+        }
+    }
+    let mut operations = operations.ok_or(MultipartError::MissingPart("operations"))?;
+    let map = map.ok_or(MultipartError::MissingPart("map"))?;
+
+    let mut uploads = Uploads::default();
+    for (name, paths) in map {
+        let file = files
+            .remove(&name)
+            .ok_or_else(|| MultipartError::MissingFile(name.clone()))?;
+        for path in paths {
+            let pointer = path
+                .split('.')
+                .map(|key| format!("/{}", key.replace('~', "~0").replace('/', "~1")))
+                .collect::<String>();
+            let value = operations
+                .pointer_mut(&pointer)
+                .ok_or(MultipartError::InvalidPath(path))?;
+            *value = serde_json::Value::String(name.clone());
+        }
+        uploads.files.insert(name, file);
+    }
+
+    let operations = serde_json::from_value(operations)
+        .map_err(|e| MultipartError::InvalidJson("operations", e))?;
+
+    Ok(MultipartRequest {
+        operations,
+        uploads,
+    })
+}
+
+/// The boundary of a `multipart/form-data` content type
+fn boundary(content_type: &str) -> Option<String> {
+    let mut params = content_type.split(';');
+    let mime = params.next()?.trim();
+    if !mime.eq_ignore_ascii_case("multipart/form-data") {
+        return None;
+    }
+    params
+        .filter_map(parameter)
+        .find(|(key, _)| key.eq_ignore_ascii_case("boundary"))
+        .map(|(_, value)| value)
+        .filter(|boundary| !boundary.is_empty())
+}
+
+/// A `key=value` header parameter, with the value unquoted
+fn parameter(param: &str) -> Option<(&str, String)> {
+    let mut split = param.splitn(2, '=');
+    let key = split.next()?.trim();
+    let value = split.next()?.trim();
+    let value = if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+        value[1..value.len() - 1].replace("\\\"", "\"")
+    } else {
+        value.to_owned()
+    };
+    Some((key, value))
+}
+
+struct Part<'a> {
+    name: String,
+    filename: Option<String>,
+    content_type: Option<String>,
+    body: &'a [u8],
+}
+
+/// Splits a multipart body into its parts, ignoring the preamble and epilogue
+fn parts<'a>(body: &'a [u8], boundary: &str) -> Result<Vec<Part<'a>>, MultipartError> {
+    let delimiter = format!("\r\n--{}", boundary).into_bytes();
+
+    // The first delimiter isn't preceded by a line break without a preamble.
+    let mut rest = if body.starts_with(&delimiter[2..]) {
+        &body[delimiter.len() - 2..]
+    } else {
+        let start = find(body, &delimiter).ok_or(MultipartError::MalformedBody)?;
+        &body[start + delimiter.len()..]
+    };
+
+    let mut parts = Vec::new();
+    loop {
+        if rest.starts_with(b"--") {
+            return Ok(parts);
+        }
+        let line_end = find(rest, b"\r\n").ok_or(MultipartError::MalformedBody)?;
+        let part = &rest[line_end + 2..];
+        let end = find(part, &delimiter).ok_or(MultipartError::MalformedBody)?;
+        parts.push(parse_part(&part[..end])?);
+        rest = &part[end + delimiter.len()..];
+    }
+}
+
+fn parse_part(part: &[u8]) -> Result<Part<'_>, MultipartError> {
+    let (headers, body) = if part.starts_with(b"\r\n") {
+        (&b""[..], &part[2..])
+    } else {
+        let end = find(part, b"\r\n\r\n").ok_or(MultipartError::MalformedBody)?;
+        (&part[..end], &part[end + 4..])
+    };
+    let headers = std::str::from_utf8(headers).map_err(|_| MultipartError::MalformedBody)?;
+
+    let (mut name, mut filename, mut content_type) = (None, None, None);
+    for header in headers.split("\r\n") {
+        let mut split = header.splitn(2, ':');
+        let key = split.next().unwrap_or_default().trim();
+        let value = split.next().ok_or(MultipartError::MalformedBody)?.trim();
+
+        if key.eq_ignore_ascii_case("content-disposition") {
+            for (key, value) in value.split(';').skip(1).filter_map(parameter) {
+                match key {
+                    "name" => name = Some(value),
+                    "filename" => filename = Some(value),
+                    _ => {}
+                }
+            }
+        } else if key.eq_ignore_ascii_case("content-type") {
+            content_type = Some(value.to_owned());
+        }
+    }
+
+    Ok(Part {
+        name: name.ok_or(MultipartError::MalformedBody)?,
+        filename,
+        content_type,
+        body,
+    })
+}
+
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_request, MultipartError, Upload, Uploads};
+
+    use crate::{
+        executor::FieldResult,
+        http::GraphQLRequest,
+        schema::model::RootNode,
+        types::scalars::{EmptyMutation, EmptySubscription},
+    };
+
+    struct Context {
+        uploads: Uploads,
+    }
+
+    impl crate::Context for Context {}
+
+    struct Query;
+
+    #[crate::graphql_object_internal(Context = Context)]
+    impl Query {
+        fn upload(context: &Context, file: Upload) -> FieldResult<String> {
+            let file = context.uploads.get(&file).ok_or("File not uploaded")?;
+            Ok(format!(
+                "{} {} {}",
+                file.filename().unwrap_or_default(),
+                file.content_type().unwrap_or_default(),
+                String::from_utf8_lossy(file.contents()),
+            ))
+        }
+    }
+
+    const CONTENT_TYPE: &str = r#"multipart/form-data; boundary="----boundary""#;
+
+    fn body(map: &str) -> String {
+        [
+            "------boundary\r\n",
+            "Content-Disposition: form-data; name=\"operations\"\r\n",
+            "\r\n",
+            r#"{"query": "query($file: Upload!) { upload(file: $file) }", "variables": {"file": null}}"#,
+            "\r\n------boundary\r\n",
+            "Content-Disposition: form-data; name=\"map\"\r\n",
+            "\r\n",
+            map,
+            "\r\n------boundary\r\n",
+            "Content-Disposition: form-data; name=\"0\"; filename=\"a.txt\"\r\n",
+            "Content-Type: text/plain\r\n",
+            "\r\n",
+            "Hello\r\nworld",
+            "\r\n------boundary--\r\n",
+        ]
+        .concat()
+    }
+
+    #[test]
+    fn resolvers_receive_the_uploaded_file() {
+        let multipart = parse_request::<GraphQLRequest>(
+            CONTENT_TYPE,
+            body(r#"{"0": ["variables.file"]}"#).as_bytes(),
+        )
+        .unwrap();
+        assert_eq!(multipart.uploads.len(), 1);
+
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<Context>::new(),
+            EmptySubscription::<Context>::new(),
+        );
+        let context = Context {
+            uploads: multipart.uploads,
+        };
+        let response = multipart.operations.execute_sync(&schema, &context);
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            serde_json::json!({"data": {"upload": "a.txt text/plain Hello\r\nworld"}}),
+        );
+    }
+
+    #[test]
+    fn rejects_a_map_to_a_missing_file() {
+        let result = parse_request::<GraphQLRequest>(
+            CONTENT_TYPE,
+            body(r#"{"1": ["variables.file"]}"#).as_bytes(),
+        );
+
+        match result {
+            Err(MultipartError::MissingFile(ref name)) => assert_eq!(name, "1"),
+            _ => panic!("Expected a missing file error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn rejects_a_map_to_a_missing_variable() {
+        let result = parse_request::<GraphQLRequest>(
+            CONTENT_TYPE,
+            body(r#"{"0": ["variables.other"]}"#).as_bytes(),
+        );
+
+        match result {
+            Err(MultipartError::InvalidPath(ref path)) => assert_eq!(path, "variables.other"),
+            _ => panic!("Expected an invalid path error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn rejects_other_content_types() {
+        let result = parse_request::<GraphQLRequest>("application/json", b"{}");
+
+        match result {
+            Err(MultipartError::InvalidContentType) => {}
+            _ => panic!("Expected an invalid content type error, got {:?}", result),
+        }
+    }
+}
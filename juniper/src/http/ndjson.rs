@@ -0,0 +1,114 @@
+//! Serialization of subscription responses as [newline-delimited JSON][ndjson]
+//!
+//! Some clients would rather POST a subscription and read its events off the
+//! response body than open a WebSocket or an SSE connection. [`ndjson_stream`]
+//! turns the stream of [`GraphQLResponse`]s of a subscription, e.g. a
+//! `juniper_subscriptions::Connection`, into a stream of body chunks each
+//! holding one compact JSON object followed by a newline, which can be sent
+//! with `Transfer-Encoding: chunked`. The body ends with the subscription.
+//!
+//! [ndjson]: http://ndjson.org
+//! [`GraphQLResponse`]: ../struct.GraphQLResponse.html
+//! [`ndjson_stream`]: fn.ndjson_stream.html
+
+use futures::{Stream, StreamExt};
+
+use super::GraphQLResponse;
+use crate::value::ScalarValue;
+
+/// The `Content-Type` of a newline-delimited JSON response body
+pub const CONTENT_TYPE: &str = "application/x-ndjson";
+
+/// Serialize `response` as a single line of newline-delimited JSON
+pub fn ndjson_line<S>(response: &GraphQLResponse<S>) -> serde_json::Result<Vec<u8>>
+where
+    S: ScalarValue,
+{
+    let mut line = serde_json::to_vec(response)?;
+    line.push(b'\n');
+    Ok(line)
+}
+
+/// Serialize every response of `responses` as a line of newline-delimited
+/// JSON
+///
+/// The returned stream ends when `responses` does.
+pub fn ndjson_stream<'a, St, S>(
+    responses: St,
+) -> impl Stream<Item = serde_json::Result<Vec<u8>>> + 'a
+where
+    St: Stream<Item = GraphQLResponse<'a, S>> + 'a,
+    S: ScalarValue + 'a,
+{
+    responses.map(|response| ndjson_line(&response))
+}
+
+#[cfg(test)]
+mod tests {
+    use futures::{stream, StreamExt};
+    use serde_json::{json, Value as Json};
+
+    use super::ndjson_stream;
+    use crate::{
+        executor::ExecutionError,
+        http::GraphQLResponse,
+        value::{DefaultScalarValue, Object, Value},
+        FieldError,
+    };
+
+    fn event(n: i32) -> GraphQLResponse<'static, DefaultScalarValue> {
+        let mut counter = Object::with_capacity(1);
+        counter.add_field("counter", Value::scalar(n));
+        GraphQLResponse::from_result(Ok((Value::Object(counter), vec![])))
+    }
+
+    #[tokio::test]
+    async fn writes_one_json_object_per_line() {
+        let responses = stream::iter(vec![
+            event(1),
+            event(2),
+            GraphQLResponse::from_result(Ok((
+                Value::null(),
+                vec![ExecutionError::at_origin(FieldError::new(
+                    "multi\nline",
+                    Value::null(),
+                ))],
+            ))),
+        ]);
+
+        let body = ndjson_stream(responses)
+            .map(|chunk| chunk.expect("response should serialize"))
+            .collect::<Vec<_>>()
+            .await
+            .concat();
+        let body = String::from_utf8(body).unwrap();
+
+        assert!(body.ends_with('\n'));
+        let lines = body
+            .lines()
+            .map(|line| serde_json::from_str::<Json>(line).expect("line should be JSON"))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            lines,
+            vec![
+                json!({"data": {"counter": 1}}),
+                json!({"data": {"counter": 2}}),
+                json!({
+                    "data": null,
+                    "errors": [{"message": "multi\nline", "locations": [{"line": 1, "column": 1}], "path": []}],
+                }),
+            ],
+        );
+    }
+
+    #[tokio::test]
+    async fn empty_stream_writes_empty_body() {
+        let responses = stream::iter(Vec::<GraphQLResponse<DefaultScalarValue>>::new());
+
+        assert!(ndjson_stream(responses)
+            .collect::<Vec<_>>()
+            .await
+            .is_empty());
+    }
+}
@@ -0,0 +1,342 @@
+//! Support for [automatic persisted queries][apq]
+//!
+//! Instead of sending the full text of a query every time, a client may send
+//! only its SHA-256 hash in `extensions.persistedQuery.sha256Hash`. If the
+//! server doesn't know the hash yet, it answers with a
+//! `PERSISTED_QUERY_NOT_FOUND` error and the client retries with both the
+//! query and its hash, registering the query for subsequent requests.
+//!
+//! A schema answers persisted queries once given a [`PersistedQueryStore`]
+//! with [`RootNode::persisted_queries`]. Integrations load the queries of the
+//! requests they receive with [`GraphQLBatchRequest::load_persisted_queries`]
+//! before executing them.
+//!
+//! [apq]: https://www.apollographql.com/docs/apollo-server/performance/apq/
+//! [`GraphQLBatchRequest::load_persisted_queries`]: ../enum.GraphQLBatchRequest.html#method.load_persisted_queries
+//! [`PersistedQueryStore`]: trait.PersistedQueryStore.html
+//! [`RootNode::persisted_queries`]: ../../struct.RootNode.html#method.persisted_queries
+
+use std::{collections::HashMap, fmt, sync::RwLock};
+
+use sha2::{Digest, Sha256};
+
+use super::GraphQLRequest;
+use crate::{
+    executor::FieldError,
+    value::{Object, ScalarValue, Value},
+};
+
+/// The error code reported when a request carries the hash of a query the
+/// store doesn't know
+pub const PERSISTED_QUERY_NOT_FOUND: &str = "PERSISTED_QUERY_NOT_FOUND";
+
+/// The error code reported when a request carries only the hash of a query
+/// to a schema without a store
+pub const PERSISTED_QUERY_NOT_SUPPORTED: &str = "PERSISTED_QUERY_NOT_SUPPORTED";
+
+/// The only version of the `persistedQuery` extension
+const PERSISTED_QUERY_VERSION: i32 = 1;
+
+/// Storage of the queries registered through automatic persisted queries,
+/// by the hex-encoded SHA-256 hash of their text
+///
+/// Implementations can be shared between requests, e.g. backed by a cache
+/// server to share the queries between several instances of the service.
+pub trait PersistedQueryStore: Send + Sync {
+    /// The query registered under `hash`, if any
+    fn get(&self, hash: &str) -> Option<String>;
+
+    /// Register `query` under its `hash`
+    fn put(&self, hash: &str, query: String);
+}
+
+/// A [`PersistedQueryStore`] keeping the queries in memory, without any
+/// eviction
+///
+/// [`PersistedQueryStore`]: trait.PersistedQueryStore.html
+#[derive(Debug, Default)]
+pub struct MemoryPersistedQueryStore {
+    queries: RwLock<HashMap<String, String>>,
+}
+
+impl MemoryPersistedQueryStore {
+    /// Construct an empty store
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl PersistedQueryStore for MemoryPersistedQueryStore {
+    fn get(&self, hash: &str) -> Option<String> {
+        self.queries.read().unwrap().get(hash).cloned()
+    }
+
+    fn put(&self, hash: &str, query: String) {
+        self.queries.write().unwrap().insert(hash.to_owned(), query);
+    }
+}
+
+/// The store of a schema, see `RootNode::persisted_queries`
+pub(crate) struct SchemaPersistedQueryStore(pub(crate) Box<dyn PersistedQueryStore>);
+
+impl fmt::Debug for SchemaPersistedQueryStore {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("SchemaPersistedQueryStore")
+    }
+}
+
+fn error_with_code<S>(message: &str, code: &str) -> FieldError<S>
+where
+    S: ScalarValue,
+{
+    let mut extensions = Object::with_capacity(1);
+    extensions.add_field("code", Value::scalar(code.to_owned()));
+    FieldError::new(message, Value::Object(extensions))
+}
+
+impl<S> GraphQLRequest<S>
+where
+    S: ScalarValue,
+{
+    /// The SHA-256 hash of the persisted query this request refers to, if any
+    pub fn persisted_query_hash(&self) -> Option<&str> {
+        self.extensions
+            .as_ref()
+            .and_then(|e| e.persisted_query.as_ref())
+            .map(|p| p.sha256_hash.as_str())
+    }
+
+    /// Resolve the automatic persisted query of this request against `store`
+    ///
+    /// - A request without a persisted query hash is left untouched.
+    /// - A request with only a hash gets the query registered under it, or
+    ///   fails with a `PERSISTED_QUERY_NOT_FOUND` error if there is none.
+    /// - A request with both a hash and a query registers the query, after
+    ///   checking that the hash matches it.
+    ///
+    /// Fails if the request uses another version of the extension than `1`.
+    ///
+    /// The error can be sent back with [`GraphQLResponse::error`].
+    ///
+    /// [`GraphQLResponse::error`]: ../struct.GraphQLResponse.html#method.error
+    pub fn load_persisted_query<P>(&mut self, store: &P) -> Result<(), FieldError<S>>
+    where
+        P: PersistedQueryStore + ?Sized,
+    {
+        let persisted_query = match self
+            .extensions
+            .as_ref()
+            .and_then(|e| e.persisted_query.as_ref())
+        {
+            Some(persisted_query) => persisted_query,
+            None => return Ok(()),
+        };
+        if persisted_query.version != PERSISTED_QUERY_VERSION {
+            return Err(FieldError::new(
+                format!(
+                    "Unsupported persisted query version {}",
+                    persisted_query.version,
+                ),
+                Value::null(),
+            ));
+        }
+        let hash = persisted_query.sha256_hash.to_ascii_lowercase();
+
+        if self.query.is_empty() {
+            self.query = store.get(&hash).ok_or_else(|| {
+                error_with_code("PersistedQueryNotFound", PERSISTED_QUERY_NOT_FOUND)
+            })?;
+        } else {
+            if format!("{:x}", Sha256::digest(self.query.as_bytes())) != hash {
+                return Err(FieldError::new(
+                    "provided sha does not match query",
+                    Value::null(),
+                ));
+            }
+            store.put(&hash, self.query.clone());
+        }
+
+        Ok(())
+    }
+
+    /// Load the persisted query of this request from the store of a schema,
+    /// keeping the error to answer the request with
+    pub(crate) fn load_persisted_query_from(&mut self, store: Option<&dyn PersistedQueryStore>) {
+        let result = match store {
+            Some(store) => self.load_persisted_query(store),
+            None if self.query.is_empty() && self.persisted_query_hash().is_some() => Err(
+                error_with_code("PersistedQueryNotSupported", PERSISTED_QUERY_NOT_SUPPORTED),
+            ),
+            None => Ok(()),
+        };
+        self.persisted_query_error = result
+            .err()
+            .map(|e| (e.message().to_owned(), e.extensions().clone()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::{MemoryPersistedQueryStore, PersistedQueryStore};
+    use crate::{
+        http::{GraphQLBatchEntry, GraphQLBatchRequest, GraphQLRequest, GraphQLResponse},
+        schema::model::RootNode,
+        tests::{model::Database, schema::Query},
+        types::scalars::{EmptyMutation, EmptySubscription},
+        value::DefaultScalarValue,
+    };
+
+    const QUERY: &str = "{ hero { name } }";
+    // `sha256sum` of `QUERY`
+    const HASH: &str = "aae585680c3470e4947255eafbd1eafe87d1c3f129259cf15e404d1bb7f1e8f4";
+
+    fn request(body: serde_json::Value) -> GraphQLRequest<DefaultScalarValue> {
+        serde_json::from_value(body).expect("request should deserialize")
+    }
+
+    fn hash_only() -> GraphQLRequest<DefaultScalarValue> {
+        request(json!({
+            "extensions": {"persistedQuery": {"version": 1, "sha256Hash": HASH}},
+        }))
+    }
+
+    fn execute(request: &GraphQLRequest<DefaultScalarValue>) -> serde_json::Value {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+        let response = request.execute_sync(&schema, &Database::new());
+        serde_json::to_value(&response).unwrap()
+    }
+
+    #[test]
+    fn registers_then_replays_query() {
+        let store = MemoryPersistedQueryStore::new();
+
+        let mut register = request(json!({
+            "query": QUERY,
+            "extensions": {"persistedQuery": {"version": 1, "sha256Hash": HASH}},
+        }));
+        register.load_persisted_query(&store).unwrap();
+        assert_eq!(store.get(HASH).as_deref(), Some(QUERY));
+        assert_eq!(
+            execute(&register),
+            json!({"data": {"hero": {"name": "R2-D2"}}}),
+        );
+
+        let mut replay = hash_only();
+        replay.load_persisted_query(&store).unwrap();
+        assert_eq!(
+            execute(&replay),
+            json!({"data": {"hero": {"name": "R2-D2"}}}),
+        );
+    }
+
+    #[test]
+    fn unknown_hash_is_not_found() {
+        let store = MemoryPersistedQueryStore::new();
+
+        let error = hash_only().load_persisted_query(&store).unwrap_err();
+        let response = GraphQLResponse::<DefaultScalarValue>::error(error);
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap()["errors"][0],
+            json!({
+                "message": "PersistedQueryNotFound",
+                "locations": [{"line": 1, "column": 1}],
+                "path": [],
+                "extensions": {"code": "PERSISTED_QUERY_NOT_FOUND"},
+            }),
+        );
+    }
+
+    #[test]
+    fn mismatched_hash_is_not_registered() {
+        let store = MemoryPersistedQueryStore::new();
+
+        let mut request = request(json!({
+            "query": "{ hero { id } }",
+            "extensions": {"persistedQuery": {"version": 1, "sha256Hash": HASH}},
+        }));
+
+        assert!(request.load_persisted_query(&store).is_err());
+        assert_eq!(store.get(HASH), None);
+    }
+
+    #[test]
+    fn unsupported_version_is_rejected() {
+        let store = MemoryPersistedQueryStore::new();
+
+        let mut request = request(json!({
+            "query": QUERY,
+            "extensions": {"persistedQuery": {"version": 2, "sha256Hash": HASH}},
+        }));
+
+        assert!(request.load_persisted_query(&store).is_err());
+        assert_eq!(store.get(HASH), None);
+    }
+
+    #[test]
+    fn batch_loads_queries_from_schema_store() {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        )
+        .persisted_queries(MemoryPersistedQueryStore::new());
+
+        let mut batch: GraphQLBatchRequest = serde_json::from_value(json!([
+            {
+                "query": QUERY,
+                "extensions": {"persistedQuery": {"version": 1, "sha256Hash": HASH}},
+            },
+            {"extensions": {"persistedQuery": {"version": 1, "sha256Hash": HASH}}},
+        ]))
+        .unwrap();
+        batch.load_persisted_queries(&schema);
+        let response = batch.execute_sync(&schema, &Database::new());
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap(),
+            json!([
+                {"data": {"hero": {"name": "R2-D2"}}},
+                {"data": {"hero": {"name": "R2-D2"}}},
+            ]),
+        );
+    }
+
+    #[test]
+    fn schema_without_store_is_not_supported() {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+
+        let mut request = GraphQLBatchRequest::Single(hash_only());
+        request.load_persisted_queries(&schema);
+        let response = request.execute_sync(&schema, &Database::new());
+
+        assert_eq!(
+            serde_json::to_value(&response).unwrap()["errors"][0]["extensions"],
+            json!({"code": "PERSISTED_QUERY_NOT_SUPPORTED"}),
+        );
+    }
+
+    #[test]
+    fn request_without_query_or_hash_is_malformed() {
+        assert!(serde_json::from_value::<GraphQLRequest>(json!({"variables": {}})).is_err());
+
+        let entry: GraphQLBatchEntry = serde_json::from_value(json!({
+            "extensions": {"persistedQuery": {"version": 1, "sha256Hash": HASH}},
+        }))
+        .unwrap();
+        assert_eq!(
+            entry.request().and_then(|r| r.persisted_query_hash()),
+            Some(HASH)
+        );
+    }
+}
@@ -1,10 +1,82 @@
 //! Utility module to generate a GraphQL Playground interface
 
+use super::{escape_html, js_string_literal};
+
+/// The GraphQL Playground version the generated page loads when none is pinned
+pub const DEFAULT_PLAYGROUND_VERSION: &str = "1.7.20";
+
+/// Options to customize the page generated by [`playground_source_with`]
+///
+/// [`playground_source_with`]: fn.playground_source_with.html
+#[derive(Clone, Debug, PartialEq)]
+pub struct PlaygroundOptions {
+    title: String,
+    default_query: Option<String>,
+    headers: Vec<(String, String)>,
+    version: String,
+}
+
+impl Default for PlaygroundOptions {
+    fn default() -> Self {
+        PlaygroundOptions {
+            title: "GraphQL Playground".to_owned(),
+            default_query: None,
+            headers: Vec::new(),
+            version: DEFAULT_PLAYGROUND_VERSION.to_owned(),
+        }
+    }
+}
+
+impl PlaygroundOptions {
+    /// Construct the default options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the page title
+    pub fn title<T: Into<String>>(mut self, title: T) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Pre-fill the first tab with a query
+    pub fn default_query<T: Into<String>>(mut self, query: T) -> Self {
+        self.default_query = Some(query.into());
+        self
+    }
+
+    /// Add an HTTP header to send along with every request
+    pub fn header<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Pin the version of the GraphQL Playground assets loaded from the CDN
+    pub fn version<T: Into<String>>(mut self, version: T) -> Self {
+        self.version = version.into();
+        self
+    }
+}
+
 /// Generate the HTML source to show a GraphQL Playground interface
-// source: https://github.com/prisma/graphql-playground/blob/master/packages/graphql-playground-html/withAnimation.html
 pub fn playground_source(
     graphql_endpoint_url: &str,
     subscriptions_endpoint_url: Option<&str>,
+) -> String {
+    playground_source_with(
+        graphql_endpoint_url,
+        subscriptions_endpoint_url,
+        &PlaygroundOptions::default(),
+    )
+}
+
+/// Generate the HTML source to show a GraphQL Playground interface, customized
+/// by `options`
+// source: https://github.com/prisma/graphql-playground/blob/master/packages/graphql-playground-html/withAnimation.html
+pub fn playground_source_with(
+    graphql_endpoint_url: &str,
+    subscriptions_endpoint_url: Option<&str>,
+    options: &PlaygroundOptions,
 ) -> String {
     let subscriptions_endpoint = if let Some(sub_url) = subscriptions_endpoint_url {
         sub_url
@@ -12,6 +84,32 @@ pub fn playground_source(
         graphql_endpoint_url
     };
 
+    let headers = format!(
+        "{{{}}}",
+        options
+            .headers
+            .iter()
+            .map(|(name, value)| format!(
+                "{}: {}",
+                js_string_literal(name),
+                js_string_literal(value)
+            ))
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+    let mut init_options = String::new();
+    if !options.headers.is_empty() {
+        init_options.push_str(&format!(", headers: {}", headers));
+    }
+    if let Some(ref query) = options.default_query {
+        init_options.push_str(&format!(
+            ", tabs: [{{ endpoint: {}, query: {}, headers: {} }}]",
+            js_string_literal(graphql_endpoint_url),
+            js_string_literal(query),
+            headers
+        ));
+    }
+
     r##"
 <!DOCTYPE html>
 
@@ -20,10 +118,10 @@ pub fn playground_source(
 <head>
   <meta charset=utf-8 />
   <meta name="viewport" content="user-scalable=no, initial-scale=1.0, minimum-scale=1.0, maximum-scale=1.0, minimal-ui">
-  <title>GraphQL Playground</title>
-  <link rel="stylesheet" href="//cdn.jsdelivr.net/npm/graphql-playground-react/build/static/css/index.css" />
-  <link rel="shortcut icon" href="//cdn.jsdelivr.net/npm/graphql-playground-react/build/favicon.png" />
-  <script src="//cdn.jsdelivr.net/npm/graphql-playground-react@1.7.20/build/static/js/middleware.js"></script>
+  <title>JUNIPER_PLAYGROUND_TITLE</title>
+  <link rel="stylesheet" href="//cdn.jsdelivr.net/npm/graphql-playground-react@JUNIPER_PLAYGROUND_VERSION/build/static/css/index.css" />
+  <link rel="shortcut icon" href="//cdn.jsdelivr.net/npm/graphql-playground-react@JUNIPER_PLAYGROUND_VERSION/build/favicon.png" />
+  <script src="//cdn.jsdelivr.net/npm/graphql-playground-react@JUNIPER_PLAYGROUND_VERSION/build/static/js/middleware.js"></script>
 
 </head>
 
@@ -546,11 +644,47 @@ pub fn playground_source(
       const root = document.getElementById('root');
       root.classList.add('playgroundIn');
 
-      GraphQLPlayground.init(root, { endpoint: 'JUNIPER_GRAPHQL_URL', subscriptionEndpoint: 'JUNIPER_SUBSCRIPTIONS_URL' })
+      GraphQLPlayground.init(root, { endpoint: 'JUNIPER_GRAPHQL_URL', subscriptionEndpoint: 'JUNIPER_SUBSCRIPTIONS_URL'JUNIPER_PLAYGROUND_OPTIONS })
     })
   </script>
 </body>
 </html>
   "##.replace("JUNIPER_GRAPHQL_URL", graphql_endpoint_url)
      .replace("JUNIPER_SUBSCRIPTIONS_URL", subscriptions_endpoint)
+     .replace("JUNIPER_PLAYGROUND_TITLE", &escape_html(&options.title))
+     .replace("JUNIPER_PLAYGROUND_VERSION", &escape_html(&options.version))
+     .replace("JUNIPER_PLAYGROUND_OPTIONS", &init_options)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{playground_source, playground_source_with, PlaygroundOptions};
+
+    #[test]
+    fn default_source_uses_default_options() {
+        let html = playground_source("/graphql", Some("/subscriptions"));
+
+        assert!(html.contains("<title>GraphQL Playground</title>"));
+        assert!(html.contains("graphql-playground-react@1.7.20/build/static/js/middleware.js"));
+        assert!(html.contains("{ endpoint: '/graphql', subscriptionEndpoint: '/subscriptions' }"));
+    }
+
+    #[test]
+    fn injects_options() {
+        let options = PlaygroundOptions::new()
+            .title("My <API>")
+            .default_query("{\n  hero { name }\n}")
+            .header("Authorization", "Bearer token")
+            .version("1.7.26");
+        let html = playground_source_with("/graphql", None, &options);
+
+        assert!(html.contains("<title>My &lt;API&gt;</title>"));
+        assert!(html.contains(
+            "tabs: [{ endpoint: \"/graphql\", query: \"{\\n  hero { name }\\n}\", \
+             headers: {\"Authorization\": \"Bearer token\"} }]"
+        ));
+        assert!(html.contains("headers: {\"Authorization\": \"Bearer token\"}, tabs"));
+        assert!(html.contains("graphql-playground-react@1.7.26/build/static/css/index.css"));
+        assert!(!html.contains("1.7.20"));
+    }
 }
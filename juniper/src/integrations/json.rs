@@ -0,0 +1,295 @@
+use std::convert::TryFrom;
+
+use serde_json::{Map, Number, Value as JsonValue};
+
+use crate::{
+    ast::InputValue,
+    parser::ScalarToken,
+    value::{DefaultScalarValue, ParseScalarResult, ParseScalarValue, ScalarValue},
+    Value,
+};
+
+impl<S> InputValue<S>
+where
+    S: ScalarValue,
+{
+    /// Convert this input value into a JSON value.
+    ///
+    /// Enum values become strings and variables become `null`, as when
+    /// serializing the input value.
+    pub fn to_json(&self) -> JsonValue {
+        serde_json::to_value(self).expect("Input values can always be converted to JSON")
+    }
+}
+
+impl InputValue<DefaultScalarValue> {
+    /// Build an input value from a JSON value, e.g. to construct variables
+    /// from arbitrary JSON.
+    ///
+    /// Integers out of the range of `Int` are kept as they are by
+    /// [`DefaultScalarValue`].
+    pub fn from_json(json: JsonValue) -> Self {
+        serde_json::from_value(json).expect("JSON values can always be read as input values")
+    }
+}
+
+/// Unstructured JSON value, read from any input value and resolved as is
+///
+/// Objects and lists are accepted as literals, but cannot contain variables.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Json(pub JsonValue);
+
+#[crate::graphql_scalar_internal(name = "JSON", description = "Unstructured JSON value")]
+impl<S> GraphQLScalar for Json
+where
+    S: ScalarValue,
+{
+    fn resolve(&self) -> Value {
+        json_to_value(&self.0)
+    }
+
+    fn from_input_value(v: &InputValue) -> Option<Json> {
+        input_value_to_json(v).map(Json)
+    }
+
+    fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
+        match value {
+            ScalarToken::String(_) => <String as ParseScalarValue<S>>::from_str(value),
+            ScalarToken::Int(_) => <i32 as ParseScalarValue<S>>::from_str(value),
+            ScalarToken::Float(_) => <f64 as ParseScalarValue<S>>::from_str(value),
+        }
+    }
+}
+
+fn json_to_value<S: ScalarValue>(json: &JsonValue) -> Value<S> {
+    match *json {
+        JsonValue::Null => Value::null(),
+        JsonValue::Bool(b) => Value::scalar(b),
+        JsonValue::Number(ref n) => match n.as_i64().and_then(|i| i32::try_from(i).ok()) {
+            Some(i) => Value::scalar(i),
+            None => Value::scalar(n.as_f64().unwrap_or_default()),
+        },
+        JsonValue::String(ref s) => Value::scalar(s.clone()),
+        JsonValue::Array(ref items) => Value::list(items.iter().map(json_to_value).collect()),
+        JsonValue::Object(ref obj) => Value::object(
+            obj.iter()
+                .map(|(k, v)| (k.clone(), json_to_value(v)))
+                .collect(),
+        ),
+    }
+}
+
+fn input_value_to_json<S: ScalarValue>(v: &InputValue<S>) -> Option<JsonValue> {
+    match *v {
+        InputValue::Null => Some(JsonValue::Null),
+        InputValue::Scalar(ref s) => s
+            .as_boolean()
+            .map(JsonValue::from)
+            .or_else(|| s.as_int().map(JsonValue::from))
+            .or_else(|| {
+                s.as_float()
+                    .and_then(Number::from_f64)
+                    .map(JsonValue::Number)
+            })
+            .or_else(|| s.as_string().map(JsonValue::from)),
+        InputValue::List(ref items) => items
+            .iter()
+            .map(|i| input_value_to_json(&i.item))
+            .collect::<Option<Vec<_>>>()
+            .map(JsonValue::Array),
+        InputValue::Object(ref fields) => fields
+            .iter()
+            .map(|&(ref k, ref v)| input_value_to_json(&v.item).map(|v| (k.item.clone(), v)))
+            .collect::<Option<Map<_, _>>>()
+            .map(JsonValue::Object),
+        InputValue::Enum(_) | InputValue::Variable(_) => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use serde_json::json;
+
+    use super::Json;
+    use crate::{
+        ast::InputValue,
+        executor::Variables,
+        schema::model::RootNode,
+        types::scalars::{EmptyMutation, EmptySubscription},
+        value::{DefaultScalarValue, Value},
+    };
+
+    struct Query;
+
+    #[crate::graphql_object_internal]
+    impl Query {
+        fn settings() -> Json {
+            Json(json!({"beta": null, "theme": {"accents": ["red", 2, 0.5], "dark": true}}))
+        }
+
+        fn echo(value: Json) -> Json {
+            value
+        }
+    }
+
+    fn execute(query: &str, vars: &Variables) -> Value {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+
+        let (value, errors) = crate::execute_sync(query, None, &schema, vars, &()).unwrap();
+        assert_eq!(errors, []);
+        value
+    }
+
+    /// `theme` of the settings
+    ///
+    /// The keys of the fixtures are sorted, so that they read the same whether
+    /// or not `serde_json` preserves their order.
+    fn theme() -> Value {
+        Value::object(
+            vec![
+                (
+                    "accents",
+                    Value::list(vec![
+                        Value::scalar("red"),
+                        Value::scalar(2),
+                        Value::scalar(0.5),
+                    ]),
+                ),
+                ("dark", Value::scalar(true)),
+            ]
+            .into_iter()
+            .collect(),
+        )
+    }
+
+    fn object(fields: Vec<(&str, Value)>) -> Value {
+        Value::object(fields.into_iter().collect())
+    }
+
+    #[test]
+    fn converts_input_values_from_and_to_json() {
+        let json = json!({
+            "after": null,
+            "filter": {"nested": {"deep": [1, 2.5, true]}, "tags": ["a", "b"]},
+            "ids": [],
+        });
+        let input = InputValue::object(
+            vec![
+                ("after", InputValue::null()),
+                (
+                    "filter",
+                    InputValue::object(
+                        vec![
+                            (
+                                "nested",
+                                InputValue::object(
+                                    vec![(
+                                        "deep",
+                                        InputValue::list(vec![
+                                            InputValue::scalar(1),
+                                            InputValue::scalar(2.5),
+                                            InputValue::scalar(true),
+                                        ]),
+                                    )]
+                                    .into_iter()
+                                    .collect(),
+                                ),
+                            ),
+                            (
+                                "tags",
+                                InputValue::list(vec![
+                                    InputValue::scalar("a"),
+                                    InputValue::scalar("b"),
+                                ]),
+                            ),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    ),
+                ),
+                ("ids", InputValue::list(vec![])),
+            ]
+            .into_iter()
+            .collect(),
+        );
+
+        assert_eq!(InputValue::from_json(json.clone()), input);
+        assert_eq!(input.to_json(), json);
+        assert_eq!(InputValue::from_json(input.to_json()), input);
+        assert_eq!(InputValue::from_json(json!(null)), InputValue::null());
+        assert_eq!(
+            InputValue::<DefaultScalarValue>::null().to_json(),
+            json!(null)
+        );
+    }
+
+    #[test]
+    fn converts_enums_and_variables_to_json() {
+        assert_eq!(
+            InputValue::<DefaultScalarValue>::enum_value("EMPIRE").to_json(),
+            json!("EMPIRE"),
+        );
+        assert_eq!(
+            InputValue::<DefaultScalarValue>::variable("id").to_json(),
+            json!(null),
+        );
+    }
+
+    #[test]
+    fn resolves_nested_json() {
+        assert_eq!(
+            execute("{ settings }", &Variables::new()),
+            object(vec![(
+                "settings",
+                object(vec![("beta", Value::null()), ("theme", theme())]),
+            )]),
+        );
+    }
+
+    #[test]
+    fn reads_json_variables_and_literals() {
+        let vars = vec![(
+            "value".to_owned(),
+            InputValue::object(
+                vec![(
+                    "theme",
+                    InputValue::object(
+                        vec![
+                            (
+                                "accents",
+                                InputValue::list(vec![
+                                    InputValue::scalar("red"),
+                                    InputValue::scalar(2),
+                                    InputValue::scalar(0.5),
+                                ]),
+                            ),
+                            ("dark", InputValue::scalar(true)),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    ),
+                )]
+                .into_iter()
+                .collect(),
+            ),
+        )]
+        .into_iter()
+        .collect();
+
+        assert_eq!(
+            execute("query q($value: JSON!) { echo(value: $value) }", &vars),
+            object(vec![("echo", object(vec![("theme", theme())]))]),
+        );
+        assert_eq!(
+            execute(
+                r#"{ echo(value: {theme: {accents: ["red", 2, 0.5], dark: true}}) }"#,
+                &Variables::new(),
+            ),
+            object(vec![("echo", object(vec![("theme", theme())]))]),
+        );
+    }
+}
@@ -14,6 +14,10 @@ pub mod url;
 /// GraphQL support for [uuid](https://doc.rust-lang.org/uuid/uuid/struct.Uuid.html) types.
 pub mod uuid;
 
+#[cfg(any(test, feature = "serde_json"))]
+/// GraphQL support for unstructured [serde_json](https://github.com/serde-rs/json) values.
+pub mod json;
+
 #[cfg(feature = "bson")]
 /// GraphQL support for [bson](https://github.com/mongodb/bson-rust) types.
 pub mod bson;
@@ -5,19 +5,22 @@ use serde::{
 };
 use serde_derive::Serialize;
 
-use std::fmt;
+use std::{
+    fmt,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
 
 use crate::{
     ast::InputValue,
-    executor::ExecutionError,
+    executor::{ExecutionError, FieldTiming, PathSegment, Tracing},
     parser::{ParseError, SourcePosition, Spanning},
     validation::RuleError,
     GraphQLError, Object, ScalarValue, Value,
 };
 
 #[derive(Serialize)]
-struct SerializeHelper {
-    message: &'static str,
+struct SerializeHelper<'a> {
+    message: &'a str,
 }
 
 impl<T> ser::Serialize for ExecutionError<T>
@@ -49,6 +52,18 @@ where
     }
 }
 
+impl ser::Serialize for PathSegment {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        match *self {
+            PathSegment::Field(ref name) => serializer.serialize_str(name),
+            PathSegment::Index(index) => serializer.serialize_u64(index as u64),
+        }
+    }
+}
+
 impl<'a> ser::Serialize for GraphQLError<'a> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -66,8 +81,8 @@ impl<'a> ser::Serialize for GraphQLError<'a> {
                           if query contains multiple operations",
             }]
             .serialize(serializer),
-            GraphQLError::UnknownOperationName => [SerializeHelper {
-                message: "Unknown operation",
+            GraphQLError::UnknownOperationName(ref name) => [SerializeHelper {
+                message: &format!("Unknown operation named \"{}\"", name),
             }]
             .serialize(serializer),
             GraphQLError::IsSubscription => [SerializeHelper {
@@ -78,10 +93,95 @@ impl<'a> ser::Serialize for GraphQLError<'a> {
                 message: "Expected subscription, got query",
             }]
             .serialize(serializer),
+            GraphQLError::TimedOut => [SerializeHelper {
+                message: "Execution timed out",
+            }]
+            .serialize(serializer),
+            GraphQLError::MalformedRequest => [SerializeHelper {
+                message: r#"Expected a GraphQL request object with a "query" string"#,
+            }]
+            .serialize(serializer),
         }
     }
 }
 
+impl ser::Serialize for Tracing {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        #[derive(Serialize)]
+        struct Execution<'a> {
+            resolvers: &'a [FieldTiming],
+        }
+
+        let mut map = serializer.serialize_map(Some(5))?;
+        map.serialize_entry("version", &1)?;
+        map.serialize_entry("startTime", &rfc3339(self.start_time()))?;
+        map.serialize_entry("endTime", &rfc3339(self.end_time()))?;
+        map.serialize_entry("duration", &nanos(self.duration()))?;
+        map.serialize_entry(
+            "execution",
+            &Execution {
+                resolvers: self.resolvers(),
+            },
+        )?;
+        map.end()
+    }
+}
+
+impl ser::Serialize for FieldTiming {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: ser::Serializer,
+    {
+        let mut map = serializer.serialize_map(Some(6))?;
+        map.serialize_entry("path", &self.path)?;
+        map.serialize_entry("parentType", &self.parent_type)?;
+        map.serialize_entry("fieldName", &self.field_name)?;
+        map.serialize_entry("returnType", &self.return_type)?;
+        map.serialize_entry("startOffset", &nanos(self.start_offset))?;
+        map.serialize_entry("duration", &nanos(self.duration))?;
+        map.end()
+    }
+}
+
+/// A duration in nanoseconds, as Apollo Tracing expects
+fn nanos(duration: Duration) -> u64 {
+    duration.as_secs() * 1_000_000_000 + u64::from(duration.subsec_nanos())
+}
+
+/// Format `time` as an RFC 3339 UTC timestamp with millisecond precision
+fn rfc3339(time: SystemTime) -> String {
+    let since_epoch = time.duration_since(UNIX_EPOCH).unwrap_or_default();
+    let secs = since_epoch.as_secs();
+    let (days, secs_of_day) = ((secs / 86_400) as i64, secs % 86_400);
+
+    // Civil date from days since the epoch, after Howard Hinnant's
+    // `civil_from_days`.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let day_of_era = z.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = year_of_era + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    format!(
+        "{:04}-{:02}-{:02}T{:02}:{:02}:{:02}.{:03}Z",
+        year,
+        month,
+        day,
+        secs_of_day / 3600,
+        secs_of_day / 60 % 60,
+        secs_of_day % 60,
+        since_epoch.subsec_millis(),
+    )
+}
+
 impl<'de, S> de::Deserialize<'de> for InputValue<S>
 where
     S: ScalarValue,
@@ -409,11 +509,16 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::{ExecutionError, GraphQLError};
+    use std::time::{Duration, UNIX_EPOCH};
+
+    use super::{rfc3339, ExecutionError, GraphQLError};
     use crate::{
         ast::InputValue,
+        executor::Variables,
+        schema::model::RootNode,
+        types::scalars::{EmptyMutation, EmptySubscription},
         value::{DefaultScalarValue, Object},
-        FieldError, Value,
+        FieldError, FieldResult, Value,
     };
     use serde_json::{from_str, to_string};
 
@@ -431,18 +536,30 @@ mod tests {
             from_str::<InputValue<DefaultScalarValue>>("2.0").unwrap(),
             InputValue::scalar(2.0)
         );
-        // large value without a decimal part is also float
+    }
+
+    #[test]
+    fn long() {
+        // integers out of the range of `Int` are kept losslessly
         assert_eq!(
             from_str::<InputValue<DefaultScalarValue>>("123567890123").unwrap(),
-            InputValue::scalar(123_567_890_123.0)
+            InputValue::scalar(123_567_890_123i64)
+        );
+        assert_eq!(
+            from_str::<InputValue<DefaultScalarValue>>("9223372036854775807").unwrap(),
+            InputValue::scalar(i64::max_value())
+        );
+        assert_eq!(
+            to_string(&InputValue::<DefaultScalarValue>::scalar(i64::min_value())).unwrap(),
+            "-9223372036854775808"
         );
     }
 
     #[test]
     fn errors() {
         assert_eq!(
-            to_string(&GraphQLError::UnknownOperationName).unwrap(),
-            r#"[{"message":"Unknown operation"}]"#
+            to_string(&GraphQLError::UnknownOperationName("Example".to_owned())).unwrap(),
+            r#"[{"message":"Unknown operation named \"Example\""}]"#
         );
     }
 
@@ -459,4 +576,68 @@ mod tests {
             r#"{"message":"foo error","locations":[{"line":1,"column":1}],"path":[],"extensions":{"foo":"bar"}}"#
         );
     }
+
+    struct Query;
+
+    #[crate::graphql_object_internal]
+    impl Query {
+        fn working() -> i32 {
+            1
+        }
+
+        fn broken() -> FieldResult<Option<i32>> {
+            Err("broken".into())
+        }
+    }
+
+    #[test]
+    fn execution_error_locations() {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+        let doc = "query {\n  working\n    broken\n}";
+
+        let (_, errors) = crate::execute_sync(doc, None, &schema, &Variables::new(), &()).unwrap();
+
+        // Lines and columns are 1-based, as the specification requires.
+        assert_eq!(
+            to_string(&errors).unwrap(),
+            r#"[{"message":"broken","locations":[{"line":3,"column":5}],"path":["broken"]}]"#
+        );
+    }
+
+    #[test]
+    fn execution_error_locations_after_non_ascii_text() {
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        );
+        let doc = "# Ünïcödé\nquery { working, broken }";
+
+        let (_, errors) = crate::execute_sync(doc, None, &schema, &Variables::new(), &()).unwrap();
+        let location = errors[0].location();
+
+        assert_eq!(
+            to_string(&errors).unwrap(),
+            r#"[{"message":"broken","locations":[{"line":2,"column":18}],"path":["broken"]}]"#
+        );
+        // The index is a byte offset, so it can be used to slice the source.
+        assert!(doc[location.index()..].starts_with("broken"));
+    }
+
+    #[test]
+    fn tracing_timestamps() {
+        assert_eq!(rfc3339(UNIX_EPOCH), "1970-01-01T00:00:00.000Z");
+        assert_eq!(
+            rfc3339(UNIX_EPOCH + Duration::from_millis(1_600_000_000_123)),
+            "2020-09-13T12:26:40.123Z"
+        );
+        assert_eq!(
+            rfc3339(UNIX_EPOCH + Duration::from_secs(951_825_599)),
+            "2000-02-29T11:59:59.000Z"
+        );
+    }
 }
@@ -115,15 +115,16 @@ extern crate bson;
 // This allows users to just depend on juniper and get the derive
 // functionality automatically.
 pub use juniper_codegen::{
-    graphql_object, graphql_subscription, graphql_union, GraphQLEnum, GraphQLInputObject,
-    GraphQLObject, GraphQLScalarValue,
+    graphql_object, graphql_scalar_impl, graphql_subscription, graphql_union, GraphQLEnum,
+    GraphQLInputObject, GraphQLObject, GraphQLScalarValue,
 };
 // Internal macros are not exported,
 // but declared at the root to make them easier to use.
 #[allow(unused_imports)]
 use juniper_codegen::{
-    graphql_object_internal, graphql_subscription_internal, graphql_union_internal,
-    GraphQLEnumInternal, GraphQLInputObjectInternal, GraphQLScalarValueInternal,
+    graphql_object_internal, graphql_scalar_internal, graphql_subscription_internal,
+    graphql_union_internal, GraphQLEnumInternal, GraphQLInputObjectInternal,
+    GraphQLScalarValueInternal,
 };
 
 #[macro_use]
@@ -131,9 +132,12 @@ mod value;
 #[macro_use]
 mod macros;
 mod ast;
+mod dataloader;
 mod executor;
 mod introspection;
 pub mod parser;
+#[cfg(feature = "relay")]
+pub mod relay;
 pub(crate) mod schema;
 mod types;
 mod util;
@@ -157,31 +161,50 @@ mod executor_tests;
 pub use crate::util::to_camel_case;
 
 use crate::{
-    executor::{execute_validated_query, get_operation},
+    executor::{
+        execute_validated_query_with_response_context, get_operation, operation_cache_max_age,
+        validate_operation_complexity, variables_with_defaults,
+    },
     introspection::{INTROSPECTION_QUERY, INTROSPECTION_QUERY_WITHOUT_DESCRIPTIONS},
     parser::{parse_document_source, ParseError, Spanning},
-    validation::{validate_input_values, visit_all_rules, ValidatorContext},
+    validation::{
+        validate_directive_conditions, validate_input_values, visit_all_rules, ValidatorContext,
+    },
 };
-use std::fmt;
+use std::{borrow::Cow, fmt};
 
 pub use crate::{
-    ast::{FromInputValue, InputValue, Selection, ToInputValue, Type},
+    ast::{
+        Definition, Document, FromInputValue, InputValue, Operation, OperationType, Selection,
+        ToInputValue, Type,
+    },
+    dataloader::{DataLoader, DataLoaderError},
     executor::{
         Applies, Context, ExecutionError, ExecutionResult, Executor, FieldError, FieldResult,
-        FromContext, IntoFieldError, IntoResolvable, LookAheadArgument, LookAheadMethods,
-        LookAheadSelection, LookAheadValue, OwnedExecutor, Registry, ValuesStream, Variables,
+        FieldTiming, FromContext, IntoFieldError, IntoResolvable, LookAheadArgument,
+        LookAheadMethods, LookAheadSelection, LookAheadValue, Lookup, OwnedExecutor, PathSegment,
+        PlannedResolver, Registry, ResponseContext, Tracing, ValuesStream, Variables,
     },
     introspection::IntrospectionFormat,
     macros::subscription_helpers::{ExtractTypeFromStream, IntoFieldResult},
     schema::{
+        diff::{schema_diff, SchemaChange},
         meta,
-        model::{RootNode, SchemaType},
+        model::{
+            DirectiveLocation, DirectiveType, FieldNaming, RootNode, SchemaBuildError, SchemaType,
+            SchemaTypeError,
+        },
+        stitching::{SchemaStitcher, StitchError, Stitched, StitchedInfo},
     },
     types::{
         async_await::GraphQLTypeAsync,
         base::{Arguments, GraphQLType, TypeKind},
-        scalars::{EmptyMutation, EmptySubscription, ID},
-        subscriptions::{GraphQLSubscriptionType, SubscriptionConnection, SubscriptionCoordinator},
+        live::Live,
+        scalars::{Displayed, EmptyMutation, EmptySubscription, ID},
+        subscriptions::{
+            BufferOverflow, BufferPressure, GraphQLSubscriptionType, SubscriptionConnection,
+            SubscriptionCoordinator,
+        },
     },
     validation::RuleError,
     value::{DefaultScalarValue, Object, ParseScalarResult, ParseScalarValue, ScalarValue, Value},
@@ -195,54 +218,74 @@ pub type BoxFuture<'a, T> = std::pin::Pin<Box<dyn std::future::Future<Output = T
 #[allow(missing_docs)]
 pub enum GraphQLError<'a> {
     ParseError(Spanning<ParseError<'a>>),
+    /// Every violation of the validation rules found in the document, not
+    /// only the first one
     ValidationError(Vec<RuleError>),
     NoOperationProvided,
     MultipleOperationsProvided,
-    UnknownOperationName,
+    UnknownOperationName(String),
     IsSubscription,
     NotSubscription,
+    /// The deadline given to [`execute_with_deadline`] passed before the
+    /// execution completed
+    TimedOut,
+    /// An entry of a batched request is not a GraphQL request object
+    MalformedRequest,
 }
 
 impl<'a> fmt::Display for GraphQLError<'a> {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            GraphQLError::ParseError(error) => write!(f, "{}", error),
+            GraphQLError::ParseError(error) => write!(f, "Parse error: {}", error),
             GraphQLError::ValidationError(errors) => {
-                for error in errors {
-                    writeln!(f, "{}", error)?;
+                write!(f, "Validation failed: ")?;
+                for (i, error) in errors.iter().enumerate() {
+                    if i > 0 {
+                        write!(f, "; ")?;
+                    }
+                    write!(f, "{}", error)?;
                 }
                 Ok(())
             }
-            GraphQLError::NoOperationProvided => write!(f, "No operation provided"),
-            GraphQLError::MultipleOperationsProvided => write!(f, "Multiple operations provided"),
-            GraphQLError::UnknownOperationName => write!(f, "Unknown operation name"),
-            GraphQLError::IsSubscription => write!(f, "Operation is a subscription"),
-            GraphQLError::NotSubscription => write!(f, "Operation is not a subscription"),
+            GraphQLError::NoOperationProvided => write!(f, "Must provide an operation"),
+            GraphQLError::MultipleOperationsProvided => write!(
+                f,
+                "Must provide operation name if query contains multiple operations",
+            ),
+            GraphQLError::UnknownOperationName(name) => {
+                write!(f, "Unknown operation named \"{}\"", name)
+            }
+            GraphQLError::IsSubscription => write!(f, "Expected query, got subscription"),
+            GraphQLError::NotSubscription => write!(f, "Expected subscription, got query"),
+            GraphQLError::TimedOut => write!(f, "Execution timed out"),
+            GraphQLError::MalformedRequest => write!(
+                f,
+                r#"Expected a GraphQL request object with a "query" string"#,
+            ),
         }
     }
 }
 
 impl<'a> std::error::Error for GraphQLError<'a> {}
 
-/// Execute a query synchronously in a provided schema
-pub fn execute_sync<'a, S, CtxT, QueryT, MutationT, SubscriptionT>(
-    document_source: &'a str,
+/// Validate `document` against `schema` and select the operation to run
+///
+/// Runs every validation rule, including the depth and alias limits of the
+/// schema, then checks the variables, the `@skip`/`@include` conditions and
+/// the complexity of the selected operation. Returns that operation together
+/// with the variables completed by their default values.
+pub(crate) fn validate_operation<'b, 'd, 'v, 'e, S>(
+    schema: &SchemaType<S>,
+    document: &'b Document<'d, S>,
     operation_name: Option<&str>,
-    root_node: &'a RootNode<QueryT, MutationT, SubscriptionT, S>,
-    variables: &Variables<S>,
-    context: &CtxT,
-) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+    variables: &'v Variables<S>,
+) -> Result<(&'b Spanning<Operation<'d, S>>, Cow<'v, Variables<S>>), GraphQLError<'e>>
 where
     S: ScalarValue,
-    QueryT: GraphQLType<S, Context = CtxT>,
-    MutationT: GraphQLType<S, Context = CtxT>,
-    SubscriptionT: GraphQLType<S, Context = CtxT>,
 {
-    let document = parse_document_source(document_source, &root_node.schema)?;
-
     {
-        let mut ctx = ValidatorContext::new(&root_node.schema, &document);
-        visit_all_rules(&mut ctx, &document);
+        let mut ctx = ValidatorContext::new(schema, document);
+        visit_all_rules(&mut ctx, document);
 
         let errors = ctx.into_errors();
         if !errors.is_empty() {
@@ -250,17 +293,99 @@ where
         }
     }
 
-    let operation = get_operation(&document, operation_name)?;
+    let operation = get_operation(document, operation_name)?;
+
+    {
+        let errors = validate_input_values(variables, operation, schema);
+
+        if !errors.is_empty() {
+            return Err(GraphQLError::ValidationError(errors));
+        }
+    }
+
+    let all_vars = variables_with_defaults(operation, variables);
+
+    {
+        let errors = validate_directive_conditions(document, operation, &all_vars);
+
+        if !errors.is_empty() {
+            return Err(GraphQLError::ValidationError(errors));
+        }
+    }
 
     {
-        let errors = validate_input_values(variables, operation, &root_node.schema);
+        let errors = validate_operation_complexity(document, operation, schema, &all_vars);
 
         if !errors.is_empty() {
             return Err(GraphQLError::ValidationError(errors));
         }
     }
 
-    execute_validated_query(&document, operation, root_node, variables, context)
+    Ok((operation, all_vars))
+}
+
+/// Execute a query synchronously in a provided schema
+pub fn execute_sync<'a, S, CtxT, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &CtxT,
+) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    S: ScalarValue,
+    QueryT: GraphQLType<S, Context = CtxT>,
+    MutationT: GraphQLType<S, Context = CtxT>,
+    SubscriptionT: GraphQLType<S, Context = CtxT>,
+{
+    execute_sync_with_response_context(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+    )
+    .map(|(value, errors, _)| (value, errors))
+}
+
+/// Execute a query synchronously in a provided schema, also returning the
+/// cache hints and headers of the executed operation
+pub(crate) fn execute_sync_with_response_context<'a, S, CtxT, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &CtxT,
+) -> Result<(Value<S>, Vec<ExecutionError<S>>, ResponseContext), GraphQLError<'a>>
+where
+    S: ScalarValue,
+    QueryT: GraphQLType<S, Context = CtxT>,
+    MutationT: GraphQLType<S, Context = CtxT>,
+    SubscriptionT: GraphQLType<S, Context = CtxT>,
+{
+    let document = parse_document_source(document_source, &root_node.schema)?;
+
+    let (operation, all_vars) =
+        validate_operation(&root_node.schema, &document, operation_name, variables)?;
+
+    root_node.inspect_operation(&document, operation, context)?;
+
+    let response_context = ResponseContext::new(operation_cache_max_age(
+        &document,
+        operation,
+        &root_node.schema,
+        &all_vars,
+    ));
+    let (value, errors) = execute_validated_query_with_response_context(
+        &document,
+        operation,
+        root_node,
+        variables,
+        context,
+        &response_context,
+    )?;
+
+    Ok((value, errors, response_context))
 }
 
 /// Execute a query in a provided schema
@@ -281,30 +406,111 @@ where
     SubscriptionT::TypeInfo: Send + Sync,
     CtxT: Send + Sync,
 {
-    let document = parse_document_source(document_source, &root_node.schema)?;
+    execute_with_response_context(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+    )
+    .await
+    .map(|(value, errors, _, _)| (value, errors))
+}
 
-    {
-        let mut ctx = ValidatorContext::new(&root_node.schema, &document);
-        visit_all_rules(&mut ctx, &document);
+/// Execute a query in a provided schema, giving up once `deadline` completes
+///
+/// The deadline can be any future, e.g. `tokio::time::delay_for(timeout)`,
+/// so it works with every runtime. If it completes first, the resolvers still
+/// running are dropped and `GraphQLError::TimedOut` is returned.
+pub async fn execute_with_deadline<'a, S, CtxT, QueryT, MutationT, SubscriptionT, D>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &CtxT,
+    deadline: D,
+) -> Result<(Value<S>, Vec<ExecutionError<S>>), GraphQLError<'a>>
+where
+    S: ScalarValue + Send + Sync,
+    QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLType<S, Context = CtxT> + Send + Sync,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Send + Sync,
+    D: std::future::Future<Output = ()>,
+{
+    use futures::future::{self, Either};
 
-        let errors = ctx.into_errors();
-        if !errors.is_empty() {
-            return Err(GraphQLError::ValidationError(errors));
-        }
+    let execution = execute(
+        document_source,
+        operation_name,
+        root_node,
+        variables,
+        context,
+    );
+    futures::pin_mut!(execution, deadline);
+
+    match future::select(execution, deadline).await {
+        Either::Left((result, _)) => result,
+        Either::Right(((), _)) => Err(GraphQLError::TimedOut),
     }
+}
 
-    let operation = get_operation(&document, operation_name)?;
+/// Execute a query in a provided schema, also returning the cache hints and
+/// headers of the executed operation and its field timings if the schema
+/// enables tracing
+#[allow(clippy::type_complexity)]
+pub(crate) async fn execute_with_response_context<'a, S, CtxT, QueryT, MutationT, SubscriptionT>(
+    document_source: &'a str,
+    operation_name: Option<&str>,
+    root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    variables: &Variables<S>,
+    context: &CtxT,
+) -> Result<
+    (
+        Value<S>,
+        Vec<ExecutionError<S>>,
+        ResponseContext,
+        Option<Tracing>,
+    ),
+    GraphQLError<'a>,
+>
+where
+    S: ScalarValue + Send + Sync,
+    QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLType<S, Context = CtxT> + Send + Sync,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Send + Sync,
+{
+    let document = parse_document_source(document_source, &root_node.schema)?;
 
-    {
-        let errors = validate_input_values(variables, operation, &root_node.schema);
+    let (operation, all_vars) =
+        validate_operation(&root_node.schema, &document, operation_name, variables)?;
 
-        if !errors.is_empty() {
-            return Err(GraphQLError::ValidationError(errors));
-        }
-    }
+    root_node.inspect_operation(&document, operation, context)?;
 
-    executor::execute_validated_query_async(&document, operation, root_node, variables, context)
-        .await
+    let response_context = ResponseContext::new(operation_cache_max_age(
+        &document,
+        operation,
+        &root_node.schema,
+        &all_vars,
+    ));
+    let (value, errors, tracing) = executor::execute_validated_query_async_with_response_context(
+        &document,
+        operation,
+        root_node,
+        variables,
+        context,
+        &response_context,
+    )
+    .await?;
+
+    Ok((value, errors, response_context, tracing))
 }
 
 /// Resolve subscription into `ValuesStream`
@@ -328,15 +534,10 @@ where
     let document: crate::ast::Document<'a, S> =
         parse_document_source(document_source, &root_node.schema)?;
 
-    let operation = get_operation(&document, operation_name)?;
-
-    {
-        let errors = validate_input_values(&variables, operation, &root_node.schema);
+    let (operation, _) =
+        validate_operation(&root_node.schema, &document, operation_name, variables)?;
 
-        if !errors.is_empty() {
-            return Err(GraphQLError::ValidationError(errors));
-        }
-    }
+    root_node.inspect_operation(&document, operation, context)?;
 
     executor::resolve_validated_subscription(&document, operation, root_node, variables, context)
         .await
@@ -152,9 +152,14 @@ macro_rules! graphql_interface {
                     $(
                         let _ = registry.get_type::<$resolver_src>(info);
                     )*
+                    let type_name: &str = $($outname)*;
                     let fields = &[$(
                         registry.field_convert::<$return_ty, _, Self::Context>(
-                            &$crate::to_camel_case(stringify!($fn_name)),
+                            registry.derived_field_name(
+                                type_name,
+                                &$crate::to_camel_case(stringify!($fn_name)),
+                                stringify!($fn_name),
+                            ),
                             info
                         )
                             $(.description($fn_description))*
@@ -188,8 +193,13 @@ macro_rules! graphql_interface {
                     args: &$crate::Arguments<$crate::__juniper_insert_generic!($($scalar)+)>,
                     executor: &$crate::Executor<Self::Context, $crate::__juniper_insert_generic!($($scalar)+)>
                 ) -> $crate::ExecutionResult<$crate::__juniper_insert_generic!($($scalar)+)> {
+                    let type_name: &str = $($outname)*;
                     $(
-                        if field == &$crate::to_camel_case(stringify!($fn_name)) {
+                        if field == executor.derived_field_name(
+                            type_name,
+                            &$crate::to_camel_case(stringify!($fn_name)),
+                            stringify!($fn_name),
+                        ) {
                             let f = (|| {
                                 $(
                                     let $arg_name: $arg_ty = args.get(&$crate::to_camel_case(stringify!($arg_name)))
@@ -1,6 +1,8 @@
 #![allow(unused)]
 
-use juniper_codegen::GraphQLInputObjectInternal as GraphQLInputObject;
+use juniper_codegen::{
+    GraphQLEnumInternal as GraphQLEnum, GraphQLInputObjectInternal as GraphQLInputObject,
+};
 
 use crate::{
     executor::Variables,
@@ -30,6 +32,22 @@ struct Point {
     x: i32,
 }
 
+#[derive(GraphQLEnum, Debug)]
+enum Status {
+    #[graphql(name = "IN_PROGRESS")]
+    Started,
+    Done,
+}
+
+#[derive(GraphQLInputObject, Debug)]
+struct SearchArgs {
+    /// The text to search for
+    text: String,
+    #[graphql(default = "10")]
+    limit: i32,
+    offset: Option<i32>,
+}
+
 #[crate::graphql_object_internal]
 impl Root {
     fn simple() -> i32 {
@@ -140,6 +158,16 @@ impl Root {
         let _ = arg2;
         0
     }
+
+    #[graphql(arguments(search(flatten)))]
+    fn flattened_args(search: SearchArgs) -> String {
+        format!("{} {} {:?}", search.text, search.limit, search.offset)
+    }
+
+    #[graphql(name = "ID", arguments(status_filter(name = "STATUS")))]
+    fn renamed_args(status_filter: Status) -> String {
+        format!("{:?}", status_filter)
+    }
 }
 
 async fn run_args_info_query<F>(field_name: &str, f: F)
@@ -925,3 +953,199 @@ async fn introspect_field_args_with_complex_default() {
     })
     .await;
 }
+
+#[tokio::test]
+async fn introspect_field_flattened_args() {
+    run_args_info_query("flattenedArgs", |args| {
+        assert_eq!(args.len(), 3);
+
+        assert!(args.contains(&Value::object(
+            vec![
+                ("name", Value::scalar("text")),
+                ("description", Value::scalar("The text to search for")),
+                ("defaultValue", Value::null()),
+                (
+                    "type",
+                    Value::object(
+                        vec![
+                            ("name", Value::null()),
+                            (
+                                "ofType",
+                                Value::object(
+                                    vec![("name", Value::scalar("String"))]
+                                        .into_iter()
+                                        .collect(),
+                                ),
+                            ),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    ),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        )));
+
+        assert!(args.contains(&Value::object(
+            vec![
+                ("name", Value::scalar("limit")),
+                ("description", Value::null()),
+                ("defaultValue", Value::scalar("10")),
+                (
+                    "type",
+                    Value::object(
+                        vec![("name", Value::scalar("Int")), ("ofType", Value::null())]
+                            .into_iter()
+                            .collect(),
+                    ),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        )));
+
+        assert!(args.contains(&Value::object(
+            vec![
+                ("name", Value::scalar("offset")),
+                ("description", Value::null()),
+                ("defaultValue", Value::null()),
+                (
+                    "type",
+                    Value::object(
+                        vec![("name", Value::scalar("Int")), ("ofType", Value::null())]
+                            .into_iter()
+                            .collect(),
+                    ),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        )));
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn resolve_flattened_args() {
+    let schema = RootNode::new(
+        Root {},
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+    let doc = r#"
+    {
+        defaults: flattenedArgs(text: "a")
+        provided: flattenedArgs(text: "b", limit: 2, offset: 4)
+    }
+    "#;
+
+    let (result, errs) = crate::execute(doc, None, &schema, &Variables::new(), &())
+        .await
+        .expect("Execution failed");
+
+    assert_eq!(errs, []);
+    assert_eq!(
+        result,
+        Value::object(
+            vec![
+                ("defaults", Value::scalar("a 10 None")),
+                ("provided", Value::scalar("b 2 Some(4)")),
+            ]
+            .into_iter()
+            .collect()
+        )
+    );
+    assert!(schema.schema.concrete_type_by_name("SearchArgs").is_none());
+}
+
+#[tokio::test]
+async fn flattened_args_are_validated() {
+    let schema = RootNode::new(
+        Root {},
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let result = crate::execute(
+        "{ flattenedArgs(limit: 2) }",
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+    )
+    .await;
+
+    match result {
+        Err(crate::GraphQLError::ValidationError(ref errors)) => assert_eq!(errors.len(), 1),
+        _ => panic!("Expected a validation error, got {:?}", result),
+    }
+}
+
+#[tokio::test]
+async fn introspect_field_renamed_arg() {
+    run_args_info_query("ID", |args| {
+        assert_eq!(args.len(), 1);
+
+        assert!(args.contains(&Value::object(
+            vec![
+                ("name", Value::scalar("STATUS")),
+                ("description", Value::null()),
+                ("defaultValue", Value::null()),
+                (
+                    "type",
+                    Value::object(
+                        vec![
+                            ("name", Value::null()),
+                            (
+                                "ofType",
+                                Value::object(
+                                    vec![("name", Value::scalar("Status"))]
+                                        .into_iter()
+                                        .collect(),
+                                ),
+                            ),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    ),
+                ),
+            ]
+            .into_iter()
+            .collect(),
+        )));
+    })
+    .await;
+}
+
+#[tokio::test]
+async fn resolve_renamed_field_arg_and_enum_value() {
+    let schema = RootNode::new(
+        Root {},
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+    let doc = r#"
+    {
+        started: ID(STATUS: IN_PROGRESS)
+        done: ID(STATUS: DONE)
+    }
+    "#;
+
+    let (result, errs) = crate::execute(doc, None, &schema, &Variables::new(), &())
+        .await
+        .expect("Execution failed");
+
+    assert_eq!(errs, []);
+    assert_eq!(
+        result,
+        Value::object(
+            vec![
+                ("started", Value::scalar("Started")),
+                ("done", Value::scalar("Done")),
+            ]
+            .into_iter()
+            .collect()
+        )
+    );
+}
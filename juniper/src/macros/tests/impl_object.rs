@@ -1,5 +1,5 @@
 use super::util;
-use crate::{graphql_value, EmptyMutation, EmptySubscription, RootNode};
+use crate::{graphql_value, EmptyMutation, EmptySubscription, FieldResult, RootNode};
 
 #[derive(Default)]
 struct Context {
@@ -306,3 +306,142 @@ async fn object_query() {
         })
     );
 }
+
+#[derive(Default)]
+struct IteratorQuery;
+
+#[crate::graphql_object_internal(context = Context)]
+impl IteratorQuery {
+    fn numbers() -> impl Iterator<Item = i32> {
+        (0..3).map(|i| i * 2)
+    }
+
+    fn names(&self) -> impl IntoIterator<Item = String> {
+        vec!["a", "b"].into_iter().map(String::from)
+    }
+
+    async fn async_numbers() -> impl Iterator<Item = i32> {
+        (0..3).map(|i| i + 10)
+    }
+
+    fn checked_numbers() -> FieldResult<impl Iterator<Item = i32>> {
+        let count: i32 = "3".parse()?;
+        Ok((0..count).map(|i| i * 3))
+    }
+
+    async fn async_checked_numbers() -> FieldResult<impl Iterator<Item = i32>> {
+        let count: i32 = "2".parse()?;
+        Ok((0..count).map(|i| i + 20))
+    }
+}
+
+#[tokio::test]
+async fn object_iterator_fields_resolve_as_lists() {
+    let result = util::run_query::<IteratorQuery, Mutation, Subscription, Context>(
+        "{ numbers names asyncNumbers checkedNumbers asyncCheckedNumbers }",
+    )
+    .await;
+    assert_eq!(
+        result,
+        graphql_value!({
+            "numbers": [0, 2, 4],
+            "names": ["a", "b"],
+            "asyncNumbers": [10, 11, 12],
+            "checkedNumbers": [0, 3, 6],
+            "asyncCheckedNumbers": [20, 21],
+        })
+    );
+}
+
+#[tokio::test]
+async fn object_iterator_fields_introspect_as_lists() {
+    let doc = r#"
+    {
+        __type(name: "IteratorQuery") {
+            fields {
+                name
+                type {
+                    kind
+                    ofType {
+                        kind
+                        ofType {
+                            kind
+                            ofType {
+                                name
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+    "#;
+    let result = util::run_query::<IteratorQuery, Mutation, Subscription, Context>(doc).await;
+    let list_of = |name| {
+        graphql_value!({
+            "kind": "NON_NULL",
+            "ofType": {
+                "kind": "LIST",
+                "ofType": {
+                    "kind": "NON_NULL",
+                    "ofType": { "name": name },
+                },
+            },
+        })
+    };
+    assert_eq!(
+        result,
+        graphql_value!({
+            "__type": {
+                "fields": [
+                    { "name": "numbers", "type": (list_of("Int")) },
+                    { "name": "names", "type": (list_of("String")) },
+                    { "name": "asyncNumbers", "type": (list_of("Int")) },
+                    { "name": "checkedNumbers", "type": (list_of("Int")) },
+                    { "name": "asyncCheckedNumbers", "type": (list_of("Int")) },
+                ],
+            },
+        })
+    );
+}
+
+#[derive(Default)]
+struct SkipQuery;
+
+#[crate::graphql_object_internal(context = Context)]
+impl SkipQuery {
+    fn greeting(&self) -> String {
+        self.shout("hello")
+    }
+
+    #[graphql(skip)]
+    fn shout(&self, text: &str) -> String {
+        text.to_uppercase()
+    }
+}
+
+#[tokio::test]
+async fn object_skipped_methods_stay_helpers() {
+    let result =
+        util::run_query::<SkipQuery, Mutation, Subscription, Context>("{ greeting }").await;
+    assert_eq!(result, graphql_value!({ "greeting": "HELLO" }));
+
+    let doc = r#"
+    {
+        __type(name: "SkipQuery") {
+            fields {
+                name
+            }
+        }
+    }
+    "#;
+    let result = util::run_query::<SkipQuery, Mutation, Subscription, Context>(doc).await;
+    assert_eq!(
+        result,
+        graphql_value!({
+            "__type": {
+                "fields": [{ "name": "greeting" }],
+            },
+        })
+    );
+}
@@ -1,8 +1,9 @@
 use crate::{
+    ast::InputValue,
     executor::Variables,
     schema::model::RootNode,
     types::scalars::{EmptyMutation, EmptySubscription},
-    value::{DefaultScalarValue, Object, ParseScalarResult, ParseScalarValue, Value},
+    value::{DefaultScalarValue, Object, ParseScalarResult, ParseScalarValue, ScalarValue, Value},
 };
 
 struct DefaultName(i32);
@@ -239,3 +240,129 @@ async fn scalar_description_introspection() {
     })
     .await;
 }
+
+#[derive(Debug, PartialEq)]
+struct Date {
+    year: i32,
+    month: u32,
+    day: u32,
+}
+
+impl Date {
+    fn parse(s: &str) -> Option<Date> {
+        let mut parts = s.splitn(3, '-');
+        let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+        if year.len() != 4 || month.len() != 2 || day.len() != 2 {
+            return None;
+        }
+
+        let date = Date {
+            year: year.parse().ok()?,
+            month: month.parse().ok()?,
+            day: day.parse().ok()?,
+        };
+        if (1..=12).contains(&date.month) && (1..=31).contains(&date.day) {
+            Some(date)
+        } else {
+            None
+        }
+    }
+}
+
+/// A calendar date, formatted as `YYYY-MM-DD`
+#[crate::graphql_scalar_internal(name = "Date")]
+impl<S> GraphQLScalar for Date
+where
+    S: ScalarValue,
+{
+    fn resolve(&self) -> Value {
+        Value::scalar(format!(
+            "{:04}-{:02}-{:02}",
+            self.year, self.month, self.day
+        ))
+    }
+
+    fn from_input_value(v: &InputValue) -> Option<Date> {
+        v.as_string_value().and_then(Date::parse)
+    }
+
+    fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
+        <String as ParseScalarValue<S>>::from_str(value)
+    }
+}
+
+struct DateRoot;
+
+#[crate::graphql_object_internal]
+impl DateRoot {
+    fn first_of_month(date: Date) -> Date {
+        Date { day: 1, ..date }
+    }
+}
+
+#[test]
+fn date_round_trips_through_variables() {
+    let schema = RootNode::new(
+        DateRoot,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+    let run = |date: &str| {
+        let vars = vec![("date".to_owned(), InputValue::scalar(date))]
+            .into_iter()
+            .collect();
+        crate::execute_sync(
+            "query($date: Date!) { firstOfMonth(date: $date) }",
+            None,
+            &schema,
+            &vars,
+            &(),
+        )
+    };
+
+    assert_eq!(
+        run("2020-02-14"),
+        Ok((
+            Value::object(
+                vec![("firstOfMonth", Value::scalar("2020-02-01"))]
+                    .into_iter()
+                    .collect()
+            ),
+            vec![]
+        ))
+    );
+    assert!(run("14/02/2020").is_err());
+    assert!(run("2020-13-01").is_err());
+
+    let (result, errs) = crate::execute_sync(
+        r#"{ firstOfMonth(date: "2021-07-31") __type(name: "Date") { description } }"#,
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+    )
+    .expect("Execution failed");
+
+    assert_eq!(errs, []);
+    assert_eq!(
+        result,
+        Value::object(
+            vec![
+                ("firstOfMonth", Value::scalar("2021-07-01")),
+                (
+                    "__type",
+                    Value::object(
+                        vec![(
+                            "description",
+                            Value::scalar("A calendar date, formatted as `YYYY-MM-DD`"),
+                        )]
+                        .into_iter()
+                        .collect(),
+                    ),
+                ),
+            ]
+            .into_iter()
+            .collect()
+        )
+    );
+}
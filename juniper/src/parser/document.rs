@@ -8,12 +8,13 @@ use crate::ast::{
 use crate::{
     parser::{
         value::parse_value_literal, Lexer, OptionParseResult, ParseError, ParseResult, Parser,
-        Spanning, Token, UnlocatedParseResult,
+        SourcePosition, Spanning, Token, UnlocatedParseResult,
     },
     schema::{
         meta::{Argument, Field as MetaField},
         model::SchemaType,
     },
+    util::ensure_sufficient_stack,
     value::ScalarValue,
 };
 
@@ -25,8 +26,20 @@ pub fn parse_document_source<'a, 'b, S>(
 where
     S: ScalarValue,
 {
+    if let Some(max_length) = schema.max_query_length {
+        if s.len() > max_length {
+            return Err(Spanning::zero_width(
+                &SourcePosition::new_origin(),
+                ParseError::DocumentTooLong(max_length),
+            ));
+        }
+    }
+
     let mut lexer = Lexer::new(s);
-    let mut parser = Parser::new(&mut lexer).map_err(|s| s.map(ParseError::LexerError))?;
+    let mut parser = match schema.max_query_tokens {
+        Some(max_tokens) => Parser::with_max_tokens(&mut lexer, max_tokens)?,
+        None => Parser::new(&mut lexer).map_err(|s| s.map(ParseError::LexerError))?,
+    };
     parse_document(&mut parser, schema)
 }
 
@@ -192,11 +205,13 @@ fn parse_selection_set<'a, 'b, S>(
 where
     S: ScalarValue,
 {
-    parser.unlocated_delimited_nonempty_list(
-        &Token::CurlyOpen,
-        |p| parse_selection(p, schema, fields),
-        &Token::CurlyClose,
-    )
+    ensure_sufficient_stack(|| {
+        parser.unlocated_delimited_nonempty_list(
+            &Token::CurlyOpen,
+            |p| parse_selection(p, schema, fields),
+            &Token::CurlyClose,
+        )
+    })
 }
 
 fn parse_selection<'a, 'b, S>(
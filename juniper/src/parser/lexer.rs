@@ -125,7 +125,7 @@ impl<'a> Lexer<'a> {
             if ch == '\n' {
                 self.position.advance_line();
             } else {
-                self.position.advance_col();
+                self.position.advance_col(ch);
             }
         }
 
@@ -16,6 +16,12 @@ pub enum ParseError<'a> {
 
     /// A scalar of unexpected type occurred in the source
     ExpectedScalarError(&'static str),
+
+    /// The source is longer than the maximum length in bytes
+    DocumentTooLong(usize),
+
+    /// The source has more than the maximum number of tokens
+    TooManyTokens(usize),
 }
 
 #[doc(hidden)]
@@ -36,13 +42,33 @@ pub struct Parser<'a> {
 impl<'a> Parser<'a> {
     #[doc(hidden)]
     pub fn new(lexer: &mut Lexer<'a>) -> Result<Parser<'a>, Spanning<LexerError>> {
+        Parser::with_max_tokens(lexer, std::usize::MAX).map_err(|e| {
+            e.map(|e| match e {
+                ParseError::LexerError(e) => e,
+                _ => unreachable!("Only lexer errors are returned without a token limit"),
+            })
+        })
+    }
+
+    /// Like `new`, but fails as soon as the source has more than
+    /// `max_tokens` tokens
+    #[doc(hidden)]
+    pub fn with_max_tokens(
+        lexer: &mut Lexer<'a>,
+        max_tokens: usize,
+    ) -> Result<Parser<'a>, Spanning<ParseError<'a>>> {
         let mut tokens = Vec::new();
 
         for res in lexer {
-            match res {
-                Ok(s) => tokens.push(s),
-                Err(e) => return Err(e),
+            let token = res.map_err(|e| e.map(ParseError::LexerError))?;
+            if tokens.len() == max_tokens && token.item != Token::EndOfFile {
+                return Err(Spanning::start_end(
+                    &token.start,
+                    &token.end,
+                    ParseError::TooManyTokens(max_tokens),
+                ));
             }
+            tokens.push(token);
         }
 
         Ok(Parser { tokens })
@@ -200,6 +226,12 @@ impl<'a> fmt::Display for ParseError<'a> {
             ParseError::UnexpectedEndOfFile => write!(f, "Unexpected end of input"),
             ParseError::LexerError(ref err) => err.fmt(f),
             ParseError::ExpectedScalarError(err) => err.fmt(f),
+            ParseError::DocumentTooLong(max) => {
+                write!(f, "Document is longer than the maximum of {} bytes", max)
+            }
+            ParseError::TooManyTokens(max) => {
+                write!(f, "Document has more than the maximum of {} tokens", max)
+            }
         }
     }
 }
@@ -40,7 +40,8 @@ impl<T> Spanning<T> {
     #[doc(hidden)]
     pub fn single_width(pos: &SourcePosition, item: T) -> Spanning<T> {
         let mut end = *pos;
-        end.advance_col();
+        // Single width items are ASCII punctuators, one byte long.
+        end.advance_col(' ');
 
         Spanning {
             item,
@@ -116,8 +117,8 @@ impl SourcePosition {
     }
 
     #[doc(hidden)]
-    pub fn advance_col(&mut self) {
-        self.index += 1;
+    pub fn advance_col(&mut self, ch: char) {
+        self.index += ch.len_utf8();
         self.col += 1;
     }
 
@@ -128,7 +129,7 @@ impl SourcePosition {
         self.col = 0;
     }
 
-    /// The index of the character in the input source
+    /// The byte offset of the character in the input source
     ///
     /// Zero-based index. Take a substring of the original source starting at
     /// this index to access the item pointed to by this `SourcePosition`.
@@ -145,7 +146,8 @@ impl SourcePosition {
 
     /// The column of the character in the input source
     ///
-    /// Zero-based index: the first column is column zero.
+    /// Zero-based index: the first column is column zero. Columns count
+    /// characters, not bytes.
     pub fn column(&self) -> usize {
         self.col
     }
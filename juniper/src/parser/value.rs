@@ -26,20 +26,15 @@ where
             },
             _,
         ) => parse_list_literal(parser, is_const, schema, tpe),
+        // Objects given to other types than input objects, e.g. to scalars
+        // accepting them, are left for validation to check.
         (
             &Spanning {
                 item: Token::CurlyOpen,
                 ..
             },
-            None,
-        ) => parse_object_literal(parser, is_const, schema, None),
-        (
-            &Spanning {
-                item: Token::CurlyOpen,
-                ..
-            },
-            Some(&MetaType::InputObject(ref o)),
-        ) => parse_object_literal(parser, is_const, schema, Some(o)),
+            _,
+        ) => parse_object_literal(parser, is_const, schema, tpe),
         (
             &Spanning {
                 item: Token::Dollar,
@@ -141,7 +136,7 @@ fn parse_object_literal<'a, 'b, S>(
     parser: &mut Parser<'a>,
     is_const: bool,
     schema: &'b SchemaType<'b, S>,
-    object_tpe: Option<&InputObjectMeta<'b, S>>,
+    object_tpe: Option<&MetaType<'b, S>>,
 ) -> ParseResult<'a, InputValue<S>>
 where
     S: ScalarValue,
@@ -159,16 +154,23 @@ fn parse_object_field<'a, 'b, S>(
     parser: &mut Parser<'a>,
     is_const: bool,
     schema: &'b SchemaType<'b, S>,
-    object_meta: Option<&InputObjectMeta<'b, S>>,
+    object_tpe: Option<&MetaType<'b, S>>,
 ) -> ParseResult<'a, (Spanning<String>, Spanning<InputValue<S>>)>
 where
     S: ScalarValue,
 {
     let key = parser.expect_name()?;
 
-    let tpe = object_meta
-        .and_then(|o| o.input_fields.iter().find(|f| f.name == key.item))
-        .and_then(|f| schema.lookup_type(&f.arg_type));
+    let tpe = match object_tpe {
+        Some(&MetaType::InputObject(InputObjectMeta {
+            ref input_fields, ..
+        })) => input_fields
+            .iter()
+            .find(|f| f.name == key.item)
+            .and_then(|f| schema.lookup_type(&f.arg_type)),
+        // The fields of an object given to a scalar are parsed by the scalar.
+        tpe => tpe,
+    };
 
     parser.expect(&Token::Colon)?;
 
@@ -0,0 +1,388 @@
+//! Relay-style cursor connections
+//!
+//! A field returning [`Connection<T>`](Connection) exposes a paginated list of
+//! `T` following the [Relay cursor connections specification][spec]:
+//!
+//! ```graphql
+//! type UserConnection {
+//!   edges: [UserEdge!]!
+//!   pageInfo: PageInfo!
+//! }
+//!
+//! type UserEdge {
+//!   node: User!
+//!   cursor: String!
+//! }
+//! ```
+//!
+//! ```
+//! # use juniper::{FieldResult, GraphQLObject};
+//! use juniper::relay::{Connection, ConnectionArguments, ConnectionNode};
+//!
+//! #[derive(GraphQLObject)]
+//! struct User {
+//!     id: i32,
+//! }
+//!
+//! impl ConnectionNode for User {
+//!     const CONNECTION_TYPE_NAME: &'static str = "UserConnection";
+//!     const EDGE_TYPE_NAME: &'static str = "UserEdge";
+//! }
+//!
+//! struct Query;
+//!
+//! #[juniper::graphql_object]
+//! impl Query {
+//!     fn users(
+//!         first: Option<i32>,
+//!         after: Option<String>,
+//!         last: Option<i32>,
+//!         before: Option<String>,
+//!     ) -> FieldResult<Connection<User>> {
+//!         let users = (0..10).map(|id| User { id }).collect();
+//!         let args = ConnectionArguments { first, after, last, before };
+//!         Ok(Connection::from_nodes(users, |u| u.id.to_string()).paginate(&args)?)
+//!     }
+//! }
+//! ```
+//!
+//! [spec]: https://relay.dev/graphql/connections.htm
+
+use std::{error::Error, fmt};
+
+use juniper_codegen::GraphQLObjectInternal as GraphQLObject;
+
+use crate::{
+    executor::{ExecutionResult, Executor, Registry},
+    schema::meta::MetaType,
+    types::{
+        async_await::GraphQLTypeAsync,
+        base::{Arguments, GraphQLType},
+    },
+    value::ScalarValue,
+    BoxFuture,
+};
+
+/// Names of the connection and edge types generated for a node type
+pub trait ConnectionNode {
+    /// The name of the `Connection<Self>` type, e.g. `UserConnection`
+    const CONNECTION_TYPE_NAME: &'static str;
+
+    /// The name of the `Edge<Self>` type, e.g. `UserEdge`
+    const EDGE_TYPE_NAME: &'static str;
+}
+
+/// The standard `first`, `after`, `last` and `before` pagination arguments
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ConnectionArguments {
+    /// Return at most this many edges from the start of the page
+    pub first: Option<i32>,
+    /// Only return edges after the one with this cursor
+    pub after: Option<String>,
+    /// Return at most this many edges from the end of the page
+    pub last: Option<i32>,
+    /// Only return edges before the one with this cursor
+    pub before: Option<String>,
+}
+
+/// An error in the pagination arguments of a connection
+#[derive(Clone, Debug, PartialEq)]
+pub enum ConnectionError {
+    /// A cursor was not produced by this server
+    InvalidCursor(String),
+    /// `first` or `last` was negative
+    NegativeCount(&'static str),
+}
+
+impl fmt::Display for ConnectionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            ConnectionError::InvalidCursor(ref cursor) => {
+                write!(f, "Invalid cursor \"{}\"", cursor)
+            }
+            ConnectionError::NegativeCount(arg) => {
+                write!(f, "Argument \"{}\" must not be negative", arg)
+            }
+        }
+    }
+}
+
+impl Error for ConnectionError {}
+
+/// Encode a key as an opaque cursor
+pub fn encode_cursor(key: &str) -> String {
+    base64::encode(key)
+}
+
+/// Decode a cursor produced by [`encode_cursor`] back into its key
+pub fn decode_cursor(cursor: &str) -> Result<String, ConnectionError> {
+    base64::decode(cursor)
+        .ok()
+        .and_then(|bytes| String::from_utf8(bytes).ok())
+        .ok_or_else(|| ConnectionError::InvalidCursor(cursor.to_owned()))
+}
+
+/// Information about the page of a connection
+#[derive(Clone, Debug, Default, PartialEq, GraphQLObject)]
+#[graphql(description = "Information about pagination in a connection")]
+pub struct PageInfo {
+    /// Whether more edges exist after the last one of the page
+    pub has_next_page: bool,
+    /// Whether more edges exist before the first one of the page
+    pub has_previous_page: bool,
+    /// The cursor of the first edge of the page
+    pub start_cursor: Option<String>,
+    /// The cursor of the last edge of the page
+    pub end_cursor: Option<String>,
+}
+
+/// A node of a connection along with its cursor
+#[derive(Clone, Debug, PartialEq)]
+pub struct Edge<T> {
+    /// The node
+    pub node: T,
+    /// The opaque cursor of the node
+    pub cursor: String,
+}
+
+/// A page of a paginated list of `T`
+#[derive(Clone, Debug, PartialEq)]
+pub struct Connection<T> {
+    /// The edges of the page
+    pub edges: Vec<Edge<T>>,
+    /// Information about the page
+    pub page_info: PageInfo,
+}
+
+impl<T> Connection<T> {
+    /// Construct a connection over all `nodes`
+    ///
+    /// `cursor_fn` returns the key identifying a node, which is encoded into
+    /// its opaque cursor.
+    pub fn from_nodes<F, K>(nodes: Vec<T>, cursor_fn: F) -> Self
+    where
+        F: Fn(&T) -> K,
+        K: AsRef<str>,
+    {
+        let edges = nodes
+            .into_iter()
+            .map(|node| Edge {
+                cursor: encode_cursor(cursor_fn(&node).as_ref()),
+                node,
+            })
+            .collect::<Vec<_>>();
+
+        Connection {
+            page_info: PageInfo {
+                has_next_page: false,
+                has_previous_page: false,
+                start_cursor: edges.first().map(|e| e.cursor.clone()),
+                end_cursor: edges.last().map(|e| e.cursor.clone()),
+            },
+            edges,
+        }
+    }
+
+    /// Select the page described by `args`
+    ///
+    /// Follows the pagination algorithm of the Relay specification: edges up
+    /// to and including `after` and from `before` on are removed, then the
+    /// first `first` and the last `last` of the remaining edges are kept.
+    /// Cursors that are valid but not part of the connection are ignored.
+    pub fn paginate(mut self, args: &ConnectionArguments) -> Result<Self, ConnectionError> {
+        let position = |edges: &[Edge<T>], cursor: &str| {
+            decode_cursor(cursor).map(|_| edges.iter().position(|e| e.cursor == cursor))
+        };
+
+        let mut start = 0;
+        let mut end = self.edges.len();
+        if let Some(ref after) = args.after {
+            if let Some(i) = position(&self.edges, after)? {
+                start = i + 1;
+            }
+        }
+        if let Some(ref before) = args.before {
+            if let Some(i) = position(&self.edges, before)? {
+                end = i.max(start);
+            }
+        }
+        if let Some(first) = args.first {
+            let first = count(first, "first")?;
+            end = end.min(start + first);
+        }
+        if let Some(last) = args.last {
+            let last = count(last, "last")?;
+            start = start.max(end.saturating_sub(last));
+        }
+
+        let has_next_page = end < self.edges.len() || self.page_info.has_next_page;
+        let has_previous_page = start > 0 || self.page_info.has_previous_page;
+        let edges = self.edges.drain(start..end).collect::<Vec<_>>();
+
+        Ok(Connection {
+            page_info: PageInfo {
+                has_next_page,
+                has_previous_page,
+                start_cursor: edges.first().map(|e| e.cursor.clone()),
+                end_cursor: edges.last().map(|e| e.cursor.clone()),
+            },
+            edges,
+        })
+    }
+}
+
+fn count(value: i32, arg: &'static str) -> Result<usize, ConnectionError> {
+    if value < 0 {
+        Err(ConnectionError::NegativeCount(arg))
+    } else {
+        Ok(value as usize)
+    }
+}
+
+impl<S, T> GraphQLType<S> for Connection<T>
+where
+    S: ScalarValue,
+    T: GraphQLType<S> + ConnectionNode,
+{
+    type Context = T::Context;
+    type TypeInfo = T::TypeInfo;
+
+    fn name(_: &T::TypeInfo) -> Option<&str> {
+        Some(T::CONNECTION_TYPE_NAME)
+    }
+
+    fn meta<'r>(info: &T::TypeInfo, registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        let fields = [
+            registry.field::<Vec<Edge<T>>>("edges", info),
+            registry.field::<PageInfo>("pageInfo", &()),
+        ];
+
+        registry
+            .build_object_type::<Self>(info, &fields)
+            .description("A paginated list")
+            .into_meta()
+    }
+
+    fn resolve_field(
+        &self,
+        info: &T::TypeInfo,
+        field_name: &str,
+        _: &Arguments<S>,
+        executor: &Executor<T::Context, S>,
+    ) -> ExecutionResult<S> {
+        match field_name {
+            "edges" => executor.resolve(info, &self.edges),
+            "pageInfo" => executor.resolve_with_ctx(&(), &self.page_info),
+            _ => panic!(
+                "Field {} not found on type {}",
+                field_name,
+                T::CONNECTION_TYPE_NAME
+            ),
+        }
+    }
+}
+
+impl<S, T> GraphQLTypeAsync<S> for Connection<T>
+where
+    S: ScalarValue + Send + Sync,
+    T: GraphQLTypeAsync<S> + ConnectionNode,
+    T::TypeInfo: Send + Sync,
+    T::Context: Send + Sync,
+{
+    fn resolve_field_async<'a>(
+        &'a self,
+        info: &'a T::TypeInfo,
+        field_name: &'a str,
+        _: &'a Arguments<S>,
+        executor: &'a Executor<T::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        Box::pin(async move {
+            match field_name {
+                "edges" => executor.resolve_async(info, &self.edges).await,
+                "pageInfo" => executor.resolve_with_ctx_async(&(), &self.page_info).await,
+                _ => panic!(
+                    "Field {} not found on type {}",
+                    field_name,
+                    T::CONNECTION_TYPE_NAME
+                ),
+            }
+        })
+    }
+}
+
+impl<S, T> GraphQLType<S> for Edge<T>
+where
+    S: ScalarValue,
+    T: GraphQLType<S> + ConnectionNode,
+{
+    type Context = T::Context;
+    type TypeInfo = T::TypeInfo;
+
+    fn name(_: &T::TypeInfo) -> Option<&str> {
+        Some(T::EDGE_TYPE_NAME)
+    }
+
+    fn meta<'r>(info: &T::TypeInfo, registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        let fields = [
+            registry.field::<T>("node", info),
+            registry.field::<String>("cursor", &()),
+        ];
+
+        registry
+            .build_object_type::<Self>(info, &fields)
+            .description("An edge in a connection")
+            .into_meta()
+    }
+
+    fn resolve_field(
+        &self,
+        info: &T::TypeInfo,
+        field_name: &str,
+        _: &Arguments<S>,
+        executor: &Executor<T::Context, S>,
+    ) -> ExecutionResult<S> {
+        match field_name {
+            "node" => executor.resolve(info, &self.node),
+            "cursor" => executor.resolve_with_ctx(&(), &self.cursor),
+            _ => panic!(
+                "Field {} not found on type {}",
+                field_name,
+                T::EDGE_TYPE_NAME
+            ),
+        }
+    }
+}
+
+impl<S, T> GraphQLTypeAsync<S> for Edge<T>
+where
+    S: ScalarValue + Send + Sync,
+    T: GraphQLTypeAsync<S> + ConnectionNode,
+    T::TypeInfo: Send + Sync,
+    T::Context: Send + Sync,
+{
+    fn resolve_field_async<'a>(
+        &'a self,
+        info: &'a T::TypeInfo,
+        field_name: &'a str,
+        _: &'a Arguments<S>,
+        executor: &'a Executor<T::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        Box::pin(async move {
+            match field_name {
+                "node" => executor.resolve_async(info, &self.node).await,
+                "cursor" => executor.resolve_with_ctx_async(&(), &self.cursor).await,
+                _ => panic!(
+                    "Field {} not found on type {}",
+                    field_name,
+                    T::EDGE_TYPE_NAME
+                ),
+            }
+        })
+    }
+}
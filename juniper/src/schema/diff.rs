@@ -0,0 +1,626 @@
+//! Comparison of two versions of a schema
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt,
+};
+
+use crate::{
+    ast::Type,
+    schema::{
+        meta::{
+            Argument, EnumMeta, Field, InputObjectMeta, InterfaceMeta, MetaType, ObjectMeta,
+            UnionMeta,
+        },
+        model::{RootNode, SchemaType},
+        sdl::EMPTY_ROOT_TYPES,
+    },
+    types::base::{GraphQLType, TypeKind},
+    value::ScalarValue,
+};
+
+/// A difference between two versions of a schema, as found by [`schema_diff`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SchemaChange {
+    /// A type was added.
+    TypeAdded {
+        /// The name of the type
+        type_name: String,
+    },
+    /// A type was removed.
+    TypeRemoved {
+        /// The name of the type
+        type_name: String,
+    },
+    /// A type became another kind of type, like an object becoming an
+    /// interface.
+    TypeKindChanged {
+        /// The name of the type
+        type_name: String,
+        /// The kind of the type in the old schema
+        old_kind: TypeKind,
+        /// The kind of the type in the new schema
+        new_kind: TypeKind,
+    },
+    /// A field was added to an object, interface or input object.
+    FieldAdded {
+        /// The name of the type owning the field
+        type_name: String,
+        /// The name of the field
+        field_name: String,
+        /// Whether the field is an input field clients have to provide
+        required: bool,
+    },
+    /// A field was removed from an object, interface or input object.
+    FieldRemoved {
+        /// The name of the type owning the field
+        type_name: String,
+        /// The name of the field
+        field_name: String,
+    },
+    /// The type of a field changed.
+    FieldTypeChanged {
+        /// The name of the type owning the field
+        type_name: String,
+        /// The name of the field
+        field_name: String,
+        /// The type of the field in the old schema
+        old_type: String,
+        /// The type of the field in the new schema
+        new_type: String,
+        /// Whether clients of the old schema can still use the field
+        safe: bool,
+    },
+    /// An argument was added to a field.
+    ArgumentAdded {
+        /// The name of the type owning the field
+        type_name: String,
+        /// The name of the field
+        field_name: String,
+        /// The name of the argument
+        argument_name: String,
+        /// Whether the argument is non-null without a default value
+        required: bool,
+    },
+    /// An argument was removed from a field.
+    ArgumentRemoved {
+        /// The name of the type owning the field
+        type_name: String,
+        /// The name of the field
+        field_name: String,
+        /// The name of the argument
+        argument_name: String,
+    },
+    /// The type of an argument changed.
+    ArgumentTypeChanged {
+        /// The name of the type owning the field
+        type_name: String,
+        /// The name of the field
+        field_name: String,
+        /// The name of the argument
+        argument_name: String,
+        /// The type of the argument in the old schema
+        old_type: String,
+        /// The type of the argument in the new schema
+        new_type: String,
+        /// Whether clients of the old schema can still pass the argument
+        safe: bool,
+    },
+    /// A value was added to an enum.
+    EnumValueAdded {
+        /// The name of the enum
+        type_name: String,
+        /// The added value
+        value: String,
+    },
+    /// A value was removed from an enum.
+    EnumValueRemoved {
+        /// The name of the enum
+        type_name: String,
+        /// The removed value
+        value: String,
+    },
+    /// A member type was added to a union.
+    UnionMemberAdded {
+        /// The name of the union
+        type_name: String,
+        /// The name of the member type
+        member: String,
+    },
+    /// A member type was removed from a union.
+    UnionMemberRemoved {
+        /// The name of the union
+        type_name: String,
+        /// The name of the member type
+        member: String,
+    },
+    /// An object started implementing an interface.
+    InterfaceAdded {
+        /// The name of the object
+        type_name: String,
+        /// The name of the interface
+        interface: String,
+    },
+    /// An object stopped implementing an interface.
+    InterfaceRemoved {
+        /// The name of the object
+        type_name: String,
+        /// The name of the interface
+        interface: String,
+    },
+}
+
+impl SchemaChange {
+    /// Whether the change can break clients written against the old schema
+    pub fn is_breaking(&self) -> bool {
+        match *self {
+            SchemaChange::TypeAdded { .. }
+            | SchemaChange::EnumValueAdded { .. }
+            | SchemaChange::UnionMemberAdded { .. }
+            | SchemaChange::InterfaceAdded { .. } => false,
+            SchemaChange::FieldAdded { required, .. }
+            | SchemaChange::ArgumentAdded { required, .. } => required,
+            SchemaChange::FieldTypeChanged { safe, .. }
+            | SchemaChange::ArgumentTypeChanged { safe, .. } => !safe,
+            SchemaChange::TypeRemoved { .. }
+            | SchemaChange::TypeKindChanged { .. }
+            | SchemaChange::FieldRemoved { .. }
+            | SchemaChange::ArgumentRemoved { .. }
+            | SchemaChange::EnumValueRemoved { .. }
+            | SchemaChange::UnionMemberRemoved { .. }
+            | SchemaChange::InterfaceRemoved { .. } => true,
+        }
+    }
+}
+
+impl fmt::Display for SchemaChange {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match *self {
+            SchemaChange::TypeAdded { ref type_name } => write!(f, "Type {} was added", type_name),
+            SchemaChange::TypeRemoved { ref type_name } => {
+                write!(f, "Type {} was removed", type_name)
+            }
+            SchemaChange::TypeKindChanged {
+                ref type_name,
+                ref old_kind,
+                ref new_kind,
+            } => write!(
+                f,
+                "Type {} changed from {:?} to {:?}",
+                type_name, old_kind, new_kind
+            ),
+            SchemaChange::FieldAdded {
+                ref type_name,
+                ref field_name,
+                ..
+            } => write!(f, "Field {}.{} was added", type_name, field_name),
+            SchemaChange::FieldRemoved {
+                ref type_name,
+                ref field_name,
+            } => write!(f, "Field {}.{} was removed", type_name, field_name),
+            SchemaChange::FieldTypeChanged {
+                ref type_name,
+                ref field_name,
+                ref old_type,
+                ref new_type,
+                ..
+            } => write!(
+                f,
+                "Field {}.{} changed type from {} to {}",
+                type_name, field_name, old_type, new_type
+            ),
+            SchemaChange::ArgumentAdded {
+                ref type_name,
+                ref field_name,
+                ref argument_name,
+                ..
+            } => write!(
+                f,
+                "Argument {} was added to {}.{}",
+                argument_name, type_name, field_name
+            ),
+            SchemaChange::ArgumentRemoved {
+                ref type_name,
+                ref field_name,
+                ref argument_name,
+            } => write!(
+                f,
+                "Argument {} was removed from {}.{}",
+                argument_name, type_name, field_name
+            ),
+            SchemaChange::ArgumentTypeChanged {
+                ref type_name,
+                ref field_name,
+                ref argument_name,
+                ref old_type,
+                ref new_type,
+                ..
+            } => write!(
+                f,
+                "Argument {} of {}.{} changed type from {} to {}",
+                argument_name, type_name, field_name, old_type, new_type
+            ),
+            SchemaChange::EnumValueAdded {
+                ref type_name,
+                ref value,
+            } => write!(f, "Value {} was added to {}", value, type_name),
+            SchemaChange::EnumValueRemoved {
+                ref type_name,
+                ref value,
+            } => write!(f, "Value {} was removed from {}", value, type_name),
+            SchemaChange::UnionMemberAdded {
+                ref type_name,
+                ref member,
+            } => write!(f, "Type {} was added to union {}", member, type_name),
+            SchemaChange::UnionMemberRemoved {
+                ref type_name,
+                ref member,
+            } => write!(f, "Type {} was removed from union {}", member, type_name),
+            SchemaChange::InterfaceAdded {
+                ref type_name,
+                ref interface,
+            } => write!(f, "{} now implements {}", type_name, interface),
+            SchemaChange::InterfaceRemoved {
+                ref type_name,
+                ref interface,
+            } => write!(f, "{} no longer implements {}", type_name, interface),
+        }
+    }
+}
+
+/// Compare the types of two versions of a schema
+///
+/// Changes are reported type by type, sorted by type name. A change
+/// [is breaking](SchemaChange::is_breaking) if a client written against
+/// `old` may fail against `new`, like a removed field, an output field
+/// becoming nullable, or an argument changing type. Directives and the names
+/// of the root types are not compared.
+///
+/// ```ignore
+/// let breaking = schema_diff(&deployed, &schema)
+///     .into_iter()
+///     .filter(SchemaChange::is_breaking)
+///     .collect::<Vec<_>>();
+/// ```
+pub fn schema_diff<
+    'a,
+    'b,
+    OldQueryT,
+    OldMutationT,
+    OldSubscriptionT,
+    QueryT,
+    MutationT,
+    SubscriptionT,
+    S,
+>(
+    old: &RootNode<'a, OldQueryT, OldMutationT, OldSubscriptionT, S>,
+    new: &RootNode<'b, QueryT, MutationT, SubscriptionT, S>,
+) -> Vec<SchemaChange>
+where
+    OldQueryT: GraphQLType<S>,
+    OldMutationT: GraphQLType<S>,
+    OldSubscriptionT: GraphQLType<S>,
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S>,
+    SubscriptionT: GraphQLType<S>,
+    S: ScalarValue,
+{
+    let mut changes = vec![];
+
+    let old_types = named_types(&old.schema);
+    let new_types = named_types(&new.schema);
+
+    for (&name, old_type) in &old_types {
+        match new_types.get(name) {
+            Some(new_type) => diff_types(&mut changes, old_type, new_type),
+            None => changes.push(SchemaChange::TypeRemoved {
+                type_name: name.to_owned(),
+            }),
+        }
+    }
+    for &name in new_types.keys() {
+        if !old_types.contains_key(name) {
+            changes.push(SchemaChange::TypeAdded {
+                type_name: name.to_owned(),
+            });
+        }
+    }
+
+    changes
+}
+
+/// The types of the schema by name, without introspection types and the
+/// types of empty mutation and subscription roots
+fn named_types<'s, S>(schema: &'s SchemaType<S>) -> BTreeMap<&'s str, &'s MetaType<'s, S>>
+where
+    S: ScalarValue,
+{
+    schema
+        .concrete_type_list()
+        .into_iter()
+        .filter_map(|t| t.name().map(|name| (name, t)))
+        .filter(|&(name, _)| !name.starts_with("__") && !EMPTY_ROOT_TYPES.contains(&name))
+        .collect()
+}
+
+fn diff_types<S>(changes: &mut Vec<SchemaChange>, old: &MetaType<S>, new: &MetaType<S>)
+where
+    S: ScalarValue,
+{
+    let type_name = old.name().unwrap_or("");
+
+    match (old, new) {
+        (
+            &MetaType::Object(ObjectMeta {
+                fields: ref old_fields,
+                interface_names: ref old_interfaces,
+                ..
+            }),
+            &MetaType::Object(ObjectMeta {
+                fields: ref new_fields,
+                interface_names: ref new_interfaces,
+                ..
+            }),
+        ) => {
+            diff_fields(changes, type_name, old_fields, new_fields);
+            for (interface, added) in diff_names(old_interfaces, new_interfaces) {
+                let (type_name, interface) = (type_name.to_owned(), interface.to_owned());
+                changes.push(if added {
+                    SchemaChange::InterfaceAdded {
+                        type_name,
+                        interface,
+                    }
+                } else {
+                    SchemaChange::InterfaceRemoved {
+                        type_name,
+                        interface,
+                    }
+                });
+            }
+        }
+        (
+            &MetaType::Interface(InterfaceMeta {
+                fields: ref old_fields,
+                ..
+            }),
+            &MetaType::Interface(InterfaceMeta {
+                fields: ref new_fields,
+                ..
+            }),
+        ) => diff_fields(changes, type_name, old_fields, new_fields),
+        (
+            &MetaType::Union(UnionMeta {
+                of_type_names: ref old_members,
+                ..
+            }),
+            &MetaType::Union(UnionMeta {
+                of_type_names: ref new_members,
+                ..
+            }),
+        ) => {
+            for (member, added) in diff_names(old_members, new_members) {
+                let (type_name, member) = (type_name.to_owned(), member.to_owned());
+                changes.push(if added {
+                    SchemaChange::UnionMemberAdded { type_name, member }
+                } else {
+                    SchemaChange::UnionMemberRemoved { type_name, member }
+                });
+            }
+        }
+        (
+            &MetaType::Enum(EnumMeta {
+                values: ref old_values,
+                ..
+            }),
+            &MetaType::Enum(EnumMeta {
+                values: ref new_values,
+                ..
+            }),
+        ) => {
+            let old_values = old_values
+                .iter()
+                .map(|v| v.name.as_str())
+                .collect::<Vec<_>>();
+            let new_values = new_values
+                .iter()
+                .map(|v| v.name.as_str())
+                .collect::<Vec<_>>();
+            for (value, added) in diff_names(&old_values, &new_values) {
+                let (type_name, value) = (type_name.to_owned(), value.to_owned());
+                changes.push(if added {
+                    SchemaChange::EnumValueAdded { type_name, value }
+                } else {
+                    SchemaChange::EnumValueRemoved { type_name, value }
+                });
+            }
+        }
+        (
+            &MetaType::InputObject(InputObjectMeta {
+                input_fields: ref old_fields,
+                ..
+            }),
+            &MetaType::InputObject(InputObjectMeta {
+                input_fields: ref new_fields,
+                ..
+            }),
+        ) => diff_input_fields(changes, type_name, old_fields, new_fields),
+        (&MetaType::Scalar(_), &MetaType::Scalar(_)) => {}
+        _ => changes.push(SchemaChange::TypeKindChanged {
+            type_name: type_name.to_owned(),
+            old_kind: old.type_kind(),
+            new_kind: new.type_kind(),
+        }),
+    }
+}
+
+fn diff_fields<S>(
+    changes: &mut Vec<SchemaChange>,
+    type_name: &str,
+    old_fields: &[Field<S>],
+    new_fields: &[Field<S>],
+) where
+    S: ScalarValue,
+{
+    let old_fields = old_fields.iter().filter(|f| !f.name.starts_with("__"));
+    let new_fields = new_fields.iter().filter(|f| !f.name.starts_with("__"));
+
+    for old_field in old_fields.clone() {
+        let field_name = &old_field.name;
+        let new_field = match new_fields.clone().find(|f| &f.name == field_name) {
+            Some(new_field) => new_field,
+            None => {
+                changes.push(SchemaChange::FieldRemoved {
+                    type_name: type_name.to_owned(),
+                    field_name: field_name.clone(),
+                });
+                continue;
+            }
+        };
+
+        if old_field.field_type != new_field.field_type {
+            changes.push(SchemaChange::FieldTypeChanged {
+                type_name: type_name.to_owned(),
+                field_name: field_name.clone(),
+                old_type: old_field.field_type.to_string(),
+                new_type: new_field.field_type.to_string(),
+                safe: is_safe_output_change(&old_field.field_type, &new_field.field_type),
+            });
+        }
+
+        let old_args = old_field.arguments.as_ref().map_or(&[][..], |a| &a[..]);
+        let new_args = new_field.arguments.as_ref().map_or(&[][..], |a| &a[..]);
+        for old_arg in old_args {
+            let argument_name = &old_arg.name;
+            match new_args.iter().find(|a| &a.name == argument_name) {
+                Some(new_arg) if old_arg.arg_type != new_arg.arg_type => {
+                    changes.push(SchemaChange::ArgumentTypeChanged {
+                        type_name: type_name.to_owned(),
+                        field_name: field_name.clone(),
+                        argument_name: argument_name.clone(),
+                        old_type: old_arg.arg_type.to_string(),
+                        new_type: new_arg.arg_type.to_string(),
+                        safe: is_safe_input_change(&old_arg.arg_type, &new_arg.arg_type),
+                    })
+                }
+                Some(_) => {}
+                None => changes.push(SchemaChange::ArgumentRemoved {
+                    type_name: type_name.to_owned(),
+                    field_name: field_name.clone(),
+                    argument_name: argument_name.clone(),
+                }),
+            }
+        }
+        for new_arg in new_args {
+            if !old_args.iter().any(|a| a.name == new_arg.name) {
+                changes.push(SchemaChange::ArgumentAdded {
+                    type_name: type_name.to_owned(),
+                    field_name: field_name.clone(),
+                    argument_name: new_arg.name.clone(),
+                    required: is_required(new_arg),
+                });
+            }
+        }
+    }
+
+    for new_field in new_fields {
+        if !old_fields.clone().any(|f| f.name == new_field.name) {
+            changes.push(SchemaChange::FieldAdded {
+                type_name: type_name.to_owned(),
+                field_name: new_field.name.clone(),
+                required: false,
+            });
+        }
+    }
+}
+
+fn diff_input_fields<S>(
+    changes: &mut Vec<SchemaChange>,
+    type_name: &str,
+    old_fields: &[Argument<S>],
+    new_fields: &[Argument<S>],
+) where
+    S: ScalarValue,
+{
+    for old_field in old_fields {
+        let field_name = &old_field.name;
+        match new_fields.iter().find(|f| &f.name == field_name) {
+            Some(new_field) if old_field.arg_type != new_field.arg_type => {
+                changes.push(SchemaChange::FieldTypeChanged {
+                    type_name: type_name.to_owned(),
+                    field_name: field_name.clone(),
+                    old_type: old_field.arg_type.to_string(),
+                    new_type: new_field.arg_type.to_string(),
+                    safe: is_safe_input_change(&old_field.arg_type, &new_field.arg_type),
+                })
+            }
+            Some(_) => {}
+            None => changes.push(SchemaChange::FieldRemoved {
+                type_name: type_name.to_owned(),
+                field_name: field_name.clone(),
+            }),
+        }
+    }
+    for new_field in new_fields {
+        if !old_fields.iter().any(|f| f.name == new_field.name) {
+            changes.push(SchemaChange::FieldAdded {
+                type_name: type_name.to_owned(),
+                field_name: new_field.name.clone(),
+                required: is_required(new_field),
+            });
+        }
+    }
+}
+
+/// The names removed from `old` in their order, followed by the names added
+/// in `new` in their order, flagged as added
+fn diff_names<'n, N>(old: &'n [N], new: &'n [N]) -> Vec<(&'n str, bool)>
+where
+    N: AsRef<str>,
+{
+    let old_names = old.iter().map(AsRef::as_ref).collect::<HashSet<_>>();
+    let new_names = new.iter().map(AsRef::as_ref).collect::<HashSet<_>>();
+
+    old.iter()
+        .map(AsRef::as_ref)
+        .filter(|n| !new_names.contains(n))
+        .map(|n| (n, false))
+        .chain(
+            new.iter()
+                .map(AsRef::as_ref)
+                .filter(|n| !old_names.contains(n))
+                .map(|n| (n, true)),
+        )
+        .collect()
+}
+
+fn is_required<S>(argument: &Argument<S>) -> bool {
+    argument.arg_type.is_non_null() && argument.default_value.is_none()
+}
+
+/// Whether clients reading a value of type `old` can read one of type `new`,
+/// which holds when `new` only adds non-null wrappers to `old`
+fn is_safe_output_change(old: &Type, new: &Type) -> bool {
+    match (old, new) {
+        (Type::Named(old), Type::Named(new))
+        | (Type::Named(old), Type::NonNullNamed(new))
+        | (Type::NonNullNamed(old), Type::NonNullNamed(new)) => old == new,
+        (Type::List(old), Type::List(new))
+        | (Type::List(old), Type::NonNullList(new))
+        | (Type::NonNullList(old), Type::NonNullList(new)) => is_safe_output_change(old, new),
+        _ => false,
+    }
+}
+
+/// Whether clients passing a value of type `old` can pass it as type `new`,
+/// which holds when `new` only removes non-null wrappers from `old`
+fn is_safe_input_change(old: &Type, new: &Type) -> bool {
+    match (old, new) {
+        (Type::Named(old), Type::Named(new))
+        | (Type::NonNullNamed(old), Type::Named(new))
+        | (Type::NonNullNamed(old), Type::NonNullNamed(new)) => old == new,
+        (Type::List(old), Type::List(new))
+        | (Type::NonNullList(old), Type::List(new))
+        | (Type::NonNullList(old), Type::NonNullList(new)) => is_safe_input_change(old, new),
+        _ => false,
+    }
+}
@@ -9,7 +9,7 @@ use crate::{
     ast::{FromInputValue, InputValue, Type},
     parser::{ParseError, ScalarToken},
     schema::model::SchemaType,
-    types::base::TypeKind,
+    types::{base::TypeKind, subscriptions::BufferOverflow},
     value::{DefaultScalarValue, ParseScalarValue, ScalarValue},
 };
 
@@ -167,6 +167,16 @@ pub struct Field<'a, S> {
     pub field_type: Type<'a>,
     #[doc(hidden)]
     pub deprecation_status: DeprecationStatus,
+    #[doc(hidden)]
+    pub cache_max_age: Option<u32>,
+    #[doc(hidden)]
+    pub complexity: Option<u32>,
+    #[doc(hidden)]
+    pub list_size: Option<u32>,
+    #[doc(hidden)]
+    pub buffer_size: Option<usize>,
+    #[doc(hidden)]
+    pub buffer_overflow: BufferOverflow,
 }
 
 /// Metadata for an argument to a field
@@ -368,6 +378,16 @@ impl<'a, S> MetaType<'a, S> {
         }
     }
 
+    /// Returns true if the type can be used in output positions, e.g. fields
+    ///
+    /// All types except input objects are output types.
+    pub fn is_output(&self) -> bool {
+        match *self {
+            MetaType::InputObject(_) => false,
+            _ => true,
+        }
+    }
+
     pub(crate) fn fields<'b>(&self, schema: &'b SchemaType<S>) -> Option<Vec<&'b Field<'b, S>>> {
         schema
             .lookup_type(&self.as_type())
@@ -644,6 +664,18 @@ impl<'a, S> Field<'a, S> {
         self
     }
 
+    /// Add several arguments to the field
+    pub fn arguments<I>(mut self, arguments: I) -> Self
+    where
+        I: IntoIterator<Item = Argument<'a, S>>,
+    {
+        self.arguments
+            .get_or_insert_with(Vec::new)
+            .extend(arguments);
+
+        self
+    }
+
     /// Set the field to be deprecated with an optional reason.
     ///
     /// This overwrites the deprecation reason if any was previously set.
@@ -651,6 +683,44 @@ impl<'a, S> Field<'a, S> {
         self.deprecation_status = DeprecationStatus::Deprecated(reason.map(ToOwned::to_owned));
         self
     }
+
+    /// Set a cache hint for the field, in seconds.
+    ///
+    /// The cache hint of a response is the minimum hint of all the fields
+    /// selected by the operation.
+    pub fn cache_control(mut self, max_age: u32) -> Self {
+        self.cache_max_age = Some(max_age);
+        self
+    }
+
+    /// Set the cost of resolving the field, used by the complexity analysis.
+    ///
+    /// Fields cost `1` by default.
+    pub fn complexity(mut self, cost: u32) -> Self {
+        self.complexity = Some(cost);
+        self
+    }
+
+    /// Set the estimated number of items a list field resolves to, used by
+    /// the complexity analysis.
+    ///
+    /// Lists are estimated to hold `10` items by default.
+    pub fn list_size(mut self, size: u32) -> Self {
+        self.list_size = Some(size);
+        self
+    }
+
+    /// Buffer up to `size` ready events of the subscription field, and
+    /// handle events arriving on a full buffer with `overflow`.
+    ///
+    /// This overrides the
+    /// [`RootNode::subscription_buffer_size`](crate::RootNode::subscription_buffer_size)
+    /// of the schema for this field.
+    pub fn buffer(mut self, size: usize, overflow: BufferOverflow) -> Self {
+        self.buffer_size = Some(size.max(1));
+        self.buffer_overflow = overflow;
+        self
+    }
 }
 
 impl<'a, S> Argument<'a, S> {
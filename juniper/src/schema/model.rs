@@ -1,17 +1,37 @@
-use std::fmt;
+use std::{error::Error, fmt};
 
-use fnv::FnvHashMap;
+use fnv::{FnvHashMap, FnvHashSet};
 
 use juniper_codegen::GraphQLEnumInternal as GraphQLEnum;
 
 use crate::{
-    ast::Type,
-    executor::{Context, Registry},
-    schema::meta::{Argument, InterfaceMeta, MetaType, ObjectMeta, PlaceholderMeta, UnionMeta},
-    types::{base::GraphQLType, name::Name},
-    value::{DefaultScalarValue, ScalarValue},
+    ast::{Document, Operation, Type},
+    executor::{
+        plan_operation, Context, FieldAuthorizerFactory, FieldDirectiveHooksFactory, FieldResult,
+        PlannedResolver, Registry, Variables,
+    },
+    introspection::IntrospectionFormat,
+    parser::{parse_document_source, Spanning},
+    schema::meta::{
+        Argument, InputObjectMeta, InterfaceMeta, MetaType, ObjectMeta, PlaceholderMeta, UnionMeta,
+    },
+    types::{
+        base::{Arguments, GraphQLType},
+        name::Name,
+    },
+    validate_operation,
+    validation::RuleError,
+    value::{DefaultScalarValue, ScalarValue, Value},
+    GraphQLError,
 };
 
+#[cfg(any(test, feature = "resolver-log"))]
+use crate::executor::{FieldError, PathSegment, ResolverLog};
+#[cfg(feature = "persisted-queries")]
+use crate::http::persisted_queries::{PersistedQueryStore, SchemaPersistedQueryStore};
+#[cfg(any(test, feature = "resolver-log"))]
+use std::time::Duration;
+
 /// Root query node of a schema
 ///
 /// This brings the mutation, subscription and query types together,
@@ -40,6 +60,27 @@ pub struct RootNode<
     pub subscription_info: SubscriptionT::TypeInfo,
     #[doc(hidden)]
     pub schema: SchemaType<'a, S>,
+    pub(crate) field_authorizer: Option<FieldAuthorizerFactory<QueryT::Context, S>>,
+    pub(crate) field_directive_hooks: FieldDirectiveHooksFactory<QueryT::Context, S>,
+    operation_inspector: Option<OperationInspector<QueryT::Context, S>>,
+    #[cfg(feature = "persisted-queries")]
+    persisted_query_store: Option<SchemaPersistedQueryStore>,
+}
+
+/// Hook called with the parsed document and the selected operation of every
+/// request before it executes
+struct OperationInspector<CtxT, S>(
+    Box<
+        dyn for<'d> Fn(&Document<'d, S>, &Operation<'d, S>, &CtxT) -> Result<(), RuleError>
+            + Send
+            + Sync,
+    >,
+);
+
+impl<CtxT, S> fmt::Debug for OperationInspector<CtxT, S> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("OperationInspector")
+    }
 }
 
 /// Metadata for a schema
@@ -50,6 +91,20 @@ pub struct SchemaType<'a, S> {
     mutation_type_name: Option<String>,
     subscription_type_name: Option<String>,
     directives: FnvHashMap<String, DirectiveType<'a, S>>,
+    pub(crate) subscription_concurrency_limit: Option<usize>,
+    pub(crate) subscription_buffer_size: Option<usize>,
+    pub(crate) batch_concurrency_limit: Option<usize>,
+    pub(crate) max_query_depth: Option<usize>,
+    pub(crate) max_query_aliases: Option<usize>,
+    pub(crate) max_query_complexity: Option<usize>,
+    pub(crate) max_query_length: Option<usize>,
+    pub(crate) max_query_tokens: Option<usize>,
+    pub(crate) introspection_disabled: bool,
+    pub(crate) null_propagation_disabled: bool,
+    pub(crate) tracing_enabled: bool,
+    pub(crate) field_naming: FieldNaming,
+    #[cfg(any(test, feature = "resolver-log"))]
+    pub(crate) resolver_log: Option<ResolverLog<S>>,
 }
 
 impl<'a, S> Context for SchemaType<'a, S> {}
@@ -61,14 +116,54 @@ pub enum TypeType<'a, S: 'a> {
     List(Box<TypeType<'a, S>>),
 }
 
+/// Metadata for a directive of a schema
 #[derive(Debug)]
 pub struct DirectiveType<'a, S> {
+    /// The name of the directive, without the leading `@`
     pub name: String,
+    /// The description shown by introspection
     pub description: Option<String>,
+    /// Where in a document the directive may be used
     pub locations: Vec<DirectiveLocation>,
+    /// The arguments the directive accepts
     pub arguments: Vec<Argument<'a, S>>,
 }
 
+/// How the fields of a schema are named
+///
+/// See [`RootNode::field_naming`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FieldNaming {
+    /// Fields keep the names they are defined with, which the object macros
+    /// convert to camel case unless renamed.
+    CamelCase,
+    /// Fields are converted to snake case, like `home_planet`.
+    SnakeCase,
+}
+
+impl Default for FieldNaming {
+    fn default() -> Self {
+        FieldNaming::CamelCase
+    }
+}
+
+impl FieldNaming {
+    /// The name of a field of `type_name` derived from its Rust identifier.
+    /// Fields of introspection types always keep their camel case names.
+    pub(crate) fn derived_name<'n>(
+        self,
+        type_name: &str,
+        camel_case: &'n str,
+        snake_case: &'n str,
+    ) -> &'n str {
+        match self {
+            FieldNaming::SnakeCase if !type_name.starts_with("__") => snake_case,
+            _ => camel_case,
+        }
+    }
+}
+
+#[allow(missing_docs)]
 #[derive(Clone, PartialEq, Eq, Debug, GraphQLEnum)]
 #[graphql(name = "__DirectiveLocation")]
 pub enum DirectiveLocation {
@@ -84,6 +179,28 @@ pub enum DirectiveLocation {
     InlineFragment,
 }
 
+impl<'a, QueryT, MutationT, SubscriptionT>
+    RootNode<'a, QueryT, MutationT, SubscriptionT, DefaultScalarValue>
+where
+    QueryT: GraphQLType<DefaultScalarValue, TypeInfo = ()>,
+    MutationT: GraphQLType<DefaultScalarValue, TypeInfo = ()>,
+    SubscriptionT: GraphQLType<DefaultScalarValue, TypeInfo = ()>,
+{
+    /// Construct a new root node from query, mutation, and subscription nodes
+    ///
+    /// The schema uses the `DefaultScalarValue`. To build it over a custom
+    /// scalar value type, use [`RootNode::new_with_scalar`] instead.
+    ///
+    /// [`RootNode::new_with_scalar`]: #method.new_with_scalar
+    pub fn new(
+        query_obj: QueryT,
+        mutation_obj: MutationT,
+        subscription_obj: SubscriptionT,
+    ) -> Self {
+        RootNode::new_with_info(query_obj, mutation_obj, subscription_obj, (), (), ())
+    }
+}
+
 impl<'a, QueryT, MutationT, SubscriptionT, S> RootNode<'a, QueryT, MutationT, SubscriptionT, S>
 where
     S: ScalarValue + 'a,
@@ -92,16 +209,43 @@ where
     SubscriptionT: GraphQLType<S, TypeInfo = ()>,
 {
     /// Construct a new root node from query, mutation, and subscription nodes
+    /// over the scalar value type `S`
     ///
-    /// If the schema should not support mutations, use the
-    /// `new` constructor instead.
-    pub fn new(
+    /// `S` is inferred from the root types, which have to implement
+    /// `GraphQLType<S>`: types generated by the object and derive macros do
+    /// so for the scalar value type named by their `scalar` attribute.
+    ///
+    /// ```ignore
+    /// #[juniper::graphql_object(Scalar = MyScalarValue)]
+    /// impl Query {
+    ///     // ...
+    /// }
+    ///
+    /// let schema = RootNode::new_with_scalar(
+    ///     Query,
+    ///     EmptyMutation::<()>::new(),
+    ///     EmptySubscription::<()>::new(),
+    /// );
+    /// ```
+    pub fn new_with_scalar(
         query_obj: QueryT,
         mutation_obj: MutationT,
         subscription_obj: SubscriptionT,
     ) -> Self {
         RootNode::new_with_info(query_obj, mutation_obj, subscription_obj, (), (), ())
     }
+
+    /// Construct a new root node from query, mutation, and subscription nodes,
+    /// checking that its types are well-formed.
+    ///
+    /// See [`SchemaType::new_validated`].
+    pub fn new_validated(
+        query_obj: QueryT,
+        mutation_obj: MutationT,
+        subscription_obj: SubscriptionT,
+    ) -> Result<Self, SchemaBuildError> {
+        RootNode::new_validated_with_info(query_obj, mutation_obj, subscription_obj, (), (), ())
+    }
 }
 
 impl<'a, S, QueryT, MutationT, SubscriptionT> RootNode<'a, QueryT, MutationT, SubscriptionT, S>
@@ -121,21 +265,446 @@ where
         query_info: QueryT::TypeInfo,
         mutation_info: MutationT::TypeInfo,
         subscription_info: SubscriptionT::TypeInfo,
+    ) -> Self {
+        let schema = SchemaType::new::<QueryT, MutationT, SubscriptionT>(
+            &query_info,
+            &mutation_info,
+            &subscription_info,
+        );
+        RootNode::with_schema(
+            query_obj,
+            mutation_obj,
+            subscription_obj,
+            query_info,
+            mutation_info,
+            subscription_info,
+            schema,
+        )
+    }
+
+    /// Construct a new root node from query, mutation, and subscription nodes
+    /// and their type info objects, checking that its types are well-formed.
+    ///
+    /// See [`SchemaType::new_validated`].
+    pub fn new_validated_with_info(
+        query_obj: QueryT,
+        mutation_obj: MutationT,
+        subscription_obj: SubscriptionT,
+        query_info: QueryT::TypeInfo,
+        mutation_info: MutationT::TypeInfo,
+        subscription_info: SubscriptionT::TypeInfo,
+    ) -> Result<Self, SchemaBuildError> {
+        let schema = SchemaType::new_validated::<QueryT, MutationT, SubscriptionT>(
+            &query_info,
+            &mutation_info,
+            &subscription_info,
+        )?;
+        Ok(RootNode::with_schema(
+            query_obj,
+            mutation_obj,
+            subscription_obj,
+            query_info,
+            mutation_info,
+            subscription_info,
+            schema,
+        ))
+    }
+
+    fn with_schema(
+        query_obj: QueryT,
+        mutation_obj: MutationT,
+        subscription_obj: SubscriptionT,
+        query_info: QueryT::TypeInfo,
+        mutation_info: MutationT::TypeInfo,
+        subscription_info: SubscriptionT::TypeInfo,
+        schema: SchemaType<'a, S>,
     ) -> Self {
         RootNode {
             query_type: query_obj,
             mutation_type: mutation_obj,
             subscription_type: subscription_obj,
-            schema: SchemaType::new::<QueryT, MutationT, SubscriptionT>(
-                &query_info,
-                &mutation_info,
-                &subscription_info,
-            ),
+            schema,
             query_info,
             mutation_info,
             subscription_info,
+            field_authorizer: None,
+            field_directive_hooks: FieldDirectiveHooksFactory::default(),
+            operation_inspector: None,
+            #[cfg(feature = "persisted-queries")]
+            persisted_query_store: None,
+        }
+    }
+
+    /// Print the schema in the GraphQL schema definition language (SDL).
+    ///
+    /// See [`SchemaType::as_sdl`].
+    pub fn as_sdl(&self) -> String {
+        self.schema.as_sdl()
+    }
+
+    /// Limit how many fields of a subscription selection set are resolved
+    /// concurrently.
+    ///
+    /// The limit applies both when the fields set up their streams, and to
+    /// the events of these streams resolved on every tick, which share the
+    /// slots of their subscription. By default all fields of a selection set
+    /// are resolved at once.
+    pub fn subscription_concurrency_limit(mut self, limit: usize) -> Self {
+        self.schema.subscription_concurrency_limit = Some(limit.max(1));
+        self
+    }
+
+    /// Buffer up to `size` events of every subscribed field that are ready
+    /// before the client takes them.
+    ///
+    /// Producers read how many events are waiting with
+    /// [`Executor::buffer_pressure`](crate::Executor::buffer_pressure). By
+    /// default events are not buffered.
+    pub fn subscription_buffer_size(mut self, size: usize) -> Self {
+        self.schema.subscription_buffer_size = Some(size.max(1));
+        self
+    }
+
+    /// Execute at most `limit` requests of a batch executed with
+    /// [`http::execute_batch`](crate::http::execute_batch) concurrently.
+    ///
+    /// By default all requests of a batch are executed at once.
+    pub fn batch_concurrency_limit(mut self, limit: usize) -> Self {
+        self.schema.batch_concurrency_limit = Some(limit.max(1));
+        self
+    }
+
+    /// Reject operations whose selection sets are nested deeper than
+    /// `max_depth` fields during validation.
+    ///
+    /// Inline fragments and fragment spreads do not add to the depth, and
+    /// introspection fields like `__schema` are not limited, so that tools
+    /// like GraphiQL can still load the schema.
+    pub fn max_query_depth(mut self, max_depth: usize) -> Self {
+        self.schema.max_query_depth = Some(max_depth);
+        self
+    }
+
+    /// Reject operations selecting more than `max_aliases` aliased fields
+    /// during validation.
+    ///
+    /// The aliases of all the selection sets of an operation are added up,
+    /// and the ones of a fragment are counted for every spread of it, so
+    /// that a cheap field can't be repeated under many aliases to amplify
+    /// the cost of a shallow query.
+    pub fn max_query_aliases(mut self, max_aliases: usize) -> Self {
+        self.schema.max_query_aliases = Some(max_aliases);
+        self
+    }
+
+    /// Reject operations whose estimated cost exceeds `max_complexity` before
+    /// executing them.
+    ///
+    /// Every selected field costs `1` unless set otherwise with
+    /// `#[graphql(complexity(cost = ...))]`, and list fields multiply their
+    /// cost by an estimated size set with
+    /// `#[graphql(complexity(list_size = ...))]`, `10` by default.
+    pub fn max_query_complexity(mut self, max_complexity: usize) -> Self {
+        self.schema.max_query_complexity = Some(max_complexity);
+        self
+    }
+
+    /// Reject documents longer than `max_length` bytes before parsing them.
+    pub fn max_query_length(mut self, max_length: usize) -> Self {
+        self.schema.max_query_length = Some(max_length);
+        self
+    }
+
+    /// Reject documents made of more than `max_tokens` tokens while parsing
+    /// them.
+    ///
+    /// Every name, value and punctuator is a token, so this bounds the size
+    /// of the parsed document.
+    pub fn max_query_tokens(mut self, max_tokens: usize) -> Self {
+        self.schema.max_query_tokens = Some(max_tokens);
+        self
+    }
+
+    /// Reject operations selecting the `__schema` or `__type` introspection
+    /// fields during validation.
+    ///
+    /// Other fields, including `__typename`, resolve unchanged.
+    pub fn disable_introspection(mut self) -> Self {
+        self.schema.introspection_disabled = true;
+        self
+    }
+
+    /// Resolve non-null fields and list items that are `null` because of an
+    /// error to `null` in place.
+    ///
+    /// By default such a `null` propagates to the nearest nullable field or
+    /// list item above it, as the GraphQL specification requires, which nulls
+    /// its successfully resolved siblings too.
+    pub fn disable_null_propagation(mut self) -> Self {
+        self.schema.null_propagation_disabled = true;
+        self
+    }
+
+    /// Name the fields of object and interface types after `naming`.
+    ///
+    /// The object macros name fields after their Rust identifiers in camel
+    /// case, like `homePlanet` for a `home_planet` method, which is what
+    /// [`FieldNaming::CamelCase`], the default, keeps. The types of the schema
+    /// are registered again under the new policy, which only applies to names
+    /// derived from Rust identifiers: fields renamed with
+    /// `#[graphql(name = "...")]` and introspection fields keep their names.
+    pub fn field_naming(mut self, naming: FieldNaming) -> Self {
+        let mut registry = Registry::new(FnvHashMap::default());
+        registry.field_naming = naming;
+        let schema = SchemaType::from_registry::<QueryT, MutationT, SubscriptionT>(
+            &mut registry,
+            &self.query_info,
+            &self.mutation_info,
+            &self.subscription_info,
+        );
+        self.schema.types.extend(schema.types);
+        self.schema.field_naming = naming;
+        self
+    }
+
+    /// Record how long every field takes to resolve during asynchronous
+    /// execution.
+    ///
+    /// The timings are available through [`GraphQLResponse::tracing`] after
+    /// executing a `GraphQLRequest`, and are emitted under the
+    /// `extensions.tracing` entry of the response in the Apollo Tracing
+    /// format. Synchronous execution doesn't record any timing.
+    ///
+    /// [`GraphQLResponse::tracing`]: http/struct.GraphQLResponse.html#method.tracing
+    pub fn enable_tracing(mut self) -> Self {
+        self.schema.tracing_enabled = true;
+        self
+    }
+
+    /// Add the `@cacheControl(maxAge: Int!)` directive to the schema, on
+    /// queries and fields.
+    ///
+    /// Its `maxAge` lowers the cache hint of the executed operation, like the
+    /// `#[graphql(cache_control(max_age = ...))]` hints of fields do, and is
+    /// available through
+    /// [`GraphQLResponse::cache_max_age`](http/struct.GraphQLResponse.html#method.cache_max_age).
+    /// The hints of fields apply whether the directive is added or not.
+    pub fn enable_cache_control(mut self) -> Self {
+        let mut registry = Registry::new(std::mem::take(&mut self.schema.types));
+        let directive = DirectiveType::new_cache_control(&mut registry);
+        self.schema.types = registry.types;
+        self.schema.add_directive(directive);
+        self
+    }
+
+    /// Call `log` after every field resolving in at least `threshold` or to
+    /// an error, with the response path of the field, how long it took to
+    /// resolve and its error.
+    ///
+    /// Unlike [`enable_tracing`](#method.enable_tracing), this applies to both
+    /// synchronous and asynchronous execution. The duration of a field
+    /// includes resolving its selection set.
+    #[cfg(any(test, feature = "resolver-log"))]
+    pub fn log_resolvers<F>(mut self, threshold: Duration, log: F) -> Self
+    where
+        F: Fn(&[PathSegment], Duration, Option<&FieldError<S>>) + Send + Sync + 'static,
+    {
+        self.schema.resolver_log = Some(ResolverLog::new(threshold, log));
+        self
+    }
+
+    /// List the resolvers executing the operation `operation_name` of `query`
+    /// would call, without calling them.
+    ///
+    /// The query is parsed and validated like for execution, and the planned
+    /// resolvers are returned in the order their fields are selected, along
+    /// with their arguments and how often they would be called. List fields
+    /// are assumed to hold as many items as their
+    /// `#[graphql(complexity(list_size = ...))]` hint, or `list_size` items
+    /// without one.
+    ///
+    /// ```
+    /// # use juniper::{EmptyMutation, EmptySubscription, RootNode, Variables};
+    /// struct Query;
+    ///
+    /// #[juniper::graphql_object]
+    /// impl Query {
+    ///     fn numbers() -> Vec<i32> {
+    ///         vec![1, 2, 3]
+    ///     }
+    /// }
+    ///
+    /// let schema = RootNode::new(Query, EmptyMutation::<()>::new(), EmptySubscription::<()>::new());
+    /// let planned = schema
+    ///     .dry_run("{ numbers }", None, &Variables::new(), 10)
+    ///     .unwrap();
+    ///
+    /// assert_eq!(planned.len(), 1);
+    /// assert_eq!(planned[0].parent_type, "Query");
+    /// assert_eq!(planned[0].field_name, "numbers");
+    /// ```
+    pub fn dry_run<'q>(
+        &self,
+        query: &'q str,
+        operation_name: Option<&str>,
+        variables: &Variables<S>,
+        list_size: usize,
+    ) -> Result<Vec<PlannedResolver<S>>, GraphQLError<'q>> {
+        let document = parse_document_source(query, &self.schema)?;
+        let (operation, all_vars) =
+            validate_operation(&self.schema, &document, operation_name, variables)?;
+
+        Ok(plan_operation(
+            &document,
+            operation,
+            &self.schema,
+            &all_vars,
+            list_size,
+        ))
+    }
+
+    /// Run the standard introspection query against the schema, returning the
+    /// value of its `__schema` field.
+    ///
+    /// This is the JSON tools like code generators consume to describe the
+    /// schema. It runs [`introspect`](../fn.introspect.html), so it fails with
+    /// the same validation error if the schema
+    /// [disables introspection](#method.disable_introspection).
+    pub fn introspect<'b>(&'b self, context: &QueryT::Context) -> Result<Value<S>, GraphQLError<'b>>
+    where
+        MutationT: GraphQLType<S, Context = QueryT::Context>,
+        SubscriptionT: GraphQLType<S, Context = QueryT::Context>,
+    {
+        let (value, _) = crate::introspect(self, context, IntrospectionFormat::All)?;
+
+        Ok(value
+            .into_object()
+            .and_then(|o| o.into_iter().find(|(name, _)| name == "__schema"))
+            .map_or_else(Value::null, |(_, schema)| schema))
+    }
+
+    /// Run `authorize` before resolving every field of an operation.
+    ///
+    /// The hook is called with the name of the parent type, the name of the
+    /// field and the context of the operation. Returning an error prevents the
+    /// field from resolving and reports the error at the position of the
+    /// field, just like an error returned by the field resolver itself.
+    /// Introspection of `__typename` is never authorized.
+    pub fn authorize_fields<F>(mut self, authorize: F) -> Self
+    where
+        F: Fn(&str, &str, &QueryT::Context) -> FieldResult<(), S> + Send + Sync + 'static,
+        QueryT::Context: Sync,
+    {
+        self.field_authorizer = Some(FieldAuthorizerFactory::new(authorize));
+        self
+    }
+
+    /// Run `inspect` on the parsed document and the selected operation of
+    /// every request once they are validated, before any field resolves.
+    ///
+    /// The hook is called with the context of the operation, so it can e.g.
+    /// check operations against an allow-list or record them for analytics
+    /// without parsing the document again. Returning an error rejects the
+    /// operation with a `GraphQLError::ValidationError`.
+    pub fn inspect_operations<F>(mut self, inspect: F) -> Self
+    where
+        F: for<'d> Fn(
+                &Document<'d, S>,
+                &Operation<'d, S>,
+                &QueryT::Context,
+            ) -> Result<(), RuleError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.operation_inspector = Some(OperationInspector(Box::new(inspect)));
+        self
+    }
+
+    /// Answer [automatic persisted queries][apq] with the queries registered
+    /// in `store`.
+    ///
+    /// Requests carrying only the hash of a query otherwise fail with a
+    /// `PERSISTED_QUERY_NOT_SUPPORTED` error.
+    ///
+    /// [apq]: http/persisted_queries/index.html
+    #[cfg(feature = "persisted-queries")]
+    pub fn persisted_queries<P>(mut self, store: P) -> Self
+    where
+        P: PersistedQueryStore + 'static,
+    {
+        self.persisted_query_store = Some(SchemaPersistedQueryStore(Box::new(store)));
+        self
+    }
+
+    /// The store set with [`persisted_queries`](#method.persisted_queries),
+    /// if any.
+    #[cfg(feature = "persisted-queries")]
+    pub(crate) fn persisted_query_store(&self) -> Option<&dyn PersistedQueryStore> {
+        self.persisted_query_store.as_ref().map(|s| &*s.0)
+    }
+
+    /// Run the hook registered with
+    /// [`inspect_operations`](#method.inspect_operations), if any.
+    pub(crate) fn inspect_operation<'d, 'e>(
+        &self,
+        document: &Document<'d, S>,
+        operation: &Spanning<Operation<'d, S>>,
+        context: &QueryT::Context,
+    ) -> Result<(), GraphQLError<'e>> {
+        match self.operation_inspector {
+            Some(OperationInspector(ref inspect)) => inspect(document, &operation.item, context)
+                .map_err(|e| GraphQLError::ValidationError(vec![e])),
+            None => Ok(()),
         }
     }
+
+    /// Register a custom directive, and run `hook` on the resolved value of
+    /// every field it is used on.
+    ///
+    /// The directive becomes part of the schema, so documents using it pass
+    /// validation, while unregistered directives are still rejected. The hook
+    /// is called with the arguments of the directive, the resolved value of
+    /// the field and the context of the operation, and returns the value to
+    /// respond with. Returning an error reports it at the position of the
+    /// field. Hooks of several directives on the same field run in the order
+    /// the directives are written, on the value of a subscription field for
+    /// every event of its stream.
+    ///
+    /// The hook runs once the field is resolved, so use
+    /// [`field_directive_guard`](#method.field_directive_guard) to deny access
+    /// to a field before its resolver runs.
+    pub fn field_directive<F>(mut self, directive: DirectiveType<'a, S>, hook: F) -> Self
+    where
+        F: Fn(&Arguments<S>, Value<S>, &QueryT::Context) -> FieldResult<Value<S>, S>
+            + Send
+            + Sync
+            + 'static,
+        QueryT::Context: Sync,
+    {
+        self.field_directive_hooks.insert(&directive.name, hook);
+        self.schema.add_directive(directive);
+        self
+    }
+
+    /// Register a custom directive, and run `guard` before resolving every
+    /// field it is used on.
+    ///
+    /// The guard is called with the arguments of the directive and the
+    /// context of the operation. Returning an error reports it at the position
+    /// of the field without calling its resolver, so a denied field has no
+    /// side effects. Registering a guard replaces a hook registered with
+    /// [`field_directive`](#method.field_directive) for the same directive.
+    pub fn field_directive_guard<F>(mut self, directive: DirectiveType<'a, S>, guard: F) -> Self
+    where
+        F: Fn(&Arguments<S>, &QueryT::Context) -> FieldResult<(), S> + Send + Sync + 'static,
+        QueryT::Context: Sync,
+    {
+        self.field_directive_hooks
+            .insert_guard(&directive.name, guard);
+        self.schema.add_directive(directive);
+        self
+    }
 }
 
 impl<'a, S> SchemaType<'a, S> {
@@ -145,6 +714,94 @@ impl<'a, S> SchemaType<'a, S> {
         mutation_info: &MutationT::TypeInfo,
         subscription_info: &SubscriptionT::TypeInfo,
     ) -> Self
+    where
+        S: ScalarValue + 'a,
+        QueryT: GraphQLType<S>,
+        MutationT: GraphQLType<S>,
+        SubscriptionT: GraphQLType<S>,
+    {
+        SchemaType::from_registry::<QueryT, MutationT, SubscriptionT>(
+            &mut Registry::new(FnvHashMap::default()),
+            query_info,
+            mutation_info,
+            subscription_info,
+        )
+    }
+
+    /// Create a new schema, checking that its types are well-formed.
+    ///
+    /// Types are otherwise only resolved by name when a query is executed,
+    /// so this catches at startup:
+    /// - union members and implemented interfaces which are not part of the
+    ///   schema,
+    /// - fields, arguments and input fields whose type is not part of the
+    ///   schema,
+    /// - objects or interfaces used as argument or input field types, and
+    ///   input objects used as field types,
+    /// - type names used by several Rust types, only one of which would
+    ///   otherwise end up in the schema,
+    /// - input objects referencing each other through non-null fields, for
+    ///   which no finite value exists,
+    /// - types other than input objects flattened into arguments, and fields
+    ///   with several arguments of the same name, e.g. when a flattened input
+    ///   object has a field named like another argument.
+    ///
+    /// Other reference cycles, e.g. between objects, are allowed.
+    ///
+    /// All the problems found are listed in the returned error.
+    pub fn new_validated<QueryT, MutationT, SubscriptionT>(
+        query_info: &QueryT::TypeInfo,
+        mutation_info: &MutationT::TypeInfo,
+        subscription_info: &SubscriptionT::TypeInfo,
+    ) -> Result<Self, SchemaBuildError>
+    where
+        S: ScalarValue + 'a,
+        QueryT: GraphQLType<S>,
+        MutationT: GraphQLType<S>,
+        SubscriptionT: GraphQLType<S>,
+    {
+        let mut registry = Registry::new(FnvHashMap::default());
+        registry.track_type_sources();
+        let schema = SchemaType::from_registry::<QueryT, MutationT, SubscriptionT>(
+            &mut registry,
+            query_info,
+            mutation_info,
+            subscription_info,
+        );
+
+        let mut errors = registry
+            .duplicate_type_names()
+            .into_iter()
+            .map(
+                |(type_name, rust_types)| SchemaTypeError::DuplicateTypeName {
+                    type_name: type_name.to_owned(),
+                    rust_types: rust_types.iter().map(|t| (*t).to_owned()).collect(),
+                },
+            )
+            .collect::<Vec<_>>();
+        errors.extend(registry.unflattenable_types().iter().map(|type_name| {
+            SchemaTypeError::UnflattenableType {
+                type_name: type_name.clone(),
+            }
+        }));
+        errors.extend(schema.unknown_type_references());
+        errors.extend(schema.misplaced_types());
+        errors.extend(schema.duplicate_argument_names());
+        errors.extend(schema.input_object_cycles());
+
+        if errors.is_empty() {
+            Ok(schema)
+        } else {
+            Err(SchemaBuildError { errors })
+        }
+    }
+
+    fn from_registry<QueryT, MutationT, SubscriptionT>(
+        registry: &mut Registry<'a, S>,
+        query_info: &QueryT::TypeInfo,
+        mutation_info: &MutationT::TypeInfo,
+        subscription_info: &SubscriptionT::TypeInfo,
+    ) -> Self
     where
         S: ScalarValue + 'a,
         QueryT: GraphQLType<S>,
@@ -156,7 +813,6 @@ impl<'a, S> SchemaType<'a, S> {
         let mutation_type_name: String;
         let subscription_type_name: String;
 
-        let mut registry = Registry::new(FnvHashMap::default());
         query_type_name = registry
             .get_type::<QueryT>(query_info)
             .innermost_name()
@@ -174,11 +830,8 @@ impl<'a, S> SchemaType<'a, S> {
 
         registry.get_type::<SchemaType<S>>(&());
 
-        directives.insert("skip".to_owned(), DirectiveType::new_skip(&mut registry));
-        directives.insert(
-            "include".to_owned(),
-            DirectiveType::new_include(&mut registry),
-        );
+        directives.insert("skip".to_owned(), DirectiveType::new_skip(registry));
+        directives.insert("include".to_owned(), DirectiveType::new_include(registry));
 
         let mut meta_fields = vec![
             registry.field::<SchemaType<S>>("__schema", &()),
@@ -203,7 +856,7 @@ impl<'a, S> SchemaType<'a, S> {
             }
         }
         SchemaType {
-            types: registry.types,
+            types: std::mem::take(&mut registry.types),
             query_type_name,
             mutation_type_name: if &mutation_type_name != "_EmptyMutation" {
                 Some(mutation_type_name)
@@ -216,9 +869,291 @@ impl<'a, S> SchemaType<'a, S> {
                 None
             },
             directives,
+            subscription_concurrency_limit: None,
+            subscription_buffer_size: None,
+            batch_concurrency_limit: None,
+            max_query_depth: None,
+            max_query_aliases: None,
+            max_query_complexity: None,
+            max_query_length: None,
+            max_query_tokens: None,
+            introspection_disabled: false,
+            null_propagation_disabled: false,
+            tracing_enabled: false,
+            field_naming: registry.field_naming,
+            #[cfg(any(test, feature = "resolver-log"))]
+            resolver_log: None,
         }
     }
 
+    /// Types referenced by the types of this schema which are not part of it.
+    fn unknown_type_references(&self) -> Vec<SchemaTypeError> {
+        let is_known = |t: &Type| self.types.contains_key(t.innermost_name());
+        let mut errors = vec![];
+
+        let mut meta_types = self.types.values().collect::<Vec<_>>();
+        meta_types.sort_by_key(|t| t.name());
+
+        for meta_type in meta_types {
+            let (type_name, fields) = match *meta_type {
+                MetaType::Object(ObjectMeta {
+                    ref name,
+                    ref fields,
+                    ref interface_names,
+                    ..
+                }) => {
+                    for interface_name in interface_names {
+                        if !self.types.contains_key(interface_name.as_str()) {
+                            errors.push(SchemaTypeError::DanglingTypeReference {
+                                type_name: name.to_string(),
+                                referenced_type: interface_name.clone(),
+                            });
+                        }
+                    }
+                    (name, fields)
+                }
+                MetaType::Interface(InterfaceMeta {
+                    ref name,
+                    ref fields,
+                    ..
+                }) => (name, fields),
+                MetaType::Union(UnionMeta {
+                    ref name,
+                    ref of_type_names,
+                    ..
+                }) => {
+                    for of_type_name in of_type_names {
+                        if !self.types.contains_key(of_type_name.as_str()) {
+                            errors.push(SchemaTypeError::DanglingTypeReference {
+                                type_name: name.to_string(),
+                                referenced_type: of_type_name.clone(),
+                            });
+                        }
+                    }
+                    continue;
+                }
+                MetaType::InputObject(InputObjectMeta {
+                    ref name,
+                    ref input_fields,
+                    ..
+                }) => {
+                    for input_field in input_fields {
+                        if !is_known(&input_field.arg_type) {
+                            errors.push(SchemaTypeError::UnknownFieldType {
+                                type_name: name.to_string(),
+                                field_name: input_field.name.clone(),
+                                field_type: input_field.arg_type.to_string(),
+                            });
+                        }
+                    }
+                    continue;
+                }
+                _ => continue,
+            };
+
+            for field in fields {
+                if !is_known(&field.field_type) {
+                    errors.push(SchemaTypeError::UnknownFieldType {
+                        type_name: type_name.to_string(),
+                        field_name: field.name.clone(),
+                        field_type: field.field_type.to_string(),
+                    });
+                }
+
+                for arg in field.arguments.iter().flatten() {
+                    if !is_known(&arg.arg_type) {
+                        errors.push(SchemaTypeError::UnknownArgumentType {
+                            type_name: type_name.to_string(),
+                            field_name: field.name.clone(),
+                            argument_name: arg.name.clone(),
+                            argument_type: arg.arg_type.to_string(),
+                        });
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+
+    /// Types used in a position they are not allowed in, e.g. objects as
+    /// argument types or input objects as field types.
+    ///
+    /// Types which are not part of the schema are reported by
+    /// [`unknown_type_references`](Self::unknown_type_references) instead.
+    fn misplaced_types(&self) -> Vec<SchemaTypeError> {
+        let is_input = |t: &Type| {
+            self.types
+                .get(t.innermost_name())
+                .map_or(true, MetaType::is_input)
+        };
+        let is_output = |t: &Type| {
+            self.types
+                .get(t.innermost_name())
+                .map_or(true, MetaType::is_output)
+        };
+        let mut errors = vec![];
+
+        let mut meta_types = self.types.values().collect::<Vec<_>>();
+        meta_types.sort_by_key(|t| t.name());
+
+        for meta_type in meta_types {
+            match *meta_type {
+                MetaType::Object(ObjectMeta {
+                    ref name,
+                    ref fields,
+                    ..
+                })
+                | MetaType::Interface(InterfaceMeta {
+                    ref name,
+                    ref fields,
+                    ..
+                }) => {
+                    for field in fields {
+                        if !is_output(&field.field_type) {
+                            errors.push(SchemaTypeError::NonOutputFieldType {
+                                type_name: name.to_string(),
+                                field_name: field.name.clone(),
+                                field_type: field.field_type.to_string(),
+                            });
+                        }
+
+                        for arg in field.arguments.iter().flatten() {
+                            if !is_input(&arg.arg_type) {
+                                errors.push(SchemaTypeError::NonInputArgumentType {
+                                    type_name: name.to_string(),
+                                    field_name: field.name.clone(),
+                                    argument_name: arg.name.clone(),
+                                    argument_type: arg.arg_type.to_string(),
+                                });
+                            }
+                        }
+                    }
+                }
+                MetaType::InputObject(InputObjectMeta {
+                    ref name,
+                    ref input_fields,
+                    ..
+                }) => {
+                    for input_field in input_fields {
+                        if !is_input(&input_field.arg_type) {
+                            errors.push(SchemaTypeError::NonInputFieldType {
+                                type_name: name.to_string(),
+                                field_name: input_field.name.clone(),
+                                field_type: input_field.arg_type.to_string(),
+                            });
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        errors
+    }
+
+    /// Arguments sharing their name with a previous argument of the same field.
+    fn duplicate_argument_names(&self) -> Vec<SchemaTypeError> {
+        let mut errors = vec![];
+
+        let mut meta_types = self.types.values().collect::<Vec<_>>();
+        meta_types.sort_by_key(|t| t.name());
+
+        for meta_type in meta_types {
+            let (type_name, fields) = match *meta_type {
+                MetaType::Object(ObjectMeta {
+                    ref name,
+                    ref fields,
+                    ..
+                })
+                | MetaType::Interface(InterfaceMeta {
+                    ref name,
+                    ref fields,
+                    ..
+                }) => (name, fields),
+                _ => continue,
+            };
+
+            for field in fields {
+                let mut seen = FnvHashSet::default();
+                for arg in field.arguments.iter().flatten() {
+                    if !seen.insert(arg.name.as_str()) {
+                        errors.push(SchemaTypeError::DuplicateArgumentName {
+                            type_name: type_name.to_string(),
+                            field_name: field.name.clone(),
+                            argument_name: arg.name.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+
+    /// The input objects referenced by the non-null, non-list fields of the
+    /// input object `name`.
+    fn required_input_fields(&self, name: &str) -> Vec<&str> {
+        match self.types.get(name) {
+            Some(MetaType::InputObject(InputObjectMeta {
+                ref input_fields, ..
+            })) => input_fields
+                .iter()
+                .filter_map(|f| match f.arg_type {
+                    Type::NonNullNamed(ref n) => Some(n.as_ref()),
+                    _ => None,
+                })
+                .filter(|n| match self.types.get(*n) {
+                    Some(MetaType::InputObject(_)) => true,
+                    _ => false,
+                })
+                .collect(),
+            _ => vec![],
+        }
+    }
+
+    /// Cycles of input objects referencing each other through non-null,
+    /// non-list fields, each reported once starting from its first type name.
+    fn input_object_cycles(&self) -> Vec<SchemaTypeError> {
+        let mut names = self
+            .types
+            .values()
+            .filter_map(|t| match *t {
+                MetaType::InputObject(InputObjectMeta { ref name, .. }) => Some(name.as_ref()),
+                _ => None,
+            })
+            .collect::<Vec<_>>();
+        names.sort();
+
+        let mut errors = vec![];
+        for &start in &names {
+            // Only walk through types sorted after `start`, so that every cycle
+            // is found from its first type name.
+            let mut stack = vec![self.required_input_fields(start).into_iter()];
+            let mut path = vec![start];
+            while let Some(next) = stack.last_mut() {
+                match next.next() {
+                    Some(name) if name == start => {
+                        errors.push(SchemaTypeError::InputObjectCycle {
+                            type_names: path.iter().map(|n| (*n).to_owned()).collect(),
+                        });
+                    }
+                    Some(name) if name > start && !path.contains(&name) => {
+                        path.push(name);
+                        stack.push(self.required_input_fields(name).into_iter());
+                    }
+                    Some(_) => {}
+                    None => {
+                        stack.pop();
+                        path.pop();
+                    }
+                }
+            }
+        }
+
+        errors
+    }
+
     /// Add a directive like `skip` or `include`.
     pub fn add_directive(&mut self, directive: DirectiveType<'a, S>) {
         self.directives.insert(directive.name.clone(), directive);
@@ -462,6 +1397,7 @@ impl<'a, S> DirectiveType<'a, S>
 where
     S: ScalarValue + 'a,
 {
+    /// Create a directive usable at `locations` and accepting `arguments`
     pub fn new(
         name: &str,
         locations: &[DirectiveLocation],
@@ -505,12 +1441,237 @@ where
         )
     }
 
+    fn new_cache_control(registry: &mut Registry<'a, S>) -> DirectiveType<'a, S>
+    where
+        S: ScalarValue,
+    {
+        Self::new(
+            "cacheControl",
+            &[DirectiveLocation::Query, DirectiveLocation::Field],
+            &[registry.arg::<i32>("maxAge", &())],
+        )
+    }
+
+    /// Set the description of the directive
+    ///
+    /// This overwrites the description if any was previously set.
     pub fn description(mut self, description: &str) -> DirectiveType<'a, S> {
         self.description = Some(description.to_owned());
         self
     }
 }
 
+/// Error returned when building a schema whose types are not well-formed.
+///
+/// See [`SchemaType::new_validated`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct SchemaBuildError {
+    errors: Vec<SchemaTypeError>,
+}
+
+impl SchemaBuildError {
+    /// The problems found in the types of the schema.
+    pub fn errors(&self) -> &[SchemaTypeError] {
+        &self.errors
+    }
+}
+
+impl fmt::Display for SchemaBuildError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Invalid schema:")?;
+        for error in &self.errors {
+            write!(f, "\n- {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for SchemaBuildError {}
+
+/// A problem found in the types of a schema.
+#[derive(Debug, Clone, PartialEq)]
+pub enum SchemaTypeError {
+    /// A union member or an interface implemented by an object is not part
+    /// of the schema.
+    DanglingTypeReference {
+        /// The union or object type.
+        type_name: String,
+        /// The name of the missing type.
+        referenced_type: String,
+    },
+    /// Several Rust types build a type with the same name.
+    DuplicateTypeName {
+        /// The shared type name.
+        type_name: String,
+        /// The Rust types using this name.
+        rust_types: Vec<String>,
+    },
+    /// The type of a field or of an input field is not part of the schema.
+    UnknownFieldType {
+        /// The type of the field.
+        type_name: String,
+        /// The name of the field.
+        field_name: String,
+        /// The missing type of the field.
+        field_type: String,
+    },
+    /// Input objects reference each other through non-null fields, so that no
+    /// value of them can be written.
+    InputObjectCycle {
+        /// The input objects in the cycle, in the order they reference each
+        /// other.
+        type_names: Vec<String>,
+    },
+    /// The type of a field argument is not part of the schema.
+    UnknownArgumentType {
+        /// The type of the field.
+        type_name: String,
+        /// The name of the field.
+        field_name: String,
+        /// The name of the argument.
+        argument_name: String,
+        /// The missing type of the argument.
+        argument_type: String,
+    },
+    /// The type of a field is not an output type, e.g. an input object.
+    NonOutputFieldType {
+        /// The type of the field.
+        type_name: String,
+        /// The name of the field.
+        field_name: String,
+        /// The type of the field, which is not an output type.
+        field_type: String,
+    },
+    /// The type of a field argument is not an input type, e.g. an object.
+    NonInputArgumentType {
+        /// The type of the field.
+        type_name: String,
+        /// The name of the field.
+        field_name: String,
+        /// The name of the argument.
+        argument_name: String,
+        /// The type of the argument, which is not an input type.
+        argument_type: String,
+    },
+    /// The type of an input field is not an input type, e.g. an object.
+    NonInputFieldType {
+        /// The input object type.
+        type_name: String,
+        /// The name of the input field.
+        field_name: String,
+        /// The type of the input field, which is not an input type.
+        field_type: String,
+    },
+    /// A type other than an input object is flattened into arguments.
+    UnflattenableType {
+        /// The flattened type.
+        type_name: String,
+    },
+    /// A field has several arguments of the same name.
+    DuplicateArgumentName {
+        /// The type of the field.
+        type_name: String,
+        /// The name of the field.
+        field_name: String,
+        /// The name shared by the arguments.
+        argument_name: String,
+    },
+}
+
+impl fmt::Display for SchemaTypeError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SchemaTypeError::DanglingTypeReference {
+                type_name,
+                referenced_type,
+            } => write!(
+                f,
+                "Type \"{}\" references type \"{}\", which is not in the schema",
+                type_name, referenced_type,
+            ),
+            SchemaTypeError::DuplicateTypeName {
+                type_name,
+                rust_types,
+            } => write!(
+                f,
+                "Type name \"{}\" is used by several types: {}",
+                type_name,
+                rust_types.join(", "),
+            ),
+            SchemaTypeError::UnknownFieldType {
+                type_name,
+                field_name,
+                field_type,
+            } => write!(
+                f,
+                "Field \"{}.{}\" has type \"{}\", which is not in the schema",
+                type_name, field_name, field_type,
+            ),
+            SchemaTypeError::UnknownArgumentType {
+                type_name,
+                field_name,
+                argument_name,
+                argument_type,
+            } => write!(
+                f,
+                "Argument \"{}\" of field \"{}.{}\" has type \"{}\", \
+                 which is not in the schema",
+                argument_name, type_name, field_name, argument_type,
+            ),
+            SchemaTypeError::InputObjectCycle { type_names } => write!(
+                f,
+                "Input objects {} -> {} reference each other through non-null fields",
+                type_names.join(" -> "),
+                type_names[0],
+            ),
+            SchemaTypeError::NonOutputFieldType {
+                type_name,
+                field_name,
+                field_type,
+            } => write!(
+                f,
+                "Field \"{}.{}\" has type \"{}\", which is not an output type",
+                type_name, field_name, field_type,
+            ),
+            SchemaTypeError::NonInputArgumentType {
+                type_name,
+                field_name,
+                argument_name,
+                argument_type,
+            } => write!(
+                f,
+                "Argument \"{}\" of field \"{}.{}\" has type \"{}\", \
+                 which is not an input type",
+                argument_name, type_name, field_name, argument_type,
+            ),
+            SchemaTypeError::NonInputFieldType {
+                type_name,
+                field_name,
+                field_type,
+            } => write!(
+                f,
+                "Input field \"{}.{}\" has type \"{}\", which is not an input type",
+                type_name, field_name, field_type,
+            ),
+            SchemaTypeError::UnflattenableType { type_name } => write!(
+                f,
+                "Type \"{}\" is flattened into arguments, but only input objects \
+                 can be flattened",
+                type_name,
+            ),
+            SchemaTypeError::DuplicateArgumentName {
+                type_name,
+                field_name,
+                argument_name,
+            } => write!(
+                f,
+                "Field \"{}.{}\" has several arguments named \"{}\"",
+                type_name, field_name, argument_name,
+            ),
+        }
+    }
+}
+
 impl fmt::Display for DirectiveLocation {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(match *self {
@@ -534,3 +1695,317 @@ impl<'a, S> fmt::Display for TypeType<'a, S> {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use juniper_codegen::GraphQLInputObjectInternal as GraphQLInputObject;
+
+    use crate::{
+        ast::{FromInputValue, InputValue, Type},
+        executor::Registry,
+        schema::{
+            meta::{Argument, Field, MetaType},
+            model::{RootNode, SchemaTypeError},
+        },
+        tests::{model::Database, schema::Query as StarWarsQuery},
+        types::{
+            base::GraphQLType,
+            scalars::{EmptyMutation, EmptySubscription},
+        },
+        value::DefaultScalarValue,
+    };
+
+    struct Foo;
+
+    #[crate::graphql_object_internal]
+    impl Foo {
+        fn bar() -> i32 {
+            1
+        }
+    }
+
+    impl FromInputValue for Foo {
+        fn from_input_value(_: &InputValue) -> Option<Self> {
+            Some(Foo)
+        }
+    }
+
+    struct Query;
+
+    #[crate::graphql_object_internal]
+    impl Query {
+        fn with_foo(foo: Foo) -> i32 {
+            drop(foo);
+            1
+        }
+    }
+
+    #[test]
+    fn object_as_argument_fails_validated_schema_build() {
+        let error = RootNode::new_validated(
+            Query,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        )
+        .err()
+        .expect("schema build should fail");
+
+        assert_eq!(
+            error.errors(),
+            [SchemaTypeError::NonInputArgumentType {
+                type_name: "Query".into(),
+                field_name: "withFoo".into(),
+                argument_name: "foo".into(),
+                argument_type: "Foo!".into(),
+            }],
+        );
+        assert_eq!(
+            error.to_string(),
+            "Invalid schema:\n\
+             - Argument \"foo\" of field \"Query.withFoo\" has type \"Foo!\", \
+             which is not an input type",
+        );
+    }
+
+    struct DanglingUnion;
+
+    impl GraphQLType for DanglingUnion {
+        type Context = ();
+        type TypeInfo = ();
+
+        fn name(_: &()) -> Option<&str> {
+            Some("DanglingUnion")
+        }
+
+        fn meta<'r>(_: &(), registry: &mut Registry<'r>) -> MetaType<'r>
+        where
+            DefaultScalarValue: 'r,
+        {
+            let types = &[
+                registry.get_type::<Foo>(&()),
+                Type::NonNullNamed("Missing".into()),
+            ];
+            registry.build_union_type::<Self>(&(), types).into_meta()
+        }
+    }
+
+    struct DanglingQuery;
+
+    impl GraphQLType for DanglingQuery {
+        type Context = ();
+        type TypeInfo = ();
+
+        fn name(_: &()) -> Option<&str> {
+            Some("DanglingQuery")
+        }
+
+        fn meta<'r>(_: &(), registry: &mut Registry<'r>) -> MetaType<'r>
+        where
+            DefaultScalarValue: 'r,
+        {
+            let fields = &[
+                registry.field::<DanglingUnion>("union", &()),
+                Field {
+                    field_type: Type::List(Box::new(Type::Named("MissingObject".into()))),
+                    ..registry.field::<i32>("missing", &())
+                }
+                .argument(Argument {
+                    arg_type: Type::Named("MissingInput".into()),
+                    ..registry.arg::<i32>("filter", &())
+                }),
+            ];
+            registry.build_object_type::<Self>(&(), fields).into_meta()
+        }
+    }
+
+    #[test]
+    fn dangling_types_fail_validated_schema_build() {
+        let error = RootNode::new_validated(
+            DanglingQuery,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        )
+        .err()
+        .expect("schema build should fail");
+
+        assert_eq!(
+            error.to_string(),
+            "Invalid schema:\n\
+             - Field \"DanglingQuery.missing\" has type \"[MissingObject]\", \
+             which is not in the schema\n\
+             - Argument \"filter\" of field \"DanglingQuery.missing\" has type \
+             \"MissingInput\", which is not in the schema\n\
+             - Type \"DanglingUnion\" references type \"Missing\", which is not in the schema",
+        );
+        assert_eq!(
+            error.errors()[2],
+            SchemaTypeError::DanglingTypeReference {
+                type_name: "DanglingUnion".into(),
+                referenced_type: "Missing".into(),
+            },
+        );
+    }
+
+    struct Thing;
+
+    #[crate::graphql_object_internal]
+    impl Thing {
+        fn id() -> i32 {
+            1
+        }
+    }
+
+    struct OtherThing;
+
+    #[crate::graphql_object_internal(name = "Thing")]
+    impl OtherThing {
+        fn name() -> String {
+            "other".into()
+        }
+    }
+
+    struct DuplicateQuery;
+
+    #[crate::graphql_object_internal]
+    impl DuplicateQuery {
+        fn thing() -> Thing {
+            Thing
+        }
+
+        fn other_thing() -> OtherThing {
+            OtherThing
+        }
+    }
+
+    #[test]
+    fn duplicate_type_names_fail_validated_schema_build() {
+        let error = RootNode::new_validated(
+            DuplicateQuery,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        )
+        .err()
+        .expect("schema build should fail");
+
+        assert_eq!(
+            error.errors(),
+            &[SchemaTypeError::DuplicateTypeName {
+                type_name: "Thing".into(),
+                rust_types: vec![
+                    std::any::type_name::<Thing>().into(),
+                    std::any::type_name::<OtherThing>().into(),
+                ],
+            }],
+        );
+    }
+
+    #[derive(GraphQLInputObject)]
+    struct Egg {
+        chicken: Box<Chicken>,
+    }
+
+    #[derive(GraphQLInputObject)]
+    struct Chicken {
+        egg: Box<Egg>,
+        parent: Option<Box<Chicken>>,
+    }
+
+    struct CycleQuery;
+
+    #[crate::graphql_object_internal]
+    impl CycleQuery {
+        fn hatch(egg: Egg) -> bool {
+            let _ = egg;
+            true
+        }
+    }
+
+    #[test]
+    fn required_input_object_cycles_fail_validated_schema_build() {
+        let error = RootNode::new_validated(
+            CycleQuery,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        )
+        .err()
+        .expect("schema build should fail");
+
+        assert_eq!(
+            error.errors(),
+            &[SchemaTypeError::InputObjectCycle {
+                type_names: vec!["Chicken".into(), "Egg".into()],
+            }],
+        );
+        assert_eq!(
+            error.to_string(),
+            "Invalid schema:\n\
+             - Input objects Chicken -> Egg -> Chicken reference each other \
+             through non-null fields",
+        );
+    }
+
+    #[derive(GraphQLInputObject)]
+    struct Page {
+        limit: i32,
+    }
+
+    struct FlattenQuery;
+
+    #[crate::graphql_object_internal]
+    impl FlattenQuery {
+        #[graphql(arguments(page(flatten)))]
+        fn things(limit: i32, page: Page) -> i32 {
+            limit + page.limit
+        }
+
+        #[graphql(arguments(limit(flatten)))]
+        fn others(limit: i32) -> i32 {
+            limit
+        }
+    }
+
+    #[test]
+    fn bad_flattened_args_fail_validated_schema_build() {
+        let error = RootNode::new_validated(
+            FlattenQuery,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        )
+        .err()
+        .expect("schema build should fail");
+
+        assert_eq!(
+            error.errors(),
+            &[
+                SchemaTypeError::UnflattenableType {
+                    type_name: "Int".into(),
+                },
+                SchemaTypeError::DuplicateArgumentName {
+                    type_name: "FlattenQuery".into(),
+                    field_name: "things".into(),
+                    argument_name: "limit".into(),
+                },
+            ],
+        );
+        assert_eq!(
+            error.to_string(),
+            "Invalid schema:\n\
+             - Type \"Int\" is flattened into arguments, but only input objects \
+             can be flattened\n\
+             - Field \"FlattenQuery.things\" has several arguments named \"limit\"",
+        );
+    }
+
+    #[test]
+    fn well_formed_schema_passes_validated_schema_build() {
+        let schema = RootNode::new_validated(
+            StarWarsQuery,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+
+        if let Err(error) = schema {
+            panic!("{}", error);
+        }
+    }
+}
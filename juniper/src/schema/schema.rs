@@ -56,6 +56,10 @@ where
         }
     }
 
+    fn concrete_type_name(&self, context: &Self::Context, info: &Self::TypeInfo) -> String {
+        self.query_type.concrete_type_name(context, info)
+    }
+
     fn resolve(
         &self,
         info: &Self::TypeInfo,
@@ -0,0 +1,299 @@
+//! Printing of a schema in the GraphQL schema definition language (SDL)
+
+use std::fmt::Write as _;
+
+use crate::{
+    schema::{
+        meta::{
+            Argument, DeprecationStatus, EnumMeta, Field, InputObjectMeta, InterfaceMeta, MetaType,
+            ObjectMeta, ScalarMeta, UnionMeta,
+        },
+        model::{DirectiveLocation, DirectiveType, SchemaType},
+    },
+    value::ScalarValue,
+};
+
+const BUILTIN_SCALARS: &[&str] = &["Boolean", "Float", "ID", "Int", "String"];
+pub(super) const EMPTY_ROOT_TYPES: &[&str] = &["_EmptyMutation", "_EmptySubscription"];
+const BUILTIN_DIRECTIVES: &[&str] = &["include", "skip"];
+
+impl<'a, S> SchemaType<'a, S>
+where
+    S: ScalarValue + 'a,
+{
+    /// Print the schema in the GraphQL schema definition language.
+    ///
+    /// Built-in scalars and directives, introspection types, and the types of
+    /// empty mutation and subscription roots are omitted. Types and directives
+    /// are sorted by name.
+    pub fn as_sdl(&self) -> String {
+        let mut definitions = vec![];
+
+        if let Some(schema) = self.schema_definition() {
+            definitions.push(schema);
+        }
+
+        let mut directives = self
+            .directive_list()
+            .into_iter()
+            .filter(|d| !BUILTIN_DIRECTIVES.contains(&d.name.as_str()))
+            .collect::<Vec<_>>();
+        directives.sort_by(|a, b| a.name.cmp(&b.name));
+        definitions.extend(directives.into_iter().map(directive_definition));
+
+        let mut types = self
+            .concrete_type_list()
+            .into_iter()
+            .filter(|t| {
+                let name = t.name().unwrap_or("");
+                !name.starts_with("__")
+                    && !BUILTIN_SCALARS.contains(&name)
+                    && !EMPTY_ROOT_TYPES.contains(&name)
+            })
+            .collect::<Vec<_>>();
+        types.sort_by(|a, b| a.name().cmp(&b.name()));
+        definitions.extend(types.into_iter().filter_map(type_definition));
+
+        definitions.join("\n\n") + "\n"
+    }
+
+    fn schema_definition(&self) -> Option<String> {
+        let roots = [
+            ("query", self.concrete_query_type().name(), "Query"),
+            (
+                "mutation",
+                self.concrete_mutation_type().and_then(MetaType::name),
+                "Mutation",
+            ),
+            (
+                "subscription",
+                self.concrete_subscription_type().and_then(MetaType::name),
+                "Subscription",
+            ),
+        ];
+
+        if roots
+            .iter()
+            .all(|&(_, name, default)| name.map_or(true, |n| n == default))
+        {
+            return None;
+        }
+
+        let mut out = "schema {\n".to_owned();
+        for &(operation, name, _) in &roots {
+            if let Some(name) = name {
+                writeln!(out, "  {}: {}", operation, name).unwrap();
+            }
+        }
+        out.push('}');
+        Some(out)
+    }
+}
+
+fn type_definition<S>(meta_type: &MetaType<S>) -> Option<String>
+where
+    S: ScalarValue,
+{
+    let mut out = String::new();
+
+    match *meta_type {
+        MetaType::Scalar(ScalarMeta {
+            ref name,
+            ref description,
+            ..
+        }) => {
+            write_description(&mut out, description, "");
+            write!(out, "scalar {}", name).unwrap();
+        }
+        MetaType::Object(ObjectMeta {
+            ref name,
+            ref description,
+            ref fields,
+            ref interface_names,
+        }) => {
+            write_description(&mut out, description, "");
+            write!(out, "type {}", name).unwrap();
+            if !interface_names.is_empty() {
+                write!(out, " implements {}", interface_names.join(" & ")).unwrap();
+            }
+            write_fields(&mut out, fields);
+        }
+        MetaType::Interface(InterfaceMeta {
+            ref name,
+            ref description,
+            ref fields,
+        }) => {
+            write_description(&mut out, description, "");
+            write!(out, "interface {}", name).unwrap();
+            write_fields(&mut out, fields);
+        }
+        MetaType::Union(UnionMeta {
+            ref name,
+            ref description,
+            ref of_type_names,
+        }) => {
+            write_description(&mut out, description, "");
+            write!(out, "union {} = {}", name, of_type_names.join(" | ")).unwrap();
+        }
+        MetaType::Enum(EnumMeta {
+            ref name,
+            ref description,
+            ref values,
+            ..
+        }) => {
+            write_description(&mut out, description, "");
+            writeln!(out, "enum {} {{", name).unwrap();
+            for value in values {
+                write_description(&mut out, &value.description, "  ");
+                write!(out, "  {}", value.name).unwrap();
+                write_deprecation(&mut out, &value.deprecation_status);
+                out.push('\n');
+            }
+            out.push('}');
+        }
+        MetaType::InputObject(InputObjectMeta {
+            ref name,
+            ref description,
+            ref input_fields,
+            ..
+        }) => {
+            write_description(&mut out, description, "");
+            writeln!(out, "input {} {{", name).unwrap();
+            for input_field in input_fields {
+                write_description(&mut out, &input_field.description, "  ");
+                writeln!(out, "  {}", input_value(input_field)).unwrap();
+            }
+            out.push('}');
+        }
+        MetaType::List(_) | MetaType::Nullable(_) | MetaType::Placeholder(_) => return None,
+    }
+
+    Some(out)
+}
+
+fn directive_definition<S>(directive: &DirectiveType<S>) -> String
+where
+    S: ScalarValue,
+{
+    let mut out = String::new();
+
+    write_description(&mut out, &directive.description, "");
+    write!(out, "directive @{}", directive.name).unwrap();
+    write_arguments(&mut out, &directive.arguments, "");
+
+    let locations = directive
+        .locations
+        .iter()
+        .map(|l| match *l {
+            DirectiveLocation::Query => "QUERY",
+            DirectiveLocation::Mutation => "MUTATION",
+            DirectiveLocation::Subscription => "SUBSCRIPTION",
+            DirectiveLocation::Field => "FIELD",
+            DirectiveLocation::FragmentDefinition => "FRAGMENT_DEFINITION",
+            DirectiveLocation::FragmentSpread => "FRAGMENT_SPREAD",
+            DirectiveLocation::InlineFragment => "INLINE_FRAGMENT",
+        })
+        .collect::<Vec<_>>();
+    write!(out, " on {}", locations.join(" | ")).unwrap();
+
+    out
+}
+
+fn write_fields<S>(out: &mut String, fields: &[Field<S>])
+where
+    S: ScalarValue,
+{
+    out.push_str(" {\n");
+    for field in fields.iter().filter(|f| !f.name.starts_with("__")) {
+        write_description(out, &field.description, "  ");
+        write!(out, "  {}", field.name).unwrap();
+        if let Some(ref arguments) = field.arguments {
+            write_arguments(out, arguments, "  ");
+        }
+        write!(out, ": {}", field.field_type).unwrap();
+        write_deprecation(out, &field.deprecation_status);
+        out.push('\n');
+    }
+    out.push('}');
+}
+
+fn write_arguments<S>(out: &mut String, arguments: &[Argument<S>], indent: &str)
+where
+    S: ScalarValue,
+{
+    if arguments.is_empty() {
+        return;
+    }
+
+    if arguments.iter().all(|a| a.description.is_none()) {
+        let arguments = arguments.iter().map(input_value).collect::<Vec<_>>();
+        write!(out, "({})", arguments.join(", ")).unwrap();
+        return;
+    }
+
+    let arg_indent = format!("{}  ", indent);
+    out.push_str("(\n");
+    for argument in arguments {
+        write_description(out, &argument.description, &arg_indent);
+        writeln!(out, "{}{}", arg_indent, input_value(argument)).unwrap();
+    }
+    write!(out, "{})", indent).unwrap();
+}
+
+fn input_value<S>(argument: &Argument<S>) -> String
+where
+    S: ScalarValue,
+{
+    match argument.default_value {
+        Some(ref default) => format!("{}: {} = {}", argument.name, argument.arg_type, default),
+        None => format!("{}: {}", argument.name, argument.arg_type),
+    }
+}
+
+fn write_deprecation(out: &mut String, status: &DeprecationStatus) {
+    match *status {
+        DeprecationStatus::Current => {}
+        DeprecationStatus::Deprecated(None) => out.push_str(" @deprecated"),
+        DeprecationStatus::Deprecated(Some(ref reason)) => {
+            write!(out, " @deprecated(reason: {})", string_literal(reason)).unwrap()
+        }
+    }
+}
+
+fn write_description(out: &mut String, description: &Option<String>, indent: &str) {
+    let description = match *description {
+        Some(ref description) => description,
+        None => return,
+    };
+
+    if description.contains('\n') {
+        writeln!(out, "{}\"\"\"", indent).unwrap();
+        for line in description.lines() {
+            if line.is_empty() {
+                out.push('\n');
+            } else {
+                writeln!(out, "{}{}", indent, line.replace("\"\"\"", "\\\"\"\"")).unwrap();
+            }
+        }
+        writeln!(out, "{}\"\"\"", indent).unwrap();
+    } else {
+        writeln!(out, "{}{}", indent, string_literal(description)).unwrap();
+    }
+}
+
+fn string_literal(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
@@ -0,0 +1,639 @@
+use std::{error::Error, fmt};
+
+use crate::{
+    executor::{ExecutionResult, Executor, FieldError, Registry, ValuesStream},
+    schema::{
+        meta::{Field, MetaType, ObjectMeta},
+        model::{RootNode, SchemaType},
+    },
+    types::{
+        async_await::GraphQLTypeAsync,
+        base::{Arguments, GraphQLType},
+        subscriptions::GraphQLSubscriptionType,
+    },
+    value::{DefaultScalarValue, ScalarValue, Value},
+    BoxFuture,
+};
+
+/// Composes several schemas sharing a context type into one
+///
+/// The root fields of the query, mutation and subscription types of every
+/// stitched schema are merged into the root types of the combined schema,
+/// and each field resolves through the schema defining it.
+///
+/// ```ignore
+/// let schema = SchemaStitcher::new(users_schema)
+///     .stitch(billing_schema)?
+///     .stitch(search_schema)?
+///     .into_root_node();
+/// ```
+///
+/// Stitching fails if two schemas define the same root field, or the same
+/// type other than their root types and scalars. The settings of the
+/// stitched root nodes, like limits and hooks, are not carried over.
+pub struct SchemaStitcher<'a, QueryT, MutationT, SubscriptionT, S = DefaultScalarValue>
+where
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S>,
+    SubscriptionT: GraphQLType<S>,
+    S: ScalarValue,
+{
+    root: RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+}
+
+impl<'a, QueryT, MutationT, SubscriptionT, S> fmt::Debug
+    for SchemaStitcher<'a, QueryT, MutationT, SubscriptionT, S>
+where
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S>,
+    SubscriptionT: GraphQLType<S>,
+    S: ScalarValue,
+{
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("SchemaStitcher")
+            .field("schema", &self.root.schema)
+            .finish()
+    }
+}
+
+impl<'a, QueryT, MutationT, SubscriptionT, S>
+    SchemaStitcher<'a, QueryT, MutationT, SubscriptionT, S>
+where
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S, Context = QueryT::Context>,
+    SubscriptionT: GraphQLType<S, Context = QueryT::Context>,
+    S: ScalarValue + 'a,
+{
+    /// Start from the schema of `root`
+    pub fn new(root: RootNode<'a, QueryT, MutationT, SubscriptionT, S>) -> Self {
+        SchemaStitcher { root }
+    }
+
+    /// Merge the root types of `other` into the ones stitched so far
+    pub fn stitch<OtherQueryT, OtherMutationT, OtherSubscriptionT>(
+        self,
+        other: RootNode<'a, OtherQueryT, OtherMutationT, OtherSubscriptionT, S>,
+    ) -> Result<
+        SchemaStitcher<
+            'a,
+            Stitched<QueryT, OtherQueryT>,
+            Stitched<MutationT, OtherMutationT>,
+            Stitched<SubscriptionT, OtherSubscriptionT>,
+            S,
+        >,
+        StitchError,
+    >
+    where
+        OtherQueryT: GraphQLType<S, Context = QueryT::Context>,
+        OtherMutationT: GraphQLType<S, Context = QueryT::Context>,
+        OtherSubscriptionT: GraphQLType<S, Context = QueryT::Context>,
+    {
+        let first = self.root;
+        let second = other;
+
+        check_types(
+            &first.schema,
+            &root_names(&first),
+            &second.schema,
+            &root_names(&second),
+        )?;
+
+        let query_fields = (
+            root_field_names(Some(first.schema.concrete_query_type()))?,
+            root_field_names(Some(second.schema.concrete_query_type()))?,
+        );
+        let mutation_fields = (
+            root_field_names(first.schema.concrete_mutation_type())?,
+            root_field_names(second.schema.concrete_mutation_type())?,
+        );
+        let subscription_fields = (
+            root_field_names(first.schema.concrete_subscription_type())?,
+            root_field_names(second.schema.concrete_subscription_type())?,
+        );
+
+        let query_info = StitchedInfo::new::<QueryT, OtherQueryT, S>(
+            first.query_info,
+            second.query_info,
+            query_fields,
+        )?;
+        let mutation_info = StitchedInfo::new::<MutationT, OtherMutationT, S>(
+            first.mutation_info,
+            second.mutation_info,
+            mutation_fields,
+        )?;
+        let subscription_info = StitchedInfo::new::<SubscriptionT, OtherSubscriptionT, S>(
+            first.subscription_info,
+            second.subscription_info,
+            subscription_fields,
+        )?;
+
+        Ok(SchemaStitcher {
+            root: RootNode::new_with_info(
+                Stitched {
+                    first: first.query_type,
+                    second: second.query_type,
+                },
+                Stitched {
+                    first: first.mutation_type,
+                    second: second.mutation_type,
+                },
+                Stitched {
+                    first: first.subscription_type,
+                    second: second.subscription_type,
+                },
+                query_info,
+                mutation_info,
+                subscription_info,
+            ),
+        })
+    }
+
+    /// The root node of the stitched schema
+    pub fn into_root_node(self) -> RootNode<'a, QueryT, MutationT, SubscriptionT, S> {
+        self.root
+    }
+}
+
+/// The names of the query, mutation and subscription types of `root`
+fn root_names<'r, QueryT, MutationT, SubscriptionT, S>(
+    root: &'r RootNode<QueryT, MutationT, SubscriptionT, S>,
+) -> Vec<&'r str>
+where
+    QueryT: GraphQLType<S>,
+    MutationT: GraphQLType<S>,
+    SubscriptionT: GraphQLType<S>,
+    S: ScalarValue,
+{
+    vec![
+        QueryT::name(&root.query_info),
+        MutationT::name(&root.mutation_info),
+        SubscriptionT::name(&root.subscription_info),
+    ]
+    .into_iter()
+    .flatten()
+    .collect()
+}
+
+/// The fields of a root type, without the introspection ones
+///
+/// Only objects can be stitched, so other root types are rejected.
+fn root_field_names<S>(root: Option<&MetaType<S>>) -> Result<Vec<String>, StitchError> {
+    match root {
+        Some(MetaType::Object(ObjectMeta { fields, .. })) => Ok(fields
+            .iter()
+            .filter(|f| !f.name.starts_with("__"))
+            .map(|f| f.name.clone())
+            .collect()),
+        Some(meta) => Err(StitchError::NotAnObject {
+            type_name: meta.name().unwrap_or_default().to_owned(),
+        }),
+        None => Ok(vec![]),
+    }
+}
+
+/// Rejects the types defined by both schemas, unless they are root types of
+/// both or scalars in both
+fn check_types<S>(
+    first: &SchemaType<S>,
+    first_roots: &[&str],
+    second: &SchemaType<S>,
+    second_roots: &[&str],
+) -> Result<(), StitchError> {
+    let mut duplicates = first
+        .concrete_type_list()
+        .into_iter()
+        .filter_map(|first_type| {
+            let name = first_type.name()?;
+            let second_type = second.concrete_type_by_name(name)?;
+            match (first_type, second_type) {
+                _ if name.starts_with("__") => None,
+                _ if first_roots.contains(&name) && second_roots.contains(&name) => None,
+                (MetaType::Scalar(_), MetaType::Scalar(_)) => None,
+                _ => Some(name),
+            }
+        })
+        .collect::<Vec<_>>();
+    duplicates.sort();
+
+    match duplicates.first() {
+        Some(name) => Err(StitchError::DuplicateType {
+            type_name: (*name).to_owned(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/// Root type merging the fields of two root types
+///
+/// Built by [`SchemaStitcher`](struct.SchemaStitcher.html).
+#[derive(Debug)]
+pub struct Stitched<A, B> {
+    first: A,
+    second: B,
+}
+
+/// Type info of a [`Stitched`](struct.Stitched.html) root type
+#[derive(Debug)]
+pub struct StitchedInfo<A, B> {
+    name: String,
+    first: A,
+    second: B,
+    first_fields: Vec<String>,
+}
+
+impl<A, B> StitchedInfo<A, B> {
+    /// Names the merged type after the first root type, unless only the
+    /// second one has fields, like when stitching a mutation onto a schema
+    /// without one
+    fn new<FirstT, SecondT, S>(
+        first: A,
+        second: B,
+        (first_fields, second_fields): (Vec<String>, Vec<String>),
+    ) -> Result<Self, StitchError>
+    where
+        FirstT: GraphQLType<S, TypeInfo = A>,
+        SecondT: GraphQLType<S, TypeInfo = B>,
+        S: ScalarValue,
+    {
+        let name = if first_fields.is_empty() && !second_fields.is_empty() {
+            SecondT::name(&second)
+        } else {
+            FirstT::name(&first)
+        }
+        .expect("Root types must be named")
+        .to_owned();
+
+        if let Some(field_name) = first_fields.iter().find(|f| second_fields.contains(f)) {
+            return Err(StitchError::DuplicateField {
+                type_name: name,
+                field_name: field_name.clone(),
+            });
+        }
+
+        Ok(StitchedInfo {
+            name,
+            first,
+            second,
+            first_fields,
+        })
+    }
+
+    fn resolves_in_first(&self, field_name: &str) -> bool {
+        self.first_fields.iter().any(|f| f == field_name)
+    }
+}
+
+/// The fields of a root type, without `__typename`
+///
+/// [`SchemaStitcher::stitch`](struct.SchemaStitcher.html#method.stitch)
+/// already rejected root types which aren't objects, so they have no fields
+/// here.
+fn fields_of<S>(meta: MetaType<S>) -> Vec<Field<S>> {
+    match meta {
+        MetaType::Object(ObjectMeta { fields, .. }) => fields
+            .into_iter()
+            .filter(|f| !f.name.starts_with("__"))
+            .collect(),
+        _ => vec![],
+    }
+}
+
+impl<A, B, S> GraphQLType<S> for Stitched<A, B>
+where
+    A: GraphQLType<S>,
+    B: GraphQLType<S, Context = A::Context>,
+    S: ScalarValue,
+{
+    type Context = A::Context;
+    type TypeInfo = StitchedInfo<A::TypeInfo, B::TypeInfo>;
+
+    fn name(info: &Self::TypeInfo) -> Option<&str> {
+        Some(&info.name)
+    }
+
+    fn meta<'r>(info: &Self::TypeInfo, registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        let mut fields = fields_of(A::meta(&info.first, registry));
+        fields.extend(fields_of(B::meta(&info.second, registry)));
+        registry
+            .build_object_type::<Self>(info, &fields)
+            .into_meta()
+    }
+
+    fn resolve_field(
+        &self,
+        info: &Self::TypeInfo,
+        field_name: &str,
+        arguments: &Arguments<S>,
+        executor: &Executor<Self::Context, S>,
+    ) -> ExecutionResult<S> {
+        if info.resolves_in_first(field_name) {
+            self.first
+                .resolve_field(&info.first, field_name, arguments, executor)
+        } else {
+            self.second
+                .resolve_field(&info.second, field_name, arguments, executor)
+        }
+    }
+
+    fn concrete_type_name(&self, _: &Self::Context, info: &Self::TypeInfo) -> String {
+        info.name.clone()
+    }
+}
+
+impl<A, B, S> GraphQLTypeAsync<S> for Stitched<A, B>
+where
+    A: GraphQLTypeAsync<S>,
+    B: GraphQLTypeAsync<S, Context = A::Context>,
+    A::Context: Send + Sync,
+    A::TypeInfo: Send + Sync,
+    B::TypeInfo: Send + Sync,
+    S: ScalarValue + Send + Sync,
+{
+    fn resolve_field_async<'a>(
+        &'a self,
+        info: &'a Self::TypeInfo,
+        field_name: &'a str,
+        arguments: &'a Arguments<S>,
+        executor: &'a Executor<Self::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        if info.resolves_in_first(field_name) {
+            self.first
+                .resolve_field_async(&info.first, field_name, arguments, executor)
+        } else {
+            self.second
+                .resolve_field_async(&info.second, field_name, arguments, executor)
+        }
+    }
+}
+
+impl<A, B, S> GraphQLSubscriptionType<S> for Stitched<A, B>
+where
+    A: GraphQLSubscriptionType<S>,
+    B: GraphQLSubscriptionType<S, Context = A::Context>,
+    A::Context: Send + Sync,
+    A::TypeInfo: Send + Sync,
+    B::TypeInfo: Send + Sync,
+    S: ScalarValue + Send + Sync,
+{
+    fn resolve_field_into_stream<'s, 'i, 'ft, 'args, 'e, 'ref_e, 'res, 'f>(
+        &'s self,
+        info: &'i Self::TypeInfo,
+        field_name: &'ft str,
+        arguments: Arguments<'args, S>,
+        executor: &'ref_e Executor<'ref_e, 'e, Self::Context, S>,
+    ) -> BoxFuture<'f, Result<Value<ValuesStream<'res, S>>, FieldError<S>>>
+    where
+        's: 'f,
+        'i: 'res,
+        'ft: 'f,
+        'args: 'f,
+        'ref_e: 'f,
+        'res: 'f,
+        'e: 'res,
+    {
+        if info.resolves_in_first(field_name) {
+            self.first
+                .resolve_field_into_stream(&info.first, field_name, arguments, executor)
+        } else {
+            self.second
+                .resolve_field_into_stream(&info.second, field_name, arguments, executor)
+        }
+    }
+}
+
+/// Error returned when stitching schemas which define the same names
+#[derive(Debug, Clone, PartialEq)]
+pub enum StitchError {
+    /// Both schemas define a type with this name, other than a root type or a
+    /// scalar.
+    DuplicateType {
+        /// The shared type name.
+        type_name: String,
+    },
+    /// Both schemas define a field with this name on the same root type.
+    DuplicateField {
+        /// The name of the root type.
+        type_name: String,
+        /// The shared field name.
+        field_name: String,
+    },
+    /// A root type of a stitched schema is not an object.
+    NotAnObject {
+        /// The name of the root type.
+        type_name: String,
+    },
+}
+
+impl fmt::Display for StitchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            StitchError::DuplicateType { type_name } => write!(
+                f,
+                "Type \"{}\" is defined by several stitched schemas",
+                type_name,
+            ),
+            StitchError::DuplicateField {
+                type_name,
+                field_name,
+            } => write!(
+                f,
+                "Field \"{}.{}\" is defined by several stitched schemas",
+                type_name, field_name,
+            ),
+            StitchError::NotAnObject { type_name } => write!(
+                f,
+                "Root type \"{}\" of a stitched schema is not an object",
+                type_name,
+            ),
+        }
+    }
+}
+
+impl Error for StitchError {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{
+        executor::Variables,
+        schema::{model::RootNode, stitching::StitchError},
+        types::scalars::{EmptyMutation, EmptySubscription},
+        value::Value,
+    };
+
+    use super::SchemaStitcher;
+
+    struct User {
+        name: String,
+    }
+
+    #[crate::graphql_object_internal]
+    impl User {
+        fn name(&self) -> &str {
+            &self.name
+        }
+    }
+
+    struct UsersQuery;
+
+    #[crate::graphql_object_internal(name = "Query")]
+    impl UsersQuery {
+        fn viewer() -> User {
+            User {
+                name: "ada".to_owned(),
+            }
+        }
+    }
+
+    struct Invoice {
+        total: i32,
+    }
+
+    #[crate::graphql_object_internal]
+    impl Invoice {
+        fn total(&self) -> i32 {
+            self.total
+        }
+    }
+
+    struct BillingQuery;
+
+    #[crate::graphql_object_internal(name = "Query")]
+    impl BillingQuery {
+        fn invoices() -> Vec<Invoice> {
+            vec![Invoice { total: 12 }, Invoice { total: 30 }]
+        }
+    }
+
+    struct OtherUser;
+
+    #[crate::graphql_object_internal(name = "User")]
+    impl OtherUser {
+        fn id() -> i32 {
+            1
+        }
+    }
+
+    struct OtherUsersQuery;
+
+    #[crate::graphql_object_internal(name = "Query")]
+    impl OtherUsersQuery {
+        fn owner() -> OtherUser {
+            OtherUser
+        }
+    }
+
+    struct OtherBillingQuery;
+
+    #[crate::graphql_object_internal(name = "Query")]
+    impl OtherBillingQuery {
+        fn invoices() -> i32 {
+            0
+        }
+    }
+
+    #[test]
+    fn resolves_fields_of_both_schemas() {
+        let schema = SchemaStitcher::new(RootNode::new(
+            UsersQuery,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        ))
+        .stitch(RootNode::new(
+            BillingQuery,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        ))
+        .unwrap()
+        .into_root_node();
+
+        let (res, errors) = crate::execute_sync(
+            "{ viewer { name } invoices { total } }",
+            None,
+            &schema,
+            &Variables::new(),
+            &(),
+        )
+        .unwrap();
+
+        assert_eq!(errors, []);
+        assert_eq!(
+            res,
+            Value::object(
+                vec![
+                    (
+                        "viewer",
+                        Value::object(vec![("name", Value::scalar("ada"))].into_iter().collect()),
+                    ),
+                    (
+                        "invoices",
+                        Value::list(vec![
+                            Value::object(vec![("total", Value::scalar(12))].into_iter().collect()),
+                            Value::object(vec![("total", Value::scalar(30))].into_iter().collect()),
+                        ]),
+                    ),
+                ]
+                .into_iter()
+                .collect()
+            )
+        );
+    }
+
+    #[test]
+    fn rejects_conflicting_names() {
+        let error = SchemaStitcher::new(RootNode::new(
+            UsersQuery,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        ))
+        .stitch(RootNode::new(
+            UsersQuery,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        ))
+        .unwrap_err();
+        assert_eq!(
+            error,
+            StitchError::DuplicateType {
+                type_name: "User".to_owned(),
+            }
+        );
+
+        let error = SchemaStitcher::new(RootNode::new(
+            BillingQuery,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        ))
+        .stitch(RootNode::new(
+            OtherBillingQuery,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        ))
+        .unwrap_err();
+        assert_eq!(
+            error,
+            StitchError::DuplicateField {
+                type_name: "Query".to_owned(),
+                field_name: "invoices".to_owned(),
+            }
+        );
+
+        let error = SchemaStitcher::new(RootNode::new(
+            UsersQuery,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        ))
+        .stitch(RootNode::new(
+            OtherUsersQuery,
+            EmptyMutation::<()>::new(),
+            EmptySubscription::<()>::new(),
+        ))
+        .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Type \"User\" is defined by several stitched schemas"
+        );
+    }
+}
@@ -0,0 +1,209 @@
+use std::{sync::Mutex, time::Duration};
+
+use crate::{
+    http::{self, GraphQLBatchEntry, GraphQLRequest},
+    schema::model::RootNode,
+    types::scalars::{EmptyMutation, EmptySubscription},
+    Context, FieldResult,
+};
+
+struct Query;
+
+#[crate::graphql_object_internal]
+impl Query {
+    fn hello() -> &str {
+        "world"
+    }
+
+    fn fails() -> FieldResult<Option<i32>> {
+        Err("resolver failed".into())
+    }
+}
+
+fn status_code(query: &str) -> u16 {
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    GraphQLRequest::new(query.to_owned(), None, None)
+        .execute_sync(&schema, &())
+        .status_code()
+}
+
+#[test]
+fn parse_error_is_bad_request() {
+    assert_eq!(status_code("{ hello"), 400);
+}
+
+#[test]
+fn validation_error_is_bad_request() {
+    assert_eq!(status_code("{ unknown }"), 400);
+}
+
+#[test]
+fn field_error_is_ok() {
+    assert_eq!(status_code("{ hello fails }"), 200);
+}
+
+#[test]
+fn success_is_ok() {
+    assert_eq!(status_code("{ hello }"), 200);
+}
+
+/// `(is_ok, has_errors, is_partial)` of the response to `query`
+fn outcome(query: &str) -> (bool, bool, bool) {
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let request = GraphQLRequest::new(query.to_owned(), None, None);
+    let response = request.execute_sync(&schema, &());
+    (
+        response.is_ok(),
+        response.has_errors(),
+        response.is_partial(),
+    )
+}
+
+#[test]
+fn success_has_no_errors() {
+    assert_eq!(outcome("{ hello }"), (true, false, false));
+}
+
+#[test]
+fn field_error_is_partial() {
+    assert_eq!(outcome("{ hello fails }"), (true, true, true));
+}
+
+#[test]
+fn validation_error_is_total_failure() {
+    assert_eq!(outcome("{ unknown }"), (false, true, false));
+}
+
+fn response_json(query: &str, pretty: bool) -> String {
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let request = GraphQLRequest::new(query.to_owned(), None, None);
+    request.execute_sync(&schema, &()).to_json_string(pretty)
+}
+
+#[test]
+fn pretty_and_compact_json_have_same_content() {
+    for query in &["{ hello }", "{ hello fails }", "{ hello"] {
+        let pretty = response_json(query, true);
+        let compact = response_json(query, false);
+
+        assert!(pretty.contains('\n'), "not pretty: {}", pretty);
+        assert!(!compact.contains('\n'), "not compact: {}", compact);
+        assert_eq!(
+            serde_json::from_str::<serde_json::Value>(&pretty).unwrap(),
+            serde_json::from_str::<serde_json::Value>(&compact).unwrap(),
+        );
+    }
+}
+
+#[test]
+fn compact_json() {
+    assert_eq!(
+        response_json("{ hello }", false),
+        r#"{"data":{"hello":"world"}}"#
+    );
+}
+
+/// Ids of the batched requests in the order they finished
+#[derive(Default)]
+struct Finished(Mutex<Vec<i32>>);
+impl Context for Finished {}
+
+struct BatchQuery;
+
+#[crate::graphql_object_internal(context = Finished)]
+impl BatchQuery {
+    async fn wait(finished: &Finished, id: i32, millis: i32) -> i32 {
+        tokio::time::delay_for(Duration::from_millis(millis as u64)).await;
+        finished.0.lock().unwrap().push(id);
+        id
+    }
+}
+
+/// Execute a batch of 10 requests, every request finishing before the ones
+/// preceding it when run concurrently
+async fn execute_batch(limit: Option<usize>) -> (Vec<String>, Vec<i32>) {
+    let mut schema = RootNode::new(
+        BatchQuery,
+        EmptyMutation::<Finished>::new(),
+        EmptySubscription::<Finished>::new(),
+    );
+    if let Some(limit) = limit {
+        schema = schema.batch_concurrency_limit(limit);
+    }
+    let entries = (0..10)
+        .map(|id| {
+            let query = format!("{{ wait(id: {}, millis: {}) }}", id, (10 - id) * 10);
+            GraphQLBatchEntry::from(GraphQLRequest::new(query, None, None))
+        })
+        .collect::<Vec<_>>();
+    let finished = Finished::default();
+
+    let responses = http::execute_batch(&entries, &schema, &finished)
+        .await
+        .iter()
+        .map(|response| response.to_json_string(false))
+        .collect();
+
+    (responses, finished.0.into_inner().unwrap())
+}
+
+fn wait_response(id: i32) -> String {
+    format!(r#"{{"data":{{"wait":{}}}}}"#, id)
+}
+
+#[tokio::test]
+async fn batch_responses_keep_request_order() {
+    let (responses, finished) = execute_batch(None).await;
+
+    assert_eq!(responses, (0..10).map(wait_response).collect::<Vec<_>>());
+    assert_eq!(finished, (0..10).rev().collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn batch_respects_concurrency_limit() {
+    let (responses, finished) = execute_batch(Some(1)).await;
+
+    assert_eq!(responses, (0..10).map(wait_response).collect::<Vec<_>>());
+    assert_eq!(finished, (0..10).collect::<Vec<_>>());
+}
+
+#[tokio::test]
+async fn response_follows_selection_order() {
+    let schema = RootNode::new(
+        BatchQuery,
+        EmptyMutation::<Finished>::new(),
+        EmptySubscription::<Finished>::new(),
+    );
+    let query = "{ slow: wait(id: 1, millis: 30) fast: wait(id: 2, millis: 0) }";
+
+    for _ in 0..2 {
+        let request = GraphQLRequest::new(query.to_owned(), None, None);
+        let finished = Finished::default();
+        let response = request.execute(&schema, &finished).await;
+
+        assert_eq!(*finished.0.lock().unwrap(), vec![2, 1]);
+        assert_eq!(
+            response.to_json_string(false),
+            r#"{"data":{"slow":1,"fast":2}}"#
+        );
+        assert_eq!(
+            response.sort_keys().to_json_string(false),
+            r#"{"data":{"fast":2,"slow":1}}"#
+        );
+    }
+}
@@ -4,9 +4,13 @@ use super::schema_introspection::*;
 use crate::{
     executor::Variables,
     introspection::IntrospectionFormat,
+    parser::SourcePosition,
     schema::model::RootNode,
     tests::{model::Database, schema::Query},
     types::scalars::{EmptyMutation, EmptySubscription},
+    validation::RuleError,
+    value::Value,
+    GraphQLError,
 };
 
 #[tokio::test]
@@ -42,6 +46,49 @@ async fn test_introspection_query_type_name() {
     );
 }
 
+#[test]
+fn test_introspection_schema_in_anonymous_query() {
+    let database = Database::new();
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    );
+
+    assert_eq!(
+        crate::execute_sync(
+            "{ __schema { queryType { name } } }",
+            None,
+            &schema,
+            &Variables::new(),
+            &database,
+        ),
+        Ok((
+            graphql_value!({ "__schema": { "queryType": { "name": "Query" } } }),
+            vec![]
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_introspection_on_missing_mutation_root() {
+    let doc = "mutation { __schema { queryType { name } } }";
+    let database = Database::new();
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    );
+
+    assert_eq!(
+        crate::execute(doc, None, &schema, &Variables::new(), &database).await,
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            "The mutation operation is not supported by the schema",
+            &[SourcePosition::new(0, 0, 0)],
+        )]))
+    );
+}
+
 #[tokio::test]
 async fn test_introspection_type_name() {
     let doc = r#"
@@ -294,3 +341,68 @@ async fn test_builtin_introspection_query_without_descriptions() {
 
     assert_eq!(result, (expected, vec![]));
 }
+
+#[test]
+fn test_introspect_schema() {
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    );
+
+    let dump = schema.introspect(&Database::new()).unwrap();
+    let object = dump.as_object_value().unwrap();
+
+    assert_eq!(
+        object.get_field_value("queryType"),
+        Some(&graphql_value!({ "name": "Query" })),
+    );
+    assert_eq!(object.get_field_value("mutationType"), Some(&Value::null()));
+
+    let human = object
+        .get_field_value("types")
+        .and_then(|types| types.as_list_value())
+        .unwrap()
+        .iter()
+        .filter_map(|t| t.as_object_value())
+        .find(|t| t.get_field_value("name") == Some(&graphql_value!("Human")))
+        .expect("Human type not found");
+    let mut fields = human
+        .get_field_value("fields")
+        .and_then(|fields| fields.as_list_value())
+        .unwrap()
+        .iter()
+        .filter_map(|f| {
+            f.as_object_value()?
+                .get_field_value("name")?
+                .as_string_value()
+        })
+        .collect::<Vec<_>>();
+    fields.sort();
+    assert_eq!(
+        fields,
+        vec!["appearsIn", "friends", "homePlanet", "id", "name"],
+    );
+
+    let (full, _) =
+        crate::introspect(&schema, &Database::new(), IntrospectionFormat::default()).unwrap();
+    assert_eq!(
+        full.as_object_value().unwrap().get_field_value("__schema"),
+        Some(&dump),
+    );
+}
+
+#[test]
+fn test_introspect_schema_with_introspection_disabled() {
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    )
+    .disable_introspection();
+
+    match schema.introspect(&Database::new()) {
+        Err(GraphQLError::ValidationError(_)) => {}
+        result => panic!("Expected a validation error, got {:?}", result),
+    }
+}
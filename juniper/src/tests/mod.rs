@@ -1,5 +1,7 @@
 //! Library tests and fixtures
 
+#[cfg(test)]
+mod http_tests;
 #[cfg(test)]
 mod introspection_tests;
 pub mod model;
@@ -7,8 +9,12 @@ pub mod model;
 mod query_tests;
 pub mod schema;
 #[cfg(test)]
+mod schema_diff_tests;
+#[cfg(test)]
 mod schema_introspection;
 #[cfg(test)]
+mod sdl_tests;
+#[cfg(test)]
 mod subscriptions;
 #[cfg(test)]
 mod type_info_tests;
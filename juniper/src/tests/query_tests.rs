@@ -1,10 +1,15 @@
+use std::sync::{Arc, Mutex};
+
 use crate::{
     ast::InputValue,
     executor::Variables,
-    schema::model::RootNode,
+    parser::{ParseError, SourcePosition, Spanning},
+    schema::model::{FieldNaming, RootNode},
     tests::{model::Database, schema::Query},
     types::scalars::{EmptyMutation, EmptySubscription},
+    validation::RuleError,
     value::Value,
+    GraphQLError,
 };
 
 #[tokio::test]
@@ -731,3 +736,349 @@ async fn test_object_typename() {
         ))
     );
 }
+
+#[tokio::test]
+async fn test_query_depth_limit() {
+    let doc = r#"
+        {
+            hero {
+                friends {
+                    friends {
+                        name
+                    }
+                }
+            }
+        }"#;
+    let database = Database::new();
+    let schema = |max_depth| {
+        RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        )
+        .max_query_depth(max_depth)
+    };
+
+    let at_limit = schema(4);
+    assert!(
+        crate::execute(doc, None, &at_limit, &Variables::new(), &database)
+            .await
+            .is_ok()
+    );
+
+    let over_limit = schema(3);
+    assert_eq!(
+        crate::execute(doc, None, &over_limit, &Variables::new(), &database).await,
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            "Operation has a depth of 4, which exceeds the maximum depth of 3",
+            &[SourcePosition::new(9, 1, 8)],
+        )]))
+    );
+
+    let introspection = crate::introspection::INTROSPECTION_QUERY;
+    let shallow = schema(1);
+    assert!(
+        crate::execute(introspection, None, &shallow, &Variables::new(), &database)
+            .await
+            .is_ok()
+    );
+}
+
+#[tokio::test]
+async fn test_query_aliases_limit() {
+    let doc = format!(
+        "{{ {} }}",
+        (0..1_000)
+            .map(|i| format!("h{}: hero {{ name }}", i))
+            .collect::<Vec<_>>()
+            .join(" ")
+    );
+    let database = Database::new();
+    let schema = |max_aliases| {
+        RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        )
+        .max_query_aliases(max_aliases)
+    };
+
+    let at_limit = schema(1_000);
+    assert!(
+        crate::execute(&doc, None, &at_limit, &Variables::new(), &database)
+            .await
+            .is_ok()
+    );
+
+    let over_limit = schema(100);
+    assert_eq!(
+        crate::execute(&doc, None, &over_limit, &Variables::new(), &database).await,
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            "Operation has 1000 aliased fields, which exceeds the maximum of 100",
+            &[SourcePosition::new(0, 0, 0)],
+        )]))
+    );
+}
+
+#[tokio::test]
+async fn test_fragment_cycles_rejected() {
+    let doc = r#"{ hero { ...A } } fragment A on Character { name ...A }"#;
+    let database = Database::new();
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    );
+
+    assert_eq!(
+        crate::execute(doc, None, &schema, &Variables::new(), &database).await,
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            r#"Cannot spread fragment "A" within itself"#,
+            &[SourcePosition::new(49, 0, 49)],
+        )]))
+    );
+}
+
+#[tokio::test]
+async fn test_query_size_limits() {
+    // 1_000 fields, 1_005 tokens and 5_012 bytes in total.
+    let doc = format!("{{ hero {{ {}}} }}", "name ".repeat(1_000));
+    let database = Database::new();
+    let schema = || {
+        RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        )
+    };
+
+    let at_limit = schema().max_query_length(doc.len());
+    assert!(
+        crate::execute(&doc, None, &at_limit, &Variables::new(), &database)
+            .await
+            .is_ok()
+    );
+
+    let over_limit = schema().max_query_length(doc.len() - 1);
+    assert_eq!(
+        crate::execute(&doc, None, &over_limit, &Variables::new(), &database).await,
+        Err(GraphQLError::ParseError(Spanning::zero_width(
+            &SourcePosition::new_origin(),
+            ParseError::DocumentTooLong(5_011),
+        )))
+    );
+
+    let at_limit = schema().max_query_tokens(1_005);
+    assert!(
+        crate::execute(&doc, None, &at_limit, &Variables::new(), &database)
+            .await
+            .is_ok()
+    );
+
+    let over_limit = schema().max_query_tokens(1_004);
+    assert_eq!(
+        crate::execute(&doc, None, &over_limit, &Variables::new(), &database).await,
+        Err(GraphQLError::ParseError(Spanning::start_end(
+            &SourcePosition::new(5_011, 0, 5_011),
+            &SourcePosition::new(5_012, 0, 5_012),
+            ParseError::TooManyTokens(1_004),
+        )))
+    );
+}
+
+#[tokio::test]
+async fn test_disabled_introspection() {
+    let doc = r#"
+        {
+            hero {
+                name
+            }
+            __schema {
+                queryType {
+                    name
+                }
+            }
+        }"#;
+    let database = Database::new();
+    let schema = || {
+        RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        )
+    };
+
+    let enabled = schema();
+    assert!(
+        crate::execute(doc, None, &enabled, &Variables::new(), &database)
+            .await
+            .is_ok()
+    );
+
+    let disabled = schema().disable_introspection();
+    assert_eq!(
+        crate::execute(doc, None, &disabled, &Variables::new(), &database).await,
+        Err(GraphQLError::ValidationError(vec![RuleError::new(
+            r#"Cannot query field "__schema": introspection is disabled"#,
+            &[SourcePosition::new(77, 5, 12)],
+        )]))
+    );
+
+    let typename_doc = r#"
+        {
+            hero {
+                __typename
+                name
+            }
+        }"#;
+    assert_eq!(
+        crate::execute(typename_doc, None, &disabled, &Variables::new(), &database).await,
+        Ok((
+            Value::object(
+                vec![(
+                    "hero",
+                    Value::object(
+                        vec![
+                            ("__typename", Value::scalar("Droid")),
+                            ("name", Value::scalar("R2-D2")),
+                        ]
+                        .into_iter()
+                        .collect(),
+                    ),
+                )]
+                .into_iter()
+                .collect()
+            ),
+            vec![]
+        ))
+    );
+}
+
+#[tokio::test]
+async fn test_field_naming() {
+    let database = Database::new();
+    let schema = |naming| {
+        RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        )
+        .field_naming(naming)
+    };
+    let home_planet = |field: &str| {
+        Value::object(
+            vec![(
+                "human",
+                Value::object(
+                    vec![(field, Value::scalar("Tatooine"))]
+                        .into_iter()
+                        .collect(),
+                ),
+            )]
+            .into_iter()
+            .collect(),
+        )
+    };
+
+    for (naming, field, other, appears_in) in vec![
+        (
+            FieldNaming::CamelCase,
+            "homePlanet",
+            "home_planet",
+            "appearsIn",
+        ),
+        (
+            FieldNaming::SnakeCase,
+            "home_planet",
+            "homePlanet",
+            "appears_in",
+        ),
+    ] {
+        let schema = schema(naming);
+        let human = schema.schema.concrete_type_by_name("Human").unwrap();
+        assert!(human.field_by_name(field).is_some());
+        assert!(human.field_by_name(other).is_none());
+        let character = schema.schema.concrete_type_by_name("Character").unwrap();
+        assert!(character.field_by_name(appears_in).is_some());
+        let type_meta = schema.schema.concrete_type_by_name("__Type").unwrap();
+        assert!(type_meta.field_by_name("ofType").is_some());
+
+        let doc = format!(r#"{{ human(id: "1000") {{ {} }} }}"#, field);
+        assert_eq!(
+            crate::execute(&doc, None, &schema, &Variables::new(), &database).await,
+            Ok((home_planet(field), vec![]))
+        );
+
+        let doc = format!(r#"{{ human(id: "1000") {{ {} }} }}"#, other);
+        assert!(
+            crate::execute(&doc, None, &schema, &Variables::new(), &database)
+                .await
+                .is_err()
+        );
+
+        let doc = format!(r#"{{ hero {{ {} }} }}"#, appears_in);
+        assert!(
+            crate::execute(&doc, None, &schema, &Variables::new(), &database)
+                .await
+                .is_ok()
+        );
+    }
+}
+
+struct Planet;
+
+#[crate::graphql_object_internal]
+impl Planet {
+    fn home_planet() -> &str {
+        "Tatooine"
+    }
+
+    #[graphql(name = "planetName")]
+    fn planet_name() -> &str {
+        "Naboo"
+    }
+}
+
+#[tokio::test]
+async fn test_field_naming_keeps_explicit_names() {
+    let authorized = Arc::new(Mutex::new(Vec::new()));
+    let recorded = authorized.clone();
+    let schema = RootNode::new(
+        Planet,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    )
+    .field_naming(FieldNaming::SnakeCase)
+    .authorize_fields(move |_, field, _| {
+        recorded.lock().unwrap().push(field.to_owned());
+        Ok(())
+    });
+
+    let result = crate::execute(
+        "{ home_planet planetName }",
+        None,
+        &schema,
+        &Variables::new(),
+        &(),
+    )
+    .await;
+
+    assert_eq!(
+        result,
+        Ok((
+            Value::object(
+                vec![
+                    ("home_planet", Value::scalar("Tatooine")),
+                    ("planetName", Value::scalar("Naboo")),
+                ]
+                .into_iter()
+                .collect()
+            ),
+            vec![]
+        ))
+    );
+    assert_eq!(
+        *authorized.lock().unwrap(),
+        vec!["home_planet".to_owned(), "planetName".to_owned()]
+    );
+}
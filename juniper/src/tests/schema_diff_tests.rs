@@ -0,0 +1,172 @@
+use crate::{
+    schema::{
+        diff::{schema_diff, SchemaChange},
+        meta::MetaType,
+        model::RootNode,
+    },
+    tests::{model::Database, schema::Query},
+    types::scalars::{EmptyMutation, EmptySubscription},
+};
+
+fn star_wars_schema(
+) -> RootNode<'static, Query, EmptyMutation<Database>, EmptySubscription<Database>> {
+    RootNode::new(
+        Query,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    )
+}
+
+#[test]
+fn identical_schemas_have_no_changes() {
+    assert_eq!(
+        schema_diff(&star_wars_schema(), &star_wars_schema()),
+        vec![]
+    );
+}
+
+#[test]
+fn removed_field_is_breaking() {
+    let old = star_wars_schema();
+    let mut new = star_wars_schema();
+    match new.schema.types.get_mut("Human") {
+        Some(MetaType::Object(human)) => human.fields.retain(|f| f.name != "homePlanet"),
+        _ => panic!("Expected a Human object"),
+    }
+
+    let changes = schema_diff(&old, &new);
+
+    assert_eq!(
+        changes,
+        vec![SchemaChange::FieldRemoved {
+            type_name: "Human".to_owned(),
+            field_name: "homePlanet".to_owned(),
+        }]
+    );
+    assert!(changes[0].is_breaking());
+    assert_eq!(changes[0].to_string(), "Field Human.homePlanet was removed");
+}
+
+mod v1 {
+    use juniper_codegen::GraphQLEnumInternal as GraphQLEnum;
+
+    #[derive(GraphQLEnum)]
+    pub enum Color {
+        Red,
+        Green,
+    }
+
+    pub struct Query;
+
+    #[crate::graphql_object_internal]
+    impl Query {
+        fn name() -> String {
+            "v1".to_owned()
+        }
+
+        fn count() -> Option<i32> {
+            None
+        }
+
+        fn color() -> Color {
+            Color::Red
+        }
+
+        fn search(text: String) -> i32 {
+            text.len() as i32
+        }
+
+        fn lookup(id: i32) -> i32 {
+            id
+        }
+
+        fn gone() -> bool {
+            true
+        }
+    }
+}
+
+mod v2 {
+    use juniper_codegen::GraphQLEnumInternal as GraphQLEnum;
+
+    #[derive(GraphQLEnum)]
+    pub enum Color {
+        Red,
+        Green,
+        Blue,
+    }
+
+    pub struct Query;
+
+    #[crate::graphql_object_internal]
+    impl Query {
+        fn name() -> Option<String> {
+            None
+        }
+
+        fn count() -> i32 {
+            0
+        }
+
+        fn color() -> Color {
+            Color::Blue
+        }
+
+        fn search(text: Option<String>, limit: i32) -> i32 {
+            text.map_or(0, |t| t.len() as i32).min(limit)
+        }
+
+        fn lookup(id: String) -> i32 {
+            id.len() as i32
+        }
+
+        fn added() -> Option<i32> {
+            None
+        }
+    }
+}
+
+#[test]
+fn classifies_changes() {
+    let old = RootNode::new(
+        v1::Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+    let new = RootNode::new(
+        v2::Query,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let changes = schema_diff(&old, &new)
+        .into_iter()
+        .map(|c| (c.to_string(), c.is_breaking()))
+        .collect::<Vec<_>>();
+
+    assert_eq!(
+        changes,
+        vec![
+            ("Value BLUE was added to Color".to_owned(), false),
+            (
+                "Field Query.name changed type from String! to String".to_owned(),
+                true,
+            ),
+            (
+                "Field Query.count changed type from Int to Int!".to_owned(),
+                false,
+            ),
+            (
+                "Argument text of Query.search changed type from String! to String".to_owned(),
+                false,
+            ),
+            ("Argument limit was added to Query.search".to_owned(), true),
+            (
+                "Argument id of Query.lookup changed type from Int! to String!".to_owned(),
+                true,
+            ),
+            ("Field Query.gone was removed".to_owned(), true),
+            ("Field Query.added was added".to_owned(), false),
+        ]
+    );
+}
@@ -0,0 +1,141 @@
+use juniper_codegen::{
+    GraphQLEnumInternal as GraphQLEnum, GraphQLInputObjectInternal as GraphQLInputObject,
+};
+
+use crate::{
+    schema::model::RootNode,
+    tests::{model::Database, schema::Query},
+    types::scalars::{EmptyMutation, EmptySubscription},
+};
+
+const STAR_WARS_SDL: &str = r#""A character in the Star Wars Trilogy"
+interface Character {
+  "The id of the character"
+  id: String!
+  "The name of the character"
+  name: String
+  "The friends of the character"
+  friends: [Character!]!
+  "Which movies they appear in"
+  appearsIn: [Episode!]!
+}
+
+"A mechanical creature in the Star Wars universe."
+type Droid implements Character {
+  "The id of the droid"
+  id: String!
+  "The name of the droid"
+  name: String
+  "The friends of the droid"
+  friends: [Character!]!
+  "Which movies they appear in"
+  appearsIn: [Episode!]!
+  "The primary function of the droid"
+  primaryFunction: String
+}
+
+enum Episode {
+  NEW_HOPE
+  EMPIRE
+  JEDI
+}
+
+"A humanoid creature in the Star Wars universe."
+type Human implements Character {
+  "The id of the human"
+  id: String!
+  "The name of the human"
+  name: String
+  "The friends of the human"
+  friends: [Character!]!
+  "Which movies they appear in"
+  appearsIn: [Episode!]!
+  "The home planet of the human"
+  homePlanet: String
+}
+
+"The root query object of the schema"
+type Query {
+  human(
+    "id of the human"
+    id: String!
+  ): Human
+  droid(
+    "id of the droid"
+    id: String!
+  ): Droid
+  hero(
+    "If omitted, returns the hero of the whole saga. If provided, returns the hero of that particular episode"
+    episode: Episode
+  ): Character
+}
+"#;
+
+#[test]
+fn star_wars_schema_as_sdl() {
+    let schema = RootNode::new(
+        Query,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    );
+
+    assert_eq!(schema.as_sdl(), STAR_WARS_SDL);
+}
+
+#[derive(GraphQLEnum)]
+enum Color {
+    Red,
+    #[graphql(deprecated = "Use RED")]
+    Crimson,
+}
+
+#[derive(GraphQLInputObject)]
+/// A point on a plane.
+struct Point {
+    x: i32,
+    #[graphql(description = "Defaults to the x axis")]
+    y: Option<i32>,
+}
+
+struct Mutation;
+
+#[crate::graphql_object_internal]
+impl Mutation {
+    #[graphql(arguments(times(default = 1)))]
+    fn paint(color: Color, at: Point, times: i32) -> Color {
+        let _ = (at, times);
+        color
+    }
+
+    #[deprecated(note = "Paint instead")]
+    fn draw() -> bool {
+        false
+    }
+}
+
+#[test]
+fn mutation_schema_as_sdl() {
+    let schema = RootNode::new(Query, Mutation, EmptySubscription::<Database>::new());
+    let sdl = schema.as_sdl();
+
+    assert!(sdl.contains(
+        r#"enum Color {
+  RED
+  CRIMSON @deprecated(reason: "Use RED")
+}"#
+    ));
+    assert!(sdl.contains(
+        r#""A point on a plane."
+input Point {
+  x: Int!
+  "Defaults to the x axis"
+  y: Int
+}"#
+    ));
+    assert!(sdl.contains(
+        r#"type Mutation {
+  paint(color: Color!, at: Point!, times: Int = 1): Color!
+  draw: Boolean! @deprecated(reason: "Paint instead")
+}"#
+    ));
+}
@@ -1,11 +1,21 @@
-use std::{iter, iter::FromIterator as _, pin::Pin};
+use std::{
+    iter,
+    iter::FromIterator as _,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    task::Poll,
+};
 
-use futures::{self, StreamExt as _};
+use futures::{self, channel::mpsc, SinkExt as _, StreamExt as _};
 use juniper_codegen::GraphQLObjectInternal;
 
 use crate::{
-    http::GraphQLRequest, Context, DefaultScalarValue, EmptyMutation, ExecutionError, FieldError,
-    Object, RootNode, Value,
+    http::GraphQLRequest, schema::meta::MetaType, BufferPressure, Context, DefaultScalarValue,
+    EmptyMutation, EmptySubscription, ExecutionError, FieldError, FieldResult, GraphQLError,
+    GraphQLSubscriptionType, GraphQLType, Object, Registry, RootNode, Value, Variables,
 };
 
 #[derive(Debug, Clone)]
@@ -21,6 +31,46 @@ struct Human {
     home_planet: String,
 }
 
+struct Robot {
+    id: i32,
+}
+
+#[crate::graphql_object_internal]
+impl Robot {
+    fn id(&self) -> i32 {
+        self.id
+    }
+
+    fn serial(&self) -> FieldResult<String> {
+        if self.id == 2 {
+            Err(FieldError::new("serial unreadable", Value::null()))
+        } else {
+            Ok(format!("R{}", self.id))
+        }
+    }
+}
+
+enum Event {
+    Arrived(Human),
+    Activated(Robot),
+}
+
+#[crate::graphql_union_internal]
+impl Event {
+    fn resolve(&self) {
+        match self {
+            Human => match *self {
+                Event::Arrived(ref h) => Some(h),
+                _ => None,
+            },
+            Robot => match *self {
+                Event::Activated(ref r) => Some(r),
+                _ => None,
+            },
+        }
+    }
+}
+
 struct MyQuery;
 
 #[crate::graphql_object_internal(context = MyContext)]
@@ -69,6 +119,66 @@ impl MySubscription {
         }))
     }
 
+    async fn channel_human() -> mpsc::Receiver<Human> {
+        let (mut tx, rx) = mpsc::channel(0);
+        std::thread::spawn(move || {
+            for id in 1..=3 {
+                let human = Human {
+                    id: id.to_string(),
+                    name: "channel name".to_string(),
+                    home_planet: "channel home planet".to_string(),
+                };
+                if futures::executor::block_on(tx.send(human)).is_err() {
+                    break;
+                }
+            }
+        });
+        rx
+    }
+
+    async fn flaky_human() -> Pin<Box<dyn futures::Stream<Item = Result<Human, FieldError>> + Send>>
+    {
+        Box::pin(futures::stream::iter((1..=3).map(|id| {
+            if id == 2 {
+                Err(FieldError::new("tick failed", Value::null()))
+            } else {
+                Ok(Human {
+                    id: id.to_string(),
+                    name: "flaky name".to_string(),
+                    home_planet: "flaky home planet".to_string(),
+                })
+            }
+        })))
+    }
+
+    async fn robots() -> Pin<Box<dyn futures::Stream<Item = Robot> + Send>> {
+        Box::pin(futures::stream::iter((1..=3).map(|id| Robot { id })))
+    }
+
+    async fn events() -> Pin<Box<dyn futures::Stream<Item = Event> + Send>> {
+        Box::pin(futures::stream::iter(vec![
+            Event::Arrived(Human {
+                id: "1".to_string(),
+                name: "event name".to_string(),
+                home_planet: "event home planet".to_string(),
+            }),
+            Event::Activated(Robot { id: 3 }),
+        ]))
+    }
+
+    async fn humans_on_planet(home_planet: String) -> HumanStream {
+        let humans = vec![("1", "Tatooine"), ("2", "Alderaan"), ("3", "Tatooine")];
+        Box::pin(
+            futures::stream::iter(humans)
+                .filter(move |(_, planet)| futures::future::ready(*planet == home_planet))
+                .map(|(id, planet)| Human {
+                    id: id.to_string(),
+                    name: "planet name".to_string(),
+                    home_planet: planet.to_string(),
+                }),
+        )
+    }
+
     async fn human_with_args(id: String, name: String) -> HumanStream {
         Box::pin(futures::stream::once(async {
             Human {
@@ -330,6 +440,38 @@ fn can_access_arguments() {
     assert_eq!(collected_values, expected_values);
 }
 
+#[test]
+fn filters_stream_by_argument() {
+    let query = r#"subscription {
+        humansOnPlanet(homePlanet: "Tatooine") {
+            id
+            homePlanet
+        }
+    }"#
+    .to_string();
+
+    let (names, collected_values) = create_and_execute(query).expect("Got error from stream");
+
+    let expected_values = vec![["1", "3"]
+        .iter()
+        .map(|id| {
+            Ok(Value::Object(Object::from_iter(vec![
+                (
+                    "id",
+                    Value::Scalar(DefaultScalarValue::String(id.to_string())),
+                ),
+                (
+                    "homePlanet",
+                    Value::Scalar(DefaultScalarValue::String("Tatooine".to_string())),
+                ),
+            ])))
+        })
+        .collect::<Vec<_>>()];
+
+    assert_eq!(names, vec!["humansOnPlanet"]);
+    assert_eq!(collected_values, expected_values);
+}
+
 #[test]
 fn type_alias() {
     let query = r#"subscription {
@@ -363,3 +505,614 @@ fn type_alias() {
     assert_eq!(names, vec!["aliasedHuman"]);
     assert_eq!(collected_values, expected_values);
 }
+
+#[test]
+fn resolves_channel_receiver() {
+    let query = r#"subscription {
+        channelHuman {
+            id
+        }
+    }"#
+    .to_string();
+
+    let (names, collected_values) = create_and_execute(query).expect("Got error from stream");
+
+    let expected_values = vec![(1..=3)
+        .map(|id| {
+            Ok(Value::Object(Object::from_iter(iter::once((
+                "id",
+                Value::Scalar(DefaultScalarValue::String(id.to_string())),
+            )))))
+        })
+        .collect::<Vec<_>>()];
+
+    assert_eq!(names, vec!["channelHuman"]);
+    assert_eq!(collected_values, expected_values);
+}
+
+#[test]
+fn item_errors_do_not_end_the_stream() {
+    let query = r#"subscription {
+        flakyHuman {
+            id
+        }
+    }"#
+    .to_string();
+
+    let (names, mut collected_values) = create_and_execute(query).expect("Got error from stream");
+    assert_eq!(names, vec!["flakyHuman"]);
+
+    let values = collected_values.remove(0);
+    assert_eq!(values.len(), 3);
+
+    let human = |id: &str| {
+        Ok(Value::Object(Object::from_iter(iter::once((
+            "id",
+            Value::Scalar(DefaultScalarValue::String(id.to_string())),
+        )))))
+    };
+    assert_eq!(values[0], human("1"));
+    match &values[1] {
+        Err(e) => {
+            assert_eq!(e.error().message(), "tick failed");
+            assert_eq!(e.path(), ["flakyHuman"]);
+        }
+        Ok(v) => panic!("Expected an error, got {:?}", v),
+    }
+    assert_eq!(values[2], human("3"));
+}
+
+#[test]
+fn item_resolution_errors_are_kept() {
+    let query = r#"subscription {
+        robots {
+            id
+            serial
+        }
+    }"#
+    .to_string();
+
+    let (names, mut collected_values) = create_and_execute(query).expect("Got error from stream");
+    assert_eq!(names, vec!["robots"]);
+
+    let values = collected_values.remove(0);
+    assert_eq!(values.len(), 3);
+
+    let robot = |id: i32| {
+        Ok(Value::Object(Object::from_iter(vec![
+            ("id", Value::scalar(id)),
+            ("serial", Value::scalar(format!("R{}", id))),
+        ])))
+    };
+    assert_eq!(values[0], robot(1));
+    match &values[1] {
+        Err(e) => {
+            assert_eq!(e.error().message(), "serial unreadable");
+            assert_eq!(e.path(), ["robots", "serial"]);
+        }
+        Ok(v) => panic!("Expected an error, got {:?}", v),
+    }
+    assert_eq!(values[2], robot(3));
+}
+
+#[test]
+fn resolves_concrete_typename_of_union_items() {
+    let query = r#"subscription {
+        events {
+            __typename
+            kind: __typename
+            ... on Human {
+                id
+            }
+            ... on Robot {
+                serial
+            }
+        }
+    }"#
+    .to_string();
+
+    let (names, collected_values) = create_and_execute(query).expect("Got error from stream");
+
+    let expected_values = vec![vec![
+        Ok(Value::Object(Object::from_iter(vec![
+            ("__typename", Value::scalar("Human")),
+            ("kind", Value::scalar("Human")),
+            ("id", Value::scalar("1")),
+        ]))),
+        Ok(Value::Object(Object::from_iter(vec![
+            ("__typename", Value::scalar("Robot")),
+            ("kind", Value::scalar("Robot")),
+            ("serial", Value::scalar("R3")),
+        ]))),
+    ]];
+
+    assert_eq!(names, vec!["events"]);
+    assert_eq!(collected_values, expected_values);
+}
+
+#[derive(Default)]
+struct InFlight {
+    current: AtomicUsize,
+    max: AtomicUsize,
+}
+impl Context for InFlight {}
+
+impl InFlight {
+    /// Count the caller as in flight while it yields to the executor a few
+    /// times, returning how many were in flight with it.
+    async fn busy(&self) -> usize {
+        let current = self.current.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max.fetch_max(current, Ordering::SeqCst);
+
+        for _ in 0..3 {
+            let mut yielded = false;
+            futures::future::poll_fn(|cx| {
+                if yielded {
+                    Poll::Ready(())
+                } else {
+                    yielded = true;
+                    cx.waker().wake_by_ref();
+                    Poll::Pending
+                }
+            })
+            .await;
+        }
+
+        self.current.fetch_sub(1, Ordering::SeqCst);
+        current
+    }
+
+    /// Count this resolver as in flight while it sets up its stream.
+    async fn track(&self) -> Pin<Box<dyn futures::Stream<Item = i32> + Send>> {
+        let current = self.busy().await;
+        Box::pin(futures::stream::once(async move { current as i32 }))
+    }
+}
+
+struct InFlightQuery;
+
+#[crate::graphql_object_internal(context = InFlight)]
+impl InFlightQuery {}
+
+struct InFlightSubscription;
+
+#[crate::graphql_subscription_internal(context = InFlight)]
+impl InFlightSubscription {
+    async fn a(ctx: &InFlight) -> Pin<Box<dyn futures::Stream<Item = i32> + Send>> {
+        ctx.track().await
+    }
+
+    async fn b(ctx: &InFlight) -> Pin<Box<dyn futures::Stream<Item = i32> + Send>> {
+        ctx.track().await
+    }
+
+    async fn c(ctx: &InFlight) -> Pin<Box<dyn futures::Stream<Item = i32> + Send>> {
+        ctx.track().await
+    }
+
+    async fn d(ctx: &InFlight) -> Pin<Box<dyn futures::Stream<Item = i32> + Send>> {
+        ctx.track().await
+    }
+
+    async fn e(ctx: &InFlight) -> Pin<Box<dyn futures::Stream<Item = i32> + Send>> {
+        ctx.track().await
+    }
+}
+
+fn max_fields_in_flight(limit: Option<usize>) -> usize {
+    let mut root_node = RootNode::new(
+        InFlightQuery,
+        EmptyMutation::<InFlight>::new(),
+        InFlightSubscription,
+    );
+    if let Some(limit) = limit {
+        root_node = root_node.subscription_concurrency_limit(limit);
+    }
+
+    let request = GraphQLRequest::new("subscription { a b c d e }".to_string(), None, None);
+    let context = InFlight::default();
+
+    let (value, errors) = run(crate::http::resolve_into_stream(
+        &request, &root_node, &context,
+    ))
+    .expect("Subscription failed");
+
+    assert!(errors.is_empty());
+    match value {
+        Value::Object(o) => assert_eq!(o.field_count(), 5),
+        _ => panic!("Expected an object"),
+    }
+
+    context.max.load(Ordering::SeqCst)
+}
+
+#[test]
+fn resolves_fields_concurrently() {
+    assert_eq!(max_fields_in_flight(None), 5);
+}
+
+#[test]
+fn respects_subscription_concurrency_limit() {
+    assert_eq!(max_fields_in_flight(Some(2)), 2);
+    assert_eq!(max_fields_in_flight(Some(1)), 1);
+}
+
+struct Tick;
+
+#[crate::graphql_object_internal(context = InFlight)]
+impl Tick {
+    async fn in_flight(ctx: &InFlight) -> i32 {
+        ctx.busy().await as i32
+    }
+}
+
+type TickStream = Pin<Box<dyn futures::Stream<Item = Tick> + Send>>;
+
+fn ticks() -> TickStream {
+    Box::pin(futures::stream::iter(vec![Tick, Tick, Tick]))
+}
+
+struct TickSubscription;
+
+#[crate::graphql_subscription_internal(context = InFlight)]
+impl TickSubscription {
+    async fn a() -> TickStream {
+        ticks()
+    }
+
+    async fn b() -> TickStream {
+        ticks()
+    }
+
+    async fn c() -> TickStream {
+        ticks()
+    }
+
+    async fn d() -> TickStream {
+        ticks()
+    }
+}
+
+/// Poll the streams of all the fields together, like a connection does on
+/// every tick, and return the most events resolved at once.
+fn max_events_in_flight(limit: Option<usize>) -> usize {
+    let mut root_node = RootNode::new(
+        InFlightQuery,
+        EmptyMutation::<InFlight>::new(),
+        TickSubscription,
+    );
+    if let Some(limit) = limit {
+        root_node = root_node.subscription_concurrency_limit(limit);
+    }
+
+    let request = GraphQLRequest::new(
+        "subscription { a { inFlight } b { inFlight } c { inFlight } d { inFlight } }".to_string(),
+        None,
+        None,
+    );
+    let context = InFlight::default();
+
+    run(async {
+        let (value, errors) = crate::http::resolve_into_stream(&request, &root_node, &context)
+            .await
+            .expect("Subscription failed");
+        assert!(errors.is_empty());
+
+        let streams = match value {
+            Value::Object(o) => o
+                .into_iter()
+                .map(|(_, v)| match v {
+                    Value::Scalar(stream) => stream,
+                    _ => panic!("Expected a stream of ticks"),
+                })
+                .collect::<Vec<_>>(),
+            _ => panic!("Expected an object"),
+        };
+        let events = futures::stream::select_all(streams)
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(events.len(), 12);
+        assert!(events.iter().all(Result::is_ok));
+    });
+
+    context.max.load(Ordering::SeqCst)
+}
+
+#[test]
+fn resolves_tick_events_concurrently() {
+    assert_eq!(max_events_in_flight(None), 4);
+}
+
+#[test]
+fn respects_subscription_concurrency_limit_on_ticks() {
+    assert_eq!(max_events_in_flight(Some(2)), 2);
+    assert_eq!(max_events_in_flight(Some(1)), 1);
+}
+
+#[derive(Default)]
+struct Ticker {
+    ticks: Mutex<Option<mpsc::UnboundedReceiver<i32>>>,
+    pressure: Mutex<Option<BufferPressure>>,
+}
+impl Context for Ticker {}
+
+struct TickerQuery;
+
+#[crate::graphql_object_internal(context = Ticker)]
+impl TickerQuery {}
+
+struct TickerSubscription;
+
+#[crate::graphql_subscription_internal(context = Ticker)]
+impl TickerSubscription {
+    async fn ticks(executor: &Executor<Ticker>) -> mpsc::UnboundedReceiver<i32> {
+        let context = executor.context();
+        *context.pressure.lock().unwrap() = Some(executor.buffer_pressure());
+        context.ticks.lock().unwrap().take().unwrap()
+    }
+
+    #[graphql(buffer(size = 2, overflow = "drop_oldest"))]
+    async fn latest_ticks(executor: &Executor<Ticker>) -> mpsc::UnboundedReceiver<i32> {
+        executor.context().ticks.lock().unwrap().take().unwrap()
+    }
+
+    #[graphql(buffer(size = 2, overflow = "error"))]
+    async fn strict_ticks(executor: &Executor<Ticker>) -> mpsc::UnboundedReceiver<i32> {
+        executor.context().ticks.lock().unwrap().take().unwrap()
+    }
+}
+
+/// Send ticks in bursts while the client takes one at a time, and read the
+/// buffer pressure after every tick taken.
+fn pressure_while_consuming_slowly(buffer_size: Option<usize>) -> Vec<usize> {
+    let mut root_node = RootNode::new(
+        TickerQuery,
+        EmptyMutation::<Ticker>::new(),
+        TickerSubscription,
+    );
+    if let Some(size) = buffer_size {
+        root_node = root_node.subscription_buffer_size(size);
+    }
+
+    let (tx, rx) = mpsc::unbounded();
+    let context = Ticker {
+        ticks: Mutex::new(Some(rx)),
+        ..Ticker::default()
+    };
+    let request = GraphQLRequest::new("subscription { ticks }".to_string(), None, None);
+
+    let (value, errors) = run(crate::http::resolve_into_stream(
+        &request, &root_node, &context,
+    ))
+    .expect("Subscription failed");
+    assert!(errors.is_empty());
+
+    let mut stream = match value {
+        Value::Object(o) => match o.into_iter().next() {
+            Some((_, Value::Scalar(stream))) => stream,
+            _ => panic!("Expected a stream of ticks"),
+        },
+        _ => panic!("Expected an object"),
+    };
+    let pressure = context.pressure.lock().unwrap().take().unwrap();
+
+    let mut sent = 0;
+    let mut readings = vec![];
+    for (taken, burst) in [3, 2, 0, 0, 0].iter().enumerate() {
+        for _ in 0..*burst {
+            sent += 1;
+            tx.unbounded_send(sent).unwrap();
+        }
+        let tick = run(stream.next()).unwrap().unwrap();
+        assert_eq!(tick, Value::scalar(taken as i32 + 1));
+        readings.push(pressure.get());
+    }
+    readings
+}
+
+#[test]
+fn producer_reads_buffer_pressure() {
+    assert_eq!(
+        pressure_while_consuming_slowly(Some(10)),
+        vec![2, 3, 2, 1, 0]
+    );
+}
+
+#[test]
+fn buffer_pressure_is_zero_without_a_buffer() {
+    assert_eq!(pressure_while_consuming_slowly(None), vec![0, 0, 0, 0, 0]);
+}
+
+/// Send a burst of five ticks before the client takes any, and collect what
+/// the client receives.
+fn ticks_after_burst(field: &str) -> Vec<Result<Value, ExecutionError<DefaultScalarValue>>> {
+    let root_node = RootNode::new(
+        TickerQuery,
+        EmptyMutation::<Ticker>::new(),
+        TickerSubscription,
+    );
+
+    let (tx, rx) = mpsc::unbounded();
+    let context = Ticker {
+        ticks: Mutex::new(Some(rx)),
+        ..Ticker::default()
+    };
+    let request = GraphQLRequest::new(format!("subscription {{ {} }}", field), None, None);
+
+    let (value, errors) = run(crate::http::resolve_into_stream(
+        &request, &root_node, &context,
+    ))
+    .expect("Subscription failed");
+    assert!(errors.is_empty());
+
+    let stream = match value {
+        Value::Object(o) => match o.into_iter().next() {
+            Some((_, Value::Scalar(stream))) => stream,
+            _ => panic!("Expected a stream of ticks"),
+        },
+        _ => panic!("Expected an object"),
+    };
+
+    for tick in 1..=5 {
+        tx.unbounded_send(tick).unwrap();
+    }
+    drop(tx);
+    run(stream.collect())
+}
+
+#[test]
+fn full_buffer_drops_oldest_ticks() {
+    assert_eq!(
+        ticks_after_burst("latestTicks"),
+        vec![Ok(Value::scalar(4)), Ok(Value::scalar(5))]
+    );
+}
+
+#[test]
+fn full_buffer_ends_stream_with_error() {
+    assert_eq!(
+        ticks_after_burst("strictTicks"),
+        vec![Err(ExecutionError::new(
+            crate::parser::SourcePosition::new(15, 0, 15),
+            &["strictTicks"],
+            FieldError::from("Subscription buffer of 2 events overflowed"),
+        ))]
+    );
+}
+
+#[test]
+fn field_without_buffer_waits_for_the_client() {
+    assert_eq!(
+        ticks_after_burst("ticks"),
+        (1..=5)
+            .map(|tick| Ok(Value::scalar(tick)))
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn rejects_introspection_on_the_subscription_root() {
+    let request = GraphQLRequest::new(
+        "subscription { __schema { queryType { name } } }".to_string(),
+        None,
+        None,
+    );
+    let root_node = Schema::new(MyQuery, EmptyMutation::new(), MySubscription);
+
+    let response = run(crate::http::resolve_into_stream(
+        &request,
+        &root_node,
+        &MyContext(2),
+    ));
+
+    match response {
+        Err(crate::GraphQLError::ValidationError(errors)) => assert_eq!(
+            errors,
+            vec![crate::RuleError::new(
+                r#"Unknown field "__schema" on type "MySubscription""#,
+                &[crate::parser::SourcePosition::new(15, 0, 15)],
+            )]
+        ),
+        _ => panic!("Expected a validation error"),
+    }
+}
+
+struct GreetingQuery;
+
+#[crate::graphql_object_internal]
+impl GreetingQuery {
+    fn hello() -> &str {
+        "world"
+    }
+}
+
+#[test]
+fn empty_subscription_supports_queries_only() {
+    let root_node = RootNode::new(
+        GreetingQuery,
+        EmptyMutation::<()>::new(),
+        EmptySubscription::<()>::new(),
+    );
+
+    let (value, errors) = run(crate::execute(
+        "{ hello }",
+        None,
+        &root_node,
+        &Variables::new(),
+        &(),
+    ))
+    .unwrap();
+    assert_eq!(errors, []);
+    assert_eq!(value, crate::graphql_value!({ "hello": "world" }));
+
+    let request = GraphQLRequest::new("subscription { hello }".to_string(), None, None);
+    let result = run(crate::http::resolve_into_stream(&request, &root_node, &()));
+    match result {
+        Err(GraphQLError::ValidationError(errors)) => assert!(errors
+            .iter()
+            .any(|e| e.message() == "The subscription operation is not supported by the schema")),
+        Err(e) => panic!("Expected a validation error, got {:?}", e),
+        Ok(_) => panic!("Expected a validation error"),
+    }
+}
+
+/// Subscription type relying on the default resolvers of
+/// `GraphQLSubscriptionType`
+struct UnimplementedSubscription;
+
+impl GraphQLType for UnimplementedSubscription {
+    type Context = ();
+    type TypeInfo = ();
+
+    fn name(_: &()) -> Option<&'static str> {
+        Some("UnimplementedSubscription")
+    }
+
+    fn meta<'r>(_: &(), registry: &mut Registry<'r>) -> MetaType<'r>
+    where
+        DefaultScalarValue: 'r,
+    {
+        let fields = &[registry.field::<Option<i32>>("tick", &())];
+        registry
+            .build_object_type::<UnimplementedSubscription>(&(), fields)
+            .into_meta()
+    }
+}
+
+impl GraphQLSubscriptionType<DefaultScalarValue> for UnimplementedSubscription {}
+
+#[test]
+fn unimplemented_subscription_resolvers_return_errors() {
+    let root_node = RootNode::new(
+        GreetingQuery,
+        EmptyMutation::<()>::new(),
+        UnimplementedSubscription,
+    );
+
+    let (value, errors) = run(crate::resolve_into_stream(
+        "subscription { tick }",
+        None,
+        &root_node,
+        &Variables::new(),
+        &(),
+    ))
+    .unwrap();
+
+    match value {
+        Value::Object(obj) => match obj.get_field_value("tick") {
+            Some(Value::Null) => {}
+            _ => panic!("Expected tick to be null"),
+        },
+        _ => panic!("Expected an object"),
+    }
+    assert_eq!(
+        errors,
+        vec![ExecutionError::new(
+            crate::parser::SourcePosition::new(15, 0, 15),
+            &["tick"],
+            FieldError::from(
+                "Subscription resolution is not implemented for type UnimplementedSubscription"
+            ),
+        )]
+    );
+}
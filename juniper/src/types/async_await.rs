@@ -1,13 +1,16 @@
+use std::time::Instant;
+
 use crate::{
     ast::Selection,
     executor::{ExecutionResult, Executor},
     parser::Spanning,
+    util::SufficientStack,
     value::{Object, ScalarValue, Value},
 };
 
 use crate::BoxFuture;
 
-use super::base::{is_excluded, merge_key_into, Arguments, GraphQLType};
+use super::base::{is_excluded, merge_key_into, non_object_resolve_error, Arguments, GraphQLType};
 
 /**
 This trait extends `GraphQLType` with asynchronous queries/mutations resolvers.
@@ -51,6 +54,9 @@ where
     /// Since the GraphQL spec specificies that errors during field processing
     /// should result in a null-value, this might return Ok(Null) in case of
     /// failure. Errors are recorded internally.
+    ///
+    /// Non-object types must override this method: without a selection set,
+    /// the default implementation returns an error.
     fn resolve_async<'a>(
         &'a self,
         info: &'a Self::TypeInfo,
@@ -64,7 +70,7 @@ where
                 Ok(value)
             })
         } else {
-            panic!("resolve() must be implemented by non-object output types");
+            Box::pin(async { Err(non_object_resolve_error()) })
         }
     }
 
@@ -104,12 +110,9 @@ where
     CtxT: Send + Sync,
     'e: 'a,
 {
-    Box::pin(resolve_selection_set_into_async_recursive(
-        instance,
-        info,
-        selection_set,
-        executor,
-    ))
+    Box::pin(SufficientStack(Box::pin(
+        resolve_selection_set_into_async_recursive(instance, info, selection_set, executor),
+    )))
 }
 
 struct AsyncField<S> {
@@ -122,6 +125,18 @@ enum AsyncValue<S> {
     Nested(Value<S>),
 }
 
+impl<S> AsyncValue<S> {
+    /// Whether a non-null field failed, turning the whole object into null
+    fn nulls_object(&self) -> bool {
+        match *self {
+            AsyncValue::Field(AsyncField { value: None, .. }) | AsyncValue::Nested(Value::Null) => {
+                true
+            }
+            _ => false,
+        }
+    }
+}
+
 pub(crate) async fn resolve_selection_set_into_async_recursive<'a, T, CtxT, S>(
     instance: &'a T,
     info: &'a T::TypeInfo,
@@ -134,19 +149,20 @@ where
     S: ScalarValue + Send + Sync,
     CtxT: Send + Sync,
 {
-    use futures::stream::{FuturesOrdered, StreamExt as _};
+    use futures::{
+        future::{self, FutureExt as _},
+        stream::{FuturesUnordered, StreamExt as _},
+    };
 
     let mut object = Object::with_capacity(selection_set.len());
 
-    let mut async_values = FuturesOrdered::<BoxFuture<'a, AsyncValue<S>>>::new();
+    // Futures are lazy, so nothing resolves until they are polled below.
+    let mut async_values = Vec::<BoxFuture<'a, AsyncValue<S>>>::new();
 
+    let type_name = T::name(info).expect("Resolving named type's selection set");
     let meta_type = executor
         .schema()
-        .concrete_type_by_name(
-            T::name(info)
-                .expect("Resolving named type's selection set")
-                .as_ref(),
-        )
+        .concrete_type_by_name(type_name.as_ref())
         .expect("Type not found in schema");
 
     for selection in selection_set {
@@ -163,20 +179,35 @@ where
                 let response_name = f.alias.as_ref().unwrap_or(&f.name).item;
 
                 if f.name.item == "__typename" {
-                    object.add_field(
-                        response_name,
-                        Value::scalar(instance.concrete_type_name(executor.context(), info)),
-                    );
+                    // Goes through `async_values` to keep its place among
+                    // the fields selected before it.
+                    let field = AsyncField {
+                        name: response_name.to_string(),
+                        value: Some(Value::scalar(
+                            instance.concrete_type_name(executor.context(), info),
+                        )),
+                    };
+                    async_values.push(Box::pin(future::ready(AsyncValue::Field(field))));
                     continue;
                 }
 
-                let meta_field = meta_type.field_by_name(f.name.item).unwrap_or_else(|| {
-                    panic!(format!(
-                        "Field {} not found on type {:?}",
-                        f.name.item,
-                        meta_type.name()
-                    ))
-                });
+                let meta_field = match meta_type.field_by_name(f.name.item) {
+                    Some(meta_field) => meta_field,
+                    None => {
+                        executor.push_unknown_field_error(
+                            type_name.as_ref(),
+                            response_name,
+                            f.name.item,
+                            start_pos.clone(),
+                        );
+                        let field = AsyncField {
+                            name: response_name.to_string(),
+                            value: Some(Value::null()),
+                        };
+                        async_values.push(Box::pin(future::ready(AsyncValue::Field(field))));
+                        continue;
+                    }
+                };
 
                 let exec_vars = executor.variables();
 
@@ -194,18 +225,36 @@ where
                             .collect()
                     }),
                     &meta_field.arguments,
+                    executor.schema(),
                 );
 
                 let pos = *start_pos;
-                let is_non_null = meta_field.field_type.is_non_null();
+                let is_non_null = meta_field.field_type.is_non_null()
+                    && !executor.schema().null_propagation_disabled;
+                let authorized = sub_exec
+                    .authorize_field(type_name.as_ref(), f.name.item)
+                    .and_then(|()| sub_exec.guard_field_directives(&f.directives));
+                let timer = sub_exec.field_timer(type_name.as_ref(), meta_field);
 
                 let response_name = response_name.to_string();
                 let field_future = async move {
+                    let start = Instant::now();
                     // TODO: implement custom future type instead of
                     //       two-level boxing.
-                    let res = instance
-                        .resolve_field_async(info, f.name.item, &args, &sub_exec)
-                        .await;
+                    let res = match authorized {
+                        Ok(()) => {
+                            instance
+                                .resolve_field_async(info, f.name.item, &args, &sub_exec)
+                                .await
+                        }
+                        Err(e) => Err(e),
+                    }
+                    .and_then(|v| sub_exec.apply_field_directives(&f.directives, v));
+                    if let Some(timer) = timer {
+                        timer.finish(start);
+                    }
+                    #[cfg(any(test, feature = "resolver-log"))]
+                    sub_exec.log_resolver(start.elapsed(), res.as_ref().err());
 
                     let value = match res {
                         Ok(Value::Null) if is_non_null => None,
@@ -305,26 +354,45 @@ where
         }
     }
 
-    while let Some(item) = async_values.next().await {
-        match item {
-            AsyncValue::Field(AsyncField { name, value }) => {
-                if let Some(value) = value {
-                    merge_key_into(&mut object, &name, value);
-                } else {
-                    return Value::null();
-                }
+    let mut values = Vec::with_capacity(async_values.len());
+    if executor.is_mutation_root(type_name.as_ref()) {
+        // Mutation fields execute serially, so none starts after another one
+        // has nulled the object.
+        for (index, async_value) in async_values.into_iter().enumerate() {
+            let value = async_value.await;
+            if value.nulls_object() {
+                return Value::null();
             }
-            AsyncValue::Nested(obj) => match obj {
-                v @ Value::Null => {
-                    return v;
-                }
-                Value::Object(obj) => {
-                    for (k, v) in obj {
-                        merge_key_into(&mut object, &k, v);
-                    }
+            values.push((index, value));
+        }
+    } else {
+        let mut pending = async_values
+            .into_iter()
+            .enumerate()
+            .map(|(index, async_value)| async_value.map(move |value| (index, value)))
+            .collect::<FuturesUnordered<_>>();
+        while let Some((index, value)) = pending.next().await {
+            if value.nulls_object() {
+                // Dropping `pending` cancels the fields still resolving.
+                return Value::null();
+            }
+            values.push((index, value));
+        }
+        values.sort_by_key(|&(index, _)| index);
+    }
+
+    for (_, value) in values {
+        match value {
+            AsyncValue::Field(AsyncField {
+                name,
+                value: Some(value),
+            }) => merge_key_into(&mut object, &name, value),
+            AsyncValue::Nested(Value::Object(obj)) => {
+                for (k, v) in obj {
+                    merge_key_into(&mut object, &k, v);
                 }
-                _ => unreachable!(),
-            },
+            }
+            _ => unreachable!(),
         }
     }
 
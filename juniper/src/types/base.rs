@@ -4,9 +4,13 @@ use juniper_codegen::GraphQLEnumInternal as GraphQLEnum;
 
 use crate::{
     ast::{Directive, FromInputValue, InputValue, Selection},
-    executor::{ExecutionResult, Executor, Registry, Variables},
+    executor::{ExecutionResult, Executor, FieldError, Registry, Variables},
     parser::Spanning,
-    schema::meta::{Argument, MetaType},
+    schema::{
+        meta::{Argument, MetaType},
+        model::SchemaType,
+    },
+    types::utilities::coerce_input_value,
     value::{DefaultScalarValue, Object, ScalarValue, Value},
 };
 
@@ -78,10 +82,13 @@ impl<'a, S> Arguments<'a, S>
 where
     S: ScalarValue,
 {
+    /// Fills absent arguments, and absent fields of input object arguments,
+    /// with their default values.
     #[doc(hidden)]
     pub fn new(
         mut args: Option<IndexMap<&'a str, InputValue<S>>>,
         meta_args: &'a Option<Vec<Argument<S>>>,
+        schema: &SchemaType<S>,
     ) -> Self {
         if meta_args.is_some() && args.is_none() {
             args = Some(IndexMap::new());
@@ -96,6 +103,8 @@ where
                         args.insert(arg.name.as_str(), InputValue::null());
                     }
                 }
+
+                coerce_input_value(schema, &arg.arg_type, &mut args[arg.name.as_str()]);
             }
         }
 
@@ -121,6 +130,23 @@ where
             None => None,
         }
     }
+
+    /// Convert all arguments together into the desired type, as if they were
+    /// the fields of an input object.
+    ///
+    /// Returns `Some` if type conversion succeeds.
+    pub fn get_flattened<T>(&self) -> Option<T>
+    where
+        T: FromInputValue<S>,
+    {
+        let fields = self
+            .args
+            .iter()
+            .flatten()
+            .map(|(&k, v)| (k, v.clone()))
+            .collect::<IndexMap<_, _>>();
+        InputValue::object(fields).convert()
+    }
 }
 
 /**
@@ -320,6 +346,9 @@ where
     /// Since the GraphQL spec specificies that errors during field processing
     /// should result in a null-value, this might return Ok(Null) in case of
     /// failure. Errors are recorded internally.
+    ///
+    /// Non-object types must override this method: without a selection set,
+    /// the default implementation returns an error.
     fn resolve(
         &self,
         info: &Self::TypeInfo,
@@ -336,11 +365,20 @@ where
                 };
             Ok(out)
         } else {
-            panic!("resolve() must be implemented by non-object output types");
+            Err(non_object_resolve_error())
         }
     }
 }
 
+/// The error returned when a type that doesn't override `resolve` is resolved
+/// as a leaf, which means the type is misconfigured.
+pub(crate) fn non_object_resolve_error<S: ScalarValue>() -> FieldError<S> {
+    FieldError::new(
+        "resolve() must be implemented by non-object output types",
+        Value::null(),
+    )
+}
+
 /// Resolver logic for queries'/mutations' selection set.
 /// Calls appropriate resolver method for each field or fragment found
 /// and then merges returned values into `result` or pushes errors to
@@ -358,13 +396,10 @@ where
     T: GraphQLType<S, Context = CtxT>,
     S: ScalarValue,
 {
+    let type_name = T::name(info).expect("Resolving named type's selection set");
     let meta_type = executor
         .schema()
-        .concrete_type_by_name(
-            T::name(info)
-                .expect("Resolving named type's selection set")
-                .as_ref(),
-        )
+        .concrete_type_by_name(type_name.as_ref())
         .expect("Type not found in schema");
 
     for selection in selection_set {
@@ -388,13 +423,19 @@ where
                     continue;
                 }
 
-                let meta_field = meta_type.field_by_name(f.name.item).unwrap_or_else(|| {
-                    panic!(format!(
-                        "Field {} not found on type {:?}",
-                        f.name.item,
-                        meta_type.name()
-                    ))
-                });
+                let meta_field = match meta_type.field_by_name(f.name.item) {
+                    Some(meta_field) => meta_field,
+                    None => {
+                        executor.push_unknown_field_error(
+                            type_name.as_ref(),
+                            response_name,
+                            f.name.item,
+                            start_pos.clone(),
+                        );
+                        result.add_field(response_name, Value::null());
+                        continue;
+                    }
+                };
 
                 let exec_vars = executor.variables();
 
@@ -405,30 +446,44 @@ where
                     f.selection_set.as_ref().map(|v| &v[..]),
                 );
 
-                let field_result = instance.resolve_field(
-                    info,
-                    f.name.item,
-                    &Arguments::new(
-                        f.arguments.as_ref().map(|m| {
-                            m.item
-                                .iter()
-                                .map(|&(ref k, ref v)| {
-                                    (k.item, v.item.clone().into_const(exec_vars))
-                                })
-                                .collect()
-                        }),
-                        &meta_field.arguments,
-                    ),
-                    &sub_exec,
-                );
+                #[cfg(any(test, feature = "resolver-log"))]
+                let start = std::time::Instant::now();
+                let field_result = sub_exec
+                    .authorize_field(type_name.as_ref(), f.name.item)
+                    .and_then(|()| sub_exec.guard_field_directives(&f.directives))
+                    .and_then(|()| {
+                        instance.resolve_field(
+                            info,
+                            f.name.item,
+                            &Arguments::new(
+                                f.arguments.as_ref().map(|m| {
+                                    m.item
+                                        .iter()
+                                        .map(|&(ref k, ref v)| {
+                                            (k.item, v.item.clone().into_const(exec_vars))
+                                        })
+                                        .collect()
+                                }),
+                                &meta_field.arguments,
+                                executor.schema(),
+                            ),
+                            &sub_exec,
+                        )
+                    })
+                    .and_then(|v| sub_exec.apply_field_directives(&f.directives, v));
+                #[cfg(any(test, feature = "resolver-log"))]
+                sub_exec.log_resolver(start.elapsed(), field_result.as_ref().err());
+
+                let is_non_null = meta_field.field_type.is_non_null()
+                    && !executor.schema().null_propagation_disabled;
 
                 match field_result {
-                    Ok(Value::Null) if meta_field.field_type.is_non_null() => return false,
+                    Ok(Value::Null) if is_non_null => return false,
                     Ok(v) => merge_key_into(result, response_name, v),
                     Err(e) => {
                         sub_exec.push_error_at(e, start_pos.clone());
 
-                        if meta_field.field_type.is_non_null() {
+                        if is_non_null {
                             return false;
                         }
 
@@ -502,7 +557,7 @@ where
     true
 }
 
-pub(super) fn is_excluded<S>(
+pub(crate) fn is_excluded<S>(
     directives: &Option<Vec<Spanning<Directive<S>>>>,
     vars: &Variables<S>,
 ) -> bool
@@ -515,6 +570,10 @@ where
             ..
         } in directives
         {
+            if directive.name.item != "skip" && directive.name.item != "include" {
+                continue;
+            }
+
             let condition: bool = directive
                 .arguments
                 .iter()
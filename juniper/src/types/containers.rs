@@ -191,11 +191,12 @@ where
         .current_type()
         .list_contents()
         .expect("Current type is not a list type")
-        .is_non_null();
+        .is_non_null()
+        && !executor.schema().null_propagation_disabled;
     let mut result = Vec::with_capacity(iter.len());
 
-    for o in iter {
-        match executor.resolve(info, &o) {
+    for (index, o) in iter.enumerate() {
+        match executor.index_sub_executor(index).resolve(info, &o) {
             Ok(value) => {
                 if stop_on_null && value.is_null() {
                     return Ok(value);
@@ -229,10 +230,15 @@ where
         .current_type()
         .list_contents()
         .expect("Current type is not a list type")
-        .is_non_null();
+        .is_non_null()
+        && !executor.schema().null_propagation_disabled;
 
-    let iter =
-        items.map(|item| async move { executor.resolve_into_value_async(info, &item).await });
+    let iter = items.enumerate().map(|(index, item)| async move {
+        executor
+            .index_sub_executor(index)
+            .resolve_into_value_async(info, &item)
+            .await
+    });
     let mut futures = FuturesOrdered::from_iter(iter);
 
     let mut values = Vec::with_capacity(futures.len());
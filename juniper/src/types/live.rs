@@ -0,0 +1,224 @@
+use std::{
+    fmt,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+use futures::Stream;
+
+use crate::{
+    ast::Selection,
+    executor::{ExecutionResult, Executor, Registry},
+    schema::meta::MetaType,
+    types::{
+        async_await::GraphQLTypeAsync,
+        base::{Arguments, GraphQLType},
+    },
+    value::ScalarValue,
+    BoxFuture,
+};
+
+/// A value that changes over time and can be both queried and subscribed to
+///
+/// Cloning a `Live` returns a new handle to the same underlying value. Return
+/// a handle from a query resolver to resolve a snapshot of the current value,
+/// or from a subscription resolver to stream it: the stream yields the
+/// current value first and then every value [`set`](Live::set) afterwards.
+/// A subscriber that falls behind skips to the latest value.
+///
+/// ```
+/// # use futures::StreamExt;
+/// # use juniper::Live;
+/// let temperature = Live::new(20);
+/// let mut updates = temperature.clone();
+///
+/// # futures::executor::block_on(async {
+/// assert_eq!(updates.next().await, Some(20));
+/// temperature.set(21);
+/// assert_eq!(updates.next().await, Some(21));
+/// temperature.close();
+/// assert_eq!(updates.next().await, None);
+/// # });
+/// ```
+pub struct Live<T> {
+    shared: Arc<Mutex<Shared<T>>>,
+    seen: Option<u64>,
+}
+
+struct Shared<T> {
+    value: T,
+    version: u64,
+    closed: bool,
+    waiting: Vec<Waker>,
+}
+
+impl<T> Live<T> {
+    /// Construct a new live value
+    pub fn new(value: T) -> Self {
+        Live {
+            shared: Arc::new(Mutex::new(Shared {
+                value,
+                version: 0,
+                closed: false,
+                waiting: Vec::new(),
+            })),
+            seen: None,
+        }
+    }
+
+    /// Replace the current value and notify the subscribers
+    pub fn set(&self, value: T) {
+        self.update(|current| *current = value);
+    }
+
+    /// Modify the current value in place and notify the subscribers
+    pub fn update<F>(&self, f: F)
+    where
+        F: FnOnce(&mut T),
+    {
+        let mut shared = self.shared.lock().unwrap();
+        f(&mut shared.value);
+        shared.version += 1;
+        for waker in shared.waiting.drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// End the streams of all subscribers once they have received the
+    /// current value
+    ///
+    /// The value can still be queried and updated afterwards.
+    pub fn close(&self) {
+        let mut shared = self.shared.lock().unwrap();
+        shared.closed = true;
+        for waker in shared.waiting.drain(..) {
+            waker.wake();
+        }
+    }
+}
+
+impl<T: Clone> Live<T> {
+    /// Get a snapshot of the current value
+    pub fn get(&self) -> T {
+        self.shared.lock().unwrap().value.clone()
+    }
+}
+
+impl<T> Clone for Live<T> {
+    fn clone(&self) -> Self {
+        Live {
+            shared: self.shared.clone(),
+            seen: None,
+        }
+    }
+}
+
+impl<T: fmt::Debug> fmt::Debug for Live<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_tuple("Live")
+            .field(&self.shared.lock().unwrap().value)
+            .finish()
+    }
+}
+
+impl<T: Clone> Stream for Live<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<T>> {
+        let this = self.get_mut();
+        let mut shared = this.shared.lock().unwrap();
+        if this.seen != Some(shared.version) {
+            this.seen = Some(shared.version);
+            Poll::Ready(Some(shared.value.clone()))
+        } else if shared.closed {
+            Poll::Ready(None)
+        } else {
+            shared.waiting.push(cx.waker().clone());
+            Poll::Pending
+        }
+    }
+}
+
+impl<S, T> GraphQLType<S> for Live<T>
+where
+    S: ScalarValue,
+    T: GraphQLType<S> + Clone,
+{
+    type Context = T::Context;
+    type TypeInfo = T::TypeInfo;
+
+    fn name(info: &T::TypeInfo) -> Option<&str> {
+        T::name(info)
+    }
+
+    fn meta<'r>(info: &T::TypeInfo, registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        T::meta(info, registry)
+    }
+
+    fn resolve_into_type(
+        &self,
+        info: &T::TypeInfo,
+        name: &str,
+        selection_set: Option<&[Selection<S>]>,
+        executor: &Executor<T::Context, S>,
+    ) -> ExecutionResult<S> {
+        self.get()
+            .resolve_into_type(info, name, selection_set, executor)
+    }
+
+    fn resolve_field(
+        &self,
+        info: &T::TypeInfo,
+        field: &str,
+        args: &Arguments<S>,
+        executor: &Executor<T::Context, S>,
+    ) -> ExecutionResult<S> {
+        self.get().resolve_field(info, field, args, executor)
+    }
+
+    fn resolve(
+        &self,
+        info: &T::TypeInfo,
+        selection_set: Option<&[Selection<S>]>,
+        executor: &Executor<T::Context, S>,
+    ) -> ExecutionResult<S> {
+        self.get().resolve(info, selection_set, executor)
+    }
+}
+
+impl<S, T> GraphQLTypeAsync<S> for Live<T>
+where
+    S: ScalarValue + Send + Sync,
+    T: GraphQLTypeAsync<S> + Clone,
+    T::TypeInfo: Send + Sync,
+    T::Context: Send + Sync,
+{
+    fn resolve_field_async<'a>(
+        &'a self,
+        info: &'a Self::TypeInfo,
+        field_name: &'a str,
+        arguments: &'a Arguments<S>,
+        executor: &'a Executor<Self::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        let snapshot = self.get();
+        Box::pin(async move {
+            snapshot
+                .resolve_field_async(info, field_name, arguments, executor)
+                .await
+        })
+    }
+
+    fn resolve_async<'a>(
+        &'a self,
+        info: &'a Self::TypeInfo,
+        selection_set: Option<&'a [Selection<S>]>,
+        executor: &'a Executor<Self::Context, S>,
+    ) -> BoxFuture<'a, ExecutionResult<S>> {
+        let snapshot = self.get();
+        Box::pin(async move { snapshot.resolve_async(info, selection_set, executor).await })
+    }
+}
@@ -1,6 +1,7 @@
 pub mod async_await;
 pub mod base;
 pub mod containers;
+pub mod live;
 pub mod name;
 pub mod pointers;
 pub mod scalars;
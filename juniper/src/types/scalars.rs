@@ -1,5 +1,5 @@
 use serde_derive::{Deserialize, Serialize};
-use std::{char, convert::From, marker::PhantomData, ops::Deref, u32};
+use std::{char, convert::From, fmt, marker::PhantomData, ops::Deref, u32};
 
 use crate::{
     ast::{InputValue, Selection, ToInputValue},
@@ -7,7 +7,7 @@ use crate::{
     parser::{LexerError, ParseError, ScalarToken, Token},
     schema::meta::MetaType,
     types::base::GraphQLType,
-    value::{ParseScalarResult, ScalarValue, Value},
+    value::{DefaultScalarValue, ParseScalarResult, ScalarValue, Value},
 };
 
 /// An ID as defined by the GraphQL specification
@@ -37,67 +37,93 @@ impl Deref for ID {
     }
 }
 
-graphql_scalar!(ID as "ID" where Scalar = <S>{
-    resolve(&self) -> Value {
+#[crate::graphql_scalar_internal(name = "ID")]
+impl<S> GraphQLScalar for ID
+where
+    S: ScalarValue,
+{
+    fn resolve(&self) -> Value {
         Value::scalar(self.0.clone())
     }
 
-    from_input_value(v: &InputValue) -> Option<ID> {
+    fn from_input_value(v: &InputValue) -> Option<ID> {
         match *v {
-            InputValue::Scalar(ref s) => {
-                s.as_string().or_else(|| s.as_int().map(|i| i.to_string()))
-                    .map(ID)
-            }
-            _ => None
+            InputValue::Scalar(ref s) => s
+                .as_string()
+                .or_else(|| s.as_int().map(|i| i.to_string()))
+                .map(ID),
+            _ => None,
         }
     }
 
-    from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
+    fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
         match value {
-            ScalarToken::String(value) | ScalarToken::Int(value) => {
-                Ok(S::from(value.to_owned()))
-            }
+            ScalarToken::String(value) | ScalarToken::Int(value) => Ok(S::from(value.to_owned())),
             _ => Err(ParseError::UnexpectedToken(Token::Scalar(value))),
         }
     }
-});
+}
 
-graphql_scalar!(String as "String" where Scalar = <S>{
-    resolve(&self) -> Value {
+#[crate::graphql_scalar_internal(name = "String")]
+impl<S> GraphQLScalar for String
+where
+    S: ScalarValue,
+{
+    fn resolve(&self) -> Value {
         Value::scalar(self.clone())
     }
 
-    from_input_value(v: &InputValue) -> Option<String> {
+    fn from_input_value(v: &InputValue) -> Option<String> {
         match *v {
             InputValue::Scalar(ref s) => s.as_string(),
             _ => None,
         }
     }
 
-    from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
+    fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
         if let ScalarToken::String(value) = value {
             let mut ret = String::with_capacity(value.len());
             let mut char_iter = value.chars();
             while let Some(ch) = char_iter.next() {
                 match ch {
-                    '\\' => {
-                        match char_iter.next() {
-                            Some('"') => {ret.push('"');}
-                            Some('/') => {ret.push('/');}
-                            Some('n') => {ret.push('\n');}
-                            Some('r') => {ret.push('\r');}
-                            Some('t') => {ret.push('\t');}
-                            Some('\\') => {ret.push('\\');}
-                            Some('f') => {ret.push('\u{000c}');}
-                            Some('b') => {ret.push('\u{0008}');}
-                            Some('u') => {
-                                ret.push(parse_unicode_codepoint(&mut char_iter)?);
-                            }
-                            Some(s) => return Err(ParseError::LexerError(LexerError::UnknownEscapeSequence(format!("\\{}", s)))),
-                            None => return Err(ParseError::LexerError(LexerError::UnterminatedString)),
+                    '\\' => match char_iter.next() {
+                        Some('"') => {
+                            ret.push('"');
+                        }
+                        Some('/') => {
+                            ret.push('/');
+                        }
+                        Some('n') => {
+                            ret.push('\n');
+                        }
+                        Some('r') => {
+                            ret.push('\r');
+                        }
+                        Some('t') => {
+                            ret.push('\t');
+                        }
+                        Some('\\') => {
+                            ret.push('\\');
                         }
+                        Some('f') => {
+                            ret.push('\u{000c}');
+                        }
+                        Some('b') => {
+                            ret.push('\u{0008}');
+                        }
+                        Some('u') => {
+                            ret.push(parse_unicode_codepoint(&mut char_iter)?);
+                        }
+                        Some(s) => {
+                            return Err(ParseError::LexerError(LexerError::UnknownEscapeSequence(
+                                format!("\\{}", s),
+                            )))
+                        }
+                        None => return Err(ParseError::LexerError(LexerError::UnterminatedString)),
                     },
-                    ch => {ret.push(ch);}
+                    ch => {
+                        ret.push(ch);
+                    }
                 }
             }
             Ok(ret.into())
@@ -105,7 +131,7 @@ graphql_scalar!(String as "String" where Scalar = <S>{
             Err(ParseError::UnexpectedToken(Token::Scalar(value)))
         }
     }
-});
+}
 
 fn parse_unicode_codepoint<'a, I>(char_iter: &mut I) -> Result<char, ParseError<'a>>
 where
@@ -218,73 +244,183 @@ where
     }
 }
 
-graphql_scalar!(bool as "Boolean" where Scalar = <S>{
-    resolve(&self) -> Value {
+/// Resolves any value implementing `Display` as a `String`
+///
+/// Handy for fields returning types that don't implement a scalar of their
+/// own, without writing one:
+///
+/// ```
+/// # use std::net::{IpAddr, Ipv4Addr};
+/// use juniper::Displayed;
+///
+/// struct Server;
+///
+/// #[juniper::graphql_object]
+/// impl Server {
+///     fn address() -> Displayed<IpAddr> {
+///         Displayed(IpAddr::V4(Ipv4Addr::LOCALHOST))
+///     }
+/// }
+/// ```
+#[derive(Clone, Copy, Debug, Default, Eq, Hash, PartialEq, PartialOrd, Ord)]
+pub struct Displayed<T>(pub T);
+
+impl<T> Deref for Displayed<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<S, T> GraphQLType<S> for Displayed<T>
+where
+    S: ScalarValue,
+    T: fmt::Display,
+{
+    type Context = ();
+    type TypeInfo = ();
+
+    fn name(_: &()) -> Option<&str> {
+        Some("String")
+    }
+
+    fn meta<'r>(_: &(), registry: &mut Registry<'r, S>) -> MetaType<'r, S>
+    where
+        S: 'r,
+    {
+        registry.build_scalar_type::<String>(&()).into_meta()
+    }
+
+    fn resolve(
+        &self,
+        _: &(),
+        _: Option<&[Selection<S>]>,
+        _: &Executor<Self::Context, S>,
+    ) -> ExecutionResult<S> {
+        Ok(Value::scalar(self.0.to_string()))
+    }
+}
+
+impl<S, T> crate::GraphQLTypeAsync<S> for Displayed<T>
+where
+    S: ScalarValue + Send + Sync,
+    T: fmt::Display + Send + Sync,
+{
+    fn resolve_async<'a>(
+        &'a self,
+        info: &'a Self::TypeInfo,
+        selection_set: Option<&'a [Selection<S>]>,
+        executor: &'a Executor<Self::Context, S>,
+    ) -> crate::BoxFuture<'a, crate::ExecutionResult<S>> {
+        use futures::future;
+        future::FutureExt::boxed(future::ready(self.resolve(info, selection_set, executor)))
+    }
+}
+
+#[crate::graphql_scalar_internal(name = "Boolean")]
+impl<S> GraphQLScalar for bool
+where
+    S: ScalarValue,
+{
+    fn resolve(&self) -> Value {
         Value::scalar(*self)
     }
 
-    from_input_value(v: &InputValue) -> Option<bool> {
+    fn from_input_value(v: &InputValue) -> Option<bool> {
         match *v {
             InputValue::Scalar(ref b) => b.as_boolean(),
             _ => None,
         }
     }
 
-
-    from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S > {
+    fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
         // Bools are parsed on it's own. This should not hit this code path
         Err(ParseError::UnexpectedToken(Token::Scalar(value)))
     }
-});
+}
 
-graphql_scalar!(i32 as "Int" where Scalar = <S>{
-    resolve(&self) -> Value {
+#[crate::graphql_scalar_internal(name = "Int")]
+impl<S> GraphQLScalar for i32
+where
+    S: ScalarValue,
+{
+    fn resolve(&self) -> Value {
         Value::scalar(*self)
     }
 
-    from_input_value(v: &InputValue) -> Option<i32> {
+    fn from_input_value(v: &InputValue) -> Option<i32> {
         match *v {
             InputValue::Scalar(ref i) => i.as_int(),
             _ => None,
         }
     }
 
-    from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
+    fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
+        if let ScalarToken::Int(v) = value {
+            v.parse()
+                .map_err(|_| ParseError::UnexpectedToken(Token::Scalar(value)))
+                .map(|s: i32| s.into())
+        } else {
+            Err(ParseError::UnexpectedToken(Token::Scalar(value)))
+        }
+    }
+}
+
+/// A 64-bit signed integer
+///
+/// Values within the range of `Int` are also accepted as input.
+#[crate::graphql_scalar_internal(name = "Long")]
+impl GraphQLScalar for i64 {
+    fn resolve(&self) -> Value {
+        Value::scalar(*self)
+    }
+
+    fn from_input_value(v: &InputValue) -> Option<i64> {
+        match *v {
+            InputValue::Scalar(DefaultScalarValue::Long(i)) => Some(i),
+            InputValue::Scalar(DefaultScalarValue::Int(i)) => Some(i64::from(i)),
+            _ => None,
+        }
+    }
+
+    fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a> {
         if let ScalarToken::Int(v) = value {
             v.parse()
-             .map_err(|_| ParseError::UnexpectedToken(Token::Scalar(value)))
-             .map(|s: i32| s.into())
+                .map_err(|_| ParseError::UnexpectedToken(Token::Scalar(value)))
+                .map(DefaultScalarValue::Long)
         } else {
             Err(ParseError::UnexpectedToken(Token::Scalar(value)))
         }
     }
-});
+}
 
-graphql_scalar!(f64 as "Float" where Scalar = <S>{
-    resolve(&self) -> Value {
+#[crate::graphql_scalar_internal(name = "Float")]
+impl<S> GraphQLScalar for f64
+where
+    S: ScalarValue,
+{
+    fn resolve(&self) -> Value {
         Value::scalar(*self)
     }
 
-    from_input_value(v: &InputValue) -> Option<f64> {
+    fn from_input_value(v: &InputValue) -> Option<f64> {
         match *v {
             InputValue::Scalar(ref s) => s.as_float(),
             _ => None,
         }
     }
 
-    from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
+    fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
         match value {
-            ScalarToken::Int(v) | ScalarToken::Float(v) => {
-                v.parse()
-                 .map_err(|_| ParseError::UnexpectedToken(Token::Scalar(value)))
-                 .map(|s: f64| s.into())
-            }
-            ScalarToken::String(_) => {
-                Err(ParseError::UnexpectedToken(Token::Scalar(value)))
-            }
+            ScalarToken::Int(v) | ScalarToken::Float(v) => v
+                .parse()
+                .map_err(|_| ParseError::UnexpectedToken(Token::Scalar(value)))
+                .map(|s: f64| s.into()),
+            ScalarToken::String(_) => Err(ParseError::UnexpectedToken(Token::Scalar(value))),
         }
     }
-});
+}
 
 /// Utillity type to define read-only schemas
 ///
@@ -339,12 +475,19 @@ where
 /// Utillity type to define read-only schemas
 ///
 /// If you instantiate `RootNode` with this as the subscription,
-/// no subscriptions will be generated for the schema.
+/// no subscriptions will be generated for the schema, and subscription
+/// operations are rejected during validation.
 #[derive(Default)]
 pub struct EmptySubscription<T> {
     phantom: PhantomData<T>,
 }
 
+impl<T> fmt::Debug for EmptySubscription<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("EmptySubscription")
+    }
+}
+
 // This is safe due to never using `T`.
 unsafe impl<T> Send for EmptySubscription<T> {}
 
@@ -376,6 +519,16 @@ where
     }
 }
 
+impl<S, T> crate::GraphQLTypeAsync<S> for EmptySubscription<T>
+where
+    S: ScalarValue + Send + Sync,
+    Self: GraphQLType<S> + Send + Sync,
+    Self::TypeInfo: Send + Sync,
+    Self::Context: Send + Sync,
+    T: Send + Sync,
+{
+}
+
 impl<T, S> crate::GraphQLSubscriptionType<S> for EmptySubscription<T>
 where
     S: ScalarValue + Send + Sync + 'static,
@@ -447,4 +600,16 @@ mod tests {
         fn check_if_send<T: Send>() {}
         check_if_send::<EmptySubscription<()>>();
     }
+
+    #[test]
+    fn empty_subscription_resolves_asynchronously() {
+        fn check_if_async<T>()
+        where
+            T: crate::GraphQLTypeAsync<DefaultScalarValue>,
+            T::Context: Send + Sync,
+            T::TypeInfo: Send + Sync,
+        {
+        }
+        check_if_async::<EmptySubscription<()>>();
+    }
 }
@@ -1,9 +1,21 @@
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    task::{Context, Poll, Waker},
+};
+
+use futures::{Stream, StreamExt as _};
+
 use crate::{
     http::{GraphQLRequest, GraphQLResponse},
     parser::Spanning,
     types::base::{is_excluded, merge_key_into},
-    Arguments, BoxFuture, Executor, FieldError, GraphQLType, Object, ScalarValue, Selection, Value,
-    ValuesStream,
+    Arguments, BoxFuture, ExecutionError, Executor, FieldError, GraphQLType, Object, ScalarValue,
+    Selection, Value, ValuesStream,
 };
 
 /// Global subscription coordinator trait.
@@ -58,6 +70,187 @@ where
 /// server integration crates.
 pub trait SubscriptionConnection<'a, S>: futures::Stream<Item = GraphQLResponse<'a, S>> {}
 
+/// Number of events of a subscribed field waiting to be taken by the client
+///
+/// Obtained from [`Executor::buffer_pressure`] while resolving a subscription
+/// field. Clones share the same count, so a handle can be moved into the
+/// producer of the field's stream.
+#[derive(Clone, Debug, Default)]
+pub struct BufferPressure(Arc<AtomicUsize>);
+
+impl BufferPressure {
+    /// The number of buffered events
+    pub fn get(&self) -> usize {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    fn set(&self, depth: usize) {
+        self.0.store(depth, Ordering::Relaxed);
+    }
+}
+
+/// Slots shared by the fields of a subscription, bounding how many of their
+/// events are resolved at once
+///
+/// Set from [`RootNode::subscription_concurrency_limit`](crate::RootNode::subscription_concurrency_limit).
+#[derive(Clone)]
+pub(crate) struct ConcurrencyLimit(Arc<Mutex<ConcurrencySlots>>);
+
+struct ConcurrencySlots {
+    free: usize,
+    waiting: Vec<Waker>,
+}
+
+impl ConcurrencyLimit {
+    pub(crate) fn new(limit: usize) -> Self {
+        ConcurrencyLimit(Arc::new(Mutex::new(ConcurrencySlots {
+            free: limit,
+            waiting: vec![],
+        })))
+    }
+
+    /// Wait for a free slot, taken until the returned permit is dropped.
+    pub(crate) async fn acquire(&self) -> ConcurrencyPermit {
+        futures::future::poll_fn(|cx| {
+            let mut slots = self.0.lock().unwrap();
+            if slots.free > 0 {
+                slots.free -= 1;
+                Poll::Ready(ConcurrencyPermit(self.clone()))
+            } else {
+                slots.waiting.push(cx.waker().clone());
+                Poll::Pending
+            }
+        })
+        .await
+    }
+}
+
+/// A slot of a [`ConcurrencyLimit`], freed on drop
+pub(crate) struct ConcurrencyPermit(ConcurrencyLimit);
+
+impl Drop for ConcurrencyPermit {
+    fn drop(&mut self) {
+        let waiting = {
+            let mut slots = (self.0).0.lock().unwrap();
+            slots.free += 1;
+            std::mem::take(&mut slots.waiting)
+        };
+        // Wake every waiting event, as some may have been dropped since they
+        // registered. The ones not getting the slot register again.
+        for waker in waiting {
+            waker.wake();
+        }
+    }
+}
+
+/// What a buffered subscription field does with an event arriving while its
+/// buffer is full
+///
+/// Set on a field with [`Field::buffer`](crate::meta::Field::buffer), or with
+/// `#[graphql(buffer(size = 10, overflow = "drop_oldest"))]` on the resolver
+/// of a subscription.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BufferOverflow {
+    /// Leave the event in the field's stream until the client takes a buffered
+    /// one, so that a slow client slows the producer down.
+    Wait,
+    /// Drop the oldest buffered event to make room for the new one.
+    DropOldest,
+    /// Drop the buffered events, send an error and end the field's stream.
+    Error,
+}
+
+impl Default for BufferOverflow {
+    fn default() -> Self {
+        BufferOverflow::Wait
+    }
+}
+
+/// Maximum number of events pulled from a field's stream in a single poll, so
+/// that an endless ready stream can't starve the client of a dropping buffer
+const MAX_READY_PER_POLL: usize = 1024;
+
+/// Stream pulling up to `size` ready events of a field's stream into a buffer
+/// whenever it is polled, and reporting the depth of the buffer to the
+/// field's [`BufferPressure`]
+struct Buffered<'res, S> {
+    stream: ValuesStream<'res, S>,
+    buffer: VecDeque<<ValuesStream<'res, S> as Stream>::Item>,
+    size: usize,
+    overflow: BufferOverflow,
+    overflow_error: Option<ExecutionError<S>>,
+    pressure: BufferPressure,
+    done: bool,
+}
+
+// The inner stream is boxed and buffered events are never pinned.
+impl<'res, S> Unpin for Buffered<'res, S> {}
+
+impl<'res, S> Stream for Buffered<'res, S> {
+    type Item = <ValuesStream<'res, S> as Stream>::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context) -> Poll<Option<Self::Item>> {
+        let this = self.get_mut();
+        let mut pulled = 0;
+        while !this.done
+            && pulled < MAX_READY_PER_POLL
+            && (this.buffer.len() < this.size || this.overflow != BufferOverflow::Wait)
+        {
+            match this.stream.as_mut().poll_next(cx) {
+                Poll::Ready(Some(item)) => {
+                    pulled += 1;
+                    if this.buffer.len() < this.size {
+                        this.buffer.push_back(item);
+                    } else if this.overflow == BufferOverflow::DropOldest {
+                        this.buffer.pop_front();
+                        this.buffer.push_back(item);
+                    } else {
+                        this.buffer.clear();
+                        this.buffer.extend(this.overflow_error.take().map(Err));
+                        this.done = true;
+                    }
+                    this.pressure.set(this.buffer.len());
+                }
+                Poll::Ready(None) => this.done = true,
+                Poll::Pending => break,
+            }
+        }
+
+        let item = this.buffer.pop_front();
+        this.pressure.set(this.buffer.len());
+        match item {
+            Some(item) => Poll::Ready(Some(item)),
+            None if this.done => Poll::Ready(None),
+            None => Poll::Pending,
+        }
+    }
+}
+
+/// Buffer the stream of a field, if the field or the schema sets a buffer size
+fn buffer_field_stream<'res, S>(
+    value: Value<ValuesStream<'res, S>>,
+    size: Option<usize>,
+    overflow: BufferOverflow,
+    overflow_error: Option<ExecutionError<S>>,
+    pressure: BufferPressure,
+) -> Value<ValuesStream<'res, S>>
+where
+    S: Send + 'res,
+{
+    match (value, size) {
+        (Value::Scalar(stream), Some(size)) => Value::Scalar(Box::pin(Buffered {
+            stream,
+            buffer: VecDeque::with_capacity(size),
+            size,
+            overflow,
+            overflow_error,
+            pressure,
+            done: false,
+        })),
+        (value, _) => value,
+    }
+}
+
 /**
  This trait adds resolver logic with asynchronous subscription execution logic
  on GraphQL types. It should be used with `GraphQLType` in order to implement
@@ -84,7 +277,7 @@ where
     /// fragment needs to be resolved.
     ///
     /// For non-object types, the selection set will be `None` and default
-    /// implementation will panic.
+    /// implementation will return an error.
     fn resolve_into_stream<'s, 'i, 'ref_e, 'e, 'res, 'f>(
         &'s self,
         info: &'i Self::TypeInfo,
@@ -102,7 +295,8 @@ where
                 async move { Ok(resolve_selection_set_into_stream(self, info, executor).await) },
             )
         } else {
-            panic!("resolve_into_stream() must be implemented");
+            let error = not_implemented::<Self, S>(info);
+            Box::pin(async move { Err(error) })
         }
     }
 
@@ -114,12 +308,12 @@ where
     /// `Value<S>`, this method resolves each field into
     /// `Value<ValuesStream<S>>`.
     ///
-    /// The default implementation panics.
+    /// The default implementation returns an error.
     fn resolve_field_into_stream<'s, 'i, 'ft, 'args, 'e, 'ref_e, 'res, 'f>(
         &'s self,
-        _: &'i Self::TypeInfo,  // this subscription's type info
-        _: &'ft str,            // field's type name
-        _: Arguments<'args, S>, // field's arguments
+        info: &'i Self::TypeInfo, // this subscription's type info
+        _: &'ft str,              // field's type name
+        _: Arguments<'args, S>,   // field's arguments
         _: &'ref_e Executor<'ref_e, 'e, Self::Context, S>, // field's executor (subscription's sub-executor
                                                            // with current field's selection set)
     ) -> BoxFuture<'f, Result<Value<ValuesStream<'res, S>>, FieldError<S>>>
@@ -132,7 +326,8 @@ where
         'res: 'f,
         'e: 'res,
     {
-        panic!("resolve_field_into_stream must be implemented");
+        let error = not_implemented::<Self, S>(info);
+        Box::pin(async move { Err(error) })
     }
 
     /// This method is called by Self's `resolve_into_stream` default
@@ -143,7 +338,8 @@ where
     /// a single `Value<S>`, this method resolves each fragment into
     /// `Value<ValuesStream<S>>`.
     ///
-    /// The default implementation panics.
+    /// The default implementation returns an error for fragments on other
+    /// types than `Self`.
     fn resolve_into_type_stream<'s, 'i, 'tn, 'e, 'ref_e, 'res, 'f>(
         &'s self,
         info: &'i Self::TypeInfo, // this subscription's type info
@@ -163,12 +359,25 @@ where
             if Self::name(info) == Some(type_name) {
                 self.resolve_into_stream(info, executor).await
             } else {
-                panic!("resolve_into_type_stream must be implemented");
+                Err(not_implemented::<Self, S>(info))
             }
         })
     }
 }
 
+/// Error returned by the default implementations of
+/// [`GraphQLSubscriptionType`] for types that don't override them
+fn not_implemented<T, S>(info: &T::TypeInfo) -> FieldError<S>
+where
+    T: GraphQLType<S> + ?Sized,
+    S: ScalarValue,
+{
+    FieldError::from(format!(
+        "Subscription resolution is not implemented for type {}",
+        T::name(info).unwrap_or("<unnamed>"),
+    ))
+}
+
 /// Wrapper function around `resolve_selection_set_into_stream_recursive`.
 /// This wrapper is necessary because async fns can not be recursive.
 /// Panics if executor's current selection set is None.
@@ -215,15 +424,15 @@ where
         .expect("Executor's selection set is none");
 
     let mut object: Object<ValuesStream<'res, S>> = Object::with_capacity(selection_set.len());
+    let type_name = T::name(info).expect("Resolving named type's selection set");
     let meta_type = executor
         .schema()
-        .concrete_type_by_name(
-            T::name(info)
-                .expect("Resolving named type's selection set")
-                .as_ref(),
-        )
+        .concrete_type_by_name(type_name.as_ref())
         .expect("Type not found in schema");
 
+    let mut selections: Vec<BoxFuture<'_, StreamSelection<'_, 'res, S>>> =
+        Vec::with_capacity(selection_set.len());
+
     for selection in selection_set {
         match selection {
             Selection::Field(Spanning {
@@ -240,64 +449,112 @@ where
                 if f.name.item == "__typename" {
                     let typename =
                         Value::scalar(instance.concrete_type_name(executor.context(), info));
-                    object.add_field(
-                        response_name,
-                        Value::Scalar(Box::pin(futures::stream::once(async { Ok(typename) }))),
-                    );
+                    let value =
+                        Value::Scalar(Box::pin(futures::stream::once(async { Ok(typename) }))
+                            as ValuesStream<'res, S>);
+                    selections.push(Box::pin(async move {
+                        StreamSelection::Field(response_name, Some(value))
+                    }));
                     continue;
                 }
 
-                let meta_field = meta_type
-                    .field_by_name(f.name.item)
-                    .unwrap_or_else(|| {
-                        panic!(format!(
-                            "Field {} not found on type {:?}",
+                let meta_field = match meta_type.field_by_name(f.name.item) {
+                    Some(meta_field) => meta_field.clone(),
+                    None => {
+                        executor.push_unknown_field_error(
+                            type_name.as_ref(),
+                            response_name,
                             f.name.item,
-                            meta_type.name()
-                        ))
-                    })
-                    .clone();
+                            start_pos.clone(),
+                        );
+                        selections.push(Box::pin(async move {
+                            StreamSelection::Field(response_name, Some(Value::Null))
+                        }));
+                        continue;
+                    }
+                };
 
                 let exec_vars = executor.variables();
 
-                let sub_exec = executor.field_sub_executor(
-                    response_name,
-                    f.name.item,
-                    start_pos.clone(),
-                    f.selection_set.as_ref().map(|x| &x[..]),
-                );
-
-                let args = Arguments::new(
-                    f.arguments.as_ref().map(|m| {
-                        m.item
-                            .iter()
-                            .map(|&(ref k, ref v)| (k.item, v.item.clone().into_const(&exec_vars)))
-                            .collect()
-                    }),
-                    &meta_field.arguments,
-                );
-
-                let is_non_null = meta_field.field_type.is_non_null();
-
-                let res = instance
-                    .resolve_field_into_stream(info, f.name.item, args, &sub_exec)
-                    .await;
-
-                match res {
-                    Ok(Value::Null) if is_non_null => {
-                        return Value::Null;
-                    }
-                    Ok(v) => merge_key_into(&mut object, response_name, v),
-                    Err(e) => {
-                        sub_exec.push_error_at(e, start_pos.clone());
+                let pressure = BufferPressure::default();
+                let sub_exec = executor
+                    .field_sub_executor(
+                        response_name,
+                        f.name.item,
+                        start_pos.clone(),
+                        f.selection_set.as_ref().map(|x| &x[..]),
+                    )
+                    .with_buffer_pressure(pressure.clone());
+                let (buffer_size, overflow) = match meta_field.buffer_size {
+                    Some(size) => (Some(size), meta_field.buffer_overflow),
+                    None => (
+                        executor.schema().subscription_buffer_size,
+                        BufferOverflow::Wait,
+                    ),
+                };
+                let overflow_error = buffer_size
+                    .filter(|_| overflow == BufferOverflow::Error)
+                    .map(|size| {
+                        sub_exec.new_error(FieldError::from(format!(
+                            "Subscription buffer of {} events overflowed",
+                            size
+                        )))
+                    });
+
+                let is_non_null = meta_field.field_type.is_non_null()
+                    && !executor.schema().null_propagation_disabled;
+                let authorized = sub_exec
+                    .authorize_field(type_name.as_ref(), f.name.item)
+                    .and_then(|()| sub_exec.guard_field_directives(&f.directives));
+
+                selections.push(Box::pin(async move {
+                    let args = Arguments::new(
+                        f.arguments.as_ref().map(|m| {
+                            m.item
+                                .iter()
+                                .map(|&(ref k, ref v)| {
+                                    (k.item, v.item.clone().into_const(&exec_vars))
+                                })
+                                .collect()
+                        }),
+                        &meta_field.arguments,
+                        executor.schema(),
+                    );
 
-                        if meta_field.field_type.is_non_null() {
-                            return Value::Null;
+                    let res = match authorized {
+                        Ok(()) => {
+                            instance
+                                .resolve_field_into_stream(info, f.name.item, args, &sub_exec)
+                                .await
                         }
+                        Err(e) => Err(e),
+                    };
 
-                        object.add_field(f.name.item, Value::Null);
+                    match res {
+                        Ok(Value::Null) if is_non_null => {
+                            StreamSelection::Field(response_name, None)
+                        }
+                        Ok(v) => StreamSelection::Field(
+                            response_name,
+                            Some(buffer_field_stream(
+                                sub_exec.apply_field_directives_to_stream(&f.directives, v),
+                                buffer_size,
+                                overflow,
+                                overflow_error,
+                                pressure,
+                            )),
+                        ),
+                        Err(e) => {
+                            sub_exec.push_error_at(e, start_pos.clone());
+
+                            if is_non_null {
+                                StreamSelection::Field(response_name, None)
+                            } else {
+                                StreamSelection::Field(response_name, Some(Value::Null))
+                            }
+                        }
                     }
-                }
+                }));
             }
 
             Selection::FragmentSpread(Spanning {
@@ -318,25 +575,26 @@ where
                     Some(&fragment.selection_set[..]),
                 );
 
-                let obj = instance
-                    .resolve_into_type_stream(info, fragment.type_condition.item, &sub_exec)
-                    .await;
-
-                match obj {
-                    Ok(val) => {
-                        match val {
-                            Value::Object(o) => {
-                                for (k, v) in o {
-                                    merge_key_into(&mut object, &k, v);
-                                }
+                selections.push(Box::pin(async move {
+                    let obj = instance
+                        .resolve_into_type_stream(info, fragment.type_condition.item, &sub_exec)
+                        .await;
+
+                    match obj {
+                        Ok(val) => {
+                            match val {
+                                Value::Object(o) => StreamSelection::Fragment(Some(o)),
+                                // since this was a wrapper of current function,
+                                // we'll rather get an object or nothing
+                                _ => unreachable!(),
                             }
-                            // since this was a wrapper of current function,
-                            // we'll rather get an object or nothing
-                            _ => unreachable!(),
+                        }
+                        Err(e) => {
+                            sub_exec.push_error_at(e, start_pos.clone());
+                            StreamSelection::Fragment(None)
                         }
                     }
-                    Err(e) => sub_exec.push_error_at(e, start_pos.clone()),
-                }
+                }));
             }
             Selection::InlineFragment(Spanning {
                 item: ref fragment,
@@ -352,36 +610,59 @@ where
                     Some(&fragment.selection_set[..]),
                 );
 
-                if let Some(ref type_condition) = fragment.type_condition {
-                    let sub_result = instance
-                        .resolve_into_type_stream(info, type_condition.item, &sub_exec)
-                        .await;
+                let type_name = match fragment.type_condition {
+                    Some(ref type_condition) => type_condition.item,
+                    None => match meta_type.name() {
+                        Some(type_name) => type_name,
+                        None => return Value::Null,
+                    },
+                };
 
-                    if let Ok(Value::Object(obj)) = sub_result {
-                        for (k, v) in obj {
-                            merge_key_into(&mut object, &k, v);
-                        }
-                    } else if let Err(e) = sub_result {
-                        sub_exec.push_error_at(e, start_pos.clone());
-                    }
-                } else if let Some(type_name) = meta_type.name() {
+                selections.push(Box::pin(async move {
                     let sub_result = instance
                         .resolve_into_type_stream(info, type_name, &sub_exec)
                         .await;
 
-                    if let Ok(Value::Object(obj)) = sub_result {
-                        for (k, v) in obj {
-                            merge_key_into(&mut object, &k, v);
+                    match sub_result {
+                        Ok(Value::Object(obj)) => StreamSelection::Fragment(Some(obj)),
+                        Ok(_) => StreamSelection::Fragment(None),
+                        Err(e) => {
+                            sub_exec.push_error_at(e, start_pos.clone());
+                            StreamSelection::Fragment(None)
                         }
-                    } else if let Err(e) = sub_result {
-                        sub_exec.push_error_at(e, start_pos.clone());
                     }
-                } else {
-                    return Value::Null;
+                }));
+            }
+        }
+    }
+
+    let limit = executor
+        .schema()
+        .subscription_concurrency_limit
+        .unwrap_or_else(|| selections.len().max(1));
+    let mut resolved = futures::stream::iter(selections).buffered(limit);
+
+    while let Some(selection) = resolved.next().await {
+        match selection {
+            StreamSelection::Field(name, Some(value)) => merge_key_into(&mut object, name, value),
+            StreamSelection::Field(_, None) => return Value::Null,
+            StreamSelection::Fragment(Some(obj)) => {
+                for (k, v) in obj {
+                    merge_key_into(&mut object, &k, v);
                 }
             }
+            StreamSelection::Fragment(None) => {}
         }
     }
 
     Value::Object(object)
 }
+
+/// Outcome of resolving a single selection of a subscription selection set.
+enum StreamSelection<'a, 'res, S> {
+    /// A field under its response name, or `None` if a non-null field
+    /// resolved to null.
+    Field(&'a str, Option<Value<ValuesStream<'res, S>>>),
+    /// The fields of a fragment, or `None` if it could not be resolved.
+    Fragment(Option<Object<ValuesStream<'res, S>>>),
+}
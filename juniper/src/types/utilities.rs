@@ -1,5 +1,6 @@
 use crate::{
-    ast::InputValue,
+    ast::{InputValue, Type},
+    parser::Spanning,
     schema::{
         meta::{EnumMeta, InputObjectMeta, MetaType},
         model::{SchemaType, TypeType},
@@ -48,6 +49,14 @@ where
                         false
                     }
                 }
+                // Scalars like `JSON` accept lists and objects as well.
+                ref v @ InputValue::List(_) | ref v @ InputValue::Object(_) if t.is_leaf() => {
+                    if let Some(parse_fn) = t.input_value_parse_fn() {
+                        parse_fn(v)
+                    } else {
+                        false
+                    }
+                }
                 InputValue::List(_) => false,
                 InputValue::Object(ref obj) => {
                     if let MetaType::InputObject(InputObjectMeta {
@@ -57,7 +66,7 @@ where
                         let mut remaining_required_fields = input_fields
                             .iter()
                             .filter_map(|f| {
-                                if f.arg_type.is_non_null() {
+                                if f.arg_type.is_non_null() && f.default_value.is_none() {
                                     Some(&f.name)
                                 } else {
                                     None
@@ -88,3 +97,63 @@ where
         }
     }
 }
+
+/// Fill the fields absent from the input objects in `value` with their
+/// default values, as declared by the input object types of `value_type`.
+///
+/// `value` must be a valid value of `value_type`: fields unknown to their
+/// input object type are left untouched, as validation rejects them.
+pub(crate) fn coerce_input_value<S>(
+    schema: &SchemaType<S>,
+    value_type: &Type,
+    value: &mut InputValue<S>,
+) where
+    S: ScalarValue,
+{
+    let name = match *value_type {
+        Type::List(ref inner) | Type::NonNullList(ref inner) => {
+            match *value {
+                InputValue::List(ref mut items) => {
+                    for item in items {
+                        coerce_input_value(schema, inner, &mut item.item);
+                    }
+                }
+                ref mut v => coerce_input_value(schema, inner, v),
+            }
+            return;
+        }
+        Type::Named(ref name) | Type::NonNullNamed(ref name) => name,
+    };
+
+    let (fields, input_fields) = match (value, schema.concrete_type_by_name(name)) {
+        (
+            &mut InputValue::Object(ref mut fields),
+            Some(&MetaType::InputObject(InputObjectMeta {
+                ref input_fields, ..
+            })),
+        ) => (fields, input_fields),
+        _ => return,
+    };
+
+    for &mut (ref key, ref mut value) in fields.iter_mut() {
+        if let Some(input_field) = input_fields.iter().find(|f| f.name == key.item) {
+            coerce_input_value(schema, &input_field.arg_type, &mut value.item);
+        }
+    }
+
+    for input_field in input_fields {
+        if let Some(ref default_value) = input_field.default_value {
+            if fields
+                .iter()
+                .all(|&(ref key, _)| key.item != input_field.name)
+            {
+                let mut default_value = default_value.clone();
+                coerce_input_value(schema, &input_field.arg_type, &mut default_value);
+                fields.push((
+                    Spanning::unlocated(input_field.name.clone()),
+                    Spanning::unlocated(default_value),
+                ));
+            }
+        }
+    }
+}
@@ -1,4 +1,39 @@
-use std::borrow::Cow;
+use std::{
+    borrow::Cow,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Remaining stack, in bytes, below which `ensure_sufficient_stack` switches
+/// to a new stack segment.
+const RED_ZONE: usize = 100 * 1024;
+
+/// Size, in bytes, of each stack segment `ensure_sufficient_stack` allocates.
+const STACK_PER_RECURSION: usize = 1024 * 1024;
+
+/// Run `f`, switching to a new stack segment allocated on the heap if the
+/// current stack is close to running out.
+///
+/// Wrap recursive steps over queries and results in this, so deeply nested
+/// ones do not overflow the stack.
+pub(crate) fn ensure_sufficient_stack<R>(f: impl FnOnce() -> R) -> R {
+    stacker::maybe_grow(RED_ZONE, STACK_PER_RECURSION, f)
+}
+
+/// Future polling the wrapped future with `ensure_sufficient_stack`.
+///
+/// Polling nested futures recurses as deep as the futures are nested.
+pub(crate) struct SufficientStack<F: ?Sized>(pub(crate) Pin<Box<F>>);
+
+impl<F: Future + ?Sized> Future for SufficientStack<F> {
+    type Output = F::Output;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<F::Output> {
+        let fut = self.0.as_mut();
+        ensure_sufficient_stack(|| fut.poll(cx))
+    }
+}
 
 /// Convert string to camel case.
 ///
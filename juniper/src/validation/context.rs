@@ -29,7 +29,7 @@ pub struct ValidatorContext<'a, S: Debug + 'a> {
 }
 
 impl RuleError {
-    #[doc(hidden)]
+    /// Create an error reported at `locations` of the document
     pub fn new(message: &str, locations: &[SourcePosition]) -> RuleError {
         RuleError {
             message: message.to_owned(),
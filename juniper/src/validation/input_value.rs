@@ -1,13 +1,18 @@
 use std::{collections::HashSet, fmt};
 
 use crate::{
-    ast::{InputValue, Operation, VariableDefinitions},
+    ast::{
+        Definition, Directive, Document, InputValue, Operation, Selection, Type,
+        VariableDefinitions,
+    },
     executor::Variables,
     parser::{SourcePosition, Spanning},
     schema::{
         meta::{EnumMeta, InputObjectMeta, MetaType, ScalarMeta},
         model::{SchemaType, TypeType},
     },
+    types::utilities::coerce_input_value,
+    util::ensure_sufficient_stack,
     validation::RuleError,
     value::ScalarValue,
 };
@@ -37,6 +42,123 @@ where
     errs
 }
 
+/// Checks that every variable passed as the `if` argument of a `@skip` or
+/// `@include` directive of the operation holds a boolean.
+///
+/// `values` must already contain the default values of the operation's
+/// variables. Document validation lets nullable variables with a default
+/// value be used in these positions, so an explicit `null` is only rejected
+/// here.
+pub(crate) fn validate_directive_conditions<S>(
+    document: &Document<S>,
+    operation: &Spanning<Operation<S>>,
+    values: &Variables<S>,
+) -> Vec<RuleError>
+where
+    S: ScalarValue,
+{
+    let mut checker = DirectiveConditions {
+        document,
+        values,
+        visited_fragments: HashSet::new(),
+        errors: vec![],
+    };
+    checker.selection_set(&operation.item.selection_set);
+
+    let mut errs = checker.errors;
+    errs.sort();
+    errs
+}
+
+struct DirectiveConditions<'a, S> {
+    document: &'a Document<'a, S>,
+    values: &'a Variables<S>,
+    visited_fragments: HashSet<&'a str>,
+    errors: Vec<RuleError>,
+}
+
+impl<'a, S> DirectiveConditions<'a, S>
+where
+    S: ScalarValue,
+{
+    fn selection_set(&mut self, selection_set: &'a [Selection<'a, S>]) {
+        ensure_sufficient_stack(|| {
+            for selection in selection_set {
+                match *selection {
+                    Selection::Field(Spanning { item: ref f, .. }) => {
+                        self.directives(&f.directives);
+                        if let Some(ref selection_set) = f.selection_set {
+                            self.selection_set(selection_set);
+                        }
+                    }
+                    Selection::FragmentSpread(Spanning {
+                        item: ref spread, ..
+                    }) => {
+                        self.directives(&spread.directives);
+                        if !self.visited_fragments.insert(spread.name.item) {
+                            continue;
+                        }
+
+                        let fragment = self.document.iter().find_map(|def| match *def {
+                            Definition::Fragment(Spanning { item: ref f, .. })
+                                if f.name.item == spread.name.item =>
+                            {
+                                Some(f)
+                            }
+                            _ => None,
+                        });
+                        if let Some(fragment) = fragment {
+                            self.selection_set(&fragment.selection_set);
+                        }
+                    }
+                    Selection::InlineFragment(Spanning {
+                        item: ref fragment, ..
+                    }) => {
+                        self.directives(&fragment.directives);
+                        self.selection_set(&fragment.selection_set);
+                    }
+                }
+            }
+        })
+    }
+
+    fn directives(&mut self, directives: &Option<Vec<Spanning<Directive<S>>>>) {
+        for directive in directives.iter().flatten() {
+            let directive_name = directive.item.name.item;
+            if directive_name != "skip" && directive_name != "include" {
+                continue;
+            }
+
+            let condition = directive
+                .item
+                .arguments
+                .as_ref()
+                .and_then(|args| args.item.get("if"));
+
+            if let Some(Spanning {
+                item: InputValue::Variable(ref var_name),
+                ref start,
+                ..
+            }) = condition
+            {
+                let found = match self.values.get(var_name) {
+                    Some(value) if value.convert::<bool>().is_some() => continue,
+                    Some(value) if !value.is_null() => format!("found {}", value),
+                    _ => "found null".to_owned(),
+                };
+
+                self.errors.push(RuleError::new(
+                    &format!(
+                        r#"Variable "${}" got invalid value; expected type "Boolean!". In argument "if" of directive "@{}": Expected "Boolean!", {}."#,
+                        var_name, directive_name, found,
+                    ),
+                    &[*start],
+                ));
+            }
+        }
+    }
+}
+
 fn validate_var_defs<S>(
     values: &Variables<S>,
     var_defs: &VariableDefinitions<S>,
@@ -63,6 +185,7 @@ fn validate_var_defs<S>(
                     errors.append(&mut unify_value(
                         name.item,
                         &name.start,
+                        &def.var_type.item,
                         v,
                         &ct,
                         schema,
@@ -81,6 +204,7 @@ fn validate_var_defs<S>(
 fn unify_value<'a, S>(
     var_name: &str,
     var_pos: &SourcePosition,
+    var_type: &Type,
     value: &InputValue<S>,
     meta_type: &TypeType<'a, S>,
     schema: &SchemaType<S>,
@@ -97,12 +221,13 @@ where
                 errors.push(unification_error(
                     var_name,
                     var_pos,
+                    var_type,
                     &path,
                     &format!(r#"Expected "{}", found null"#, meta_type),
                 ));
             } else {
                 errors.append(&mut unify_value(
-                    var_name, var_pos, value, inner, schema, path,
+                    var_name, var_pos, var_type, value, inner, schema, path,
                 ));
             }
         }
@@ -118,6 +243,7 @@ where
                         errors.append(&mut unify_value(
                             var_name,
                             var_pos,
+                            var_type,
                             v,
                             inner,
                             schema,
@@ -126,7 +252,7 @@ where
                     }
                 }
                 _ => errors.append(&mut unify_value(
-                    var_name, var_pos, value, inner, schema, path,
+                    var_name, var_pos, var_type, value, inner, schema, path,
                 )),
             }
         }
@@ -137,21 +263,31 @@ where
             }
 
             match *mt {
-                MetaType::Scalar(ref sm) => {
-                    errors.append(&mut unify_scalar(var_name, var_pos, value, sm, &path))
-                }
-                MetaType::Enum(ref em) => {
-                    errors.append(&mut unify_enum(var_name, var_pos, value, em, &path))
-                }
+                MetaType::Scalar(ref sm) => errors.append(&mut unify_scalar(
+                    var_name, var_pos, var_type, value, sm, &path,
+                )),
+                MetaType::Enum(ref em) => errors.append(&mut unify_enum(
+                    var_name, var_pos, var_type, value, em, &path,
+                )),
                 MetaType::InputObject(ref iom) => {
-                    let mut e = unify_input_object(var_name, var_pos, value, iom, schema, &path);
+                    let mut e =
+                        unify_input_object(var_name, var_pos, var_type, value, iom, schema, &path);
                     if e.is_empty() {
                         // All the fields didn't have errors, see if there is an
-                        // overall error when parsing the input value.
-                        if !(iom.try_parse_fn)(value) {
+                        // overall error when parsing the input value, once the
+                        // absent fields are filled with their defaults like
+                        // the executor does.
+                        let mut coerced = value.clone();
+                        coerce_input_value(
+                            schema,
+                            &Type::Named(iom.name.as_ref().into()),
+                            &mut coerced,
+                        );
+                        if !(iom.try_parse_fn)(&coerced) {
                             errors.push(unification_error(
                                 var_name,
                                 var_pos,
+                                var_type,
                                 &path,
                                 &format!(
                                     r#"Expected input of type "{}". Got: "{}""#,
@@ -173,45 +309,33 @@ where
 fn unify_scalar<'a, S>(
     var_name: &str,
     var_pos: &SourcePosition,
+    var_type: &Type,
     value: &InputValue<S>,
     meta: &ScalarMeta<S>,
     path: &Path<'a>,
 ) -> Vec<RuleError>
 where
-    S: fmt::Debug,
+    S: ScalarValue,
 {
-    let mut errors: Vec<RuleError> = vec![];
-
+    // Lists and objects are left to the scalar to accept or not, as scalars
+    // like `JSON` read them.
     if !(meta.try_parse_fn)(value) {
         return vec![unification_error(
             var_name,
             var_pos,
+            var_type,
             path,
-            &format!(r#"Expected "{}""#, meta.name),
+            &format!(r#"Expected "{}", found {}"#, meta.name, value),
         )];
     }
 
-    match *value {
-        InputValue::List(_) => errors.push(unification_error(
-            var_name,
-            var_pos,
-            path,
-            &format!(r#"Expected "{}", found list"#, meta.name),
-        )),
-        InputValue::Object(_) => errors.push(unification_error(
-            var_name,
-            var_pos,
-            path,
-            &format!(r#"Expected "{}", found object"#, meta.name),
-        )),
-        _ => (),
-    }
-    errors
+    vec![]
 }
 
 fn unify_enum<'a, S>(
     var_name: &str,
     var_pos: &SourcePosition,
+    var_type: &Type,
     value: &InputValue<S>,
     meta: &EnumMeta<S>,
     path: &Path<'a>,
@@ -229,8 +353,9 @@ where
                     errors.push(unification_error(
                         var_name,
                         var_pos,
+                        var_type,
                         path,
-                        &format!(r#"Invalid value for enum "{}""#, meta.name),
+                        &invalid_enum_value_message(meta),
                     ))
                 }
             }
@@ -240,14 +365,16 @@ where
                 errors.push(unification_error(
                     var_name,
                     var_pos,
+                    var_type,
                     path,
-                    &format!(r#"Invalid value for enum "{}""#, meta.name),
+                    &invalid_enum_value_message(meta),
                 ))
             }
         }
         _ => errors.push(unification_error(
             var_name,
             var_pos,
+            var_type,
             path,
             &format!(r#"Expected "{}", found not a string or enum"#, meta.name),
         )),
@@ -255,9 +382,23 @@ where
     errors
 }
 
+fn invalid_enum_value_message<S>(meta: &EnumMeta<S>) -> String {
+    let values = meta
+        .values
+        .iter()
+        .map(|v| v.name.as_str())
+        .collect::<Vec<_>>();
+    format!(
+        r#"Invalid value for enum "{}", expected one of {}"#,
+        meta.name,
+        values.join(", ")
+    )
+}
+
 fn unify_input_object<'a, S>(
     var_name: &str,
     var_pos: &SourcePosition,
+    var_type: &Type,
     value: &InputValue<S>,
     meta: &InputObjectMeta<S>,
     schema: &SchemaType<S>,
@@ -282,6 +423,7 @@ where
                     errors.append(&mut unify_value(
                         var_name,
                         var_pos,
+                        var_type,
                         value,
                         &schema.make_type(&input_field.arg_type),
                         schema,
@@ -290,10 +432,14 @@ where
                 }
             }
 
-            if !has_value && input_field.arg_type.is_non_null() {
+            if !has_value
+                && input_field.arg_type.is_non_null()
+                && input_field.default_value.is_none()
+            {
                 errors.push(unification_error(
                     var_name,
                     var_pos,
+                    var_type,
                     &Path::ObjectField(&input_field.name, path),
                     &format!(r#"Expected "{}", found null"#, input_field.arg_type),
                 ));
@@ -304,6 +450,7 @@ where
             errors.push(unification_error(
                 var_name,
                 var_pos,
+                var_type,
                 &Path::ObjectField(key, path),
                 "Unknown field",
             ));
@@ -312,6 +459,7 @@ where
         errors.push(unification_error(
             var_name,
             var_pos,
+            var_type,
             path,
             &format!(r#"Expected "{}", found not an object"#, meta.name),
         ));
@@ -329,13 +477,14 @@ where
 fn unification_error<'a>(
     var_name: &str,
     var_pos: &SourcePosition,
+    var_type: &Type,
     path: &Path<'a>,
     message: &str,
 ) -> RuleError {
     RuleError::new(
         &format!(
-            r#"Variable "${}" got invalid value. {}{}."#,
-            var_name, path, message,
+            r#"Variable "${}" got invalid value; expected type "{}". {}{}."#,
+            var_name, var_type, path, message,
         ),
         &[*var_pos],
     )
@@ -10,7 +10,6 @@ mod visitor;
 #[cfg(test)]
 pub(crate) mod test_harness;
 
-pub(crate) use self::rules::visit_all_rules;
 pub use self::{
     context::{RuleError, ValidatorContext},
     input_value::validate_input_values,
@@ -18,6 +17,10 @@ pub use self::{
     traits::Visitor,
     visitor::visit,
 };
+pub(crate) use self::{
+    input_value::validate_directive_conditions,
+    rules::{visit_all_rules, visit_fragment_cycles},
+};
 
 #[cfg(test)]
 pub use self::test_harness::{
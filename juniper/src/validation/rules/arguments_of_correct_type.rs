@@ -1,7 +1,7 @@
 use crate::{
     ast::{Directive, Field, InputValue},
     parser::Spanning,
-    schema::meta::Argument,
+    schema::meta::{Argument, EnumMeta, MetaType},
     types::utilities::is_valid_literal_value,
     validation::{ValidatorContext, Visitor},
     value::ScalarValue,
@@ -58,10 +58,19 @@ where
             let meta_type = ctx.schema.make_type(&argument_meta.arg_type);
 
             if !is_valid_literal_value(ctx.schema, &meta_type, &arg_value.item) {
-                ctx.report_error(
-                    &error_message(arg_name.item, &format!("{}", argument_meta.arg_type)),
-                    &[arg_value.start],
-                );
+                let type_name = format!("{}", argument_meta.arg_type);
+                let message = match ctx
+                    .schema
+                    .concrete_type_by_name(argument_meta.arg_type.innermost_name())
+                {
+                    Some(&MetaType::Enum(EnumMeta { ref values, .. })) => enum_error_message(
+                        arg_name.item,
+                        &type_name,
+                        &values.iter().map(|v| v.name.as_str()).collect::<Vec<_>>(),
+                    ),
+                    _ => error_message(arg_name.item, &type_name),
+                };
+                ctx.report_error(&message, &[arg_value.start]);
             }
         }
     }
@@ -74,9 +83,17 @@ fn error_message(arg_name: &str, type_name: &str) -> String {
     )
 }
 
+fn enum_error_message(arg_name: &str, type_name: &str, values: &[&str]) -> String {
+    format!(
+        "{}: one of {}",
+        error_message(arg_name, type_name),
+        values.join(", ")
+    )
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{error_message, factory};
+    use super::{enum_error_message, error_message, factory};
 
     use crate::{
         parser::SourcePosition,
@@ -564,7 +581,7 @@ mod tests {
             }
         "#,
             &[RuleError::new(
-                &error_message("dogCommand", "DogCommand"),
+                &enum_error_message("dogCommand", "DogCommand", &["SIT", "HEEL", "DOWN"]),
                 &[SourcePosition::new(79, 3, 44)],
             )],
         );
@@ -582,7 +599,7 @@ mod tests {
             }
         "#,
             &[RuleError::new(
-                &error_message("dogCommand", "DogCommand"),
+                &enum_error_message("dogCommand", "DogCommand", &["SIT", "HEEL", "DOWN"]),
                 &[SourcePosition::new(79, 3, 44)],
             )],
         );
@@ -600,7 +617,7 @@ mod tests {
             }
         "#,
             &[RuleError::new(
-                &error_message("dogCommand", "DogCommand"),
+                &enum_error_message("dogCommand", "DogCommand", &["SIT", "HEEL", "DOWN"]),
                 &[SourcePosition::new(79, 3, 44)],
             )],
         );
@@ -618,7 +635,7 @@ mod tests {
             }
         "#,
             &[RuleError::new(
-                &error_message("dogCommand", "DogCommand"),
+                &enum_error_message("dogCommand", "DogCommand", &["SIT", "HEEL", "DOWN"]),
                 &[SourcePosition::new(79, 3, 44)],
             )],
         );
@@ -636,7 +653,7 @@ mod tests {
             }
         "#,
             &[RuleError::new(
-                &error_message("dogCommand", "DogCommand"),
+                &enum_error_message("dogCommand", "DogCommand", &["SIT", "HEEL", "DOWN"]),
                 &[SourcePosition::new(79, 3, 44)],
             )],
         );
@@ -654,7 +671,7 @@ mod tests {
             }
         "#,
             &[RuleError::new(
-                &error_message("dogCommand", "DogCommand"),
+                &enum_error_message("dogCommand", "DogCommand", &["SIT", "HEEL", "DOWN"]),
                 &[SourcePosition::new(79, 3, 44)],
             )],
         );
@@ -0,0 +1,100 @@
+use crate::{
+    ast::{Operation, OperationType},
+    parser::Spanning,
+    validation::{ValidatorContext, Visitor},
+    value::ScalarValue,
+};
+
+pub struct KnownOperationTypes;
+
+pub fn factory() -> KnownOperationTypes {
+    KnownOperationTypes
+}
+
+impl<'a, S> Visitor<'a, S> for KnownOperationTypes
+where
+    S: ScalarValue,
+{
+    fn enter_operation_definition(
+        &mut self,
+        ctx: &mut ValidatorContext<'a, S>,
+        op: &'a Spanning<Operation<S>>,
+    ) {
+        let (supported, operation_type) = match op.item.operation_type {
+            OperationType::Query => return,
+            OperationType::Mutation => (ctx.schema.concrete_mutation_type().is_some(), "mutation"),
+            OperationType::Subscription => (
+                ctx.schema.concrete_subscription_type().is_some(),
+                "subscription",
+            ),
+        };
+
+        if !supported {
+            ctx.report_error(&error_message(operation_type), &[op.start]);
+        }
+    }
+}
+
+fn error_message(operation_type: &str) -> String {
+    format!(
+        "The {} operation is not supported by the schema",
+        operation_type
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use crate::{
+        parser::SourcePosition,
+        types::scalars::EmptyMutation,
+        validation::{
+            expect_fails_rule, expect_fails_rule_with_schema, expect_passes_rule,
+            test_harness::QueryRoot, RuleError,
+        },
+        value::DefaultScalarValue,
+    };
+
+    #[test]
+    fn supported_operations() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          query Q { dog { name } }
+          mutation M { testInput(input: {}) }
+          subscription S { dog { name } }
+        "#,
+        );
+    }
+
+    #[test]
+    fn unsupported_mutation() {
+        expect_fails_rule_with_schema::<_, _, _, _, DefaultScalarValue>(
+            QueryRoot,
+            EmptyMutation::<()>::new(),
+            factory,
+            r#"
+          mutation { __schema { queryType { name } } }
+        "#,
+            &[RuleError::new(
+                &error_message("mutation"),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn unsupported_subscription() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+          subscription { __typename }
+        "#,
+            &[RuleError::new(
+                &error_message("subscription"),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+}
@@ -0,0 +1,246 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ast::{Document, Field, Fragment, FragmentSpread, Operation},
+    parser::{SourcePosition, Spanning},
+    validation::{ValidatorContext, Visitor},
+    value::ScalarValue,
+};
+
+use super::max_query_depth::Scope;
+
+#[derive(Default)]
+struct ScopeAliases<'a> {
+    aliases: usize,
+    spreads: Vec<&'a str>,
+}
+
+pub struct MaxQueryAliases<'a> {
+    max_aliases: Option<usize>,
+    aliases: HashMap<Scope<'a>, ScopeAliases<'a>>,
+    operations: Vec<(Scope<'a>, SourcePosition)>,
+    current_scope: Option<Scope<'a>>,
+}
+
+pub fn factory<'a>(max_aliases: Option<usize>) -> MaxQueryAliases<'a> {
+    MaxQueryAliases {
+        max_aliases,
+        aliases: HashMap::new(),
+        operations: Vec::new(),
+        current_scope: None,
+    }
+}
+
+impl<'a> MaxQueryAliases<'a> {
+    /// Aliased fields of the scope, counting the ones of a fragment once per
+    /// spread of it
+    fn aliases_of(
+        &self,
+        scope: &Scope<'a>,
+        visiting: &mut HashSet<&'a str>,
+        fragments: &mut HashMap<&'a str, usize>,
+    ) -> usize {
+        let aliases = match self.aliases.get(scope) {
+            Some(aliases) => aliases,
+            None => return 0,
+        };
+
+        let mut total = aliases.aliases;
+        for &fragment in &aliases.spreads {
+            let spread = match fragments.get(fragment).cloned() {
+                Some(spread) => spread,
+                // Fragment cycles are reported by `NoFragmentCycles`.
+                None if !visiting.insert(fragment) => 0,
+                None => {
+                    let spread = self.aliases_of(&Scope::Fragment(fragment), visiting, fragments);
+                    visiting.remove(fragment);
+                    fragments.insert(fragment, spread);
+                    spread
+                }
+            };
+            total = total.saturating_add(spread);
+        }
+        total
+    }
+}
+
+impl<'a, S> Visitor<'a, S> for MaxQueryAliases<'a>
+where
+    S: ScalarValue,
+{
+    fn exit_document(&mut self, ctx: &mut ValidatorContext<'a, S>, _: &'a Document<S>) {
+        let max_aliases = match self.max_aliases {
+            Some(max_aliases) => max_aliases,
+            None => return,
+        };
+
+        let mut fragments = HashMap::new();
+        for (scope, pos) in &self.operations {
+            let aliases = self.aliases_of(scope, &mut HashSet::new(), &mut fragments);
+            if aliases > max_aliases {
+                ctx.report_error(&error_message(aliases, max_aliases), &[*pos]);
+            }
+        }
+    }
+
+    fn enter_operation_definition(
+        &mut self,
+        _: &mut ValidatorContext<'a, S>,
+        op: &'a Spanning<Operation<S>>,
+    ) {
+        let op_name = op.item.name.as_ref().map(|s| s.item);
+        self.current_scope = Some(Scope::Operation(op_name));
+        self.operations.push((Scope::Operation(op_name), op.start));
+    }
+
+    fn enter_fragment_definition(
+        &mut self,
+        _: &mut ValidatorContext<'a, S>,
+        f: &'a Spanning<Fragment<S>>,
+    ) {
+        self.current_scope = Some(Scope::Fragment(f.item.name.item));
+    }
+
+    fn enter_field(&mut self, _: &mut ValidatorContext<'a, S>, field: &'a Spanning<Field<S>>) {
+        if field.item.alias.is_none() {
+            return;
+        }
+
+        if let Some(ref scope) = self.current_scope {
+            self.aliases.entry(scope.clone()).or_default().aliases += 1;
+        }
+    }
+
+    fn enter_fragment_spread(
+        &mut self,
+        _: &mut ValidatorContext<'a, S>,
+        spread: &'a Spanning<FragmentSpread<S>>,
+    ) {
+        if let Some(ref scope) = self.current_scope {
+            self.aliases
+                .entry(scope.clone())
+                .or_default()
+                .spreads
+                .push(spread.item.name.item);
+        }
+    }
+}
+
+fn error_message(aliases: usize, max_aliases: usize) -> String {
+    format!(
+        "Operation has {} aliased fields, which exceeds the maximum of {}",
+        aliases, max_aliases
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use crate::{
+        parser::SourcePosition,
+        validation::{expect_fails_rule, expect_passes_rule, RuleError},
+        value::DefaultScalarValue,
+    };
+
+    #[test]
+    fn no_limit() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(None),
+            r#"
+          {
+            dog {
+              a: name
+              b: name
+              c: name
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn aliases_at_limit() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(2)),
+            r#"
+          {
+            first: dog {
+              name
+              nickname: name
+            }
+            dog {
+              name
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn aliases_over_limit_across_selection_sets() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(2)),
+            r#"
+          {
+            dog {
+              a: name
+              ... on Dog {
+                b: name
+              }
+            }
+            human {
+              c: name
+            }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(3, 2),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn aliases_counted_per_fragment_spread() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(3)),
+            r#"
+          query Names {
+            dog {
+              ...names
+              ...names
+            }
+          }
+
+          fragment names on Dog {
+            a: name
+            b: name
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(4, 3),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn fragment_cycles_terminate() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(3)),
+            r#"
+          {
+            dog {
+              ...names
+            }
+          }
+
+          fragment names on Dog {
+            a: name
+            ...names
+          }
+        "#,
+        );
+    }
+}
@@ -0,0 +1,313 @@
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    ast::{Document, Field, Fragment, FragmentSpread, Operation},
+    parser::{SourcePosition, Spanning},
+    validation::{ValidatorContext, Visitor},
+    value::ScalarValue,
+};
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Scope<'a> {
+    Operation(Option<&'a str>),
+    Fragment(&'a str),
+}
+
+#[derive(Default)]
+struct ScopeDepth<'a> {
+    fields: usize,
+    spreads: Vec<(usize, &'a str)>,
+}
+
+pub struct MaxQueryDepth<'a> {
+    max_depth: Option<usize>,
+    depths: HashMap<Scope<'a>, ScopeDepth<'a>>,
+    operations: Vec<(Scope<'a>, SourcePosition)>,
+    current_scope: Option<Scope<'a>>,
+    current_depth: usize,
+    /// Number of fields entered since the current introspection field.
+    introspection_depth: usize,
+}
+
+pub fn factory<'a>(max_depth: Option<usize>) -> MaxQueryDepth<'a> {
+    MaxQueryDepth {
+        max_depth,
+        depths: HashMap::new(),
+        operations: Vec::new(),
+        current_scope: None,
+        current_depth: 0,
+        introspection_depth: 0,
+    }
+}
+
+impl<'a> MaxQueryDepth<'a> {
+    fn depth_of(&self, scope: &Scope<'a>, visiting: &mut HashSet<&'a str>) -> usize {
+        let depth = match self.depths.get(scope) {
+            Some(depth) => depth,
+            None => return 0,
+        };
+
+        let mut max = depth.fields;
+        for &(spread_depth, fragment) in &depth.spreads {
+            // Fragment cycles are reported by `NoFragmentCycles`.
+            if visiting.insert(fragment) {
+                max = max.max(spread_depth + self.depth_of(&Scope::Fragment(fragment), visiting));
+                visiting.remove(fragment);
+            }
+        }
+        max
+    }
+}
+
+impl<'a, S> Visitor<'a, S> for MaxQueryDepth<'a>
+where
+    S: ScalarValue,
+{
+    fn exit_document(&mut self, ctx: &mut ValidatorContext<'a, S>, _: &'a Document<S>) {
+        let max_depth = match self.max_depth {
+            Some(max_depth) => max_depth,
+            None => return,
+        };
+
+        for (scope, pos) in &self.operations {
+            let depth = self.depth_of(scope, &mut HashSet::new());
+            if depth > max_depth {
+                ctx.report_error(&error_message(depth, max_depth), &[*pos]);
+            }
+        }
+    }
+
+    fn enter_operation_definition(
+        &mut self,
+        _: &mut ValidatorContext<'a, S>,
+        op: &'a Spanning<Operation<S>>,
+    ) {
+        let op_name = op.item.name.as_ref().map(|s| s.item);
+        self.current_scope = Some(Scope::Operation(op_name));
+        self.operations.push((Scope::Operation(op_name), op.start));
+    }
+
+    fn enter_fragment_definition(
+        &mut self,
+        _: &mut ValidatorContext<'a, S>,
+        f: &'a Spanning<Fragment<S>>,
+    ) {
+        self.current_scope = Some(Scope::Fragment(f.item.name.item));
+    }
+
+    fn enter_field(&mut self, _: &mut ValidatorContext<'a, S>, field: &'a Spanning<Field<S>>) {
+        // The depth of the introspection query of tools like GraphiQL is
+        // fixed, so only the fields of the schema are limited.
+        if self.introspection_depth > 0 || field.item.name.item.starts_with("__") {
+            self.introspection_depth += 1;
+            return;
+        }
+
+        self.current_depth += 1;
+
+        if let Some(ref scope) = self.current_scope {
+            let depth = self.depths.entry(scope.clone()).or_default();
+            depth.fields = depth.fields.max(self.current_depth);
+        }
+    }
+
+    fn exit_field(&mut self, _: &mut ValidatorContext<'a, S>, _: &'a Spanning<Field<S>>) {
+        if self.introspection_depth > 0 {
+            self.introspection_depth -= 1;
+        } else {
+            self.current_depth -= 1;
+        }
+    }
+
+    fn enter_fragment_spread(
+        &mut self,
+        _: &mut ValidatorContext<'a, S>,
+        spread: &'a Spanning<FragmentSpread<S>>,
+    ) {
+        if self.introspection_depth > 0 {
+            return;
+        }
+
+        if let Some(ref scope) = self.current_scope {
+            self.depths
+                .entry(scope.clone())
+                .or_default()
+                .spreads
+                .push((self.current_depth, spread.item.name.item));
+        }
+    }
+}
+
+fn error_message(depth: usize, max_depth: usize) -> String {
+    format!(
+        "Operation has a depth of {}, which exceeds the maximum depth of {}",
+        depth, max_depth
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use crate::{
+        parser::SourcePosition,
+        validation::{expect_fails_rule, expect_passes_rule, RuleError},
+        value::DefaultScalarValue,
+    };
+
+    #[test]
+    fn no_limit() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(None),
+            r#"
+          {
+            human {
+              relatives {
+                relatives {
+                  relatives {
+                    name
+                  }
+                }
+              }
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn depth_at_limit() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(3)),
+            r#"
+          {
+            human {
+              relatives {
+                name
+              }
+            }
+            dog {
+              name
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn depth_over_limit() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(3)),
+            r#"
+          {
+            human {
+              relatives {
+                relatives {
+                  name
+                }
+              }
+            }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(4, 3),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn inline_fragments_do_not_add_depth() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(2)),
+            r#"
+          {
+            human {
+              ... on Human {
+                name
+              }
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn depth_through_fragment_spreads() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(3)),
+            r#"
+          query Relatives {
+            human {
+              ...relatives
+            }
+          }
+
+          fragment relatives on Human {
+            relatives {
+              ...names
+            }
+          }
+
+          fragment names on Human {
+            relatives {
+              name
+            }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message(4, 3),
+                &[SourcePosition::new(11, 1, 10)],
+            )],
+        );
+    }
+
+    #[test]
+    fn fragment_cycles_terminate() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(3)),
+            r#"
+          {
+            human {
+              ...relatives
+            }
+          }
+
+          fragment relatives on Human {
+            relatives {
+              ...relatives
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn introspection_does_not_add_depth() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(Some(2)),
+            r#"
+          {
+            human {
+              name
+            }
+            __schema {
+              types {
+                ...fullType
+              }
+            }
+          }
+
+          fragment fullType on __Type {
+            fields {
+              type {
+                ofType {
+                  name
+                }
+              }
+            }
+          }
+        "#,
+        );
+    }
+}
@@ -5,9 +5,13 @@ mod fragments_on_composite_types;
 mod known_argument_names;
 mod known_directives;
 mod known_fragment_names;
+mod known_operation_types;
 mod known_type_names;
 mod lone_anonymous_operation;
+mod max_query_aliases;
+mod max_query_depth;
 mod no_fragment_cycles;
+mod no_introspection;
 mod no_undefined_variables;
 mod no_unused_fragments;
 mod no_unused_variables;
@@ -42,9 +46,17 @@ where
         .with(self::known_argument_names::factory())
         .with(self::known_directives::factory())
         .with(self::known_fragment_names::factory())
+        .with(self::known_operation_types::factory())
         .with(self::known_type_names::factory())
         .with(self::lone_anonymous_operation::factory())
+        .with(self::max_query_aliases::factory(
+            ctx.schema.max_query_aliases,
+        ))
+        .with(self::max_query_depth::factory(ctx.schema.max_query_depth))
         .with(self::no_fragment_cycles::factory())
+        .with(self::no_introspection::factory(
+            ctx.schema.introspection_disabled,
+        ))
         .with(self::no_undefined_variables::factory())
         .with(self::no_unused_fragments::factory())
         .with(self::no_unused_variables::factory())
@@ -62,3 +74,16 @@ where
 
     visit(&mut mv, ctx, doc)
 }
+
+/// Reports the fragments spreading themselves, directly or through other
+/// fragments, which any traversal of the document would follow forever
+pub(crate) fn visit_fragment_cycles<'a, S: Debug>(
+    ctx: &mut ValidatorContext<'a, S>,
+    doc: &'a Document<S>,
+) where
+    S: ScalarValue,
+{
+    let mut mv = MultiVisitorNil.with(self::no_fragment_cycles::factory());
+
+    visit(&mut mv, ctx, doc)
+}
@@ -0,0 +1,116 @@
+use crate::{
+    ast::Field,
+    parser::Spanning,
+    validation::{ValidatorContext, Visitor},
+    value::ScalarValue,
+};
+
+pub struct NoIntrospection {
+    disabled: bool,
+}
+
+pub fn factory(disabled: bool) -> NoIntrospection {
+    NoIntrospection { disabled }
+}
+
+impl<'a, S> Visitor<'a, S> for NoIntrospection
+where
+    S: ScalarValue,
+{
+    fn enter_field(&mut self, ctx: &mut ValidatorContext<'a, S>, field: &'a Spanning<Field<S>>) {
+        let field_name = field.item.name.item;
+        if self.disabled && (field_name == "__schema" || field_name == "__type") {
+            ctx.report_error(&error_message(field_name), &[field.start]);
+        }
+    }
+}
+
+fn error_message(field_name: &str) -> String {
+    format!(
+        r#"Cannot query field "{}": introspection is disabled"#,
+        field_name
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{error_message, factory};
+
+    use crate::{
+        parser::SourcePosition,
+        validation::{expect_fails_rule, expect_passes_rule, RuleError},
+        value::DefaultScalarValue,
+    };
+
+    #[test]
+    fn introspection_allowed_by_default() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(false),
+            r#"
+          {
+            __schema {
+              queryType { name }
+            }
+            __type(name: "Dog") { name }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn typename_allowed_when_disabled() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            || factory(true),
+            r#"
+          {
+            dog {
+              __typename
+              name
+            }
+          }
+        "#,
+        );
+    }
+
+    #[test]
+    fn introspection_rejected_when_disabled() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(true),
+            r#"
+          {
+            __schema {
+              queryType { name }
+            }
+            __type(name: "Dog") { name }
+          }
+        "#,
+            &[
+                RuleError::new(
+                    &error_message("__schema"),
+                    &[SourcePosition::new(25, 2, 12)],
+                ),
+                RuleError::new(&error_message("__type"), &[SourcePosition::new(95, 5, 12)]),
+            ],
+        );
+    }
+
+    #[test]
+    fn introspection_in_fragments_rejected_when_disabled() {
+        expect_fails_rule::<_, _, DefaultScalarValue>(
+            || factory(true),
+            r#"
+          {
+            ...schema
+          }
+
+          fragment schema on QueryRoot {
+            __schema { queryType { name } }
+          }
+        "#,
+            &[RuleError::new(
+                &error_message("__schema"),
+                &[SourcePosition::new(101, 6, 12)],
+            )],
+        );
+    }
+}
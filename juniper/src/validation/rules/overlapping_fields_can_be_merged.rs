@@ -165,7 +165,7 @@ impl<'a, S: Debug> OverlappingFieldsCanBeMerged<'a, S> {
             self.collect_conflicts_between_fields_and_fragment(
                 &mut conflicts,
                 &field_map,
-                frag_name1,
+                *frag_name1,
                 false,
                 ctx,
             );
@@ -264,12 +264,40 @@ impl<'a, S: Debug> OverlappingFieldsCanBeMerged<'a, S> {
         &self,
         conflicts: &mut Vec<Conflict>,
         field_map: &AstAndDefCollection<'a, S>,
-        fragment_name: &str,
+        fragment_name: &'a str,
         mutually_exclusive: bool,
         ctx: &ValidatorContext<'a, S>,
     ) where
         S: ScalarValue,
     {
+        self.collect_conflicts_between_fields_and_spread_fragment(
+            conflicts,
+            field_map,
+            fragment_name,
+            mutually_exclusive,
+            ctx,
+            &mut Vec::new(),
+        );
+    }
+
+    fn collect_conflicts_between_fields_and_spread_fragment(
+        &self,
+        conflicts: &mut Vec<Conflict>,
+        field_map: &AstAndDefCollection<'a, S>,
+        fragment_name: &'a str,
+        mutually_exclusive: bool,
+        ctx: &ValidatorContext<'a, S>,
+        visited_fragments: &mut Vec<&'a str>,
+    ) where
+        S: ScalarValue,
+    {
+        // Cyclic spreads are reported by `NoFragmentCycles`, following them
+        // here would never end.
+        if visited_fragments.contains(&fragment_name) {
+            return;
+        }
+        visited_fragments.push(fragment_name);
+
         let fragment = match self.named_fragments.get(fragment_name) {
             Some(f) => f,
             None => return,
@@ -281,12 +309,13 @@ impl<'a, S: Debug> OverlappingFieldsCanBeMerged<'a, S> {
         self.collect_conflicts_between(conflicts, mutually_exclusive, field_map, &field_map2, ctx);
 
         for fragment_name2 in fragment_names2 {
-            self.collect_conflicts_between_fields_and_fragment(
+            self.collect_conflicts_between_fields_and_spread_fragment(
                 conflicts,
                 field_map,
                 fragment_name2,
                 mutually_exclusive,
                 ctx,
+                visited_fragments,
             );
         }
     }
@@ -460,7 +489,7 @@ impl<'a, S: Debug> OverlappingFieldsCanBeMerged<'a, S> {
             self.collect_conflicts_between_fields_and_fragment(
                 &mut conflicts,
                 &field_map1,
-                fragment_name,
+                *fragment_name,
                 mutually_exclusive,
                 ctx,
             );
@@ -470,7 +499,7 @@ impl<'a, S: Debug> OverlappingFieldsCanBeMerged<'a, S> {
             self.collect_conflicts_between_fields_and_fragment(
                 &mut conflicts,
                 &field_map2,
-                fragment_name,
+                *fragment_name,
                 mutually_exclusive,
                 ctx,
             );
@@ -1341,6 +1370,25 @@ mod tests {
         );
     }
 
+    #[test]
+    fn terminates_on_fragment_cycles() {
+        expect_passes_rule::<_, _, DefaultScalarValue>(
+            factory,
+            r#"
+        {
+          dog {
+            ...DogFields
+          }
+        }
+
+        fragment DogFields on Dog {
+          name
+          ...DogFields
+        }
+        "#,
+        );
+    }
+
     #[test]
     fn ignores_unknown_fragments() {
         expect_passes_rule::<_, _, DefaultScalarValue>(
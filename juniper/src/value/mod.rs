@@ -6,6 +6,7 @@ use std::fmt::{self, Display, Formatter};
 mod object;
 mod scalar;
 
+pub(crate) use self::object::sort_value_by_field;
 pub use self::object::Object;
 
 pub use self::scalar::{DefaultScalarValue, ParseScalarResult, ParseScalarValue, ScalarValue};
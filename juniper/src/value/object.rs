@@ -77,18 +77,26 @@ impl<S> Object<S> {
             .map(|&(_, ref value)| value)
     }
 
-    /// Recursively sort all keys by field.
+    /// Recursively sort all keys by field, including the ones of objects
+    /// nested in lists.
     pub fn sort_by_field(&mut self) {
         self.key_value_list
             .sort_by(|(key1, _), (key2, _)| key1.cmp(key2));
         for (_, ref mut value) in &mut self.key_value_list {
-            if let Value::Object(ref mut o) = value {
-                o.sort_by_field();
-            }
+            sort_value_by_field(value);
         }
     }
 }
 
+/// Recursively sort the keys of the objects in `value`
+pub(crate) fn sort_value_by_field<S>(value: &mut Value<S>) {
+    match *value {
+        Value::Object(ref mut o) => o.sort_by_field(),
+        Value::List(ref mut items) => items.iter_mut().for_each(sort_value_by_field),
+        _ => {}
+    }
+}
+
 impl<S> IntoIterator for Object<S> {
     type Item = (String, Value<S>);
     type IntoIter = IntoIter<Self::Item>;
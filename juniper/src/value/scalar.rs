@@ -224,11 +224,14 @@ pub trait ScalarValue:
 
 /// The default scalar value representation in juniper
 ///
-/// This types closely follows the graphql specification.
+/// This types closely follows the graphql specification, with an additional
+/// `Long` variant holding the values of the `Long` scalar (`i64`) which don't
+/// fit into an `Int`.
 #[derive(Debug, PartialEq, Clone, GraphQLScalarValue)]
 #[allow(missing_docs)]
 pub enum DefaultScalarValue {
     Int(i32),
+    Long(i64),
     Float(f64),
     String(String),
     Boolean(bool),
@@ -247,6 +250,7 @@ impl ScalarValue for DefaultScalarValue {
     fn as_float(&self) -> Option<f64> {
         match *self {
             DefaultScalarValue::Int(ref i) => Some(*i as f64),
+            DefaultScalarValue::Long(ref i) => Some(*i as f64),
             DefaultScalarValue::Float(ref f) => Some(*f),
             _ => None,
         }
@@ -301,12 +305,11 @@ impl<'de> de::Visitor<'de> for DefaultScalarValueVisitor {
         if value >= i64::from(i32::min_value()) && value <= i64::from(i32::max_value()) {
             Ok(DefaultScalarValue::Int(value as i32))
         } else {
-            // Browser's JSON.stringify serialize all numbers having no
-            // fractional part as integers (no decimal point), so we
-            // must parse large integers as floating point otherwise
-            // we would error on transferring large floating point
-            // numbers.
-            Ok(DefaultScalarValue::Float(value as f64))
+            // Integers outside of the `Int` range are kept losslessly for
+            // `Long` inputs. `Float` inputs accept them as well, as browser's
+            // JSON.stringify serialize all numbers having no fractional part
+            // as integers (no decimal point).
+            Ok(DefaultScalarValue::Long(value))
         }
     }
 
@@ -314,7 +317,7 @@ impl<'de> de::Visitor<'de> for DefaultScalarValueVisitor {
     where
         E: de::Error,
     {
-        if value <= i32::max_value() as u64 {
+        if value <= i64::max_value() as u64 {
             self.visit_i64(value as i64)
         } else {
             // Browser's JSON.stringify serialize all numbers having no
@@ -0,0 +1,66 @@
+#![deny(warnings)]
+
+extern crate log;
+
+use actix_web::{middleware, web, App, Error, HttpRequest, HttpResponse, HttpServer};
+use juniper::{
+    tests::{model::Database, schema::Query},
+    EmptyMutation, EmptySubscription, RootNode,
+};
+use juniper_actix::{graphiql_handler, graphql_handler, playground_handler};
+
+type Schema = RootNode<'static, Query, EmptyMutation<Database>, EmptySubscription<Database>>;
+
+fn schema() -> Schema {
+    Schema::new(
+        Query,
+        EmptyMutation::<Database>::new(),
+        EmptySubscription::<Database>::new(),
+    )
+}
+
+async fn graphiql() -> Result<HttpResponse, Error> {
+    graphiql_handler("/graphql").await
+}
+
+async fn playground() -> Result<HttpResponse, Error> {
+    playground_handler("/graphql", None).await
+}
+
+async fn graphql(
+    req: HttpRequest,
+    payload: web::Payload,
+    schema: web::Data<Schema>,
+) -> Result<HttpResponse, Error> {
+    let context = Database::new();
+    graphql_handler(schema.get_ref(), &context, req, payload).await
+}
+
+#[actix_rt::main]
+async fn main() -> std::io::Result<()> {
+    ::std::env::set_var("RUST_LOG", "actix_web=info");
+    env_logger::init();
+
+    log::info!("Listening on 127.0.0.1:8080");
+
+    HttpServer::new(|| {
+        App::new()
+            .data(schema())
+            .wrap(middleware::Logger::default())
+            .service(
+                web::resource("/graphql")
+                    .route(web::get().to(graphql))
+                    .route(web::post().to(graphql)),
+            )
+            .service(web::resource("/graphiql").route(web::get().to(graphiql)))
+            .service(web::resource("/playground").route(web::get().to(playground)))
+            .default_service(web::route().to(|| {
+                HttpResponse::Found()
+                    .header("location", "/playground")
+                    .finish()
+            }))
+    })
+    .bind("127.0.0.1:8080")?
+    .run()
+    .await
+}
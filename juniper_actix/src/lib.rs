@@ -0,0 +1,814 @@
+/*!
+
+# juniper_actix
+
+This repository contains the [actix-web][actix-web] web framework integration for
+[Juniper][Juniper], a [GraphQL][GraphQL] implementation for Rust.
+
+## Documentation
+
+For documentation, including guides and examples, check out [Juniper][Juniper].
+
+A basic usage example can also be found in the [Api documentation][documentation].
+
+## Examples
+
+Check [examples/actix_server][example] for example code of a working actix-web
+server with GraphQL handlers.
+
+## Links
+
+* [Juniper][Juniper]
+* [Api Reference][documentation]
+* [actix-web][actix-web]
+
+## License
+
+This project is under the BSD-2 license.
+
+Check the LICENSE file for details.
+
+[actix-web]: https://github.com/actix/actix-web
+[Juniper]: https://github.com/graphql-rust/juniper
+[GraphQL]: http://graphql.org
+[documentation]: https://docs.rs/juniper_actix
+[example]: https://github.com/graphql-rust/juniper/blob/master/juniper_actix/examples/actix_server.rs
+
+*/
+
+#![deny(missing_docs)]
+#![deny(warnings)]
+#![doc(html_root_url = "https://docs.rs/juniper_actix/0.1.0")]
+
+use actix_web::{
+    error::{ErrorBadRequest, ErrorMethodNotAllowed, ErrorUnsupportedMediaType},
+    http::{header::CONTENT_TYPE, Method, StatusCode},
+    web, Error, FromRequest, HttpRequest, HttpResponse,
+};
+use juniper::{
+    http::{
+        graphiql::graphiql_source, playground::playground_source, GraphQLBatchRequest,
+        GraphQLRequest,
+    },
+    GraphQLSubscriptionType, GraphQLTypeAsync, RootNode, ScalarValue,
+};
+use serde::{Deserialize, Serialize};
+
+/// Query parameters of a GraphQL `GET` request.
+#[derive(Deserialize)]
+struct GetGraphQLRequest {
+    query: String,
+    #[serde(rename = "operationName")]
+    operation_name: Option<String>,
+    variables: Option<String>,
+}
+
+/// Handles a GraphQL request sent with either the `GET` or the `POST` method,
+/// see [`get_graphql_handler`] and [`post_graphql_handler`].
+///
+/// Other methods are answered with `405 Method Not Allowed`.
+///
+/// Example:
+///
+/// ```
+/// use actix_web::{web, App, Error, HttpRequest, HttpResponse};
+/// use juniper::{
+///     tests::{model::Database, schema::Query},
+///     EmptyMutation, EmptySubscription, RootNode,
+/// };
+/// use juniper_actix::graphql_handler;
+///
+/// type Schema = RootNode<'static, Query, EmptyMutation<Database>, EmptySubscription<Database>>;
+///
+/// async fn graphql(
+///     req: HttpRequest,
+///     payload: web::Payload,
+///     schema: web::Data<Schema>,
+/// ) -> Result<HttpResponse, Error> {
+///     let context = Database::new();
+///     graphql_handler(schema.get_ref(), &context, req, payload).await
+/// }
+///
+/// let schema = Schema::new(Query, EmptyMutation::new(), EmptySubscription::new());
+///
+/// let app = App::new()
+///     .data(schema)
+///     .service(
+///         web::resource("/graphql")
+///             .route(web::get().to(graphql))
+///             .route(web::post().to(graphql)),
+///     );
+/// ```
+pub async fn graphql_handler<Query, Mutation, Subscription, CtxT, S>(
+    schema: &RootNode<'static, Query, Mutation, Subscription, S>,
+    context: &CtxT,
+    req: HttpRequest,
+    payload: web::Payload,
+) -> Result<HttpResponse, Error>
+where
+    S: ScalarValue + Send + Sync + 'static,
+    CtxT: Send + Sync + 'static,
+    Query: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + Sync + 'static,
+    Subscription::TypeInfo: Send + Sync,
+{
+    match *req.method() {
+        Method::GET => get_graphql_handler(schema, context, req).await,
+        Method::POST => post_graphql_handler(schema, context, req, payload).await,
+        _ => Err(ErrorMethodNotAllowed(
+            "GraphQL requests can only be sent with GET or POST",
+        )),
+    }
+}
+
+/// Handles a GraphQL `GET` request.
+///
+/// The request is read from the `query`, `operationName` and `variables`
+/// query parameters, the latter being JSON encoded.
+pub async fn get_graphql_handler<Query, Mutation, Subscription, CtxT, S>(
+    schema: &RootNode<'static, Query, Mutation, Subscription, S>,
+    context: &CtxT,
+    req: HttpRequest,
+) -> Result<HttpResponse, Error>
+where
+    S: ScalarValue + Send + Sync + 'static,
+    CtxT: Send + Sync + 'static,
+    Query: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + Sync + 'static,
+    Subscription::TypeInfo: Send + Sync,
+{
+    let get_req = web::Query::<GetGraphQLRequest>::from_query(req.query_string())
+        .map_err(ErrorBadRequest)?
+        .into_inner();
+    let variables = match get_req.variables {
+        Some(variables) => Some(serde_json::from_str(&variables).map_err(ErrorBadRequest)?),
+        None => None,
+    };
+    let request = GraphQLRequest::new(get_req.query, get_req.operation_name, variables);
+
+    let response = request.execute(schema, context).await;
+    json_response(response.is_ok(), &response)
+}
+
+/// Handles a GraphQL `POST` request.
+///
+/// The body is either a JSON encoded request (or batch of requests) or, if
+/// the `Content-Type` is `application/graphql`, the query document itself.
+pub async fn post_graphql_handler<Query, Mutation, Subscription, CtxT, S>(
+    schema: &RootNode<'static, Query, Mutation, Subscription, S>,
+    context: &CtxT,
+    req: HttpRequest,
+    payload: web::Payload,
+) -> Result<HttpResponse, Error>
+where
+    S: ScalarValue + Send + Sync + 'static,
+    CtxT: Send + Sync + 'static,
+    Query: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + Sync + 'static,
+    Subscription::TypeInfo: Send + Sync,
+{
+    let content_type = req
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.split(';').next())
+        .map(|value| value.trim().to_owned());
+    let body = web::Bytes::from_request(&req, &mut payload.into_inner()).await?;
+
+    let mut request = match content_type.as_deref() {
+        Some("application/graphql") => {
+            let query = String::from_utf8(body.to_vec()).map_err(ErrorBadRequest)?;
+            GraphQLBatchRequest::Single(GraphQLRequest::new(query, None, None))
+        }
+        None | Some("application/json") => {
+            serde_json::from_slice::<GraphQLBatchRequest<S>>(&body).map_err(ErrorBadRequest)?
+        }
+        Some(_) => {
+            return Err(ErrorUnsupportedMediaType(
+                "GraphQL requests must be sent as application/json or application/graphql",
+            ))
+        }
+    };
+
+    request.load_persisted_queries(schema);
+    let response = request.execute(schema, context).await;
+    json_response(response.is_ok(), &response)
+}
+
+fn json_response<T: Serialize>(is_ok: bool, body: &T) -> Result<HttpResponse, Error> {
+    let status = if is_ok {
+        StatusCode::OK
+    } else {
+        StatusCode::BAD_REQUEST
+    };
+    Ok(HttpResponse::build(status)
+        .content_type("application/json")
+        .body(serde_json::to_string(body)?))
+}
+
+/// Serves the GraphiQL IDE, sending its requests to `graphql_endpoint_url`.
+pub async fn graphiql_handler(graphql_endpoint_url: &str) -> Result<HttpResponse, Error> {
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(graphiql_source(graphql_endpoint_url)))
+}
+
+/// Serves the GraphQL Playground IDE, sending its requests to
+/// `graphql_endpoint_url` and opening subscriptions on
+/// `subscriptions_endpoint_url`, if any.
+pub async fn playground_handler(
+    graphql_endpoint_url: &str,
+    subscriptions_endpoint_url: Option<&str>,
+) -> Result<HttpResponse, Error> {
+    Ok(HttpResponse::Ok()
+        .content_type("text/html; charset=utf-8")
+        .body(playground_source(
+            graphql_endpoint_url,
+            subscriptions_endpoint_url,
+        )))
+}
+
+/// `juniper_actix` subscriptions handler implementation, serving
+/// subscriptions over websockets with the GraphQL over WS[1] protocol.
+///
+/// [1]: https://github.com/apollographql/subscriptions-transport-ws/blob/master/PROTOCOL.md
+#[cfg(feature = "subscriptions")]
+pub mod subscriptions {
+    use std::{collections::HashMap, sync::Arc};
+
+    use actix_codec::{Decoder as _, Encoder as _};
+    use actix_http::ws;
+    use actix_web::{
+        web::{self, BytesMut},
+        Error, HttpRequest, HttpResponse,
+    };
+    use futures::{
+        channel::mpsc,
+        future::{self, AbortHandle},
+        FutureExt as _, StreamExt as _,
+    };
+    use juniper::{
+        http::GraphQLRequest, GraphQLSubscriptionType, GraphQLTypeAsync, InputValue, ScalarValue,
+        SubscriptionCoordinator as _,
+    };
+    use juniper_subscriptions::Coordinator;
+    use serde::{Deserialize, Serialize};
+
+    /// Upgrades the request to a websocket serving GraphQL subscriptions.
+    ///
+    /// Each `start` message of the client runs a subscription with the given
+    /// `context`, sending its responses as `data` messages until it ends or
+    /// the client sends a matching `stop` message, and then a `complete`
+    /// message.
+    ///
+    /// Example:
+    ///
+    /// ```
+    /// use std::sync::Arc;
+    ///
+    /// use actix_web::{web, App, Error, HttpRequest, HttpResponse};
+    /// use juniper::{
+    ///     tests::{model::Database, schema::Query},
+    ///     DefaultScalarValue, EmptyMutation, EmptySubscription, RootNode,
+    /// };
+    /// use juniper_actix::subscriptions::subscriptions_handler;
+    /// use juniper_subscriptions::Coordinator;
+    ///
+    /// type Schema = RootNode<'static, Query, EmptyMutation<Database>, EmptySubscription<Database>>;
+    /// type Subscriptions = Coordinator<
+    ///     'static,
+    ///     Query,
+    ///     EmptyMutation<Database>,
+    ///     EmptySubscription<Database>,
+    ///     Database,
+    ///     DefaultScalarValue,
+    /// >;
+    ///
+    /// async fn subscriptions(
+    ///     req: HttpRequest,
+    ///     payload: web::Payload,
+    ///     coordinator: web::Data<Subscriptions>,
+    /// ) -> Result<HttpResponse, Error> {
+    ///     subscriptions_handler(req, payload, coordinator.into_inner(), Database::new()).await
+    /// }
+    ///
+    /// let schema = Schema::new(Query, EmptyMutation::new(), EmptySubscription::new());
+    ///
+    /// let app = App::new()
+    ///     .data(Coordinator::new(schema))
+    ///     .service(web::resource("/subscriptions").route(web::get().to(subscriptions)));
+    /// ```
+    pub async fn subscriptions_handler<Query, Mutation, Subscription, CtxT, S>(
+        req: HttpRequest,
+        payload: web::Payload,
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, CtxT, S>>,
+        context: CtxT,
+    ) -> Result<HttpResponse, Error>
+    where
+        S: ScalarValue + Send + Sync + 'static,
+        CtxT: Send + Sync + 'static,
+        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+    {
+        let mut response = ws::handshake(req.head())?;
+
+        let (ws_tx, ws_rx) = mpsc::unbounded();
+        actix_web::rt::spawn(handle_connection(
+            payload,
+            coordinator,
+            Arc::new(context),
+            ws_tx,
+        ));
+
+        let mut codec = ws::Codec::new();
+        let frames = ws_rx.map(move |msg| {
+            let mut buf = BytesMut::new();
+            codec.encode(msg, &mut buf).map(|()| buf.freeze())
+        });
+
+        Ok(response
+            .header("Sec-WebSocket-Protocol", "graphql-ws")
+            .streaming(frames))
+    }
+
+    /// Decodes the frames sent by the client and dispatches their messages,
+    /// until the client closes the connection.
+    async fn handle_connection<Query, Mutation, Subscription, CtxT, S>(
+        mut payload: web::Payload,
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, CtxT, S>>,
+        context: Arc<CtxT>,
+        ws_tx: mpsc::UnboundedSender<ws::Message>,
+    ) where
+        S: ScalarValue + Send + Sync + 'static,
+        CtxT: Send + Sync + 'static,
+        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+    {
+        let mut codec = ws::Codec::new();
+        let mut buf = BytesMut::new();
+        let mut running = HashMap::<String, AbortHandle>::new();
+
+        'connection: while let Some(Ok(chunk)) = payload.next().await {
+            buf.extend_from_slice(&chunk);
+
+            loop {
+                let frame = match codec.decode(&mut buf) {
+                    Ok(Some(frame)) => frame,
+                    Ok(None) => break,
+                    Err(_) => {
+                        let reason = ws::CloseCode::Protocol.into();
+                        let _ = ws_tx.unbounded_send(ws::Message::Close(Some(reason)));
+                        break 'connection;
+                    }
+                };
+
+                let message = match frame {
+                    ws::Frame::Text(bytes) | ws::Frame::Binary(bytes) => {
+                        serde_json::from_slice::<ClientMessage<S>>(&bytes)
+                    }
+                    ws::Frame::Ping(bytes) => {
+                        let _ = ws_tx.unbounded_send(ws::Message::Pong(bytes));
+                        continue;
+                    }
+                    ws::Frame::Close(reason) => {
+                        let _ = ws_tx.unbounded_send(ws::Message::Close(reason));
+                        break 'connection;
+                    }
+                    ws::Frame::Pong(_) | ws::Frame::Continuation(_) => continue,
+                };
+
+                match message {
+                    Ok(ClientMessage::ConnectionInit {}) => {
+                        send(
+                            &ws_tx,
+                            &ServerMessage::<()>::new("connection_ack", None, None),
+                        );
+                    }
+                    Ok(ClientMessage::Start { id, payload }) => {
+                        let (subscription, handle) = future::abortable(run_subscription(
+                            coordinator.clone(),
+                            context.clone(),
+                            id.clone(),
+                            payload,
+                            ws_tx.clone(),
+                        ));
+                        if let Some(previous) = running.insert(id, handle) {
+                            previous.abort();
+                        }
+                        actix_web::rt::spawn(subscription.map(|_| ()));
+                    }
+                    Ok(ClientMessage::Stop { id }) => {
+                        if let Some(handle) = running.remove(&id) {
+                            handle.abort();
+                        }
+                        send(
+                            &ws_tx,
+                            &ServerMessage::<()>::new("complete", Some(&id), None),
+                        );
+                    }
+                    Ok(ClientMessage::ConnectionTerminate {}) => {
+                        let _ = ws_tx.unbounded_send(ws::Message::Close(None));
+                        break 'connection;
+                    }
+                    Err(e) => {
+                        let payload = ErrorPayload {
+                            message: e.to_string(),
+                        };
+                        send(
+                            &ws_tx,
+                            &ServerMessage::new("connection_error", None, Some(&payload)),
+                        );
+                    }
+                }
+            }
+        }
+
+        for handle in running.values() {
+            handle.abort();
+        }
+    }
+
+    /// Runs the subscription started by the client with the given `id`.
+    async fn run_subscription<Query, Mutation, Subscription, CtxT, S>(
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, CtxT, S>>,
+        context: Arc<CtxT>,
+        id: String,
+        request: GraphQLRequest<S>,
+        ws_tx: mpsc::UnboundedSender<ws::Message>,
+    ) where
+        S: ScalarValue + Send + Sync + 'static,
+        CtxT: Send + Sync + 'static,
+        Query: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription: GraphQLSubscriptionType<S, Context = CtxT> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+    {
+        match coordinator.subscribe(&request, &context).await {
+            Ok(mut stream) => {
+                while let Some(response) = stream.next().await {
+                    send(
+                        &ws_tx,
+                        &ServerMessage::new("data", Some(&id), Some(&response)),
+                    );
+                }
+            }
+            Err(err) => {
+                send(&ws_tx, &ServerMessage::new("error", Some(&id), Some(&err)));
+            }
+        }
+
+        send(
+            &ws_tx,
+            &ServerMessage::<()>::new("complete", Some(&id), None),
+        );
+    }
+
+    fn send<T: Serialize>(ws_tx: &mpsc::UnboundedSender<ws::Message>, message: &T) {
+        if let Ok(text) = serde_json::to_string(message) {
+            let _ = ws_tx.unbounded_send(ws::Message::Text(text));
+        }
+    }
+
+    #[derive(Deserialize)]
+    #[serde(tag = "type", rename_all = "snake_case")]
+    #[serde(bound = "InputValue<S>: Deserialize<'de>")]
+    enum ClientMessage<S>
+    where
+        S: ScalarValue,
+    {
+        ConnectionInit {},
+        Start {
+            id: String,
+            payload: GraphQLRequest<S>,
+        },
+        Stop {
+            id: String,
+        },
+        ConnectionTerminate {},
+    }
+
+    #[derive(Serialize)]
+    struct ServerMessage<'a, T> {
+        #[serde(rename = "type")]
+        type_name: &'static str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<&'a str>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        payload: Option<&'a T>,
+    }
+
+    impl<'a, T> ServerMessage<'a, T> {
+        fn new(type_name: &'static str, id: Option<&'a str>, payload: Option<&'a T>) -> Self {
+            Self {
+                type_name,
+                id,
+                payload,
+            }
+        }
+    }
+
+    #[derive(Serialize)]
+    struct ErrorPayload {
+        message: String,
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use std::pin::Pin;
+
+        use actix_web::{test, App};
+        use futures::{SinkExt as _, Stream};
+        use juniper::{DefaultScalarValue, EmptyMutation, RootNode};
+        use serde_json::{json, Value as Json};
+
+        use super::*;
+
+        struct Query;
+
+        #[juniper::graphql_object]
+        impl Query {
+            fn empty() -> bool {
+                true
+            }
+        }
+
+        struct Subscription;
+
+        #[juniper::graphql_subscription]
+        impl Subscription {
+            async fn counter() -> Pin<Box<dyn Stream<Item = i32> + Send>> {
+                Box::pin(futures::stream::iter(vec![1, 2]))
+            }
+
+            async fn paused() -> Pin<Box<dyn Stream<Item = i32> + Send>> {
+                Box::pin(futures::stream::pending())
+            }
+        }
+
+        type Schema = RootNode<'static, Query, EmptyMutation<()>, Subscription>;
+        type Subscriptions =
+            Coordinator<'static, Query, EmptyMutation<()>, Subscription, (), DefaultScalarValue>;
+
+        async fn subscriptions(
+            req: HttpRequest,
+            payload: web::Payload,
+            coordinator: web::Data<Subscriptions>,
+        ) -> Result<HttpResponse, Error> {
+            subscriptions_handler(req, payload, coordinator.into_inner(), ()).await
+        }
+
+        fn server() -> test::TestServer {
+            test::start(|| {
+                App::new()
+                    .data(Coordinator::new(Schema::new(
+                        Query,
+                        EmptyMutation::new(),
+                        Subscription,
+                    )))
+                    .service(web::resource("/").route(web::get().to(subscriptions)))
+            })
+        }
+
+        fn text(value: Json) -> ws::Message {
+            ws::Message::Text(value.to_string())
+        }
+
+        fn parse(frame: Option<Result<ws::Frame, ws::ProtocolError>>) -> Json {
+            match frame.expect("connection closed").expect("invalid frame") {
+                ws::Frame::Text(bytes) => serde_json::from_slice(&bytes).unwrap(),
+                frame => panic!("expected a text frame, got {:?}", frame),
+            }
+        }
+
+        #[actix_rt::test]
+        async fn runs_subscriptions_until_completion() {
+            let mut server = server();
+            let mut client = server.ws_at("/").await.expect("handshake");
+
+            client
+                .send(text(json!({"type": "connection_init"})))
+                .await
+                .unwrap();
+            assert_eq!(
+                parse(client.next().await),
+                json!({"type": "connection_ack"}),
+            );
+
+            client
+                .send(text(json!({
+                    "type": "start",
+                    "id": "1",
+                    "payload": {"query": "subscription { counter }"},
+                })))
+                .await
+                .unwrap();
+            for expected in &[1, 2] {
+                assert_eq!(
+                    parse(client.next().await),
+                    json!({
+                        "type": "data",
+                        "id": "1",
+                        "payload": {"data": {"counter": expected}},
+                    }),
+                );
+            }
+            assert_eq!(
+                parse(client.next().await),
+                json!({"type": "complete", "id": "1"}),
+            );
+        }
+
+        #[actix_rt::test]
+        async fn stops_subscriptions() {
+            let mut server = server();
+            let mut client = server.ws_at("/").await.expect("handshake");
+
+            client
+                .send(text(json!({
+                    "type": "start",
+                    "id": "1",
+                    "payload": {"query": "subscription { paused }"},
+                })))
+                .await
+                .unwrap();
+            client
+                .send(text(json!({"type": "stop", "id": "1"})))
+                .await
+                .unwrap();
+            assert_eq!(
+                parse(client.next().await),
+                json!({"type": "complete", "id": "1"}),
+            );
+
+            client
+                .send(text(json!({"type": "connection_terminate"})))
+                .await
+                .unwrap();
+            match client.next().await {
+                Some(Ok(ws::Frame::Close(None))) => {}
+                frame => panic!("expected a close frame, got {:?}", frame),
+            }
+        }
+
+        #[actix_rt::test]
+        async fn reports_invalid_subscriptions() {
+            let mut server = server();
+            let mut client = server.ws_at("/").await.expect("handshake");
+
+            client
+                .send(text(json!({
+                    "type": "start",
+                    "id": "1",
+                    "payload": {"query": "subscription { unknown }"},
+                })))
+                .await
+                .unwrap();
+
+            let error = parse(client.next().await);
+            assert_eq!(error["type"], "error");
+            assert_eq!(error["id"], "1");
+            assert_eq!(
+                parse(client.next().await),
+                json!({"type": "complete", "id": "1"}),
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use actix_web::{http::header, test, App};
+    use juniper::{
+        http::tests as http_tests,
+        tests::{model::Database, schema::Query},
+        EmptyMutation, EmptySubscription,
+    };
+
+    use super::*;
+
+    type Schema = RootNode<'static, Query, EmptyMutation<Database>, EmptySubscription<Database>>;
+
+    async fn graphql(
+        req: HttpRequest,
+        payload: web::Payload,
+        schema: web::Data<Schema>,
+    ) -> Result<HttpResponse, Error> {
+        graphql_handler(schema.get_ref(), &Database::new(), req, payload).await
+    }
+
+    fn make_test_request(request: test::TestRequest) -> http_tests::TestResponse {
+        actix_web::rt::System::new("test").block_on(async move {
+            let mut app = test::init_service(
+                App::new()
+                    .data(Schema::new(
+                        Query,
+                        EmptyMutation::new(),
+                        EmptySubscription::new(),
+                    ))
+                    .service(web::resource("/").to(graphql)),
+            )
+            .await;
+
+            let response = test::call_service(&mut app, request.to_request()).await;
+            let status_code = response.status().as_u16() as i32;
+            let content_type = response
+                .headers()
+                .get(header::CONTENT_TYPE)
+                .map(|value| value.to_str().unwrap().to_owned())
+                .unwrap_or_default();
+            let body = test::read_body(response).await;
+
+            http_tests::TestResponse {
+                status_code,
+                body: Some(String::from_utf8(body.to_vec()).unwrap()),
+                content_type,
+            }
+        })
+    }
+
+    struct TestActixIntegration;
+
+    impl http_tests::HTTPIntegration for TestActixIntegration {
+        fn get(&self, url: &str) -> http_tests::TestResponse {
+            make_test_request(test::TestRequest::get().uri(url))
+        }
+
+        fn post(&self, url: &str, body: &str) -> http_tests::TestResponse {
+            make_test_request(
+                test::TestRequest::post()
+                    .uri(url)
+                    .header(header::CONTENT_TYPE, "application/json")
+                    .set_payload(body.to_owned()),
+            )
+        }
+    }
+
+    #[test]
+    fn test_actix_integration() {
+        http_tests::run_http_test_suite(&TestActixIntegration);
+    }
+
+    #[test]
+    fn accepts_application_graphql_body() {
+        let response = make_test_request(
+            test::TestRequest::post()
+                .uri("/")
+                .header(header::CONTENT_TYPE, "application/graphql")
+                .set_payload("{hero{name}}"),
+        );
+
+        assert_eq!(response.status_code, 200);
+        assert_eq!(
+            response.body.unwrap(),
+            r#"{"data":{"hero":{"name":"R2-D2"}}}"#,
+        );
+    }
+
+    #[test]
+    fn rejects_unsupported_requests() {
+        let response = make_test_request(
+            test::TestRequest::post()
+                .uri("/")
+                .header(header::CONTENT_TYPE, "text/plain")
+                .set_payload("{hero{name}}"),
+        );
+        assert_eq!(response.status_code, 415);
+
+        let response = make_test_request(test::TestRequest::put().uri("/"));
+        assert_eq!(response.status_code, 405);
+    }
+
+    #[actix_rt::test]
+    async fn serves_graphiql_and_playground() {
+        let response = graphiql_handler("/graphql").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers().get(header::CONTENT_TYPE).unwrap(),
+            "text/html; charset=utf-8",
+        );
+
+        let response = playground_handler("/graphql", Some("/subscriptions"))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+}
@@ -43,6 +43,10 @@ pub fn build_derive_object(ast: syn::DeriveInput, is_internal: bool) -> TokenStr
             None
         } else {
             let field_name = field.ident.unwrap();
+            let snake_case_name = match field_attrs.name {
+                Some(_) => None,
+                None => Some(util::unraw(&field_name.to_string())),
+            };
             let name = field_attrs
                 .name
                 .clone()
@@ -54,10 +58,15 @@ pub fn build_derive_object(ast: syn::DeriveInput, is_internal: bool) -> TokenStr
 
             Some(util::GraphQLTypeDefinitionField {
                 name,
+                snake_case_name,
                 _type: field.ty,
                 args: Vec::new(),
                 description: field_attrs.description,
                 deprecation: field_attrs.deprecation,
+                cache_max_age: field_attrs.cache_max_age,
+                complexity: field_attrs.complexity,
+                list_size: field_attrs.list_size,
+                buffer: None,
                 resolver_code,
                 is_type_inferred: true,
                 is_async: false,
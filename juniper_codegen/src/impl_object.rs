@@ -3,24 +3,30 @@
 use crate::util;
 use proc_macro::TokenStream;
 use quote::quote;
+use syn::parse_quote;
 
 /// Generate code for the juniper::graphql_object macro.
 pub fn build_object(args: TokenStream, body: TokenStream, is_internal: bool) -> TokenStream {
-    let definition = create(args, body);
+    let (definition, skipped) = create(args, body);
     let juniper_crate_name = if is_internal { "crate" } else { "juniper" };
-    definition.into_tokens(juniper_crate_name).into()
+    let output = definition.into_tokens(juniper_crate_name);
+    quote!( #output #skipped ).into()
 }
 
 /// Generate code for the juniper::graphql_subscription macro.
 pub fn build_subscription(args: TokenStream, body: TokenStream, is_internal: bool) -> TokenStream {
-    let definition = create(args, body);
+    let (definition, skipped) = create(args, body);
     let juniper_crate_name = if is_internal { "crate" } else { "juniper" };
-    definition
-        .into_subscription_tokens(juniper_crate_name)
-        .into()
+    let output = definition.into_subscription_tokens(juniper_crate_name);
+    quote!( #output #skipped ).into()
 }
 
-fn create(args: TokenStream, body: TokenStream) -> util::GraphQLTypeDefiniton {
+/// Parse the impl block into a type definition, and an impl block keeping
+/// the methods marked with `#[graphql(skip)]` as regular methods.
+fn create(
+    args: TokenStream,
+    body: TokenStream,
+) -> (util::GraphQLTypeDefiniton, proc_macro2::TokenStream) {
     let _impl = util::parse_impl::ImplBlock::parse(args, body);
 
     let name = _impl
@@ -47,21 +53,11 @@ fn create(args: TokenStream, body: TokenStream) -> util::GraphQLTypeDefiniton {
         no_async: _impl.attrs.no_async,
     };
 
-    for method in _impl.methods {
-        let _type = match &method.sig.output {
-            syn::ReturnType::Type(_, ref t) => (**t).clone(),
-            syn::ReturnType::Default => {
-                panic!(
-                    "Invalid field method {}: must return a value",
-                    method.sig.ident
-                );
-            }
-        };
-
-        let is_async = method.sig.asyncness.is_some();
+    let mut skipped = Vec::new();
 
+    for mut method in _impl.methods {
         let attrs = match util::FieldAttributes::from_attrs(
-            method.attrs,
+            method.attrs.clone(),
             util::FieldAttributeParseMode::Impl,
         ) {
             Ok(attrs) => attrs,
@@ -71,6 +67,24 @@ fn create(args: TokenStream, body: TokenStream) -> util::GraphQLTypeDefiniton {
             ),
         };
 
+        if attrs.skip {
+            method.attrs.retain(|attr| !attr.path.is_ident("graphql"));
+            skipped.push(method);
+            continue;
+        }
+
+        let _type = match &method.sig.output {
+            syn::ReturnType::Type(_, ref t) => (**t).clone(),
+            syn::ReturnType::Default => {
+                panic!(
+                    "Invalid field method {}: must return a value",
+                    method.sig.ident
+                );
+            }
+        };
+
+        let mut is_async = method.sig.asyncness.is_some();
+
         let mut args = Vec::new();
         let mut resolve_parts = Vec::new();
 
@@ -128,16 +142,27 @@ fn create(args: TokenStream, body: TokenStream) -> util::GraphQLTypeDefiniton {
                         // Regular argument.
 
                         let ty = &captured.ty;
-                        // TODO: respect graphql attribute overwrite.
-                        let final_name = util::to_camel_case(&arg_name);
+                        let final_name = attrs
+                            .argument(&arg_name)
+                            .and_then(|arg| arg.rename.as_ref().map(|name| name.value()))
+                            .unwrap_or_else(|| util::to_camel_case(&arg_name));
+                        let flatten = attrs
+                            .argument(&arg_name)
+                            .map(|arg| arg.flatten)
+                            .unwrap_or(false);
                         let expect_text = format!(
                             "Internal error: missing argument {} - validation must have failed",
                             &final_name
                         );
                         let mut_modifier = if is_mut { quote!(mut) } else { quote!() };
+                        let get_arg = if flatten {
+                            quote!( get_flattened::<#ty>() )
+                        } else {
+                            quote!( get::<#ty>(#final_name) )
+                        };
                         resolve_parts.push(quote!(
                             let #mut_modifier #arg_ident = args
-                                .get::<#ty>(#final_name)
+                                .#get_arg
                                 .expect(#expect_text);
                         ));
                         args.push(util::GraphQLTypeDefinitionFieldArg {
@@ -149,6 +174,7 @@ fn create(args: TokenStream, body: TokenStream) -> util::GraphQLTypeDefiniton {
                                 .and_then(|arg| arg.default.clone()),
                             _type: ty.clone(),
                             name: final_name,
+                            flatten,
                         })
                     }
                 }
@@ -156,26 +182,111 @@ fn create(args: TokenStream, body: TokenStream) -> util::GraphQLTypeDefiniton {
         }
 
         let body = &method.block;
-        let resolver_code = quote!(
-            #( #resolve_parts )*
-            #body
-        );
+        let future_output = util::future_output_type(&_type).cloned();
+        let result_iterator = util::result_impl_iterator_item_type(&_type);
+        let (_type, resolver_code) = match (
+            future_output,
+            util::impl_iterator_item_type(&_type),
+            result_iterator,
+        ) {
+            // Futures are awaited while resolving the field asynchronously.
+            (Some(output), _, _) => {
+                let future = if is_async {
+                    quote!( async move #body.await )
+                } else {
+                    quote!( (|| #body)() )
+                };
+                is_async = true;
+                (
+                    output,
+                    quote!(
+                        #( #resolve_parts )*
+                        let future = #future;
+                        future.await
+                    ),
+                )
+            }
+            // Iterators are collected into a list while resolving the field,
+            // since `impl Trait` can't be named in the generated code.
+            (None, Some(item), _) => {
+                let iter = if is_async {
+                    quote!( async move #body.await )
+                } else {
+                    quote!( (|| #body)() )
+                };
+                (
+                    parse_quote!( Vec<#item> ),
+                    quote!(
+                        #( #resolve_parts )*
+                        let iter = #iter;
+                        ::std::iter::IntoIterator::into_iter(iter).collect::<Vec<_>>()
+                    ),
+                )
+            }
+            // The same goes for iterators returned in a `Result`.
+            (None, None, Some((_, result_type))) => {
+                let result = if is_async {
+                    quote!( async move #body.await )
+                } else {
+                    quote!( (|| #body)() )
+                };
+                (
+                    result_type,
+                    quote!(
+                        #( #resolve_parts )*
+                        let result = #result;
+                        ::std::result::Result::map(result, |iter| {
+                            ::std::iter::IntoIterator::into_iter(iter).collect::<Vec<_>>()
+                        })
+                    ),
+                )
+            }
+            (None, None, None) => (
+                _type,
+                quote!(
+                    #( #resolve_parts )*
+                    #body
+                ),
+            ),
+        };
 
         let ident = &method.sig.ident;
+        let snake_case_name = match attrs.name {
+            Some(_) => None,
+            None => Some(util::unraw(&ident.to_string())),
+        };
         let name = attrs
             .name
             .unwrap_or_else(|| util::to_camel_case(&ident.to_string()));
 
         definition.fields.push(util::GraphQLTypeDefinitionField {
             name,
+            snake_case_name,
             _type,
             args,
             description: attrs.description,
             deprecation: attrs.deprecation,
+            cache_max_age: attrs.cache_max_age,
+            complexity: attrs.complexity,
+            list_size: attrs.list_size,
+            buffer: attrs.buffer,
             resolver_code,
             is_type_inferred: false,
             is_async,
         });
     }
-    definition
+
+    let skipped = if skipped.is_empty() {
+        quote!()
+    } else {
+        let target_type = &_impl.target_type;
+        let (impl_generics, _, where_clause) = _impl.generics.split_for_impl();
+        quote!(
+            impl #impl_generics #target_type #where_clause {
+                #( #skipped )*
+            }
+        )
+    };
+
+    (definition, skipped)
 }
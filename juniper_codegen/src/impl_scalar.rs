@@ -0,0 +1,234 @@
+use proc_macro::TokenStream;
+
+use proc_macro_error::MacroError;
+use quote::quote;
+use syn::{parse_quote, spanned::Spanned};
+
+use crate::util;
+
+/// The parts of a `from_input_value` or `from_str` method that are copied into
+/// the generated trait implementations.
+struct ParseMethod<'a> {
+    arg: &'a syn::Ident,
+    lifetime: Option<&'a syn::Lifetime>,
+    result: &'a syn::Type,
+    body: &'a syn::Block,
+}
+
+impl<'a> ParseMethod<'a> {
+    fn from_method(method: &'a syn::ImplItemMethod) -> Result<Self, MacroError> {
+        let arg = match method.sig.inputs.iter().collect::<Vec<_>>().as_slice() {
+            [syn::FnArg::Typed(arg)] => match &*arg.pat {
+                syn::Pat::Ident(pat) => &pat.ident,
+                _ => {
+                    return Err(MacroError::new(
+                        arg.pat.span(),
+                        "Invalid argument: expected an identifier".to_string(),
+                    ))
+                }
+            },
+            _ => {
+                return Err(MacroError::new(
+                    method.sig.span(),
+                    format!("{}() must take exactly one argument", method.sig.ident),
+                ))
+            }
+        };
+
+        let result = match &method.sig.output {
+            syn::ReturnType::Type(_, ty) => &**ty,
+            syn::ReturnType::Default => {
+                return Err(MacroError::new(
+                    method.sig.span(),
+                    format!("{}() must declare its return type", method.sig.ident),
+                ))
+            }
+        };
+
+        Ok(ParseMethod {
+            arg,
+            lifetime: method.sig.generics.lifetimes().next().map(|l| &l.lifetime),
+            result,
+            body: &method.block,
+        })
+    }
+}
+
+pub fn build_scalar(
+    is_internal: bool,
+    attrs: TokenStream,
+    body: TokenStream,
+) -> Result<TokenStream, MacroError> {
+    let _impl = util::parse_impl::ImplBlock::parse(attrs, body);
+
+    // Validate trait target name, if present.
+    if let Some((name, path)) = &_impl.target_trait {
+        if !(name == "GraphQLScalar" || name == "juniper.GraphQLScalar") {
+            return Err(MacroError::new(
+                path.span(),
+                "Invalid impl target trait: expected 'GraphQLScalar'".to_string(),
+            ));
+        }
+    }
+
+    let find_method = |name: &str| {
+        _impl
+            .methods
+            .iter()
+            .find(|m| m.sig.ident == name)
+            .ok_or_else(|| {
+                MacroError::new(
+                    _impl.target_type.span(),
+                    format!("Invalid impl body: missing {} function", name),
+                )
+            })
+    };
+    let resolve_body = &find_method("resolve")?.block;
+    let from_input_value = ParseMethod::from_method(find_method("from_input_value")?)?;
+    let from_str = ParseMethod::from_method(find_method("from_str")?)?;
+    let from_str_lifetime = from_str.lifetime.ok_or_else(|| {
+        MacroError::new(
+            from_str.result.span(),
+            "from_str() must declare the lifetime of its ScalarToken, e.g. from_str<'a>"
+                .to_string(),
+        )
+    })?;
+
+    let type_ident = &_impl.type_ident;
+    let name = _impl
+        .attrs
+        .name
+        .clone()
+        .unwrap_or_else(|| type_ident.to_string());
+    let crate_name = util::juniper_path(is_internal);
+
+    // A type parameter of the impl block stands for the scalar value
+    // representation, otherwise the one given in the attribute is used.
+    let scalar = match _impl.generics.type_params().next() {
+        Some(param) => {
+            let ident = &param.ident;
+            quote!( #ident )
+        }
+        None => _impl
+            .attrs
+            .scalar
+            .as_ref()
+            .map(|s| quote!( #s ))
+            .unwrap_or_else(|| quote!( #crate_name::DefaultScalarValue )),
+    };
+
+    let generics = &_impl.generics;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    let mut async_generics = generics.clone();
+    {
+        let where_clause = async_generics.make_where_clause();
+        where_clause
+            .predicates
+            .push(parse_quote!( #scalar: Send + Sync ));
+        where_clause
+            .predicates
+            .push(parse_quote!( Self: #crate_name::GraphQLType<#scalar> + Send + Sync ));
+        where_clause
+            .predicates
+            .push(parse_quote!( Self::Context: Send + Sync ));
+        where_clause
+            .predicates
+            .push(parse_quote!( Self::TypeInfo: Send + Sync ));
+    }
+    let (_, _, async_where_clause) = async_generics.split_for_impl();
+
+    let description = match _impl.description.as_ref() {
+        Some(value) => quote!( .description( #value ) ),
+        None => quote!(),
+    };
+
+    let ty = &_impl.target_type;
+    let ParseMethod {
+        arg: from_input_value_arg,
+        result: from_input_value_result,
+        body: from_input_value_body,
+        ..
+    } = from_input_value;
+    let ParseMethod {
+        arg: from_str_arg,
+        result: from_str_result,
+        body: from_str_body,
+        ..
+    } = from_str;
+
+    let output = quote! {
+        impl #impl_generics #crate_name::GraphQLType<#scalar> for #ty #where_clause
+        {
+            type Context = ();
+            type TypeInfo = ();
+
+            fn name(_: &Self::TypeInfo) -> Option<&str> {
+                Some(#name)
+            }
+
+            fn meta<'r>(
+                info: &Self::TypeInfo,
+                registry: &mut #crate_name::Registry<'r, #scalar>,
+            ) -> #crate_name::meta::MetaType<'r, #scalar>
+            where
+                #scalar: 'r,
+            {
+                registry.build_scalar_type::<Self>(info)
+                    #description
+                    .into_meta()
+            }
+
+            fn resolve(
+                &self,
+                _: &(),
+                _: Option<&[#crate_name::Selection<#scalar>]>,
+                _: &#crate_name::Executor<Self::Context, #scalar>,
+            ) -> #crate_name::ExecutionResult<#scalar> {
+                Ok(#resolve_body)
+            }
+        }
+
+        impl #impl_generics #crate_name::GraphQLTypeAsync<#scalar> for #ty #async_where_clause
+        {
+            fn resolve_async<'a>(
+                &'a self,
+                info: &'a Self::TypeInfo,
+                selection_set: Option<&'a [#crate_name::Selection<#scalar>]>,
+                executor: &'a #crate_name::Executor<Self::Context, #scalar>,
+            ) -> #crate_name::BoxFuture<'a, #crate_name::ExecutionResult<#scalar>> {
+                use #crate_name::GraphQLType;
+                use futures::future;
+                let v = self.resolve(info, selection_set, executor);
+                Box::pin(future::ready(v))
+            }
+        }
+
+        impl #impl_generics #crate_name::ToInputValue<#scalar> for #ty #where_clause
+        {
+            fn to_input_value(&self) -> #crate_name::InputValue<#scalar> {
+                let v = #resolve_body;
+                #crate_name::ToInputValue::to_input_value(&v)
+            }
+        }
+
+        impl #impl_generics #crate_name::FromInputValue<#scalar> for #ty #where_clause
+        {
+            fn from_input_value(
+                #from_input_value_arg: &#crate_name::InputValue<#scalar>
+            ) -> #from_input_value_result {
+                #from_input_value_body
+            }
+        }
+
+        impl #impl_generics #crate_name::ParseScalarValue<#scalar> for #ty #where_clause
+        {
+            fn from_str<#from_str_lifetime>(
+                #from_str_arg: #crate_name::parser::ScalarToken<#from_str_lifetime>,
+            ) -> #from_str_result {
+                #from_str_body
+            }
+        }
+    };
+    Ok(output.into())
+}
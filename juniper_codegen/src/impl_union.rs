@@ -2,7 +2,7 @@ use proc_macro::TokenStream;
 
 use proc_macro_error::MacroError;
 use quote::quote;
-use syn::spanned::Spanned;
+use syn::{parse_quote, spanned::Spanned};
 
 use crate::util;
 
@@ -137,9 +137,35 @@ pub fn impl_union(
         }
     });
 
+    let resolve_into_type_async = body.variants.iter().map(|var| {
+        let var_ty = &var.ty;
+        let resolve = &var.resolver;
+
+        quote! {
+            if type_name == (<#var_ty as #crate_name::GraphQLType<#scalar>>::name(&())).unwrap() {
+                let inner_res = { #resolve };
+                return Box::pin(async move {
+                    executor.resolve_with_ctx_async(&(), &inner_res).await
+                });
+            }
+        }
+    });
+
     let generics = _impl.generics;
     let (impl_generics, _, where_clause) = generics.split_for_impl();
 
+    let mut async_generics = generics.clone();
+    {
+        let where_clause = async_generics.make_where_clause();
+        where_clause
+            .predicates
+            .push(parse_quote!( #scalar: Send + Sync ));
+        where_clause
+            .predicates
+            .push(parse_quote!( Self: Send + Sync ));
+    }
+    let (_, _, async_where_clause) = async_generics.split_for_impl();
+
     let description = match _impl.description.as_ref() {
         Some(value) => quote!( .description( #value ) ),
         None => quote!(),
@@ -202,6 +228,28 @@ pub fn impl_union(
             }
         }
 
+        impl #impl_generics #crate_name::GraphQLTypeAsync<#scalar> for #ty #async_where_clause
+        {
+            fn resolve_into_type_async<'b>(
+                &'b self,
+                _info: &'b Self::TypeInfo,
+                type_name: &str,
+                _: Option<&'b [#crate_name::Selection<'b, #scalar>]>,
+                executor: &'b #crate_name::Executor<'b, 'b, Self::Context, #scalar>,
+            ) -> #crate_name::BoxFuture<'b, #crate_name::ExecutionResult<#scalar>> {
+                let context = &executor.context();
+                #( #resolve_args )*
+
+                #( #resolve_into_type_async )*
+
+                Box::pin(async move {
+                    Err(#crate_name::FieldError::from(format!(
+                        "Concrete type not handled by instance resolvers on {}",
+                        #name,
+                    )))
+                })
+            }
+        }
 
     };
     Ok(output.into())
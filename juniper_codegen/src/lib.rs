@@ -16,6 +16,7 @@ mod derive_input_object;
 mod derive_object;
 mod derive_scalar_value;
 mod impl_object;
+mod impl_scalar;
 mod impl_union;
 
 use proc_macro::TokenStream;
@@ -254,6 +255,37 @@ impl Query {
 
 ```
 
+## Async fields
+
+Fields declared as `async fn` are resolved asynchronously, and so are fields
+returning a future: `BoxFuture<'_, T>`, `Pin<Box<dyn Future<Output = T> + Send>>`
+or `impl Future<Output = T>`. Such a field has the type `T`, and the future is
+awaited by the executor.
+
+```
+use juniper::BoxFuture;
+
+struct User {
+    name: String,
+}
+
+// A future built outside of the resolver.
+fn load_name(user: &User) -> BoxFuture<'_, String> {
+    Box::pin(async move { user.name.clone() })
+}
+
+#[juniper::graphql_object]
+impl User {
+    async fn greeting(&self) -> String {
+        format!("Hello, {}!", self.name)
+    }
+
+    fn name(&self) -> BoxFuture<'_, String> {
+        load_name(self)
+    }
+}
+```
+
 ## Customization (Documentation, Renaming, ...)
 
 ```
@@ -304,6 +336,8 @@ impl InternalQuery {
                 description = "Argument description....",
             ),
             arg2(
+                // Overrides the camelCased name of the argument.
+                name = "secondArg",
                 default = false,
                 description = "arg2 description...",
             ),
@@ -315,6 +349,35 @@ impl InternalQuery {
 }
 ```
 
+## Flattened arguments
+
+Fields with many arguments can bind them all to a single parameter whose type
+derives `GraphQLInputObject`. The fields of the input object, with their
+defaults and descriptions, become the arguments of the field, and the input
+object itself is not added to the schema.
+
+```
+#[derive(juniper::GraphQLInputObject)]
+struct SearchArgs {
+    text: String,
+    #[graphql(default = "10")]
+    limit: i32,
+    offset: Option<i32>,
+}
+
+struct Query;
+
+#[juniper::graphql_object]
+impl Query {
+    // Creates the field search(text: String!, limit: Int = 10, offset: Int)
+    #[graphql(arguments(args(flatten)))]
+    fn search(args: SearchArgs) -> Vec<String> {
+        let _ = (args.text, args.limit, args.offset);
+        vec![]
+    }
+}
+```
+
 ## Lifetimes, Generics and custom Scalars
 
 Lifetimes work just like you'd expect.
@@ -386,6 +449,18 @@ pub fn graphql_object_internal(args: TokenStream, input: TokenStream) -> TokenSt
 }
 
 /// A proc macro for defining a GraphQL subscription.
+///
+/// Subscription resolvers are `async fn`s returning any `Stream` of values
+/// (or a `Result` of one), so channel receivers such as
+/// `futures::channel::mpsc::Receiver<T>` can be returned directly.
+///
+/// A stream may also yield `Result<T, E>` items where `E: Into<FieldError>`.
+/// An `Err` item is reported as an error for that event at the subscribed
+/// field, and the stream keeps going with the following items.
+///
+/// Resolvers take arguments, the context and the executor just like the
+/// fields of `graphql_object`, so the arguments of a subscription can be used
+/// to build its stream, e.g. to only stream the messages of a given channel.
 #[proc_macro_attribute]
 pub fn graphql_subscription(args: TokenStream, input: TokenStream) -> TokenStream {
     impl_object::build_subscription(args, input, false)
@@ -415,3 +490,92 @@ pub fn graphql_union_internal(attrs: TokenStream, body: TokenStream) -> TokenStr
         Err(err) => proc_macro_error::abort!(err),
     }
 }
+
+/// Expose GraphQL scalars
+///
+/// The GraphQL language defines a number of built-in scalars: strings, numbers, and
+/// booleans. This macro can be used either to define new types of scalars (e.g.
+/// timestamps), or expose other types as one of the built-in scalars (e.g. bigints
+/// as numbers or strings).
+///
+/// Since the preferred transport protocol for GraphQL responses is JSON, most
+/// custom scalars will be transferred as strings. You therefore need to ensure that
+/// the client library you are sending data to can parse the custom value into a
+/// datatype appropriate for that platform.
+///
+/// The macro is applied to an `impl GraphQLScalar for T` block declaring three
+/// functions: `resolve` turns the value into its output representation,
+/// `from_input_value` parses it from a variable or argument, and `from_str`
+/// parses the literal tokens of the scalar in a query. The scalar is named
+/// after the type unless a `name` is given, and the `description` defaults to
+/// the doc comment of the block. It generates the same code as the
+/// `graphql_scalar!` macro.
+///
+/// ```rust
+/// # use juniper::{Value, ParseScalarResult, ParseScalarValue};
+/// struct UserID(String);
+///
+/// #[juniper::graphql_scalar_impl(description = "An opaque identifier, represented as a string")]
+/// impl GraphQLScalar for UserID {
+///     fn resolve(&self) -> Value {
+///         Value::scalar(self.0.to_owned())
+///     }
+///
+///     fn from_input_value(value: &juniper::InputValue) -> Option<UserID> {
+///         value.as_string_value().map(|s| UserID(s.to_owned()))
+///     }
+///
+///     fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a> {
+///         <String as ParseScalarValue>::from_str(value)
+///     }
+/// }
+/// ```
+///
+/// By default the scalar is implemented in terms of the default scalar value
+/// representation provided by juniper. Declare a type parameter on the `impl`
+/// block to implement it for any representation instead:
+///
+/// ```rust
+/// # use juniper::{Value, ParseScalarResult, ParseScalarValue, ScalarValue};
+/// struct UserID(String);
+///
+/// #[juniper::graphql_scalar_impl(name = "UserId")]
+/// impl<S> GraphQLScalar for UserID
+/// where
+///     S: ScalarValue,
+/// {
+///     fn resolve(&self) -> Value {
+///         Value::scalar(self.0.to_owned())
+///     }
+///
+///     fn from_input_value(value: &juniper::InputValue) -> Option<UserID> {
+///         value.as_string_value().map(|s| UserID(s.to_owned()))
+///     }
+///
+///     fn from_str<'a>(value: ScalarToken<'a>) -> ParseScalarResult<'a, S> {
+///         <String as ParseScalarValue<S>>::from_str(value)
+///     }
+/// }
+/// ```
+///
+/// In addition to implementing `GraphQLType` for the type in question,
+/// `FromInputValue` and `ToInputValue` is also implemented. This makes the type
+/// usable as arguments and default values.
+#[proc_macro_attribute]
+#[proc_macro_error::proc_macro_error]
+pub fn graphql_scalar_impl(attrs: TokenStream, body: TokenStream) -> TokenStream {
+    match impl_scalar::build_scalar(false, attrs, body) {
+        Ok(toks) => toks,
+        Err(err) => proc_macro_error::abort!(err),
+    }
+}
+
+#[doc(hidden)]
+#[proc_macro_attribute]
+#[proc_macro_error::proc_macro_error]
+pub fn graphql_scalar_internal(attrs: TokenStream, body: TokenStream) -> TokenStream {
+    match impl_scalar::build_scalar(true, attrs, body) {
+        Ok(toks) => toks,
+        Err(err) => proc_macro_error::abort!(err),
+    }
+}
@@ -69,6 +69,136 @@ pub fn type_is_identifier_ref(ty: &syn::Type, name: &str) -> bool {
     }
 }
 
+/// Returns the item type of an `impl Iterator<Item = T>` or
+/// `impl IntoIterator<Item = T>` type.
+pub fn impl_iterator_item_type(ty: &syn::Type) -> Option<&syn::Type> {
+    let bounds = match ty {
+        syn::Type::ImplTrait(ref impl_trait) => &impl_trait.bounds,
+        _ => return None,
+    };
+
+    bounds.iter().find_map(|bound| {
+        let segment = match bound {
+            syn::TypeParamBound::Trait(ref trait_bound) => trait_bound.path.segments.last()?,
+            _ => return None,
+        };
+        match segment.ident.to_string().as_str() {
+            "Iterator" | "IntoIterator" | "ExactSizeIterator" | "DoubleEndedIterator" => {}
+            _ => return None,
+        }
+        match segment.arguments {
+            syn::PathArguments::AngleBracketed(ref args) => {
+                args.args.iter().find_map(|arg| match arg {
+                    syn::GenericArgument::Binding(ref binding) if binding.ident == "Item" => {
+                        Some(&binding.ty)
+                    }
+                    _ => None,
+                })
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Returns the item type of an `impl Iterator<Item = T>` returned as the
+/// `Ok` value of a `Result` or `FieldResult`, along with the result type
+/// holding a `Vec<T>` instead.
+pub fn result_impl_iterator_item_type(ty: &syn::Type) -> Option<(syn::Type, syn::Type)> {
+    let mut result_type = ty.clone();
+    let ok_type = match result_type {
+        syn::Type::Path(ref mut path) => {
+            let segment = path.path.segments.last_mut()?;
+            match segment.ident.to_string().as_str() {
+                "Result" | "FieldResult" => {}
+                _ => return None,
+            }
+            match segment.arguments {
+                syn::PathArguments::AngleBracketed(ref mut args) => {
+                    args.args.iter_mut().find_map(|arg| match arg {
+                        syn::GenericArgument::Type(ref mut ty) => Some(ty),
+                        _ => None,
+                    })?
+                }
+                _ => return None,
+            }
+        }
+        _ => return None,
+    };
+
+    let item = impl_iterator_item_type(ok_type)?.clone();
+    *ok_type = parse_quote!( Vec<#item> );
+    Some((item, result_type))
+}
+
+/// Returns the output type of a future returned by a resolver:
+/// `BoxFuture<'a, T>`, `Pin<Box<dyn Future<Output = T>>>` or
+/// `impl Future<Output = T>`.
+///
+/// A `Box<dyn Future>` which is not pinned is not a future, and is resolved
+/// like any other type.
+pub fn future_output_type(ty: &syn::Type) -> Option<&syn::Type> {
+    if let syn::Type::ImplTrait(ref impl_trait) = ty {
+        return future_trait_output_type(&impl_trait.bounds);
+    }
+
+    let (ident, arg) = path_type_argument(ty)?;
+    if ident == "BoxFuture" {
+        Some(arg)
+    } else if ident == "Pin" {
+        match path_type_argument(arg)? {
+            (pointer, syn::Type::TraitObject(ref trait_object)) if pointer == "Box" => {
+                future_trait_output_type(&trait_object.bounds)
+            }
+            _ => None,
+        }
+    } else {
+        None
+    }
+}
+
+/// Returns the last segment of a path type, along with its first type
+/// argument.
+fn path_type_argument(ty: &syn::Type) -> Option<(&syn::Ident, &syn::Type)> {
+    let segment = match ty {
+        syn::Type::Path(ref path) => path.path.segments.last()?,
+        _ => return None,
+    };
+    match segment.arguments {
+        syn::PathArguments::AngleBracketed(ref args) => {
+            args.args.iter().find_map(|arg| match arg {
+                syn::GenericArgument::Type(ref ty) => Some((&segment.ident, ty)),
+                _ => None,
+            })
+        }
+        _ => None,
+    }
+}
+
+fn future_trait_output_type(
+    bounds: &syn::punctuated::Punctuated<syn::TypeParamBound, syn::Token![+]>,
+) -> Option<&syn::Type> {
+    bounds.iter().find_map(|bound| {
+        let segment = match bound {
+            syn::TypeParamBound::Trait(ref trait_bound) => trait_bound.path.segments.last()?,
+            _ => return None,
+        };
+        if segment.ident != "Future" {
+            return None;
+        }
+        match segment.arguments {
+            syn::PathArguments::AngleBracketed(ref args) => {
+                args.args.iter().find_map(|arg| match arg {
+                    syn::GenericArgument::Binding(ref binding) if binding.ident == "Output" => {
+                        Some(&binding.ty)
+                    }
+                    _ => None,
+                })
+            }
+            _ => None,
+        }
+    })
+}
+
 pub enum AttributeValidation {
     Any,
     // Bare,
@@ -409,8 +539,10 @@ impl ObjectAttributes {
 #[derive(Debug)]
 pub struct FieldAttributeArgument {
     pub name: syn::Ident,
+    pub rename: Option<syn::LitStr>,
     pub default: Option<syn::Expr>,
     pub description: Option<syn::LitStr>,
+    pub flatten: bool,
 }
 
 impl parse::Parse for FieldAttributeArgument {
@@ -419,17 +551,37 @@ impl parse::Parse for FieldAttributeArgument {
 
         let mut arg = Self {
             name,
+            rename: None,
             default: None,
             description: None,
+            flatten: false,
         };
 
         let content;
         syn::parenthesized!(content in input);
         while !content.is_empty() {
             let name = content.parse::<syn::Ident>()?;
+
+            if name == "flatten" {
+                arg.flatten = true;
+                content.parse::<Token![,]>().ok();
+                continue;
+            }
+
             content.parse::<Token![=]>()?;
 
             match name.to_string().as_str() {
+                "name" => {
+                    let lit = content.parse::<syn::LitStr>()?;
+                    if !is_valid_name(&lit.value()) {
+                        return Err(content.error(format!(
+                            "Invalid argument name: '{}'\nNames must \
+                             match /^[_a-zA-Z][_a-zA-Z0-9]*$/",
+                            lit.value(),
+                        )));
+                    }
+                    arg.rename = Some(lit);
+                }
                 "description" => {
                     arg.description = Some(content.parse()?);
                 }
@@ -445,6 +597,14 @@ impl parse::Parse for FieldAttributeArgument {
             content.parse::<Token![,]>().ok();
         }
 
+        if arg.flatten
+            && (arg.rename.is_some() || arg.default.is_some() || arg.description.is_some())
+        {
+            return Err(content.error(
+                "A flattened argument takes its names, defaults and descriptions from its input object",
+            ));
+        }
+
         Ok(arg)
     }
 }
@@ -461,6 +621,9 @@ enum FieldAttribute {
     Deprecation(DeprecationAttr),
     Skip(syn::Ident),
     Arguments(HashMap<String, FieldAttributeArgument>),
+    CacheControl(u32),
+    Complexity(Option<u32>, Option<u32>),
+    Buffer(usize, syn::Ident),
 }
 
 impl parse::Parse for FieldAttribute {
@@ -509,6 +672,79 @@ impl parse::Parse for FieldAttribute {
                     .collect();
                 Ok(FieldAttribute::Arguments(map))
             }
+            "cache_control" => {
+                let content;
+                syn::parenthesized!(content in input);
+                let key = content.parse::<syn::Ident>()?;
+                if key != "max_age" {
+                    return Err(content.error(format!("Unknown cache_control attribute: {}", key)));
+                }
+                content.parse::<Token![=]>()?;
+                let max_age = content.parse::<syn::LitInt>()?.base10_parse()?;
+                Ok(FieldAttribute::CacheControl(max_age))
+            }
+            "complexity" => {
+                let content;
+                syn::parenthesized!(content in input);
+                let (mut cost, mut list_size) = (None, None);
+                while !content.is_empty() {
+                    let key = content.parse::<syn::Ident>()?;
+                    content.parse::<Token![=]>()?;
+                    let value = content.parse::<syn::LitInt>()?.base10_parse()?;
+                    match key.to_string().as_str() {
+                        "cost" => cost = Some(value),
+                        "list_size" => list_size = Some(value),
+                        other => {
+                            return Err(
+                                content.error(format!("Unknown complexity attribute: {}", other))
+                            )
+                        }
+                    }
+                    if content.lookahead1().peek(Token![,]) {
+                        content.parse::<Token![,]>()?;
+                    }
+                }
+                Ok(FieldAttribute::Complexity(cost, list_size))
+            }
+            "buffer" => {
+                let content;
+                syn::parenthesized!(content in input);
+                let (mut size, mut overflow) = (None, None);
+                while !content.is_empty() {
+                    let key = content.parse::<syn::Ident>()?;
+                    content.parse::<Token![=]>()?;
+                    match key.to_string().as_str() {
+                        "size" => size = Some(content.parse::<syn::LitInt>()?.base10_parse()?),
+                        "overflow" => {
+                            let lit = content.parse::<syn::LitStr>()?;
+                            let variant = match lit.value().as_str() {
+                                "wait" => "Wait",
+                                "drop_oldest" => "DropOldest",
+                                "error" => "Error",
+                                other => {
+                                    return Err(syn::Error::new(
+                                        lit.span(),
+                                        format!("Unknown buffer overflow policy: {}", other),
+                                    ))
+                                }
+                            };
+                            overflow = Some(syn::Ident::new(variant, lit.span()));
+                        }
+                        other => {
+                            return Err(
+                                content.error(format!("Unknown buffer attribute: {}", other))
+                            )
+                        }
+                    }
+                    if content.lookahead1().peek(Token![,]) {
+                        content.parse::<Token![,]>()?;
+                    }
+                }
+                let size =
+                    size.ok_or_else(|| syn::Error::new(ident.span(), "Missing buffer size"))?;
+                let overflow = overflow.unwrap_or_else(|| syn::Ident::new("Wait", ident.span()));
+                Ok(FieldAttribute::Buffer(size, overflow))
+            }
             other => Err(input.error(format!("Unknown attribute: {}", other))),
         }
     }
@@ -519,10 +755,15 @@ pub struct FieldAttributes {
     pub name: Option<String>,
     pub description: Option<String>,
     pub deprecation: Option<DeprecationAttr>,
-    // Only relevant for GraphQLObject derive.
+    // Only relevant for GraphQLObject derive and object macro.
     pub skip: bool,
     /// Only relevant for object macro.
     pub arguments: HashMap<String, FieldAttributeArgument>,
+    pub cache_max_age: Option<u32>,
+    pub complexity: Option<u32>,
+    pub list_size: Option<u32>,
+    /// Only relevant for subscription macro.
+    pub buffer: Option<(usize, syn::Ident)>,
 }
 
 impl parse::Parse for FieldAttributes {
@@ -535,6 +776,10 @@ impl parse::Parse for FieldAttributes {
             deprecation: None,
             skip: false,
             arguments: Default::default(),
+            cache_max_age: None,
+            complexity: None,
+            list_size: None,
+            buffer: None,
         };
 
         for item in items {
@@ -554,6 +799,16 @@ impl parse::Parse for FieldAttributes {
                 FieldAttribute::Arguments(args) => {
                     output.arguments = args;
                 }
+                FieldAttribute::CacheControl(max_age) => {
+                    output.cache_max_age = Some(max_age);
+                }
+                FieldAttribute::Complexity(cost, list_size) => {
+                    output.complexity = cost;
+                    output.list_size = list_size;
+                }
+                FieldAttribute::Buffer(size, overflow) => {
+                    output.buffer = Some((size, overflow));
+                }
             }
         }
 
@@ -602,20 +857,68 @@ pub struct GraphQLTypeDefinitionFieldArg {
     pub description: Option<String>,
     pub default: Option<syn::Expr>,
     pub _type: Box<syn::Type>,
+    /// The fields of the argument's input object are the field's arguments.
+    pub flatten: bool,
 }
 
 #[derive(Debug)]
 pub struct GraphQLTypeDefinitionField {
     pub name: String,
+    /// Name of the field under the snake case naming policy, if its name is
+    /// derived from its Rust identifier rather than set explicitly.
+    pub snake_case_name: Option<String>,
     pub _type: syn::Type,
     pub description: Option<String>,
     pub deprecation: Option<DeprecationAttr>,
+    pub cache_max_age: Option<u32>,
+    pub complexity: Option<u32>,
+    pub list_size: Option<u32>,
+    /// Buffer size and overflow policy of a subscription field.
+    pub buffer: Option<(usize, syn::Ident)>,
     pub args: Vec<GraphQLTypeDefinitionFieldArg>,
     pub resolver_code: proc_macro2::TokenStream,
     pub is_type_inferred: bool,
     pub is_async: bool,
 }
 
+impl GraphQLTypeDefinitionField {
+    /// Expression evaluating to the name of the field following the field
+    /// naming policy of the schema, looked up on `on`, a `Registry` or an
+    /// `Executor`.
+    fn name_tokens(
+        &self,
+        type_name: &str,
+        on: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        let name = unraw(&self.name);
+        match self.snake_case_name {
+            Some(ref snake_case) if *snake_case != name => {
+                quote!( #on.derived_field_name(#type_name, #name, #snake_case) )
+            }
+            _ => quote!( #name ),
+        }
+    }
+
+    /// Pattern of the arm resolving the field when matching on the field name
+    /// in `scrutinee`.
+    fn resolve_pattern(
+        &self,
+        type_name: &str,
+        scrutinee: proc_macro2::TokenStream,
+    ) -> proc_macro2::TokenStream {
+        match self.snake_case_name {
+            Some(ref snake_case) if *snake_case != unraw(&self.name) => {
+                let name = self.name_tokens(type_name, quote!(executor));
+                quote!( _ if #scrutinee == #name )
+            }
+            _ => {
+                let name = &self.name;
+                quote!( #name )
+            }
+        }
+    }
+}
+
 pub fn unraw(s: &str) -> String {
     use syn::ext::IdentExt;
     quote::format_ident!("{}", s).unraw().to_string()
@@ -677,6 +980,10 @@ impl GraphQLTypeDefiniton {
                     None => quote!(),
                 };
 
+                if arg.flatten {
+                    return quote!( .arguments(registry.flattened_args::<#arg_type>(info)) );
+                }
+
                 // Code.
                 match arg.default.as_ref() {
                     Some(value) => quote!(
@@ -710,7 +1017,15 @@ impl GraphQLTypeDefiniton {
                 None => quote!(),
             };
 
-            let field_name = unraw(&field.name);
+            let cache_control = field
+                .cache_max_age
+                .map(|max_age| quote!( .cache_control(#max_age) ));
+
+            let complexity = field.complexity.map(|cost| quote!( .complexity(#cost) ));
+
+            let list_size = field.list_size.map(|size| quote!( .list_size(#size) ));
+
+            let field_name = field.name_tokens(name, quote!(registry));
 
             let _type = &field._type;
             quote! {
@@ -719,6 +1034,9 @@ impl GraphQLTypeDefiniton {
                     #(#args)*
                     #description
                     #deprecation
+                    #cache_control
+                    #complexity
+                    #list_size
             }
         });
 
@@ -738,15 +1056,20 @@ impl GraphQLTypeDefiniton {
 
         let resolve_matches = self.fields.iter().map(|field| {
             let name = &field.name;
+            let pattern = field.resolve_pattern(&self.name, quote!(field));
             let code = &field.resolver_code;
 
             if field.is_async {
                 quote!(
-                    #name => {
-                        panic!("Tried to resolve async field {} on type {:?} with a sync resolver",
-                            #name,
-                            <Self as #juniper_crate_name::GraphQLType<#scalar>>::name(_info)
-                        );
+                    #pattern => {
+                        Err(#juniper_crate_name::FieldError::new(
+                            format!(
+                                "Tried to resolve async field {} on type {:?} with a sync resolver",
+                                #name,
+                                <Self as #juniper_crate_name::GraphQLType<#scalar>>::name(_info),
+                            ),
+                            #juniper_crate_name::Value::null(),
+                        ))
                     },
                 )
             } else {
@@ -757,7 +1080,7 @@ impl GraphQLTypeDefiniton {
                     quote!(: #_type)
                 };
                 quote!(
-                    #name => {
+                    #pattern => {
                         let res #_type = (|| { #code })();
                         #juniper_crate_name::IntoResolvable::into(
                             res,
@@ -816,7 +1139,7 @@ impl GraphQLTypeDefiniton {
 
         let resolve_field_async = {
             let resolve_matches_async = self.fields.iter().map(|field| {
-                let name = &field.name;
+                let pattern = field.resolve_pattern(&self.name, quote!(field));
                 let code = &field.resolver_code;
                 let _type = if field.is_type_inferred {
                     quote!()
@@ -827,7 +1150,7 @@ impl GraphQLTypeDefiniton {
 
                 if field.is_async {
                     quote!(
-                        #name => {
+                        #pattern => {
                             let f = async move {
                                 let res #_type = async move { #code }.await;
 
@@ -879,7 +1202,7 @@ impl GraphQLTypeDefiniton {
                     };
 
                     quote!(
-                        #name => {
+                        #pattern => {
                             let res #_type = (||{ #code })();
                             let res2 = #juniper_crate_name::IntoResolvable::into(
                                 res,
@@ -1022,6 +1345,10 @@ impl GraphQLTypeDefiniton {
                     None => quote!(),
                 };
 
+                if arg.flatten {
+                    return quote!( .arguments(registry.flattened_args::<#arg_type>(info)) );
+                }
+
                 match arg.default.as_ref() {
                     Some(value) => quote!(
                         .argument(
@@ -1054,7 +1381,11 @@ impl GraphQLTypeDefiniton {
                 None => quote!(),
             };
 
-            let field_name = &field.name;
+            let buffer = field.buffer.as_ref().map(|(size, overflow)| {
+                quote!( .buffer(#size, #juniper_crate_name::BufferOverflow::#overflow) )
+            });
+
+            let field_name = field.name_tokens(name, quote!(registry));
 
             let type_name = &field._type;
 
@@ -1072,6 +1403,7 @@ impl GraphQLTypeDefiniton {
                     #(#args)*
                     #description
                     #deprecation
+                    #buffer
             }
         });
 
@@ -1119,7 +1451,7 @@ impl GraphQLTypeDefiniton {
             .iter()
             .filter(|field| field.is_async)
             .map(|field| {
-                let name = &field.name;
+                let pattern = field.resolve_pattern(&self.name, quote!(field_name));
                 let code = &field.resolver_code;
 
                 let _type;
@@ -1130,7 +1462,7 @@ impl GraphQLTypeDefiniton {
                     _type = quote!(: #_type_name);
                 };
                 quote!(
-                    #name => {
+                    #pattern => {
                         futures::FutureExt::boxed(async move {
                             let res #_type = { #code };
                             let res = #juniper_crate_name::IntoFieldResult::<_, #scalar>::into_result(res)?;
@@ -1144,9 +1476,7 @@ impl GraphQLTypeDefiniton {
                                     match res2 {
                                         Ok(Some((ctx, r))) => {
                                             let sub = ex.replaced_context(ctx);
-                                            sub.resolve_with_ctx_async(&(), &r)
-                                                .await
-                                                .map_err(|e| ex.new_error(e))
+                                            ex.stream_item(sub.resolve_with_ctx_async(&(), &r)).await
                                         }
                                         Ok(None) => Ok(Value::null()),
                                         Err(e) => Err(ex.new_error(e)),
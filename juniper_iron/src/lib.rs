@@ -133,7 +133,7 @@ where
     S: ScalarValue,
 {
     Single(http::GraphQLRequest<S>),
-    Batch(Vec<http::GraphQLRequest<S>>),
+    Batch(Vec<http::GraphQLBatchEntry<S>>),
 }
 
 #[derive(serde_derive::Serialize)]
@@ -284,7 +284,7 @@ where
     ) -> Self {
         GraphQLHandler {
             context_factory,
-            root_node: RootNode::new(query, mutation, subscription),
+            root_node: RootNode::new_with_scalar(query, mutation, subscription),
         }
     }
 
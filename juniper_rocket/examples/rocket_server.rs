@@ -1,6 +1,6 @@
 #![feature(decl_macro, proc_macro_hygiene)]
 
-use rocket::{response::content, State};
+use rocket::{request::Form, response::content, State};
 
 use juniper::{
     tests::{model::Database, schema::Query},
@@ -14,10 +14,10 @@ fn graphiql() -> content::Html<String> {
     juniper_rocket::graphiql_source("/graphql")
 }
 
-#[rocket::get("/graphql?<request>")]
+#[rocket::get("/graphql?<request..>")]
 fn get_graphql_handler(
     context: State<Database>,
-    request: juniper_rocket::GraphQLRequest,
+    request: Form<juniper_rocket::GraphQLRequest>,
     schema: State<Schema>,
 ) -> juniper_rocket::GraphQLResponse {
     request.execute_sync(&schema, &context)
@@ -54,79 +54,38 @@ use rocket::{
 use juniper::{http, InputValue};
 
 use juniper::{
-    serde::Deserialize, DefaultScalarValue, FieldError, GraphQLType, RootNode, ScalarValue,
+    http::{GraphQLBatchRequest, GraphQLBatchResponse},
+    DefaultScalarValue, FieldError, GraphQLSubscriptionType, GraphQLType, GraphQLTypeAsync,
+    RootNode, ScalarValue,
 };
 
-#[derive(Debug, serde_derive::Deserialize, PartialEq)]
-#[serde(untagged)]
-#[serde(bound = "InputValue<S>: Deserialize<'de>")]
-enum GraphQLBatchRequest<S = DefaultScalarValue>
+fn into_response<S>(
+    response: GraphQLBatchResponse<S>,
+    formatter: Option<&dyn http::ErrorFormatter<S>>,
+) -> GraphQLResponse
 where
     S: ScalarValue,
 {
-    Single(http::GraphQLRequest<S>),
-    Batch(Vec<http::GraphQLRequest<S>>),
-}
-
-#[derive(serde_derive::Serialize)]
-#[serde(untagged)]
-enum GraphQLBatchResponse<'a, S = DefaultScalarValue>
-where
-    S: ScalarValue,
-{
-    Single(http::GraphQLResponse<'a, S>),
-    Batch(Vec<http::GraphQLResponse<'a, S>>),
-}
-
-impl<S> GraphQLBatchRequest<S>
-where
-    S: ScalarValue,
-{
-    pub fn execute_sync<'a, CtxT, QueryT, MutationT, SubscriptionT>(
-        &'a self,
-        root_node: &'a RootNode<QueryT, MutationT, SubscriptionT, S>,
-        context: &CtxT,
-    ) -> GraphQLBatchResponse<'a, S>
-    where
-        QueryT: GraphQLType<S, Context = CtxT>,
-        MutationT: GraphQLType<S, Context = CtxT>,
-        SubscriptionT: GraphQLType<S, Context = CtxT>,
-    {
-        match *self {
-            GraphQLBatchRequest::Single(ref request) => {
-                GraphQLBatchResponse::Single(request.execute_sync(root_node, context))
-            }
-            GraphQLBatchRequest::Batch(ref requests) => GraphQLBatchResponse::Batch(
-                requests
-                    .iter()
-                    .map(|request| request.execute_sync(root_node, context))
-                    .collect(),
-            ),
-        }
-    }
-
-    pub fn operation_names(&self) -> Vec<Option<&str>> {
-        match self {
-            GraphQLBatchRequest::Single(req) => vec![req.operation_name()],
-            GraphQLBatchRequest::Batch(reqs) => {
-                reqs.iter().map(|req| req.operation_name()).collect()
-            }
+    let status = if response.is_ok() {
+        Status::Ok
+    } else {
+        Status::BadRequest
+    };
+    let json = match (&response, formatter) {
+        (_, None) => serde_json::to_string(&response),
+        (GraphQLBatchResponse::Single(response), Some(formatter)) => {
+            serde_json::to_string(&response.with_error_formatter(formatter))
         }
+        (GraphQLBatchResponse::Batch(responses), Some(formatter)) => serde_json::to_string(
+            &responses
+                .iter()
+                .map(|response| response.with_error_formatter(formatter))
+                .collect::<Vec<_>>(),
+        ),
     }
-}
+    .unwrap();
 
-impl<'a, S> GraphQLBatchResponse<'a, S>
-where
-    S: ScalarValue,
-{
-    fn is_ok(&self) -> bool {
-        match *self {
-            GraphQLBatchResponse::Single(ref response) => response.is_ok(),
-            GraphQLBatchResponse::Batch(ref responses) => {
-                responses.iter().all(|response| response.is_ok())
-            }
-        }
-    }
+    GraphQLResponse(status, json)
 }
 
 /// Simple wrapper around an incoming GraphQL request
@@ -134,6 +93,46 @@ where
 /// See the `http` module for more information. This type can be constructed
 /// automatically from both GET and POST routes by implementing the `FromForm`
 /// and `FromData` traits.
+///
+/// For GET requests, the `query`, `operation_name` and `variables` parameters
+/// are read from the query string. Each of them may occur at most once.
+///
+/// # Examples
+///
+/// ```
+/// # #![feature(decl_macro, proc_macro_hygiene)]
+/// #
+/// # extern crate juniper;
+/// # extern crate juniper_rocket;
+/// # extern crate rocket;
+/// #
+/// # use rocket::request::Form;
+/// # use rocket::State;
+/// #
+/// # use juniper::tests::schema::Query;
+/// # use juniper::tests::model::Database;
+/// # use juniper::{EmptyMutation, EmptySubscription, RootNode};
+/// #
+/// # type Schema = RootNode<'static, Query, EmptyMutation<Database>, EmptySubscription<Database>>;
+/// #
+/// #[rocket::get("/graphql?<request..>")]
+/// fn get_graphql_handler(
+///     context: State<Database>,
+///     request: Form<juniper_rocket::GraphQLRequest>,
+///     schema: State<Schema>,
+/// ) -> juniper_rocket::GraphQLResponse {
+///     request.execute_sync(&schema, &context)
+/// }
+///
+/// #[rocket::post("/graphql", data = "<request>")]
+/// fn post_graphql_handler(
+///     context: State<Database>,
+///     request: juniper_rocket::GraphQLRequest,
+///     schema: State<Schema>,
+/// ) -> juniper_rocket::GraphQLResponse {
+///     request.execute_sync(&schema, &context)
+/// }
+/// ```
 #[derive(Debug, PartialEq)]
 pub struct GraphQLRequest<S = DefaultScalarValue>(GraphQLBatchRequest<S>)
 where
@@ -162,6 +161,22 @@ impl<S> GraphQLRequest<S>
 where
     S: ScalarValue,
 {
+    /// The requests with their persisted queries loaded from the store of
+    /// `root_node`
+    fn loaded<QueryT, MutationT, SubscriptionT>(
+        &self,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
+    ) -> GraphQLBatchRequest<S>
+    where
+        QueryT: GraphQLType<S>,
+        MutationT: GraphQLType<S>,
+        SubscriptionT: GraphQLType<S>,
+    {
+        let mut request = self.0.clone();
+        request.load_persisted_queries(root_node);
+        request
+    }
+
     /// Execute an incoming GraphQL query
     pub fn execute_sync<CtxT, QueryT, MutationT, SubscriptionT>(
         &self,
@@ -173,15 +188,151 @@ where
         MutationT: GraphQLType<S, Context = CtxT>,
         SubscriptionT: GraphQLType<S, Context = CtxT>,
     {
-        let response = self.0.execute_sync(root_node, context);
-        let status = if response.is_ok() {
-            Status::Ok
-        } else {
-            Status::BadRequest
-        };
-        let json = serde_json::to_string(&response).unwrap();
+        into_response(
+            self.loaded(root_node).execute_sync(root_node, context),
+            None,
+        )
+    }
 
-        GraphQLResponse(status, json)
+    /// Execute an incoming GraphQL query, serializing errors with a custom
+    /// [`ErrorFormatter`](juniper::http::ErrorFormatter)
+    pub fn execute_sync_with_error_formatter<CtxT, QueryT, MutationT, SubscriptionT>(
+        &self,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
+        context: &CtxT,
+        error_formatter: &dyn http::ErrorFormatter<S>,
+    ) -> GraphQLResponse
+    where
+        QueryT: GraphQLType<S, Context = CtxT>,
+        MutationT: GraphQLType<S, Context = CtxT>,
+        SubscriptionT: GraphQLType<S, Context = CtxT>,
+    {
+        into_response(
+            self.loaded(root_node).execute_sync(root_node, context),
+            Some(error_formatter),
+        )
+    }
+
+    /// Asynchronously execute an incoming GraphQL query, resolving its
+    /// fields with the async resolvers of the schema
+    ///
+    /// Rocket 0.4 handlers are synchronous, so they should call
+    /// [`execute_blocking`](GraphQLRequest::execute_blocking) instead.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # extern crate juniper;
+    /// # extern crate juniper_rocket;
+    /// #
+    /// # use juniper::tests::schema::Query;
+    /// # use juniper::tests::model::Database;
+    /// # use juniper::{EmptyMutation, EmptySubscription, RootNode};
+    /// #
+    /// # type Schema = RootNode<'static, Query, EmptyMutation<Database>, EmptySubscription<Database>>;
+    /// #
+    /// async fn execute_graphql(
+    ///     request: juniper_rocket::GraphQLRequest,
+    ///     schema: &Schema,
+    ///     context: &Database,
+    /// ) -> juniper_rocket::GraphQLResponse {
+    ///     request.execute(schema, context).await
+    /// }
+    /// ```
+    pub async fn execute<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+        &'a self,
+        root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+        context: &'a CtxT,
+    ) -> GraphQLResponse
+    where
+        QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+        QueryT::TypeInfo: Send + Sync,
+        MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+        MutationT::TypeInfo: Send + Sync,
+        SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send + Sync,
+        SubscriptionT::TypeInfo: Send + Sync,
+        CtxT: Send + Sync,
+        S: Send + Sync,
+    {
+        let request = self.loaded(root_node);
+        into_response(request.execute(root_node, context).await, None)
+    }
+
+    /// Asynchronously execute an incoming GraphQL query, serializing errors
+    /// with a custom [`ErrorFormatter`](juniper::http::ErrorFormatter)
+    pub async fn execute_with_error_formatter<'a, CtxT, QueryT, MutationT, SubscriptionT>(
+        &'a self,
+        root_node: &'a RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+        context: &'a CtxT,
+        error_formatter: &'a dyn http::ErrorFormatter<S>,
+    ) -> GraphQLResponse
+    where
+        QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+        QueryT::TypeInfo: Send + Sync,
+        MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+        MutationT::TypeInfo: Send + Sync,
+        SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send + Sync,
+        SubscriptionT::TypeInfo: Send + Sync,
+        CtxT: Send + Sync,
+        S: Send + Sync,
+    {
+        let request = self.loaded(root_node);
+        into_response(
+            request.execute(root_node, context).await,
+            Some(error_formatter),
+        )
+    }
+
+    /// Execute an incoming GraphQL query with the async resolvers of the
+    /// schema, blocking the current thread until the response is ready
+    ///
+    /// Rocket 0.4 handlers are synchronous, so this is the entry point to use
+    /// from a handler when the schema has async fields.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #![feature(decl_macro, proc_macro_hygiene)]
+    /// #
+    /// # extern crate juniper;
+    /// # extern crate juniper_rocket;
+    /// # extern crate rocket;
+    /// #
+    /// # use rocket::{post, State};
+    /// #
+    /// # use juniper::tests::schema::Query;
+    /// # use juniper::tests::model::Database;
+    /// # use juniper::{EmptyMutation, EmptySubscription, RootNode};
+    /// #
+    /// # type Schema = RootNode<'static, Query, EmptyMutation<Database>, EmptySubscription<Database>>;
+    /// #
+    /// #[post("/graphql", data = "<request>")]
+    /// fn post_graphql_handler(
+    ///     context: State<Database>,
+    ///     request: juniper_rocket::GraphQLRequest,
+    ///     schema: State<Schema>,
+    /// ) -> juniper_rocket::GraphQLResponse {
+    ///     request.execute_blocking(&schema, &context)
+    /// }
+    /// #
+    /// # fn main() {}
+    /// ```
+    pub fn execute_blocking<CtxT, QueryT, MutationT, SubscriptionT>(
+        &self,
+        root_node: &RootNode<QueryT, MutationT, SubscriptionT, S>,
+        context: &CtxT,
+    ) -> GraphQLResponse
+    where
+        QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+        QueryT::TypeInfo: Send + Sync,
+        MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+        MutationT::TypeInfo: Send + Sync,
+        SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send + Sync,
+        SubscriptionT::TypeInfo: Send + Sync,
+        CtxT: Send + Sync,
+        S: Send + Sync,
+    {
+        futures::executor::block_on(self.execute(root_node, context))
     }
 
     /// Returns the operation names associated with this request.
@@ -543,6 +694,93 @@ mod tests {
         http_tests::run_http_test_suite(&integration);
     }
 
+    #[test]
+    fn test_simple_get() {
+        let client = Client::new(make_rocket()).expect("valid rocket");
+
+        // {hero{name}}
+        let resp = make_test_response(&client.get("/?query=%7Bhero%7Bname%7D%7D"));
+
+        assert_eq!(resp.status_code, 200);
+        assert_eq!(
+            resp.body,
+            Some(r#"{"data":{"hero":{"name":"R2-D2"}}}"#.to_owned())
+        );
+    }
+
+    #[test]
+    fn test_encoded_get() {
+        let client = Client::new(make_rocket()).expect("valid rocket");
+
+        // query { human(id: "1000") { id, name } }
+        let resp = make_test_response(&client.get(
+            "/?query=query%20%7B%20human(id%3A%20%221000%22)%20%7B%20id%2C%20name%20%7D%20%7D",
+        ));
+
+        assert_eq!(resp.status_code, 200);
+        assert_eq!(
+            resp.body,
+            Some(r#"{"data":{"human":{"id":"1000","name":"Luke Skywalker"}}}"#.to_owned())
+        );
+    }
+
+    #[test]
+    fn test_get_with_variables() {
+        let client = Client::new(make_rocket()).expect("valid rocket");
+
+        // query($id: String!) { human(id: $id) { id, name } }
+        // with variables = { "id": "1000" }
+        let resp = make_test_response(&client.get(
+            "/?query=query(%24id%3A%20String!)%20%7B%20human(id%3A%20%24id)%20%7B%20id%2C%20name%20%7D%20%7D&variables=%7B%20%22id%22%3A%20%221000%22%20%7D",
+        ));
+
+        assert_eq!(resp.status_code, 200);
+        assert_eq!(
+            resp.body,
+            Some(r#"{"data":{"human":{"id":"1000","name":"Luke Skywalker"}}}"#.to_owned())
+        );
+    }
+
+    #[test]
+    fn test_get_with_duplicate_query() {
+        let client = Client::new(make_rocket()).expect("valid rocket");
+
+        // A request which fails to parse is forwarded, no other route matches.
+        let response = client
+            .get("/?query=%7Bhero%7Bname%7D%7D&query=%7Bhero%7Bname%7D%7D")
+            .dispatch();
+
+        assert_eq!(response.status().code, 404);
+    }
+
+    #[test]
+    fn test_custom_error_formatter() {
+        #[post("/", data = "<request>")]
+        fn post_graphql_legacy_errors_handler(
+            context: State<Database>,
+            request: super::GraphQLRequest,
+            schema: State<Schema>,
+        ) -> super::GraphQLResponse {
+            request.execute_sync_with_error_formatter(&schema, &context, &http_tests::LegacyErrors)
+        }
+
+        let rocket =
+            make_rocket_without_routes().mount("/", routes![post_graphql_legacy_errors_handler]);
+        let client = Client::new(rocket).expect("valid rocket");
+
+        let req = client
+            .post("/")
+            .header(ContentType::JSON)
+            .body(r#"{"query": "{hero{nope}}"}"#);
+        let resp = make_test_response(&req);
+
+        assert_eq!(resp.status_code, 400);
+        assert_eq!(
+            resp.body,
+            Some(r#"{"errors":["Unknown field \"nope\" on type \"Character\""]}"#.to_owned())
+        );
+    }
+
     #[test]
     fn test_operation_names() {
         #[post("/", data = "<request>")]
@@ -568,6 +806,49 @@ mod tests {
         assert_eq!(resp.status_code, 200);
     }
 
+    #[test]
+    fn test_async_execute() {
+        struct AsyncQuery;
+
+        #[juniper::graphql_object]
+        impl AsyncQuery {
+            async fn greeting() -> String {
+                "Hello, async world".to_string()
+            }
+        }
+
+        type AsyncSchema = RootNode<'static, AsyncQuery, EmptyMutation<()>, EmptySubscription<()>>;
+
+        #[post("/", data = "<request>")]
+        fn post_graphql_async_handler(
+            request: super::GraphQLRequest,
+            schema: State<AsyncSchema>,
+        ) -> super::GraphQLResponse {
+            request.execute_blocking(&schema, &())
+        }
+
+        let rocket = rocket::ignite()
+            .manage(AsyncSchema::new(
+                AsyncQuery,
+                EmptyMutation::<()>::new(),
+                EmptySubscription::<()>::new(),
+            ))
+            .mount("/", routes![post_graphql_async_handler]);
+        let client = Client::new(rocket).expect("valid rocket");
+
+        let req = client
+            .post("/")
+            .header(ContentType::JSON)
+            .body(r#"{"query": "{greeting}"}"#);
+        let resp = make_test_response(&req);
+
+        assert_eq!(resp.status_code, 200);
+        assert_eq!(
+            resp.body,
+            Some(r#"{"data":{"greeting":"Hello, async world"}}"#.to_owned())
+        );
+    }
+
     fn make_rocket() -> Rocket {
         make_rocket_without_routes().mount("/", routes![post_graphql_handler, get_graphql_handler])
     }
@@ -20,6 +20,8 @@ use juniper::{
     ScalarValue, SubscriptionConnection, SubscriptionCoordinator, Value, ValuesStream,
 };
 
+pub mod test;
+
 /// Simple [`SubscriptionCoordinator`] implementation:
 /// - contains the schema
 /// - handles subscription start
@@ -95,7 +97,9 @@ where
 /// [`Value::Scalar`] - returns `Ok` value or [`Value::Null`] and errors vector
 /// [`Value::List`] - resolves each stream from the list using current logic and returns
 ///                   values in the order received
-/// [`Value::Object`] - waits while each field of the [`Object`] is returned, then yields the whole object
+/// [`Value::Object`] - waits while each field of the [`Object`] is returned, then yields the whole object,
+///                     with fields whose stream yielded an error set to [`Value::Null`] and the
+///                     errors added to the response
 /// `Value::Object<Value::Object<_>>` - returns [`Value::Null`] if [`Value::Object`] consists of sub-objects
 pub struct Connection<'a, S> {
     stream: Pin<Box<dyn futures::Stream<Item = GraphQLResponse<'a, S>> + Send + 'a>>,
@@ -135,13 +139,67 @@ where
     }
 }
 
+/// Synchronously subscribes to `req`, returning an [`IteratorConnection`]
+/// yielding a [`GraphQLResponse`] for every event.
+///
+/// This is the blocking counterpart of [`Coordinator::subscribe`]. The
+/// subscription is resolved and its events are awaited on the current thread
+/// with [`futures::executor`], so no async runtime is needed as long as the
+/// streams returned by the subscription resolvers don't depend on one.
+pub fn subscribe<'a, QueryT, MutationT, SubscriptionT, CtxT, S>(
+    root_node: &'a juniper::RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
+    req: &'a GraphQLRequest<S>,
+    context: &'a CtxT,
+) -> Result<IteratorConnection<'a, S>, GraphQLError<'a>>
+where
+    S: ScalarValue + Send + Sync + 'static,
+    QueryT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+    QueryT::TypeInfo: Send + Sync,
+    MutationT: GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
+    MutationT::TypeInfo: Send + Sync,
+    SubscriptionT: GraphQLSubscriptionType<S, Context = CtxT> + Send + Sync,
+    SubscriptionT::TypeInfo: Send + Sync,
+    CtxT: Send + Sync,
+{
+    let (stream, errors) =
+        futures::executor::block_on(juniper::http::resolve_into_stream(req, root_node, context))?;
+
+    Ok(IteratorConnection {
+        responses: futures::executor::block_on_stream(Connection::from_stream(stream, errors)),
+    })
+}
+
+/// Blocking [`Connection`] returned by [`subscribe`].
+///
+/// Iterating blocks the current thread until the next event of the
+/// subscription is resolved, and ends with the subscription's streams.
+pub struct IteratorConnection<'a, S>
+where
+    S: ScalarValue + Send + Sync + 'a,
+{
+    responses: futures::executor::BlockingStream<Connection<'a, S>>,
+}
+
+impl<'a, S> Iterator for IteratorConnection<'a, S>
+where
+    S: ScalarValue + Send + Sync + 'a,
+{
+    type Item = GraphQLResponse<'a, S>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.responses.next()
+    }
+}
+
 /// Creates [`futures::Stream`] that yields [`GraphQLResponse`]s depending on the given [`Value`]:
 ///
 /// [`Value::Null`] - returns [`Value::Null`] once
 /// [`Value::Scalar`] - returns `Ok` value or [`Value::Null`] and errors vector
 /// [`Value::List`] - resolves each stream from the list using current logic and returns
 ///                   values in the order received
-/// [`Value::Object`] - waits while each field of the [`Object`] is returned, then yields the whole object
+/// [`Value::Object`] - waits while each field of the [`Object`] is returned, then yields the whole object,
+///                     with fields whose stream yielded an error set to [`Value::Null`] and the
+///                     errors added to the response
 /// `Value::Object<Value::Object<_>>` - returns [`Value::Null`] if [`Value::Object`] consists of sub-objects
 fn whole_responses_stream<'a, S>(
     stream: Value<ValuesStream<'a, S>>,
@@ -230,18 +288,21 @@ where
                         filled_count = 0;
                         let new_vec = (0..obj_len).map(|_| None).collect::<Vec<_>>();
                         let ready_vec = std::mem::replace(&mut ready_vec, new_vec);
+                        let mut errors = vec![];
                         let ready_vec_iterator = ready_vec.into_iter().map(|el| {
                             let (name, val) = el.unwrap();
-                            if let Ok(value) = val {
-                                (name, value)
-                            } else {
-                                (name, Value::Null)
+                            match val {
+                                Ok(value) => (name, value),
+                                Err(e) => {
+                                    errors.push(e);
+                                    (name, Value::Null)
+                                }
                             }
                         });
                         let obj = Object::from_iter(ready_vec_iterator);
                         Poll::Ready(Some(GraphQLResponse::from_result(Ok((
                             Value::Object(obj),
-                            vec![],
+                            errors,
                         )))))
                     } else {
                         Poll::Pending
@@ -383,6 +444,40 @@ mod whole_responses_stream {
         assert_eq!(result, expected);
     }
 
+    #[tokio::test]
+    async fn value_object_with_item_error() {
+        let error = || ExecutionError::at_origin(FieldError::new("tick failed", Value::Null));
+        let response = |val: Value<DefaultScalarValue>, errors| {
+            GraphQLResponse::from_result(Ok((
+                Value::Object(Object::from_iter(vec![("tick", val)].into_iter())),
+                errors,
+            )))
+        };
+        let expected = vec![
+            response(Value::Scalar(DefaultScalarValue::Int(1i32)), vec![]),
+            response(Value::Null, vec![error()]),
+            response(Value::Scalar(DefaultScalarValue::Int(3i32)), vec![]),
+        ];
+        let expected = serde_json::to_string(&expected).unwrap();
+
+        let ticks = stream::iter(vec![
+            Ok(Value::Scalar(DefaultScalarValue::Int(1i32))),
+            Err(error()),
+            Ok(Value::Scalar(DefaultScalarValue::Int(3i32))),
+        ]);
+        let vals: Vec<(&str, Value<ValuesStream>)> = vec![("tick", Value::Scalar(Box::pin(ticks)))];
+
+        let result = whole_responses_stream::<DefaultScalarValue>(
+            Value::Object(Object::from_iter(vals.into_iter())),
+            vec![],
+        )
+        .collect::<Vec<_>>()
+        .await;
+        let result = serde_json::to_string(&result).unwrap();
+
+        assert_eq!(result, expected);
+    }
+
     #[tokio::test]
     async fn value_object() {
         let expected = vec![
@@ -443,3 +538,79 @@ mod whole_responses_stream {
         assert_eq!(result, expected);
     }
 }
+
+#[cfg(test)]
+mod subscribe {
+    use std::pin::Pin;
+
+    use futures::{stream, Stream};
+    use juniper::{
+        http::{GraphQLRequest, GraphQLResponse},
+        DefaultScalarValue, EmptyMutation, GraphQLError, RootNode,
+    };
+
+    struct Query;
+
+    #[juniper::graphql_object]
+    impl Query {
+        fn ping() -> bool {
+            true
+        }
+    }
+
+    struct Subscription;
+
+    #[juniper::graphql_subscription]
+    impl Subscription {
+        async fn countdown(from: i32) -> Pin<Box<dyn Stream<Item = i32> + Send>> {
+            Box::pin(stream::iter((1..=from).rev()))
+        }
+    }
+
+    type Schema = RootNode<'static, Query, EmptyMutation<()>, Subscription>;
+
+    fn schema() -> Schema {
+        RootNode::new(Query, EmptyMutation::new(), Subscription)
+    }
+
+    fn to_json(response: &GraphQLResponse<DefaultScalarValue>) -> String {
+        serde_json::to_string(response).unwrap()
+    }
+
+    #[test]
+    fn iterates_a_finite_subscription() {
+        let schema = schema();
+        let request = GraphQLRequest::new(
+            "subscription { countdown(from: 3) }".to_string(),
+            None,
+            None,
+        );
+
+        let responses = super::subscribe(&schema, &request, &())
+            .expect("Subscription failed")
+            .map(|response| to_json(&response))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            responses,
+            vec![
+                r#"{"data":{"countdown":3}}"#,
+                r#"{"data":{"countdown":2}}"#,
+                r#"{"data":{"countdown":1}}"#,
+            ],
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_subscriptions() {
+        let schema = schema();
+        let request = GraphQLRequest::new("subscription {".to_string(), None, None);
+
+        let result = super::subscribe(&schema, &request, &());
+        match result {
+            Err(GraphQLError::ParseError(_)) => {}
+            Err(e) => panic!("Expected a parse error, got {:?}", e),
+            Ok(_) => panic!("Expected a parse error"),
+        };
+    }
+}
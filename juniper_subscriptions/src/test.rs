@@ -0,0 +1,168 @@
+//! Helpers for testing subscriptions
+
+use std::{sync::mpsc, thread, time::Duration};
+
+use futures::{
+    channel::oneshot,
+    future::{self, Either},
+    pin_mut, Stream, StreamExt as _,
+};
+
+/// Collects up to `n` frames of `stream`, stopping early when the stream ends
+/// or when `timeout` elapses.
+///
+/// The stream is driven by the runtime awaiting the returned future, so
+/// streams depending on e.g. tokio timers have to be collected on a tokio
+/// runtime. The timeout itself is kept by a separate thread and doesn't need
+/// any runtime.
+///
+/// ```ignore
+/// let connection = coordinator.subscribe(&request, &context).await?;
+/// let responses = juniper_subscriptions::test::collect(connection, 3, Duration::from_secs(1)).await;
+/// ```
+pub async fn collect<St>(stream: St, n: usize, timeout: Duration) -> Vec<St::Item>
+where
+    St: Stream,
+{
+    let (done_tx, done_rx) = mpsc::channel::<()>();
+    let (timeout_tx, mut timeout_rx) = oneshot::channel();
+    thread::spawn(move || {
+        // Returns early once `collect` is done and drops `done_tx`.
+        if let Err(mpsc::RecvTimeoutError::Timeout) = done_rx.recv_timeout(timeout) {
+            let _ = timeout_tx.send(());
+        }
+    });
+
+    pin_mut!(stream);
+    let mut frames = Vec::new();
+    while frames.len() < n {
+        match future::select(stream.next(), timeout_rx).await {
+            Either::Left((Some(frame), rx)) => {
+                frames.push(frame);
+                timeout_rx = rx;
+            }
+            Either::Left((None, _)) | Either::Right(_) => break,
+        }
+    }
+    drop(done_tx);
+
+    frames
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        pin::Pin,
+        time::{Duration, Instant},
+    };
+
+    use futures::{Stream, StreamExt as _};
+    use juniper::{
+        http::GraphQLRequest, DefaultScalarValue, EmptyMutation, RootNode,
+        SubscriptionCoordinator as _,
+    };
+
+    use super::collect;
+    use crate::Coordinator;
+
+    struct Query;
+
+    #[juniper::graphql_object]
+    impl Query {
+        fn ping() -> bool {
+            true
+        }
+    }
+
+    struct Subscription;
+
+    #[juniper::graphql_subscription]
+    impl Subscription {
+        async fn ticks(count: i32) -> Pin<Box<dyn Stream<Item = i32> + Send>> {
+            Box::pin(
+                tokio::time::interval(Duration::from_millis(10))
+                    .zip(futures::stream::iter(1..=count))
+                    .map(|(_, tick)| tick),
+            )
+        }
+
+        async fn never() -> Pin<Box<dyn Stream<Item = i32> + Send>> {
+            Box::pin(futures::stream::pending())
+        }
+    }
+
+    fn coordinator(
+    ) -> Coordinator<'static, Query, EmptyMutation<()>, Subscription, (), DefaultScalarValue> {
+        Coordinator::new(RootNode::new(Query, EmptyMutation::new(), Subscription))
+    }
+
+    async fn collect_json(query: &str, n: usize, timeout: Duration) -> Vec<String> {
+        let coordinator = coordinator();
+        let request = GraphQLRequest::new(query.to_string(), None, None);
+        let connection = coordinator
+            .subscribe(&request, &())
+            .await
+            .expect("Subscription failed");
+
+        collect(connection, n, timeout)
+            .await
+            .iter()
+            .map(|response| serde_json::to_string(response).unwrap())
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn collects_frames_of_a_finite_interval_stream() {
+        let frames = collect_json(
+            "subscription { ticks(count: 3) }",
+            5,
+            Duration::from_secs(5),
+        )
+        .await;
+
+        assert_eq!(
+            frames,
+            vec![
+                r#"{"data":{"ticks":1}}"#,
+                r#"{"data":{"ticks":2}}"#,
+                r#"{"data":{"ticks":3}}"#,
+            ],
+        );
+    }
+
+    #[tokio::test]
+    async fn stops_after_n_frames() {
+        let frames = collect_json(
+            "subscription { ticks(count: 3) }",
+            2,
+            Duration::from_secs(5),
+        )
+        .await;
+
+        assert_eq!(
+            frames,
+            vec![r#"{"data":{"ticks":1}}"#, r#"{"data":{"ticks":2}}"#],
+        );
+    }
+
+    #[tokio::test]
+    async fn stops_on_timeout() {
+        let start = Instant::now();
+        let frames = collect_json("subscription { never }", 1, Duration::from_millis(50)).await;
+
+        assert!(frames.is_empty());
+        assert!(start.elapsed() >= Duration::from_millis(50));
+    }
+
+    #[tokio::test]
+    async fn collects_any_stream() {
+        let frames = collect(
+            futures::stream::iter(vec![1, 2]).chain(futures::stream::pending()),
+            3,
+            Duration::from_millis(20),
+        )
+        .await;
+
+        assert_eq!(frames, vec![1, 2]);
+    }
+}
@@ -40,106 +40,47 @@ Check the LICENSE file for details.
 #![deny(warnings)]
 #![doc(html_root_url = "https://docs.rs/juniper_warp/0.2.0")]
 
-use std::{pin::Pin, sync::Arc};
+use std::{collections::HashMap, fmt, net::SocketAddr, pin::Pin, sync::Arc};
 
-use futures::{Future, FutureExt as _, TryFutureExt};
-use juniper::{DefaultScalarValue, InputValue, ScalarValue};
-use serde::Deserialize;
+use futures::{Future, TryFutureExt};
+use juniper::{
+    http::{ErrorFormatter, GraphQLBatchRequest, GraphQLBatchResponse},
+    ScalarValue,
+};
 use tokio::task;
 use warp::{filters::BoxedFilter, Filter};
 
-#[derive(Debug, serde_derive::Deserialize, PartialEq)]
-#[serde(untagged)]
-#[serde(bound = "InputValue<S>: Deserialize<'de>")]
-enum GraphQLBatchRequest<S = DefaultScalarValue>
+fn batch_response_to_json<S>(
+    response: &GraphQLBatchResponse<S>,
+    formatter: Option<&dyn ErrorFormatter<S>>,
+) -> serde_json::Result<Vec<u8>>
 where
     S: ScalarValue,
 {
-    Single(juniper::http::GraphQLRequest<S>),
-    Batch(Vec<juniper::http::GraphQLRequest<S>>),
-}
-
-impl<S> GraphQLBatchRequest<S>
-where
-    S: ScalarValue,
-{
-    pub fn execute_sync<'a, CtxT, QueryT, MutationT, SubscriptionT>(
-        &'a self,
-        root_node: &'a juniper::RootNode<QueryT, MutationT, SubscriptionT, S>,
-        context: &CtxT,
-    ) -> GraphQLBatchResponse<'a, S>
-    where
-        QueryT: juniper::GraphQLType<S, Context = CtxT>,
-        MutationT: juniper::GraphQLType<S, Context = CtxT>,
-        SubscriptionT: juniper::GraphQLType<S, Context = CtxT>,
-        SubscriptionT::TypeInfo: Send + Sync,
-        CtxT: Send + Sync,
-    {
-        match *self {
-            GraphQLBatchRequest::Single(ref request) => {
-                GraphQLBatchResponse::Single(request.execute_sync(root_node, context))
-            }
-            GraphQLBatchRequest::Batch(ref requests) => GraphQLBatchResponse::Batch(
-                requests
-                    .iter()
-                    .map(|request| request.execute_sync(root_node, context))
-                    .collect(),
-            ),
-        }
-    }
-
-    pub async fn execute<'a, CtxT, QueryT, MutationT, SubscriptionT>(
-        &'a self,
-        root_node: &'a juniper::RootNode<'a, QueryT, MutationT, SubscriptionT, S>,
-        context: &'a CtxT,
-    ) -> GraphQLBatchResponse<'a, S>
-    where
-        QueryT: juniper::GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
-        QueryT::TypeInfo: Send + Sync,
-        MutationT: juniper::GraphQLTypeAsync<S, Context = CtxT> + Send + Sync,
-        MutationT::TypeInfo: Send + Sync,
-        SubscriptionT: juniper::GraphQLSubscriptionType<S, Context = CtxT> + Send + Sync,
-        SubscriptionT::TypeInfo: Send + Sync,
-        CtxT: Send + Sync,
-        S: Send + Sync,
-    {
-        match *self {
-            GraphQLBatchRequest::Single(ref request) => {
-                let res = request.execute(root_node, context).await;
-                GraphQLBatchResponse::Single(res)
-            }
-            GraphQLBatchRequest::Batch(ref requests) => {
-                let futures = requests
-                    .iter()
-                    .map(|request| request.execute(root_node, context))
-                    .collect::<Vec<_>>();
-                let responses = futures::future::join_all(futures).await;
-
-                GraphQLBatchResponse::Batch(responses)
-            }
+    match (response, formatter) {
+        (_, None) => serde_json::to_vec(response),
+        (GraphQLBatchResponse::Single(res), Some(formatter)) => {
+            serde_json::to_vec(&res.with_error_formatter(formatter))
         }
+        (GraphQLBatchResponse::Batch(reses), Some(formatter)) => serde_json::to_vec(
+            &reses
+                .iter()
+                .map(|res| res.with_error_formatter(formatter))
+                .collect::<Vec<_>>(),
+        ),
     }
 }
 
-#[derive(serde_derive::Serialize)]
-#[serde(untagged)]
-enum GraphQLBatchResponse<'a, S = DefaultScalarValue>
-where
-    S: ScalarValue,
-{
-    Single(juniper::http::GraphQLResponse<'a, S>),
-    Batch(Vec<juniper::http::GraphQLResponse<'a, S>>),
-}
-
-impl<'a, S> GraphQLBatchResponse<'a, S>
+fn response_to_json<S>(
+    response: &juniper::http::GraphQLResponse<S>,
+    formatter: Option<&dyn ErrorFormatter<S>>,
+) -> serde_json::Result<Vec<u8>>
 where
     S: ScalarValue,
 {
-    fn is_ok(&self) -> bool {
-        match self {
-            GraphQLBatchResponse::Single(res) => res.is_ok(),
-            GraphQLBatchResponse::Batch(reses) => reses.iter().all(|res| res.is_ok()),
-        }
+    match formatter {
+        Some(formatter) => serde_json::to_vec(&response.with_error_formatter(formatter)),
+        None => serde_json::to_vec(response),
     }
 }
 
@@ -207,6 +148,213 @@ pub fn make_graphql_filter<Query, Mutation, Subscription, Context, S>(
     schema: juniper::RootNode<'static, Query, Mutation, Subscription, S>,
     context_extractor: BoxedFilter<(Context,)>,
 ) -> BoxedFilter<(warp::http::Response<Vec<u8>>,)>
+where
+    S: ScalarValue + Send + Sync + 'static,
+    Context: Send + Sync + 'static,
+    Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+    Subscription::TypeInfo: Send + Sync,
+{
+    graphql_filter(schema, context_extractor, None)
+}
+
+/// Make a filter for graphql queries/mutations, serializing errors with a
+/// custom [`ErrorFormatter`].
+///
+/// See [`make_graphql_filter`] for the other arguments.
+///
+/// [`ErrorFormatter`]: juniper::http::ErrorFormatter
+pub fn make_graphql_filter_with_error_formatter<Query, Mutation, Subscription, Context, S, F>(
+    schema: juniper::RootNode<'static, Query, Mutation, Subscription, S>,
+    context_extractor: BoxedFilter<(Context,)>,
+    error_formatter: F,
+) -> BoxedFilter<(warp::http::Response<Vec<u8>>,)>
+where
+    S: ScalarValue + Send + Sync + 'static,
+    Context: Send + Sync + 'static,
+    Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+    Subscription::TypeInfo: Send + Sync,
+    F: ErrorFormatter<S> + 'static,
+{
+    graphql_filter(schema, context_extractor, Some(Arc::new(error_formatter)))
+}
+
+/// The parts of an incoming request handed to a context factory.
+///
+/// See [`make_graphql_filter_with_context_factory`].
+#[derive(Clone, Debug)]
+pub struct RequestParts {
+    /// The request headers.
+    pub headers: warp::http::HeaderMap,
+    /// The request method.
+    pub method: warp::http::Method,
+    /// The address of the remote peer, if known.
+    pub remote_addr: Option<SocketAddr>,
+}
+
+/// Make a filter for graphql queries/mutations, building the GraphQL context
+/// of each request from its [`RequestParts`].
+///
+/// This is handy to authenticate requests from their headers:
+///
+/// ```
+/// # use juniper::{EmptyMutation, EmptySubscription, RootNode};
+/// # use juniper_warp::{make_graphql_filter_with_context_factory, RequestParts};
+/// # use warp::Filter;
+/// #
+/// struct Context {
+///     token: Option<String>,
+/// }
+///
+/// impl juniper::Context for Context {}
+///
+/// struct Query;
+///
+/// #[juniper::graphql_object(Context = Context)]
+/// impl Query {
+///     fn authenticated(context: &Context) -> bool {
+///         context.token.is_some()
+///     }
+/// }
+///
+/// let schema = RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new());
+///
+/// let graphql_filter = make_graphql_filter_with_context_factory(schema, |parts: RequestParts| {
+///     Context {
+///         token: parts
+///             .headers
+///             .get("authorization")
+///             .and_then(|value| value.to_str().ok())
+///             .map(ToOwned::to_owned),
+///     }
+/// });
+///
+/// let graphql_endpoint = warp::path("graphql").and(graphql_filter);
+/// ```
+pub fn make_graphql_filter_with_context_factory<Query, Mutation, Subscription, Context, S, F>(
+    schema: juniper::RootNode<'static, Query, Mutation, Subscription, S>,
+    context_factory: F,
+) -> BoxedFilter<(warp::http::Response<Vec<u8>>,)>
+where
+    S: ScalarValue + Send + Sync + 'static,
+    Context: Send + Sync + 'static,
+    Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+    Subscription::TypeInfo: Send + Sync,
+    F: Fn(RequestParts) -> Context + Clone + Send + Sync + 'static,
+{
+    make_graphql_filter_with_fallible_context_factory(schema, move |parts| {
+        Ok(context_factory(parts))
+    })
+}
+
+/// Make a filter for graphql queries/mutations, building the GraphQL context
+/// of each request from its [`RequestParts`] or rejecting the request.
+///
+/// Same as [`make_graphql_filter_with_context_factory`], but the context
+/// factory can fail, e.g. on an invalid authentication token. Its rejection
+/// can be turned into a `401 Unauthorized` response with
+/// [`Filter::recover`]:
+///
+/// ```
+/// # use juniper::{EmptyMutation, EmptySubscription, RootNode};
+/// # use juniper_warp::{make_graphql_filter_with_fallible_context_factory, RequestParts};
+/// # use warp::{http::StatusCode, Filter};
+/// #
+/// struct Context {
+///     user: String,
+/// }
+///
+/// impl juniper::Context for Context {}
+///
+/// #[derive(Debug)]
+/// struct Unauthorized;
+///
+/// impl warp::reject::Reject for Unauthorized {}
+///
+/// struct Query;
+///
+/// #[juniper::graphql_object(Context = Context)]
+/// impl Query {
+///     fn user(context: &Context) -> &str {
+///         &context.user
+///     }
+/// }
+///
+/// let schema = RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new());
+///
+/// let graphql_filter =
+///     make_graphql_filter_with_fallible_context_factory(schema, |parts: RequestParts| {
+///         match parts.headers.get("authorization") {
+///             Some(token) if token == "Bearer secret" => Ok(Context {
+///                 user: "admin".to_owned(),
+///             }),
+///             _ => Err(warp::reject::custom(Unauthorized)),
+///         }
+///     });
+///
+/// let graphql_endpoint = warp::path("graphql")
+///     .and(graphql_filter)
+///     .recover(|rejection: warp::Rejection| async move {
+///         if rejection.find::<Unauthorized>().is_some() {
+///             Ok(warp::reply::with_status("Unauthorized", StatusCode::UNAUTHORIZED))
+///         } else {
+///             Err(rejection)
+///         }
+///     });
+/// ```
+pub fn make_graphql_filter_with_fallible_context_factory<
+    Query,
+    Mutation,
+    Subscription,
+    Context,
+    S,
+    F,
+>(
+    schema: juniper::RootNode<'static, Query, Mutation, Subscription, S>,
+    context_factory: F,
+) -> BoxedFilter<(warp::http::Response<Vec<u8>>,)>
+where
+    S: ScalarValue + Send + Sync + 'static,
+    Context: Send + Sync + 'static,
+    Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Query::TypeInfo: Send + Sync,
+    Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+    Mutation::TypeInfo: Send + Sync,
+    Subscription: juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+    Subscription::TypeInfo: Send + Sync,
+    F: Fn(RequestParts) -> Result<Context, warp::Rejection> + Clone + Send + Sync + 'static,
+{
+    let context_extractor = warp::header::headers_cloned()
+        .and(warp::method())
+        .and(warp::addr::remote())
+        .and_then(move |headers, method, remote_addr| {
+            futures::future::ready(context_factory(RequestParts {
+                headers,
+                method,
+                remote_addr,
+            }))
+        })
+        .boxed();
+
+    graphql_filter(schema, context_extractor, None)
+}
+
+fn graphql_filter<Query, Mutation, Subscription, Context, S>(
+    schema: juniper::RootNode<'static, Query, Mutation, Subscription, S>,
+    context_extractor: BoxedFilter<(Context,)>,
+    error_formatter: Option<Arc<dyn ErrorFormatter<S>>>,
+) -> BoxedFilter<(warp::http::Response<Vec<u8>>,)>
 where
     S: ScalarValue + Send + Sync + 'static,
     Context: Send + Sync + 'static,
@@ -218,17 +366,20 @@ where
     Subscription::TypeInfo: Send + Sync,
 {
     let schema = Arc::new(schema);
+    let post_error_formatter = error_formatter.clone();
     let post_schema = schema.clone();
 
-    let handle_post_request = move |context: Context, request: GraphQLBatchRequest<S>| {
+    let handle_post_request = move |context: Context, mut request: GraphQLBatchRequest<S>| {
         let schema = post_schema.clone();
+        let error_formatter = post_error_formatter.clone();
 
         Box::pin(async move {
+            request.load_persisted_queries(&schema);
             let res = request.execute(&schema, &context).await;
 
             Ok::<_, warp::Rejection>(build_response(
-                serde_json::to_vec(&res)
-                    .map(|json| (json, res.is_ok()))
+                batch_response_to_json(&res, error_formatter.as_deref())
+                    .map(|json| (json, res.status_code()))
                     .map_err(Into::into),
             ))
         })
@@ -239,30 +390,25 @@ where
         .and(warp::body::json())
         .and_then(handle_post_request);
 
-    let handle_get_request =
-        move |context: Context, mut request: std::collections::HashMap<String, String>| {
-            let schema = schema.clone();
-
-            async move {
-                let variables = match request.remove("variables") {
-                    None => None,
-                    Some(vs) => serde_json::from_str(&vs)?,
-                };
+    let handle_get_request = move |context: Context, request: HashMap<String, String>| {
+        let schema = schema.clone();
+        let error_formatter = error_formatter.clone();
 
-                let graphql_request = juniper::http::GraphQLRequest::new(
-                    request.remove("query").ok_or_else(|| {
-                        failure::format_err!("Missing GraphQL query string in query parameters")
-                    })?,
-                    request.get("operation_name").map(|s| s.to_owned()),
-                    variables,
-                );
+        async move {
+            let graphql_request = match from_get(request) {
+                Ok(r) => r,
+                Err(e) => return Ok::<_, warp::Rejection>(build_bad_request(e)),
+            };
 
-                let response = graphql_request.execute(&schema, &context).await;
+            let response = graphql_request.execute(&schema, &context).await;
 
-                Ok((serde_json::to_vec(&response)?, response.is_ok()))
-            }
-            .then(|result| async move { Ok::<_, warp::Rejection>(build_response(result)) })
-        };
+            Ok(build_response(
+                response_to_json(&response, error_formatter.as_deref())
+                    .map(|json| (json, response.status_code()))
+                    .map_err(Into::into),
+            ))
+        }
+    };
 
     let get_filter = warp::get()
         .and(context_extractor)
@@ -277,6 +423,41 @@ pub fn make_graphql_filter_sync<Query, Mutation, Subscription, Context, S>(
     schema: juniper::RootNode<'static, Query, Mutation, Subscription, S>,
     context_extractor: BoxedFilter<(Context,)>,
 ) -> BoxedFilter<(warp::http::Response<Vec<u8>>,)>
+where
+    S: ScalarValue + Send + Sync + 'static,
+    Context: Send + Sync + 'static,
+    Query: juniper::GraphQLType<S, Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Mutation: juniper::GraphQLType<S, Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Subscription: juniper::GraphQLType<S, Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+{
+    graphql_filter_sync(schema, context_extractor, None)
+}
+
+/// Make a synchronous filter for graphql endpoint, serializing errors with a
+/// custom [`ErrorFormatter`].
+///
+/// [`ErrorFormatter`]: juniper::http::ErrorFormatter
+pub fn make_graphql_filter_sync_with_error_formatter<Query, Mutation, Subscription, Context, S, F>(
+    schema: juniper::RootNode<'static, Query, Mutation, Subscription, S>,
+    context_extractor: BoxedFilter<(Context,)>,
+    error_formatter: F,
+) -> BoxedFilter<(warp::http::Response<Vec<u8>>,)>
+where
+    S: ScalarValue + Send + Sync + 'static,
+    Context: Send + Sync + 'static,
+    Query: juniper::GraphQLType<S, Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Mutation: juniper::GraphQLType<S, Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    Subscription: juniper::GraphQLType<S, Context = Context, TypeInfo = ()> + Send + Sync + 'static,
+    F: ErrorFormatter<S> + 'static,
+{
+    graphql_filter_sync(schema, context_extractor, Some(Arc::new(error_formatter)))
+}
+
+fn graphql_filter_sync<Query, Mutation, Subscription, Context, S>(
+    schema: juniper::RootNode<'static, Query, Mutation, Subscription, S>,
+    context_extractor: BoxedFilter<(Context,)>,
+    error_formatter: Option<Arc<dyn ErrorFormatter<S>>>,
+) -> BoxedFilter<(warp::http::Response<Vec<u8>>,)>
 where
     S: ScalarValue + Send + Sync + 'static,
     Context: Send + Sync + 'static,
@@ -285,17 +466,23 @@ where
     Subscription: juniper::GraphQLType<S, Context = Context, TypeInfo = ()> + Send + Sync + 'static,
 {
     let schema = Arc::new(schema);
+    let post_error_formatter = error_formatter.clone();
     let post_schema = schema.clone();
 
     let handle_post_request =
-        move |context: Context, request: GraphQLBatchRequest<S>| -> Response {
+        move |context: Context, mut request: GraphQLBatchRequest<S>| -> Response {
             let schema = post_schema.clone();
+            let error_formatter = post_error_formatter.clone();
 
             Box::pin(
                 async move {
                     let result = task::spawn_blocking(move || {
+                        request.load_persisted_queries(&schema);
                         let response = request.execute_sync(&schema, &context);
-                        Ok((serde_json::to_vec(&response)?, response.is_ok()))
+                        Ok((
+                            batch_response_to_json(&response, error_formatter.as_deref())?,
+                            response.status_code(),
+                        ))
                     })
                     .await?;
 
@@ -310,37 +497,32 @@ where
         .and(warp::body::json())
         .and_then(handle_post_request);
 
-    let handle_get_request = move |context: Context,
-                                   mut request: std::collections::HashMap<String, String>|
-          -> Response {
-        let schema = schema.clone();
+    let handle_get_request =
+        move |context: Context, request: HashMap<String, String>| -> Response {
+            let schema = schema.clone();
+            let error_formatter = error_formatter.clone();
 
-        Box::pin(
-            async move {
-                let result = task::spawn_blocking(move || {
-                    let variables = match request.remove("variables") {
-                        None => None,
-                        Some(vs) => serde_json::from_str(&vs)?,
+            Box::pin(
+                async move {
+                    let graphql_request = match from_get(request) {
+                        Ok(r) => r,
+                        Err(e) => return Ok(build_bad_request(e)),
                     };
 
-                    let graphql_request = juniper::http::GraphQLRequest::new(
-                        request.remove("query").ok_or_else(|| {
-                            failure::format_err!("Missing GraphQL query string in query parameters")
-                        })?,
-                        request.get("operation_name").map(|s| s.to_owned()),
-                        variables,
-                    );
-
-                    let response = graphql_request.execute_sync(&schema, &context);
-                    Ok((serde_json::to_vec(&response)?, response.is_ok()))
-                })
-                .await?;
+                    let result = task::spawn_blocking(move || {
+                        let response = graphql_request.execute_sync(&schema, &context);
+                        Ok((
+                            response_to_json(&response, error_formatter.as_deref())?,
+                            response.status_code(),
+                        ))
+                    })
+                    .await?;
 
-                Ok(build_response(result))
-            }
-            .map_err(|e: task::JoinError| warp::reject::custom(JoinError(e))),
-        )
-    };
+                    Ok(build_response(result))
+                }
+                .map_err(|e: task::JoinError| warp::reject::custom(JoinError(e))),
+            )
+        };
 
     let get_filter = warp::get()
         .and(context_extractor)
@@ -364,12 +546,61 @@ impl std::fmt::Debug for JoinError {
     }
 }
 
+/// Error of a GraphQL request read from the query string of a GET request
+#[derive(Debug)]
+enum GetRequestError {
+    MissingQuery,
+    Variables(serde_json::Error),
+}
+
+impl fmt::Display for GetRequestError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match *self {
+            GetRequestError::MissingQuery => write!(f, "'query' parameter is missing"),
+            GetRequestError::Variables(ref err) => {
+                write!(f, "'variables' parameter is not valid JSON: {}", err)
+            }
+        }
+    }
+}
+
+/// Read a GraphQL request from the parameters of a GET request.
+///
+/// An absent `variables` parameter means no variables, while a present one
+/// must be valid JSON.
+fn from_get<S>(
+    mut request: HashMap<String, String>,
+) -> Result<juniper::http::GraphQLRequest<S>, GetRequestError>
+where
+    S: ScalarValue,
+{
+    let variables = match request.remove("variables") {
+        None => None,
+        Some(vs) => Some(serde_json::from_str(&vs).map_err(GetRequestError::Variables)?),
+    };
+
+    Ok(juniper::http::GraphQLRequest::new(
+        request
+            .remove("query")
+            .ok_or(GetRequestError::MissingQuery)?,
+        request.remove("operation_name"),
+        variables,
+    ))
+}
+
+fn build_bad_request(error: GetRequestError) -> warp::http::Response<Vec<u8>> {
+    warp::http::Response::builder()
+        .status(warp::http::StatusCode::BAD_REQUEST)
+        .body(error.to_string().into_bytes())
+        .expect("status code is valid")
+}
+
 fn build_response(
-    response: Result<(Vec<u8>, bool), failure::Error>,
+    response: Result<(Vec<u8>, u16), failure::Error>,
 ) -> warp::http::Response<Vec<u8>> {
     match response {
-        Ok((body, is_ok)) => warp::http::Response::builder()
-            .status(if is_ok { 200 } else { 400 })
+        Ok((body, status)) => warp::http::Response::builder()
+            .status(status)
             .header("content-type", "application/json")
             .body(body)
             .expect("response is valid"),
@@ -449,21 +680,70 @@ fn playground_response(
 pub mod subscriptions {
     use std::{
         collections::HashMap,
+        convert::Infallible,
         sync::{
             atomic::{AtomicBool, Ordering},
-            Arc,
+            Arc, Mutex,
         },
+        time::Duration,
     };
 
-    use futures::{channel::mpsc, Future, StreamExt as _};
-    use juniper::{http::GraphQLRequest, InputValue, ScalarValue, SubscriptionCoordinator as _};
+    use futures::{
+        channel::mpsc,
+        future::{self, AbortHandle, Abortable, Either},
+        Future, FutureExt as _, SinkExt as _, StreamExt as _,
+    };
+    use juniper::{
+        http::{GraphQLRequest, GraphQLResponse},
+        InputValue, ScalarValue, SubscriptionCoordinator as _,
+    };
     use juniper_subscriptions::Coordinator;
-    use serde::{Deserialize, Serialize};
-    use warp::ws::Message;
+    use serde::{de::DeserializeOwned, Deserialize, Serialize};
+    use tokio::time::Instant;
+    use warp::{filters::BoxedFilter, ws::Message, Filter as _};
+
+    /// Encoding used for the messages exchanged over a subscriptions websocket.
+    ///
+    /// The same codec is used to decode incoming client messages and to encode
+    /// outgoing server messages, so binary formats (e.g. MessagePack or CBOR)
+    /// can be used instead of text JSON frames.
+    pub trait Codec: Send + Sync + 'static {
+        /// Decodes an incoming websocket message.
+        fn decode<T: DeserializeOwned>(&self, msg: &Message) -> Result<T, failure::Error>;
+
+        /// Encodes an outgoing value into a websocket message.
+        fn encode<T: Serialize>(&self, value: &T) -> Result<Message, failure::Error>;
+    }
+
+    /// Default [`Codec`] sending text JSON frames.
+    ///
+    /// Binary frames are accepted as well, as long as they contain JSON.
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct JsonCodec;
+
+    impl Codec for JsonCodec {
+        fn decode<T: DeserializeOwned>(&self, msg: &Message) -> Result<T, failure::Error> {
+            Ok(serde_json::from_slice(msg.as_bytes())?)
+        }
+
+        fn encode<T: Serialize>(&self, value: &T) -> Result<Message, failure::Error> {
+            Ok(Message::text(serde_json::to_string(value)?))
+        }
+    }
 
     /// Listen to incoming messages and do one of the following:
-    ///  - execute subscription and return values from stream
-    ///  - stop stream and close ws connection
+    ///  - acknowledge `connection_init` with `connection_ack`
+    ///  - execute subscription and return values from stream, followed by
+    ///    `complete` once the stream ends
+    ///  - stop the stream of the subscription with the given `id`
+    ///
+    /// Several subscriptions run concurrently over the same connection, each
+    /// started with its own `id`. Their `data` messages are interleaved as
+    /// their streams yield, tagged with the `id` of their subscription. A
+    /// `start` reusing the `id` of a running subscription is ignored.
+    ///
+    /// Messages are encoded as JSON, see [`graphql_subscriptions_with_codec`]
+    /// to use another format.
     #[allow(dead_code)]
     pub fn graphql_subscriptions<Query, Mutation, Subscription, Context, S>(
         websocket: warp::ws::WebSocket,
@@ -481,75 +761,457 @@ pub mod subscriptions {
             juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
         Subscription::TypeInfo: Send + Sync,
     {
-        let (sink_tx, sink_rx) = websocket.split();
-        let (ws_tx, ws_rx) = mpsc::unbounded();
-        tokio::task::spawn(
-            ws_rx
-                .take_while(|v: &Option<_>| futures::future::ready(v.is_some()))
-                .map(|x| x.unwrap())
-                .forward(sink_tx),
-        );
-
-        let context = Arc::new(context);
-        let running = Arc::new(AtomicBool::new(false));
-        let got_close_signal = Arc::new(AtomicBool::new(false));
+        graphql_subscriptions_with_codec(websocket, coordinator, context, JsonCodec)
+    }
 
-        sink_rx.fold(Ok(()), move |_, msg| {
-            let coordinator = coordinator.clone();
-            let context = context.clone();
-            let running = running.clone();
-            let got_close_signal = got_close_signal.clone();
-            let ws_tx = ws_tx.clone();
+    /// Same as [`graphql_subscriptions`], but decodes and encodes websocket
+    /// messages with the given [`Codec`].
+    pub fn graphql_subscriptions_with_codec<Query, Mutation, Subscription, Context, S, C>(
+        websocket: warp::ws::WebSocket,
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+        context: Context,
+        codec: C,
+    ) -> impl Future<Output = Result<(), failure::Error>> + Send
+    where
+        S: ScalarValue + Send + Sync + 'static,
+        Context: Clone + Send + Sync + 'static,
+        Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription:
+            juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        C: Codec,
+    {
+        graphql_subscriptions_with(
+            websocket,
+            coordinator,
+            context,
+            codec,
+            ConnectionConfig::default(),
+        )
+    }
 
-            async move {
-                let msg = match msg {
-                    Ok(m) => m,
-                    Err(e) => {
-                        got_close_signal.store(true, Ordering::Relaxed);
-                        return Err(failure::format_err!("Websocket error: {}", e));
-                    }
-                };
+    /// Why the server closed a subscriptions websocket connection.
+    ///
+    /// Sent to the client as the code and reason of the websocket close frame
+    /// ending the connection.
+    #[derive(Clone, Debug, PartialEq)]
+    pub struct CloseReason {
+        /// Close code, in the `4000-4999` range reserved for applications
+        pub code: u16,
+        /// Human-readable reason
+        pub message: String,
+    }
 
-                if msg.is_close() {
-                    return Ok(());
-                }
+    impl CloseReason {
+        /// The client sent a malformed message, or a message not allowed at
+        /// this point of the protocol, e.g. a `start` before `connection_init`.
+        pub fn protocol_error(message: impl Into<String>) -> Self {
+            Self {
+                code: 4400,
+                message: message.into(),
+            }
+        }
 
-                let msg = msg
-                    .to_str()
-                    .map_err(|_| failure::format_err!("Non-text messages are not accepted"))?;
-                let request: WsPayload<S> = serde_json::from_str(msg)
-                    .map_err(|e| failure::format_err!("Invalid WsPayload: {}", e))?;
+        /// The `connection_init` payload was rejected by the
+        /// [connection init handler](ConnectionConfig::with_connection_init_handler).
+        pub fn unauthorized(message: impl Into<String>) -> Self {
+            Self {
+                code: 4401,
+                message: message.into(),
+            }
+        }
 
-                match request.type_name.as_str() {
-                    "connection_init" => {}
-                    "start" => {
-                        {
-                            let closed = got_close_signal.load(Ordering::Relaxed);
-                            if closed {
-                                return Ok(());
-                            }
+        /// The server failed to handle the connection, e.g. the [`Codec`]
+        /// failed to encode an outgoing message.
+        pub fn internal_error(message: impl Into<String>) -> Self {
+            Self {
+                code: 1011,
+                message: message.into(),
+            }
+        }
+
+        /// No `connection_init` was received within the
+        /// [connection init timeout](ConnectionConfig::with_connection_init_timeout).
+        pub fn connection_init_timeout() -> Self {
+            Self {
+                code: 4408,
+                message: "Connection initialisation timeout".to_owned(),
+            }
+        }
+
+        /// The websocket close frame carrying this reason.
+        pub fn to_message(&self) -> Message {
+            Message::close_with(self.code, self.message.clone())
+        }
+    }
+
+    /// Sender of the messages to write to a subscriptions websocket, `None`
+    /// closing it.
+    type WsSender = mpsc::Sender<Option<Result<Message, warp::Error>>>;
+
+    /// Number of messages queued for a subscriptions websocket before the
+    /// subscriptions wait for the client to read them.
+    ///
+    /// Every subscription sends from its own [`mpsc::Sender`], which holds one
+    /// more message and is woken in turn with the others once the queue has
+    /// room, so a busy subscription can't starve the other ones.
+    const WS_BUFFER: usize = 16;
+
+    type ConnectionInitHandler =
+        Arc<dyn Fn(Option<&serde_json::Value>) -> Result<(), String> + Send + Sync>;
+
+    /// Settings of a subscriptions websocket connection.
+    ///
+    /// The default configuration sends no keep-alive messages, lets
+    /// subscriptions run until they end or the client stops them, and accepts
+    /// any `connection_init` whenever it is sent.
+    #[derive(Clone, Default)]
+    pub struct ConnectionConfig {
+        keep_alive_interval: Option<Duration>,
+        max_subscription_lifetime: Option<Duration>,
+        max_subscription_lifetime_note: Option<&'static str>,
+        connection_init_timeout: Option<Duration>,
+        connection_init_handler: Option<ConnectionInitHandler>,
+    }
+
+    impl std::fmt::Debug for ConnectionConfig {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("ConnectionConfig")
+                .field("keep_alive_interval", &self.keep_alive_interval)
+                .field("max_subscription_lifetime", &self.max_subscription_lifetime)
+                .field(
+                    "max_subscription_lifetime_note",
+                    &self.max_subscription_lifetime_note,
+                )
+                .field("connection_init_timeout", &self.connection_init_timeout)
+                .field(
+                    "connection_init_handler",
+                    &self.connection_init_handler.as_ref().map(|_| ".."),
+                )
+                .finish()
+        }
+    }
+
+    impl ConnectionConfig {
+        /// Sends a `connection_keep_alive` (`ka`) message on each tick of the
+        /// given interval during which no data was sent, so that idle
+        /// connections are not dropped by proxies or load balancers.
+        pub fn with_keep_alive_interval(mut self, interval: Duration) -> Self {
+            self.keep_alive_interval = Some(interval);
+            self
+        }
+
+        /// Completes subscriptions still running after the given lifetime
+        /// with a `complete` message, as if the client had stopped them.
+        pub fn with_max_subscription_lifetime(mut self, lifetime: Duration) -> Self {
+            self.max_subscription_lifetime = Some(lifetime);
+            self
+        }
+
+        /// Sends `{"message": note}` as the payload of the `complete`
+        /// message of subscriptions completed because they exceeded their
+        /// [maximum lifetime](Self::with_max_subscription_lifetime).
+        pub fn with_max_subscription_lifetime_note(mut self, note: &'static str) -> Self {
+            self.max_subscription_lifetime_note = Some(note);
+            self
+        }
+
+        /// Closes the connection with
+        /// [`CloseReason::connection_init_timeout`] if the client didn't send
+        /// `connection_init` within the given timeout.
+        pub fn with_connection_init_timeout(mut self, timeout: Duration) -> Self {
+            self.connection_init_timeout = Some(timeout);
+            self
+        }
+
+        /// Checks the payload of `connection_init`, e.g. for an authentication
+        /// token. An `Err` closes the connection with
+        /// [`CloseReason::unauthorized`] and the returned message.
+        pub fn with_connection_init_handler<F>(mut self, handler: F) -> Self
+        where
+            F: Fn(Option<&serde_json::Value>) -> Result<(), String> + Send + Sync + 'static,
+        {
+            self.connection_init_handler = Some(Arc::new(handler));
+            self
+        }
+    }
+
+    /// Same as [`graphql_subscriptions_with_codec`], but with the given
+    /// [`ConnectionConfig`].
+    pub fn graphql_subscriptions_with<Query, Mutation, Subscription, Context, S, C>(
+        websocket: warp::ws::WebSocket,
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+        context: Context,
+        codec: C,
+        config: ConnectionConfig,
+    ) -> impl Future<Output = Result<(), failure::Error>> + Send
+    where
+        S: ScalarValue + Send + Sync + 'static,
+        Context: Clone + Send + Sync + 'static,
+        Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription:
+            juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        C: Codec,
+    {
+        graphql_subscriptions_with_shutdown(
+            websocket,
+            coordinator,
+            context,
+            codec,
+            config,
+            future::pending(),
+        )
+    }
+
+    /// Same as [`graphql_subscriptions_with`], but drains the connection once
+    /// `shutdown` resolves.
+    ///
+    /// On shutdown, a `complete` message is sent for every running
+    /// subscription, followed by a websocket close frame, and the returned
+    /// future resolves. Serving each connection with a clone of a shared
+    /// signal, e.g. a [`Shared`](futures::future::Shared) future resolving
+    /// once the server stops accepting connections, lets clients resubscribe
+    /// elsewhere instead of being cut off mid-stream.
+    pub fn graphql_subscriptions_with_shutdown<Query, Mutation, Subscription, Context, S, C, F>(
+        websocket: warp::ws::WebSocket,
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+        context: Context,
+        codec: C,
+        config: ConnectionConfig,
+        shutdown: F,
+    ) -> impl Future<Output = Result<(), failure::Error>> + Send
+    where
+        S: ScalarValue + Send + Sync + 'static,
+        Context: Clone + Send + Sync + 'static,
+        Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription:
+            juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+        C: Codec,
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let (sink_tx, sink_rx) = websocket.split();
+        let (ws_tx, ws_rx) = mpsc::channel(WS_BUFFER);
+        tokio::task::spawn(
+            ws_rx
+                .take_while(|v: &Option<_>| futures::future::ready(v.is_some()))
+                .map(|x| x.unwrap())
+                .forward(sink_tx),
+        );
+
+        let context = Arc::new(context);
+        let codec = Arc::new(codec);
+        let initialized = Arc::new(AtomicBool::new(false));
+        let got_close_signal = Arc::new(AtomicBool::new(false));
+        let data_sent = Arc::new(AtomicBool::new(false));
+        let active = Arc::new(Mutex::new(HashMap::<String, AbortHandle>::new()));
 
-                            if running.load(Ordering::Relaxed) {
+        if let Some(interval) = config.keep_alive_interval {
+            let codec = codec.clone();
+            let got_close_signal = got_close_signal.clone();
+            let data_sent = data_sent.clone();
+            let ws_tx = ws_tx.clone();
+
+            tokio::task::spawn(async move {
+                let mut ticks = tokio::time::interval_at(Instant::now() + interval, interval);
+                let keep_alive_message = ServerMessage::<()> {
+                    type_name: "ka",
+                    id: None,
+                    payload: None,
+                };
+
+                loop {
+                    ticks.tick().await;
+
+                    if got_close_signal.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    if data_sent.swap(false, Ordering::Relaxed) {
+                        continue;
+                    }
+
+                    if !send_encoded(&*codec, &keep_alive_message, &ws_tx, &got_close_signal).await
+                    {
+                        break;
+                    }
+                }
+            });
+        }
+
+        if let Some(timeout) = config.connection_init_timeout {
+            let initialized = initialized.clone();
+            let got_close_signal = got_close_signal.clone();
+            let ws_tx = ws_tx.clone();
+
+            tokio::task::spawn(async move {
+                tokio::time::delay_for(timeout).await;
+
+                if !initialized.load(Ordering::Relaxed) && !got_close_signal.load(Ordering::Relaxed)
+                {
+                    close_connection(
+                        &ws_tx,
+                        &got_close_signal,
+                        &CloseReason::connection_init_timeout(),
+                    )
+                    .await;
+                }
+            });
+        }
+
+        let drain = {
+            let codec = codec.clone();
+            let got_close_signal = got_close_signal.clone();
+            let active = active.clone();
+            let mut ws_tx = ws_tx.clone();
+
+            async move {
+                shutdown.await;
+
+                got_close_signal.store(true, Ordering::Relaxed);
+
+                let mut request_ids = active
+                    .lock()
+                    .unwrap()
+                    .drain()
+                    .map(|(request_id, handle)| {
+                        handle.abort();
+                        request_id
+                    })
+                    .collect::<Vec<String>>();
+                request_ids.sort();
+                for request_id in request_ids {
+                    let close_message = ServerMessage::<()> {
+                        type_name: "complete",
+                        id: Some(&request_id),
+                        payload: None,
+                    };
+                    if !send_encoded(&*codec, &close_message, &ws_tx, &got_close_signal).await {
+                        return;
+                    }
+                }
+                let _ = ws_tx.send(Some(Ok(Message::close()))).await;
+                // close channel
+                let _ = ws_tx.send(None).await;
+            }
+        };
+
+        let connection = sink_rx.fold(Ok(()), move |_, msg| {
+            let coordinator = coordinator.clone();
+            let context = context.clone();
+            let codec = codec.clone();
+            let initialized = initialized.clone();
+            let got_close_signal = got_close_signal.clone();
+            let data_sent = data_sent.clone();
+            let active = active.clone();
+            let config = config.clone();
+            let ws_tx = ws_tx.clone();
+
+            async move {
+                let msg = match msg {
+                    Ok(m) => m,
+                    Err(e) => {
+                        got_close_signal.store(true, Ordering::Relaxed);
+                        return Err(failure::format_err!("Websocket error: {}", e));
+                    }
+                };
+
+                if msg.is_close() || got_close_signal.load(Ordering::Relaxed) {
+                    return Ok(());
+                }
+
+                let protocol_error = |message: String| {
+                    close_connection(
+                        &ws_tx,
+                        &got_close_signal,
+                        &CloseReason::protocol_error(message),
+                    )
+                    .map(Ok)
+                };
+
+                let message: ClientMessage = match codec.decode(&msg) {
+                    Ok(message) => message,
+                    Err(e) => return protocol_error(format!("Invalid message: {}", e)).await,
+                };
+
+                match message.type_name.as_str() {
+                    "connection_init" => {
+                        if initialized.swap(true, Ordering::Relaxed) {
+                            return protocol_error("Too many initialisation requests".to_owned())
+                                .await;
+                        }
+
+                        if let Some(ref handler) = config.connection_init_handler {
+                            let init: InitMessage = match codec.decode(&msg) {
+                                Ok(init) => init,
+                                Err(e) => {
+                                    return protocol_error(format!("Invalid message: {}", e)).await
+                                }
+                            };
+                            if let Err(message) = handler(init.payload.as_ref()) {
+                                close_connection(
+                                    &ws_tx,
+                                    &got_close_signal,
+                                    &CloseReason::unauthorized(message),
+                                )
+                                .await;
                                 return Ok(());
                             }
-                            running.store(true, Ordering::Relaxed);
                         }
 
-                        let ws_tx = ws_tx.clone();
+                        let ack_message = ServerMessage::<()> {
+                            type_name: "connection_ack",
+                            id: None,
+                            payload: None,
+                        };
+                        send_encoded(&*codec, &ack_message, &ws_tx, &got_close_signal).await;
+                    }
+                    "start" => {
+                        if !initialized.load(Ordering::Relaxed) {
+                            return protocol_error(
+                                "Received `start` before `connection_init`".to_owned(),
+                            )
+                            .await;
+                        }
+
+                        let request: WsPayload<S> = match codec.decode(&msg) {
+                            Ok(request) => request,
+                            Err(e) => {
+                                return protocol_error(format!("Invalid message: {}", e)).await
+                            }
+                        };
+                        match request.payload {
+                            Some(ref payload) if payload.query.is_none() => {
+                                return protocol_error("Query not found".to_owned()).await;
+                            }
+                            None => return protocol_error("Payload not found".to_owned()).await,
+                            _ => {}
+                        }
 
-                        if let Some(ref payload) = request.payload {
-                            if payload.query.is_none() {
-                                return Err(failure::format_err!("Query not found"));
+                        let request_id = request.id.unwrap_or("1".to_owned());
+                        let (abort_handle, abort_registration) = AbortHandle::new_pair();
+                        {
+                            let mut active = active.lock().unwrap();
+                            if active.contains_key(&request_id) {
+                                return Ok(());
                             }
-                        } else {
-                            return Err(failure::format_err!("Payload not found"));
+                            active.insert(request_id.clone(), abort_handle);
                         }
 
+                        let ws_tx = ws_tx.clone();
+
                         tokio::task::spawn(async move {
                             let payload = request.payload.unwrap();
 
-                            let request_id = request.id.unwrap_or("1".to_owned());
-
                             let graphql_request = GraphQLRequest::<S>::new(
                                 payload.query.unwrap(),
                                 None,
@@ -560,69 +1222,294 @@ pub mod subscriptions {
                                 match coordinator.subscribe(&graphql_request, &context).await {
                                     Ok(s) => s,
                                     Err(err) => {
-                                        let _ =
-                                            ws_tx.unbounded_send(Some(Ok(Message::text(format!(
-                                                r#"{{"type":"error","id":"{}","payload":{}}}"#,
-                                                request_id,
-                                                serde_json::ser::to_string(&err).unwrap_or(
-                                                    "Error deserializing GraphQLError".to_owned()
-                                                )
-                                            )))));
-
-                                        let close_message = format!(
-                                            r#"{{"type":"complete","id":"{}","payload":null}}"#,
-                                            request_id
-                                        );
-                                        let _ = ws_tx
-                                            .unbounded_send(Some(Ok(Message::text(close_message))));
-                                        // close channel
-                                        let _ = ws_tx.unbounded_send(None);
+                                        let error_message = ServerMessage {
+                                            type_name: "error",
+                                            id: Some(&request_id),
+                                            payload: Some(&err),
+                                        };
+                                        send_encoded(
+                                            &*codec,
+                                            &error_message,
+                                            &ws_tx,
+                                            &got_close_signal,
+                                        )
+                                        .await;
+
+                                        if active.lock().unwrap().remove(&request_id).is_none() {
+                                            return;
+                                        }
+                                        let close_message = ServerMessage::<()> {
+                                            type_name: "complete",
+                                            id: Some(&request_id),
+                                            payload: None,
+                                        };
+                                        send_encoded(
+                                            &*codec,
+                                            &close_message,
+                                            &ws_tx,
+                                            &got_close_signal,
+                                        )
+                                        .await;
                                         return;
                                     }
                                 };
 
-                            values_stream
-                                .take_while(move |response| {
-                                    let request_id = request_id.clone();
-                                    let closed = got_close_signal.load(Ordering::Relaxed);
-                                    if !closed {
-                                        let mut response_text = serde_json::to_string(&response)
-                                            .unwrap_or("Error deserializing response".to_owned());
-
-                                        response_text = format!(
-                                            r#"{{"type":"data","id":"{}","payload":{} }}"#,
-                                            request_id, response_text
-                                        );
-
-                                        let _ = ws_tx
-                                            .unbounded_send(Some(Ok(Message::text(response_text))));
-                                    }
+                            let active_request_id = request_id.clone();
 
-                                    async move { !closed }
+                            let lifetime_request_id = request_id.clone();
+                            let lifetime_codec = codec.clone();
+                            let lifetime_ws_tx = ws_tx.clone();
+                            let lifetime_close_signal = got_close_signal.clone();
+                            let closing = got_close_signal.clone();
+
+                            // Waits for the client to catch up once the queue of
+                            // the connection is full, taking turns with the
+                            // other subscriptions of the connection.
+                            let forward = values_stream
+                                .take_while(move |_| {
+                                    future::ready(!closing.load(Ordering::Relaxed))
                                 })
-                                .for_each(|_| async {})
-                                .await;
+                                .for_each(move |response| {
+                                    let data_message = ServerMessage {
+                                        type_name: "data",
+                                        id: Some(&request_id),
+                                        payload: Some(&response),
+                                    };
+                                    let sent = send_encoded(
+                                        &*codec,
+                                        &data_message,
+                                        &ws_tx,
+                                        &got_close_signal,
+                                    );
+                                    let data_sent = data_sent.clone();
+
+                                    async move {
+                                        if sent.await {
+                                            data_sent.store(true, Ordering::Relaxed);
+                                        }
+                                    }
+                                });
+                            // aborted by `stop` and on shutdown
+                            let forward = Abortable::new(forward, abort_registration);
+
+                            let expired = match config.max_subscription_lifetime {
+                                Some(lifetime) => {
+                                    tokio::time::timeout(lifetime, forward).await.is_err()
+                                }
+                                None => {
+                                    let _ = forward.await;
+                                    false
+                                }
+                            };
+                            // Stopped and drained subscriptions got their
+                            // `complete` already.
+                            if active.lock().unwrap().remove(&active_request_id).is_none() {
+                                return;
+                            }
+
+                            let note = if expired {
+                                config.max_subscription_lifetime_note
+                            } else {
+                                None
+                            };
+                            let close_message = ServerMessage {
+                                type_name: "complete",
+                                id: Some(&lifetime_request_id),
+                                payload: note.map(|message| CompletionNote { message }),
+                            };
+                            send_encoded(
+                                &*lifetime_codec,
+                                &close_message,
+                                &lifetime_ws_tx,
+                                &lifetime_close_signal,
+                            )
+                            .await;
                         });
                     }
                     "stop" => {
-                        got_close_signal.store(true, Ordering::Relaxed);
+                        let request: WsPayload<S> = match codec.decode(&msg) {
+                            Ok(request) => request,
+                            Err(e) => {
+                                return protocol_error(format!("Invalid message: {}", e)).await
+                            }
+                        };
 
                         let request_id = request.id.unwrap_or("1".to_owned());
-                        let close_message = format!(
-                            r#"{{"type":"complete","id":"{}","payload":null}}"#,
-                            request_id
-                        );
-                        let _ = ws_tx.unbounded_send(Some(Ok(Message::text(close_message))));
-
-                        // close channel
-                        let _ = ws_tx.unbounded_send(None);
+                        let handle = match active.lock().unwrap().remove(&request_id) {
+                            Some(handle) => handle,
+                            // already completed, or never started
+                            None => return Ok(()),
+                        };
+                        handle.abort();
+
+                        let close_message = ServerMessage::<()> {
+                            type_name: "complete",
+                            id: Some(&request_id),
+                            payload: None,
+                        };
+                        send_encoded(&*codec, &close_message, &ws_tx, &got_close_signal).await;
                     }
                     _ => {}
                 }
 
                 Ok(())
             }
-        })
+        });
+
+        async move {
+            futures::pin_mut!(connection);
+            match future::select(connection, drain.boxed()).await {
+                Either::Left((result, _)) => result,
+                Either::Right(((), _)) => Ok(()),
+            }
+        }
+    }
+
+    /// Make a filter serving subscriptions over Server-Sent Events.
+    ///
+    /// The subscription is read from the `query`, `variables` and
+    /// `operation_name` query parameters of a GET request, as in
+    /// [`make_graphql_filter`](crate::make_graphql_filter). Each response of
+    /// the subscription is sent as a JSON `data:` event, and the event stream
+    /// is closed once the subscription completes.
+    ///
+    /// The `context_extractor` argument should be a filter that provides the
+    /// GraphQL context required by the schema.
+    pub fn graphql_subscriptions_sse<Query, Mutation, Subscription, Context, S>(
+        coordinator: Arc<Coordinator<'static, Query, Mutation, Subscription, Context, S>>,
+        context_extractor: BoxedFilter<(Context,)>,
+    ) -> BoxedFilter<(Box<dyn warp::Reply>,)>
+    where
+        S: ScalarValue + Send + Sync + 'static,
+        Context: Send + Sync + 'static,
+        Query: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Query::TypeInfo: Send + Sync,
+        Mutation: juniper::GraphQLTypeAsync<S, Context = Context> + Send + Sync + 'static,
+        Mutation::TypeInfo: Send + Sync,
+        Subscription:
+            juniper::GraphQLSubscriptionType<S, Context = Context> + Send + Sync + 'static,
+        Subscription::TypeInfo: Send + Sync,
+    {
+        let handle_request = move |context: Context, request: HashMap<String, String>| {
+            let coordinator = coordinator.clone();
+
+            let graphql_request = match crate::from_get::<S>(request) {
+                Ok(r) => r,
+                Err(e) => return Box::new(crate::build_bad_request(e)) as Box<dyn warp::Reply>,
+            };
+
+            let (events_tx, events_rx) = mpsc::unbounded();
+
+            tokio::task::spawn(async move {
+                let responses = match coordinator.subscribe(&graphql_request, &context).await {
+                    Ok(s) => s,
+                    Err(err) => {
+                        let response = GraphQLResponse::<S>::from_result(Err(err));
+                        if let Ok(data) = serde_json::to_string(&response) {
+                            let _ = events_tx.unbounded_send(data);
+                        }
+                        return;
+                    }
+                };
+
+                // stops as soon as the client goes away
+                responses
+                    .take_while(move |response| {
+                        let sent = serde_json::to_string(response)
+                            .map(|data| events_tx.unbounded_send(data).is_ok())
+                            .unwrap_or(true);
+
+                        async move { sent }
+                    })
+                    .for_each(|_| async {})
+                    .await;
+            });
+
+            Box::new(warp::sse::reply(
+                events_rx.map(|data| Ok::<_, Infallible>(warp::sse::data(data))),
+            ))
+        };
+
+        warp::get()
+            .and(context_extractor)
+            .and(warp::filters::query::query())
+            .map(handle_request)
+            .boxed()
+    }
+
+    /// Closes the connection, sending `reason` in the close frame.
+    ///
+    /// No message is handled once this returns, the returned future only
+    /// sends the close frame.
+    fn close_connection(
+        ws_tx: &WsSender,
+        got_close_signal: &AtomicBool,
+        reason: &CloseReason,
+    ) -> impl Future<Output = ()> {
+        got_close_signal.store(true, Ordering::Relaxed);
+        let close_frame = reason.to_message();
+        let mut ws_tx = ws_tx.clone();
+
+        async move {
+            let _ = ws_tx.send(Some(Ok(close_frame))).await;
+            // close channel
+            let _ = ws_tx.send(None).await;
+        }
+    }
+
+    /// Sends `value` encoded with `codec`, returning whether it was sent.
+    ///
+    /// A value the codec fails to encode closes the connection with
+    /// [`CloseReason::internal_error`] instead of being silently dropped.
+    fn send_encoded<C: Codec, T: Serialize>(
+        codec: &C,
+        value: &T,
+        ws_tx: &WsSender,
+        got_close_signal: &AtomicBool,
+    ) -> impl Future<Output = bool> {
+        let encoded = codec.encode(value).map_err(|e| {
+            close_connection(
+                ws_tx,
+                got_close_signal,
+                &CloseReason::internal_error(format!("Failed to encode message: {}", e)),
+            )
+        });
+        let mut ws_tx = ws_tx.clone();
+
+        async move {
+            match encoded {
+                Ok(msg) => ws_tx.send(Some(Ok(msg))).await.is_ok(),
+                Err(close) => {
+                    close.await;
+                    false
+                }
+            }
+        }
+    }
+
+    #[derive(Serialize)]
+    struct ServerMessage<'a, T> {
+        #[serde(rename = "type")]
+        type_name: &'a str,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        id: Option<&'a str>,
+        payload: Option<T>,
+    }
+
+    #[derive(Serialize)]
+    struct CompletionNote<'a> {
+        message: &'a str,
+    }
+
+    #[derive(Deserialize)]
+    struct ClientMessage {
+        #[serde(rename(deserialize = "type"))]
+        type_name: String,
+    }
+
+    #[derive(Deserialize)]
+    struct InitMessage {
+        payload: Option<serde_json::Value>,
     }
 
     #[derive(Deserialize)]
@@ -632,8 +1519,6 @@ pub mod subscriptions {
         S: ScalarValue + Send + Sync + 'static,
     {
         id: Option<String>,
-        #[serde(rename(deserialize = "type"))]
-        type_name: String,
         payload: Option<GraphQLPayload<S>>,
     }
 
@@ -655,6 +1540,593 @@ pub mod subscriptions {
         data: String,
         variables: String,
     }
+
+    #[cfg(test)]
+    mod tests {
+        use std::pin::Pin;
+
+        use futures::{FutureExt as _, Stream};
+        use juniper::{EmptyMutation, RootNode};
+        use serde_json::{json, Value as Json};
+        use warp::{test::ws, Filter};
+
+        use super::*;
+
+        struct MessagePackCodec;
+
+        impl Codec for MessagePackCodec {
+            fn decode<T: DeserializeOwned>(&self, msg: &Message) -> Result<T, failure::Error> {
+                Ok(rmp_serde::from_slice(msg.as_bytes())?)
+            }
+
+            fn encode<T: Serialize>(&self, value: &T) -> Result<Message, failure::Error> {
+                Ok(Message::binary(rmp_serde::to_vec_named(value)?))
+            }
+        }
+
+        struct Query;
+
+        #[juniper::graphql_object]
+        impl Query {
+            fn empty() -> bool {
+                true
+            }
+        }
+
+        struct Subscription;
+
+        #[juniper::graphql_subscription]
+        impl Subscription {
+            async fn counter() -> Pin<Box<dyn Stream<Item = i32> + Send>> {
+                Box::pin(futures::stream::iter(vec![1, 2]))
+            }
+
+            async fn paused() -> Pin<Box<dyn Stream<Item = i32> + Send>> {
+                Box::pin(futures::stream::pending())
+            }
+
+            async fn started() -> Pin<Box<dyn Stream<Item = i32> + Send>> {
+                Box::pin(futures::stream::once(async { 0 }).chain(futures::stream::pending()))
+            }
+        }
+
+        type Schema = RootNode<'static, Query, EmptyMutation<()>, Subscription>;
+
+        #[tokio::test]
+        async fn round_trips_binary_frames_with_custom_codec() {
+            let coordinator = Arc::new(Coordinator::new(Schema::new(
+                Query,
+                EmptyMutation::new(),
+                Subscription,
+            )));
+            let route = warp::ws().map(move |ws: warp::ws::Ws| {
+                let coordinator = coordinator.clone();
+                ws.on_upgrade(move |websocket| {
+                    graphql_subscriptions_with_codec(websocket, coordinator, (), MessagePackCodec)
+                        .map(|r| r.expect("websocket session failed"))
+                })
+            });
+
+            let mut client = ws().handshake(route).await.expect("handshake");
+            let codec = MessagePackCodec;
+
+            client
+                .send(codec.encode(&json!({"type": "connection_init"})).unwrap())
+                .await;
+            let msg: Json = codec.decode(&client.recv().await.unwrap()).unwrap();
+            assert_eq!(msg, json!({"type": "connection_ack", "payload": null}));
+            client
+                .send(
+                    codec
+                        .encode(&json!({
+                            "type": "start",
+                            "id": "1",
+                            "payload": {"query": "subscription { counter }"},
+                        }))
+                        .unwrap(),
+                )
+                .await;
+
+            for expected in &[1, 2] {
+                let msg = client.recv().await.expect("data message");
+                assert!(msg.is_binary());
+                let msg: Json = codec.decode(&msg).unwrap();
+                assert_eq!(
+                    msg,
+                    json!({
+                        "type": "data",
+                        "id": "1",
+                        "payload": {"data": {"counter": expected}},
+                    }),
+                );
+            }
+
+            client
+                .send(codec.encode(&json!({"type": "stop", "id": "1"})).unwrap())
+                .await;
+            let msg: Json = codec.decode(&client.recv().await.unwrap()).unwrap();
+            assert_eq!(msg, json!({"type": "complete", "id": "1", "payload": null}));
+        }
+
+        #[tokio::test]
+        async fn sends_keep_alives_while_idle() {
+            tokio::time::pause();
+
+            let coordinator = Arc::new(Coordinator::new(Schema::new(
+                Query,
+                EmptyMutation::new(),
+                Subscription,
+            )));
+            let route = warp::ws().map(move |ws: warp::ws::Ws| {
+                let coordinator = coordinator.clone();
+                ws.on_upgrade(move |websocket| {
+                    graphql_subscriptions_with(
+                        websocket,
+                        coordinator,
+                        (),
+                        JsonCodec,
+                        ConnectionConfig::default()
+                            .with_keep_alive_interval(Duration::from_secs(10)),
+                    )
+                    .map(|r| r.expect("websocket session failed"))
+                })
+            });
+
+            let mut client = ws().handshake(route).await.expect("handshake");
+
+            client
+                .send(Message::text(
+                    json!({"type": "connection_init"}).to_string(),
+                ))
+                .await;
+            let msg: Json = JsonCodec.decode(&client.recv().await.unwrap()).unwrap();
+            assert_eq!(msg, json!({"type": "connection_ack", "payload": null}));
+            client
+                .send(Message::text(
+                    json!({
+                        "type": "start",
+                        "id": "1",
+                        "payload": {"query": "subscription { paused }"},
+                    })
+                    .to_string(),
+                ))
+                .await;
+
+            for _ in 0..2 {
+                tokio::time::advance(Duration::from_secs(10)).await;
+
+                let msg: Json = JsonCodec.decode(&client.recv().await.unwrap()).unwrap();
+                assert_eq!(msg, json!({"type": "ka", "payload": null}));
+            }
+        }
+
+        #[tokio::test]
+        async fn completes_subscriptions_exceeding_their_lifetime() {
+            tokio::time::pause();
+
+            let coordinator = Arc::new(Coordinator::new(Schema::new(
+                Query,
+                EmptyMutation::new(),
+                Subscription,
+            )));
+            let route = warp::ws().map(move |ws: warp::ws::Ws| {
+                let coordinator = coordinator.clone();
+                ws.on_upgrade(move |websocket| {
+                    graphql_subscriptions_with(
+                        websocket,
+                        coordinator,
+                        (),
+                        JsonCodec,
+                        ConnectionConfig::default()
+                            .with_max_subscription_lifetime(Duration::from_secs(60))
+                            .with_max_subscription_lifetime_note("Subscription expired"),
+                    )
+                    .map(|r| r.expect("websocket session failed"))
+                })
+            });
+
+            let mut client = ws().handshake(route).await.expect("handshake");
+
+            client
+                .send(Message::text(
+                    json!({"type": "connection_init"}).to_string(),
+                ))
+                .await;
+            let msg: Json = JsonCodec.decode(&client.recv().await.unwrap()).unwrap();
+            assert_eq!(msg, json!({"type": "connection_ack", "payload": null}));
+            client
+                .send(Message::text(
+                    json!({
+                        "type": "start",
+                        "id": "1",
+                        "payload": {"query": "subscription { paused }"},
+                    })
+                    .to_string(),
+                ))
+                .await;
+
+            tokio::time::advance(Duration::from_secs(60)).await;
+
+            let msg: Json = JsonCodec.decode(&client.recv().await.unwrap()).unwrap();
+            assert_eq!(
+                msg,
+                json!({
+                    "type": "complete",
+                    "id": "1",
+                    "payload": {"message": "Subscription expired"},
+                }),
+            );
+        }
+
+        #[tokio::test]
+        async fn completes_running_subscriptions_on_shutdown() {
+            let coordinator = Arc::new(Coordinator::new(Schema::new(
+                Query,
+                EmptyMutation::new(),
+                Subscription,
+            )));
+            let (trigger, shutdown) = futures::channel::oneshot::channel::<()>();
+            let shutdown = shutdown.map(|_| ()).shared();
+            let route = warp::ws().map(move |ws: warp::ws::Ws| {
+                let coordinator = coordinator.clone();
+                let shutdown = shutdown.clone();
+                ws.on_upgrade(move |websocket| {
+                    graphql_subscriptions_with_shutdown(
+                        websocket,
+                        coordinator,
+                        (),
+                        JsonCodec,
+                        ConnectionConfig::default(),
+                        shutdown,
+                    )
+                    .map(|r| r.expect("websocket session failed"))
+                })
+            });
+
+            let mut clients = vec![];
+            for id in &["1", "2"] {
+                let mut client = ws().handshake(route.clone()).await.expect("handshake");
+                client
+                    .send(Message::text(
+                        json!({"type": "connection_init"}).to_string(),
+                    ))
+                    .await;
+                let msg: Json = JsonCodec.decode(&client.recv().await.unwrap()).unwrap();
+                assert_eq!(msg, json!({"type": "connection_ack", "payload": null}));
+                client
+                    .send(Message::text(
+                        json!({
+                            "type": "start",
+                            "id": id,
+                            "payload": {"query": "subscription { started }"},
+                        })
+                        .to_string(),
+                    ))
+                    .await;
+
+                let msg: Json = JsonCodec.decode(&client.recv().await.unwrap()).unwrap();
+                assert_eq!(
+                    msg,
+                    json!({"type": "data", "id": id, "payload": {"data": {"started": 0}}}),
+                );
+                clients.push((id, client));
+            }
+
+            trigger.send(()).unwrap();
+
+            for (id, mut client) in clients {
+                let msg: Json = JsonCodec.decode(&client.recv().await.unwrap()).unwrap();
+                assert_eq!(msg, json!({"type": "complete", "id": id, "payload": null}));
+                assert!(client.recv_closed().await.is_ok());
+            }
+        }
+
+        fn route_with(config: ConnectionConfig) -> warp::filters::BoxedFilter<(impl warp::Reply,)> {
+            let coordinator = Arc::new(Coordinator::new(Schema::new(
+                Query,
+                EmptyMutation::new(),
+                Subscription,
+            )));
+            warp::ws()
+                .map(move |ws: warp::ws::Ws| {
+                    let coordinator = coordinator.clone();
+                    let config = config.clone();
+                    ws.on_upgrade(move |websocket| {
+                        graphql_subscriptions_with(websocket, coordinator, (), JsonCodec, config)
+                            .map(|r| r.expect("websocket session failed"))
+                    })
+                })
+                .boxed()
+        }
+
+        #[tokio::test]
+        async fn closes_with_protocol_error_on_start_before_init() {
+            let mut client = ws()
+                .handshake(route_with(ConnectionConfig::default()))
+                .await
+                .expect("handshake");
+
+            client
+                .send(Message::text(
+                    json!({
+                        "type": "start",
+                        "id": "1",
+                        "payload": {"query": "subscription { counter }"},
+                    })
+                    .to_string(),
+                ))
+                .await;
+
+            assert_eq!(
+                client.recv().await.unwrap(),
+                CloseReason::protocol_error("Received `start` before `connection_init`")
+                    .to_message(),
+            );
+        }
+
+        #[tokio::test]
+        async fn closes_with_protocol_error_on_malformed_message() {
+            let mut client = ws()
+                .handshake(route_with(ConnectionConfig::default()))
+                .await
+                .expect("handshake");
+
+            client.send(Message::text("not json")).await;
+
+            let msg = client.recv().await.unwrap();
+            assert!(msg.is_close());
+            assert_ne!(msg, Message::close());
+        }
+
+        #[tokio::test]
+        async fn closes_as_unauthorized_on_rejected_init() {
+            let config = ConnectionConfig::default().with_connection_init_handler(|payload| {
+                match payload.and_then(|p| p.get("token")) {
+                    Some(token) if token == "secret" => Ok(()),
+                    _ => Err("Invalid token".to_owned()),
+                }
+            });
+
+            let mut client = ws()
+                .handshake(route_with(config.clone()))
+                .await
+                .expect("handshake");
+            client
+                .send(Message::text(
+                    json!({"type": "connection_init", "payload": {"token": "guess"}}).to_string(),
+                ))
+                .await;
+            assert_eq!(
+                client.recv().await.unwrap(),
+                CloseReason::unauthorized("Invalid token").to_message(),
+            );
+
+            let mut client = ws().handshake(route_with(config)).await.expect("handshake");
+            client
+                .send(Message::text(
+                    json!({"type": "connection_init", "payload": {"token": "secret"}}).to_string(),
+                ))
+                .await;
+            let msg: Json = JsonCodec.decode(&client.recv().await.unwrap()).unwrap();
+            assert_eq!(msg, json!({"type": "connection_ack", "payload": null}));
+            client
+                .send(Message::text(
+                    json!({
+                        "type": "start",
+                        "id": "1",
+                        "payload": {"query": "subscription { counter }"},
+                    })
+                    .to_string(),
+                ))
+                .await;
+            let msg: Json = JsonCodec.decode(&client.recv().await.unwrap()).unwrap();
+            assert_eq!(
+                msg,
+                json!({"type": "data", "id": "1", "payload": {"data": {"counter": 1}}}),
+            );
+        }
+
+        #[tokio::test]
+        async fn closes_when_init_times_out() {
+            tokio::time::pause();
+
+            let mut client = ws()
+                .handshake(route_with(
+                    ConnectionConfig::default()
+                        .with_connection_init_timeout(Duration::from_secs(3)),
+                ))
+                .await
+                .expect("handshake");
+
+            tokio::time::advance(Duration::from_secs(3)).await;
+
+            assert_eq!(
+                client.recv().await.unwrap(),
+                CloseReason::connection_init_timeout().to_message(),
+            );
+        }
+
+        #[tokio::test]
+        async fn keeps_connection_initialised_before_timeout() {
+            tokio::time::pause();
+
+            let mut client = ws()
+                .handshake(route_with(
+                    ConnectionConfig::default()
+                        .with_connection_init_timeout(Duration::from_secs(3)),
+                ))
+                .await
+                .expect("handshake");
+
+            client
+                .send(Message::text(
+                    json!({"type": "connection_init"}).to_string(),
+                ))
+                .await;
+            let msg: Json = JsonCodec.decode(&client.recv().await.unwrap()).unwrap();
+            assert_eq!(msg, json!({"type": "connection_ack", "payload": null}));
+
+            tokio::time::advance(Duration::from_secs(3)).await;
+
+            client
+                .send(Message::text(
+                    json!({
+                        "type": "start",
+                        "id": "1",
+                        "payload": {"query": "subscription { counter }"},
+                    })
+                    .to_string(),
+                ))
+                .await;
+            let msg: Json = JsonCodec.decode(&client.recv().await.unwrap()).unwrap();
+            assert_eq!(
+                msg,
+                json!({"type": "data", "id": "1", "payload": {"data": {"counter": 1}}}),
+            );
+        }
+
+        #[tokio::test]
+        async fn acknowledges_init_and_completes_finished_subscriptions() {
+            let mut client = ws()
+                .handshake(route_with(ConnectionConfig::default()))
+                .await
+                .expect("handshake");
+
+            client
+                .send(Message::text(
+                    json!({"type": "connection_init"}).to_string(),
+                ))
+                .await;
+            client
+                .send(Message::text(
+                    json!({
+                        "type": "start",
+                        "id": "1",
+                        "payload": {"query": "subscription { counter }"},
+                    })
+                    .to_string(),
+                ))
+                .await;
+
+            let mut frames = vec![];
+            for _ in 0..4 {
+                let msg = client.recv().await.expect("server message");
+                assert!(msg.is_text());
+                frames.push(JsonCodec.decode::<Json>(&msg).unwrap());
+            }
+            assert_eq!(
+                frames,
+                vec![
+                    json!({"type": "connection_ack", "payload": null}),
+                    json!({"type": "data", "id": "1", "payload": {"data": {"counter": 1}}}),
+                    json!({"type": "data", "id": "1", "payload": {"data": {"counter": 2}}}),
+                    json!({"type": "complete", "id": "1", "payload": null}),
+                ],
+            );
+        }
+
+        #[tokio::test]
+        async fn multiplexes_subscriptions_by_id() {
+            let mut client = ws()
+                .handshake(route_with(ConnectionConfig::default()))
+                .await
+                .expect("handshake");
+
+            client
+                .send(Message::text(
+                    json!({"type": "connection_init"}).to_string(),
+                ))
+                .await;
+            let msg: Json = JsonCodec.decode(&client.recv().await.unwrap()).unwrap();
+            assert_eq!(msg, json!({"type": "connection_ack", "payload": null}));
+            for (id, query) in &[
+                ("a", "subscription { started }"),
+                ("b", "subscription { counter }"),
+            ] {
+                client
+                    .send(Message::text(
+                        json!({"type": "start", "id": id, "payload": {"query": query}}).to_string(),
+                    ))
+                    .await;
+            }
+
+            let mut frames = vec![];
+            for _ in 0..4 {
+                frames.push(
+                    JsonCodec
+                        .decode::<Json>(&client.recv().await.unwrap())
+                        .unwrap(),
+                );
+            }
+            let frames_of = |id: &str| {
+                frames
+                    .iter()
+                    .filter(|frame| frame["id"] == id)
+                    .cloned()
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(
+                frames_of("a"),
+                vec![json!({"type": "data", "id": "a", "payload": {"data": {"started": 0}}})],
+            );
+            assert_eq!(
+                frames_of("b"),
+                vec![
+                    json!({"type": "data", "id": "b", "payload": {"data": {"counter": 1}}}),
+                    json!({"type": "data", "id": "b", "payload": {"data": {"counter": 2}}}),
+                    json!({"type": "complete", "id": "b", "payload": null}),
+                ],
+            );
+
+            // stopping one subscription leaves the connection open
+            client
+                .send(Message::text(
+                    json!({"type": "stop", "id": "a"}).to_string(),
+                ))
+                .await;
+            let msg: Json = JsonCodec.decode(&client.recv().await.unwrap()).unwrap();
+            assert_eq!(msg, json!({"type": "complete", "id": "a", "payload": null}));
+
+            client
+                .send(Message::text(
+                    json!({"type": "start", "id": "a", "payload": {"query": "subscription { counter }"}})
+                        .to_string(),
+                ))
+                .await;
+            let msg: Json = JsonCodec.decode(&client.recv().await.unwrap()).unwrap();
+            assert_eq!(
+                msg,
+                json!({"type": "data", "id": "a", "payload": {"data": {"counter": 1}}}),
+            );
+        }
+
+        #[tokio::test]
+        async fn streams_subscription_as_server_sent_events() {
+            let coordinator = Arc::new(Coordinator::new(Schema::new(
+                Query,
+                EmptyMutation::new(),
+                Subscription,
+            )));
+            let route = graphql_subscriptions_sse(coordinator, warp::any().map(|| ()).boxed());
+
+            let response = warp::test::request()
+                .method("GET")
+                .path("/?query=subscription%20%7B%20counter%20%7D")
+                .reply(&route)
+                .await;
+
+            assert_eq!(response.status(), 200);
+            assert_eq!(
+                response.headers().get("content-type").unwrap(),
+                "text/event-stream"
+            );
+            assert_eq!(
+                std::str::from_utf8(response.body()).unwrap(),
+                "data:{\"data\":{\"counter\":1}}\n\n\
+                 data:{\"data\":{\"counter\":2}}\n\n",
+            );
+        }
+    }
 }
 
 #[cfg(test)]
@@ -786,6 +2258,194 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn graphql_handler_rejects_malformed_get_variables() {
+        use juniper::{
+            tests::{model::Database, schema::Query},
+            EmptyMutation, EmptySubscription, RootNode,
+        };
+
+        let schema = RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+        let state = warp::any().map(Database::new);
+        let filter = warp::path("graphql").and(make_graphql_filter(schema, state.boxed()));
+
+        let response = request()
+            .method("GET")
+            .path("/graphql?query=%7Bhero%7Bname%7D%7D&variables=notjson")
+            .reply(&filter)
+            .await;
+
+        assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+        let body = String::from_utf8(response.body().to_vec()).unwrap();
+        assert!(
+            body.starts_with("'variables' parameter is not valid JSON: "),
+            "unexpected body: {}",
+            body,
+        );
+
+        let response = request()
+            .method("GET")
+            .path("/graphql?variables=%7B%7D")
+            .reply(&filter)
+            .await;
+
+        assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+        assert_eq!(
+            String::from_utf8(response.body().to_vec()).unwrap(),
+            "'query' parameter is missing",
+        );
+
+        let response = request()
+            .method("GET")
+            .path("/graphql?query=%7Bhero%7Bname%7D%7D")
+            .reply(&filter)
+            .await;
+
+        assert_eq!(response.status(), http::StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn context_factory_receives_request_headers() {
+        use juniper::{EmptyMutation, EmptySubscription, RootNode};
+
+        struct Context {
+            tenant: Option<String>,
+            method: String,
+        }
+
+        impl juniper::Context for Context {}
+
+        struct Query;
+
+        #[juniper::graphql_object(Context = Context)]
+        impl Query {
+            fn tenant(context: &Context) -> Option<&str> {
+                context.tenant.as_deref()
+            }
+
+            fn method(context: &Context) -> &str {
+                &context.method
+            }
+        }
+
+        let schema = RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new());
+        let filter = warp::path("graphql").and(make_graphql_filter_with_context_factory(
+            schema,
+            |parts: RequestParts| Context {
+                tenant: parts
+                    .headers
+                    .get("x-tenant")
+                    .and_then(|value| value.to_str().ok())
+                    .map(ToOwned::to_owned),
+                method: parts.method.to_string(),
+            },
+        ));
+
+        let response = request()
+            .method("POST")
+            .path("/graphql")
+            .header("content-type", "application/json")
+            .header("x-tenant", "acme")
+            .body(r#"{ "query": "{ tenant method }" }"#)
+            .reply(&filter)
+            .await;
+
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(
+            String::from_utf8(response.body().to_vec()).unwrap(),
+            r#"{"data":{"tenant":"acme","method":"POST"}}"#
+        );
+
+        let response = request()
+            .method("GET")
+            .path("/graphql?query={tenant}")
+            .reply(&filter)
+            .await;
+
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(
+            String::from_utf8(response.body().to_vec()).unwrap(),
+            r#"{"data":{"tenant":null}}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn fallible_context_factory_rejects_unauthorized_requests() {
+        use juniper::{EmptyMutation, EmptySubscription, RootNode};
+
+        struct Context {
+            user: String,
+        }
+
+        impl juniper::Context for Context {}
+
+        #[derive(Debug)]
+        struct Unauthorized;
+
+        impl warp::reject::Reject for Unauthorized {}
+
+        struct Query;
+
+        #[juniper::graphql_object(Context = Context)]
+        impl Query {
+            fn user(context: &Context) -> &str {
+                &context.user
+            }
+        }
+
+        let schema = RootNode::new(Query, EmptyMutation::new(), EmptySubscription::new());
+        let filter = warp::path("graphql")
+            .and(make_graphql_filter_with_fallible_context_factory(
+                schema,
+                |parts: RequestParts| match parts.headers.get("authorization") {
+                    Some(token) if token == "Bearer secret" => Ok(Context {
+                        user: "admin".to_owned(),
+                    }),
+                    _ => Err(warp::reject::custom(Unauthorized)),
+                },
+            ))
+            .recover(|rejection: warp::Rejection| async move {
+                if rejection.find::<Unauthorized>().is_some() {
+                    Ok(warp::reply::with_status(
+                        "Unauthorized",
+                        http::StatusCode::UNAUTHORIZED,
+                    ))
+                } else {
+                    Err(rejection)
+                }
+            });
+
+        let response = request()
+            .method("POST")
+            .path("/graphql")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer guess")
+            .body(r#"{ "query": "{ user }" }"#)
+            .reply(&filter)
+            .await;
+
+        assert_eq!(response.status(), http::StatusCode::UNAUTHORIZED);
+
+        let response = request()
+            .method("POST")
+            .path("/graphql")
+            .header("content-type", "application/json")
+            .header("authorization", "Bearer secret")
+            .body(r#"{ "query": "{ user }" }"#)
+            .reply(&filter)
+            .await;
+
+        assert_eq!(response.status(), http::StatusCode::OK);
+        assert_eq!(
+            String::from_utf8(response.body().to_vec()).unwrap(),
+            r#"{"data":{"user":"admin"}}"#
+        );
+    }
+
     #[tokio::test]
     async fn batch_requests_work() {
         use juniper::{
@@ -830,6 +2490,113 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn custom_error_formatter_shapes_errors() {
+        use juniper::{
+            http::tests::LegacyErrors,
+            tests::{model::Database, schema::Query},
+            EmptyMutation, EmptySubscription, RootNode,
+        };
+
+        type Schema =
+            juniper::RootNode<'static, Query, EmptyMutation<Database>, EmptySubscription<Database>>;
+
+        let schema: Schema = RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+
+        let state = warp::any().map(Database::new);
+        let filter = warp::path("graphql2").and(make_graphql_filter_with_error_formatter(
+            schema,
+            state.boxed(),
+            LegacyErrors,
+        ));
+
+        let response = request()
+            .method("POST")
+            .path("/graphql2")
+            .header("accept", "application/json")
+            .header("content-type", "application/json")
+            .body(r##"{ "variables": null, "query": "{ hero { nope } }" }"##)
+            .reply(&filter)
+            .await;
+
+        assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+        assert_eq!(
+            String::from_utf8(response.body().to_vec()).unwrap(),
+            r#"{"errors":["Unknown field \"nope\" on type \"Character\""]}"#
+        );
+    }
+
+    #[tokio::test]
+    async fn batch_requests_isolate_malformed_entries() {
+        use juniper::{
+            tests::{model::Database, schema::Query},
+            EmptyMutation, EmptySubscription, RootNode,
+        };
+
+        type Schema =
+            juniper::RootNode<'static, Query, EmptyMutation<Database>, EmptySubscription<Database>>;
+
+        let schema: Schema = RootNode::new(
+            Query,
+            EmptyMutation::<Database>::new(),
+            EmptySubscription::<Database>::new(),
+        );
+
+        let state = warp::any().map(Database::new);
+        let filter = warp::path("graphql2").and(make_graphql_filter(schema, state.boxed()));
+
+        let response = request()
+            .method("POST")
+            .path("/graphql2")
+            .header("accept", "application/json")
+            .header("content-type", "application/json")
+            .body(
+                r##"[
+                     { "variables": null, "query": "{ hero(episode: NEW_HOPE) { name } }" },
+                     { "variables": null, "query": 42 },
+                     { "variables": null, "query": "{ hero(episode: EMPIRE) { id name } }" }
+                 ]"##,
+            )
+            .reply(&filter)
+            .await;
+
+        assert_eq!(response.status(), http::StatusCode::BAD_REQUEST);
+        assert_eq!(
+            String::from_utf8(response.body().to_vec()).unwrap(),
+            concat!(
+                r#"[{"data":{"hero":{"name":"R2-D2"}}},"#,
+                r#"{"errors":[{"message":"Expected a GraphQL request object with a \"query\" string"}]},"#,
+                r#"{"data":{"hero":{"id":"1000","name":"Luke Skywalker"}}}]"#,
+            )
+        );
+    }
+
+    #[test]
+    fn batch_request_deserialization_isolates_malformed_entries() {
+        let json = r#"[{"query": "{ hero { name } }"}, {"operationName": "Hero"}]"#;
+        let result: GraphQLBatchRequest = serde_json::from_str(json).unwrap();
+
+        match result {
+            GraphQLBatchRequest::Batch(ref entries) => {
+                assert_eq!(entries.len(), 2);
+                assert_eq!(
+                    entries[0].request(),
+                    Some(&juniper::http::GraphQLRequest::new(
+                        "{ hero { name } }".to_owned(),
+                        None,
+                        None,
+                    ))
+                );
+                assert_eq!(entries[1].request(), None);
+            }
+            GraphQLBatchRequest::Single(_) => panic!("Expected a batch, got {:?}", result),
+        }
+    }
+
     #[test]
     fn batch_request_deserialization_can_fail() {
         let json = r#"blah"#;